@@ -0,0 +1,47 @@
+// How fast each engine ticks a random soup, at a few sizes:
+//
+//     cargo bench -p game-of-life-core
+//     cargo bench -p game-of-life-core --features parallel
+//
+// The SIMD path only exists on wasm32 built with simd128, so natively
+// its group is skipped; the others all run on the same soups.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use game_of_life_core::{simd_enabled, Engine, Universe};
+
+const SIZES: [u32; 3] = [64, 256, 1024];
+
+fn soup(size: u32, engine: Engine) -> Universe {
+    let mut universe = Universe::random(size, size, 0.3, 42).expect("the sizes are non-zero");
+    universe.set_engine(engine);
+    // Undo history is not what is being measured.
+    universe.set_history_depth(0);
+    universe
+}
+
+fn engines(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tick");
+    // A 1024x1024 naive tick takes tens of milliseconds, and a hundred
+    // samples of each would take the best part of a minute.
+    group.sample_size(20);
+    for &size in &SIZES {
+        group.throughput(Throughput::Elements(u64::from(size) * u64::from(size)));
+        for &(name, engine) in &[("naive", Engine::Naive), ("bit_parallel", Engine::BitParallel), ("hashlife", Engine::Hashlife)] {
+            let mut universe = soup(size, engine);
+            group.bench_with_input(BenchmarkId::new(name, size), &size, |b, _| b.iter(|| universe.tick()));
+        }
+        // Without the parallel feature the bands run one after another,
+        // which still shows what splitting the work costs.
+        let mut universe = soup(size, Engine::Naive);
+        group.bench_with_input(BenchmarkId::new("parallel_4", size), &size, |b, _| b.iter(|| universe.tick_parallel(4)));
+        if simd_enabled() {
+            // Naive picks SIMD up by itself where it has it.
+            let mut universe = soup(size, Engine::Naive);
+            group.bench_with_input(BenchmarkId::new("simd", size), &size, |b, _| b.iter(|| universe.tick()));
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, engines);
+criterion_main!(benches);
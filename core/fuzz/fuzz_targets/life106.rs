@@ -0,0 +1,16 @@
+// Life 1.06 cell lists. The header is put in front so that every input
+// is read as one, rather than whatever format it happens to look like.
+//
+//     cargo +nightly fuzz run life106
+
+#![no_main]
+
+use game_of_life_core::{Pattern, PatternFormat};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|text: &str| {
+    if let Ok(pattern) = Pattern::parse(&format!("#Life 1.06\n{}", text)) {
+        let written = pattern.to_text(PatternFormat::Life106).expect("Life 1.06 is always written");
+        Pattern::parse(&written).expect("written Life 1.06 parses");
+    }
+});
@@ -0,0 +1,13 @@
+// Files dropped onto the page: any format, possibly gzipped, and not
+// necessarily text at all.
+//
+//     cargo +nightly fuzz run pattern_file
+
+#![no_main]
+
+use game_of_life_core::Pattern;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|bytes: &[u8]| {
+    let _ = Pattern::from_file(bytes);
+});
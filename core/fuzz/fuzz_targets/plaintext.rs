@@ -0,0 +1,15 @@
+// LifeWiki .cells files, with . for dead and O for live cells.
+//
+//     cargo +nightly fuzz run plaintext
+
+#![no_main]
+
+use game_of_life_core::Pattern;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|text: &str| {
+    // Writing may refuse a pattern too sparse to lay out as text.
+    if let Ok(Ok(written)) = Pattern::from_plaintext(text).map(|pattern| pattern.to_plaintext()) {
+        Pattern::from_plaintext(&written).expect("written plaintext parses");
+    }
+});
@@ -0,0 +1,15 @@
+// RLE as pasted into the page. Whatever parses has to write back out
+// and parse again.
+//
+//     cargo +nightly fuzz run rle
+
+#![no_main]
+
+use game_of_life_core::Pattern;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|text: &str| {
+    if let Ok(pattern) = Pattern::from_rle(text) {
+        Pattern::from_rle(&pattern.to_rle()).expect("written RLE parses");
+    }
+});
@@ -0,0 +1,184 @@
+use fixedbitset::FixedBitSet;
+
+use crate::Error;
+
+// Draws frames for animated exports the way the canvas Renderer draws
+// a universe: one cell_size square per cell, with a 1px grid line
+// around each of them.
+pub struct Raster {
+    width: u32,
+    height: u32,
+    cell_size: u32,
+}
+
+impl Raster {
+    // Frames may be at most `max_size` pixels wide and high.
+    pub fn new(width: u32, height: u32, cell_size: u32, max_size: u32) -> Result<Raster, Error> {
+        if cell_size == 0 {
+            return Err(Error::new("cells must be at least one pixel big"));
+        }
+        let pixels = |cells: u32| u64::from(cells) * (u64::from(cell_size) + 1) + 1;
+        if pixels(width) > u64::from(max_size) || pixels(height) > u64::from(max_size) {
+            return Err(Error::new(&format!(
+                "frames can be at most {} pixels wide and high, try a smaller cell size",
+                max_size
+            )));
+        }
+        Ok(Raster {
+            width,
+            height,
+            cell_size,
+        })
+    }
+
+    pub fn pixel_width(&self) -> u32 {
+        self.width * (self.cell_size + 1) + 1
+    }
+
+    pub fn pixel_height(&self) -> u32 {
+        self.height * (self.cell_size + 1) + 1
+    }
+
+    // One pixel per item, row by row: `grid` for the grid lines and
+    // color(idx) inside the cell at idx.
+    pub fn draw<T: Copy>(&self, grid: T, color: impl Fn(usize) -> T) -> Vec<T> {
+        let pitch = self.cell_size + 1;
+        let (width, height) = (self.pixel_width(), self.pixel_height());
+        let mut pixels = vec![grid; width as usize * height as usize];
+        for (y, line) in pixels.chunks_exact_mut(width as usize).enumerate() {
+            let y = y as u32;
+            if y.is_multiple_of(pitch) {
+                continue;
+            }
+            let row = y / pitch;
+            for (x, pixel) in line.iter_mut().enumerate() {
+                let x = x as u32;
+                if !x.is_multiple_of(pitch) {
+                    *pixel = color((row * self.width + x / pitch) as usize);
+                }
+            }
+        }
+        pixels
+    }
+}
+
+const DEAD_COLOR: [u8; 3] = [0xFF, 0xFF, 0xFF];
+const ALIVE_COLOR: [u8; 3] = [0x00, 0x00, 0x00];
+const GRID_COLOR: [u8; 3] = [0xCC, 0xCC, 0xCC];
+// Newborns are amber and turn purple as they reach OLD_AGE.
+#[cfg(feature = "apng")]
+const YOUNG_COLOR: [u8; 3] = [0xFF, 0xC8, 0x00];
+#[cfg(feature = "apng")]
+const OLD_COLOR: [u8; 3] = [0x5A, 0x00, 0x8C];
+#[cfg(feature = "apng")]
+const OLD_AGE: f32 = 100.0;
+// Dead cells get redder the more has happened to them lately.
+#[cfg(feature = "apng")]
+const HOT_COLOR: [u8; 3] = [0xFF, 0x45, 0x00];
+
+#[cfg(feature = "apng")]
+fn mix(from: [u8; 3], to: [u8; 3], t: f32) -> [u8; 3] {
+    let t = t.clamp(0.0, 1.0);
+    let channel = |i: usize| (f32::from(from[i]) + (f32::from(to[i]) - f32::from(from[i])) * t).round() as u8;
+    [channel(0), channel(1), channel(2)]
+}
+
+// The color of every cell for a full-color frame. `ages` and `activity`
+// are only read with the colorings that need them.
+#[cfg(feature = "apng")]
+pub fn cell_colors(coloring: crate::Coloring, cells: &FixedBitSet, ages: &[u16], activity: &[f32]) -> Vec<[u8; 3]> {
+    (0..cells.len())
+        .map(|idx| match (coloring, cells[idx]) {
+            (crate::Coloring::Age, true) => mix(YOUNG_COLOR, OLD_COLOR, f32::from(ages[idx].saturating_sub(1)) / OLD_AGE),
+            (crate::Coloring::Activity, false) => mix(DEAD_COLOR, HOT_COLOR, activity[idx]),
+            (_, true) => ALIVE_COLOR,
+            (_, false) => DEAD_COLOR,
+        })
+        .collect()
+}
+
+// GIF frames only have the plain colors, as indices into these, so that
+// a cell's index is just whether it is alive.
+#[cfg(feature = "gif")]
+const GIF_PALETTE: [[u8; 3]; 3] = [DEAD_COLOR, ALIVE_COLOR, GRID_COLOR];
+
+// How long each frame shows, in hundredths of a second.
+const FRAME_DELAY: u16 = 5;
+
+// Writes frames into a looping GIF as they come, so that a long run
+// never has to keep more than one of them.
+#[cfg(feature = "gif")]
+pub struct GifRecorder {
+    raster: Raster,
+    encoder: gif::Encoder<Vec<u8>>,
+}
+
+#[cfg(feature = "gif")]
+fn gif_error(e: gif::EncodingError) -> Error {
+    Error::new(&format!("could not encode the GIF: {}", e))
+}
+
+#[cfg(feature = "gif")]
+impl GifRecorder {
+    pub fn new(width: u32, height: u32, cell_size: u32) -> Result<GifRecorder, Error> {
+        let raster = Raster::new(width, height, cell_size, u32::from(u16::MAX))?;
+        let (pixel_width, pixel_height) = (raster.pixel_width() as u16, raster.pixel_height() as u16);
+        let mut encoder = gif::Encoder::new(Vec::new(), pixel_width, pixel_height, GIF_PALETTE.as_flattened()).map_err(gif_error)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(gif_error)?;
+        Ok(GifRecorder { raster, encoder })
+    }
+
+    pub fn add_frame(&mut self, cells: &FixedBitSet) -> Result<(), Error> {
+        let (width, height) = (self.raster.pixel_width() as u16, self.raster.pixel_height() as u16);
+        let pixels = self.raster.draw(2, |idx| u8::from(cells[idx]));
+        let mut frame = gif::Frame::from_indexed_pixels(width, height, pixels, None);
+        frame.delay = FRAME_DELAY;
+        self.encoder.write_frame(&frame).map_err(gif_error)
+    }
+
+    pub fn finish(self) -> Result<Vec<u8>, Error> {
+        self.encoder.into_inner().map_err(gif_error)
+    }
+}
+
+// Big enough for any sensible export, small enough that a frame's
+// pixels always fit in memory.
+#[cfg(feature = "apng")]
+const MAX_APNG_SIZE: u32 = 16384;
+
+// The same for animated PNGs, in full color. APNG has to know how many
+// frames are coming before the first one.
+#[cfg(feature = "apng")]
+pub struct ApngRecorder<'a> {
+    raster: Raster,
+    writer: png::Writer<&'a mut Vec<u8>>,
+}
+
+#[cfg(feature = "apng")]
+fn png_error(e: png::EncodingError) -> Error {
+    Error::new(&format!("could not encode the APNG: {}", e))
+}
+
+#[cfg(feature = "apng")]
+impl<'a> ApngRecorder<'a> {
+    pub fn new(out: &'a mut Vec<u8>, width: u32, height: u32, cell_size: u32, frames: u32) -> Result<ApngRecorder<'a>, Error> {
+        let raster = Raster::new(width, height, cell_size, MAX_APNG_SIZE)?;
+        let mut encoder = png::Encoder::new(out, raster.pixel_width(), raster.pixel_height());
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(frames, 0).map_err(png_error)?;
+        encoder.set_frame_delay(FRAME_DELAY, 100).map_err(png_error)?;
+        let writer = encoder.write_header().map_err(png_error)?;
+        Ok(ApngRecorder { raster, writer })
+    }
+
+    // `colors` holds one color per cell, from cell_colors().
+    pub fn add_frame(&mut self, colors: &[[u8; 3]]) -> Result<(), Error> {
+        let pixels = self.raster.draw(GRID_COLOR, |idx| colors[idx]);
+        self.writer.write_image_data(pixels.as_flattened()).map_err(png_error)
+    }
+
+    pub fn finish(self) -> Result<(), Error> {
+        self.writer.finish().map_err(png_error)
+    }
+}
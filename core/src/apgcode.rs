@@ -0,0 +1,243 @@
+use std::collections::HashSet;
+
+use crate::{Error, LifeRule, Pattern};
+
+// apgcodes name objects the way Catagolue and LifeWiki do: a prefix
+// saying what the object is, then its cells in the extended Wechsler
+// format.
+//
+//     xs4_33      a block, a still life of 4 cells
+//     xp2_7       a blinker, an oscillator of period 2
+//     xq4_153     a glider, a spaceship of period 4
+//
+// The cells are cut into strips 5 rows high, and every column of a
+// strip is one character from 0-9a-v, the top row being bit 0. Strips
+// are separated by z. Runs of empty columns are shortened: w is two, x
+// three, and y followed by 0-9a-z four to 39. Each object has a single
+// code: out of every phase and orientation, the shortest one, and the
+// first in alphabetical order of the shortest.
+
+// How long an object gets to come back to its first phase.
+const MAX_PERIOD: u32 = 1024;
+
+pub(crate) type Cells = Vec<(i64, i64)>;
+
+// The cells moved to the top-left corner, sorted, and how far they moved.
+fn normalize(cells: &HashSet<(i64, i64)>) -> (Cells, (i64, i64)) {
+    let top = cells.iter().map(|&(row, _)| row).min().unwrap_or(0);
+    let left = cells.iter().map(|&(_, col)| col).min().unwrap_or(0);
+    let mut shape: Cells = cells.iter().map(|&(row, col)| (row - top, col - left)).collect();
+    shape.sort_unstable();
+    (shape, (top, left))
+}
+
+// One generation on an unbounded plane.
+fn step(cells: &HashSet<(i64, i64)>, rule: &LifeRule) -> HashSet<(i64, i64)> {
+    let mut candidates = HashSet::new();
+    for &(row, col) in cells {
+        for delta_row in -1..=1 {
+            for delta_col in -1..=1 {
+                candidates.insert((row + delta_row, col + delta_col));
+            }
+        }
+    }
+    candidates
+        .into_iter()
+        .filter(|&(row, col)| {
+            // Neighbor bits in the order LifeRule::next_with() expects.
+            let mut neighborhood = 0;
+            for delta_row in -1..=1 {
+                for delta_col in -1..=1 {
+                    if delta_row != 0 || delta_col != 0 {
+                        neighborhood = neighborhood << 1 | cells.contains(&(row + delta_row, col + delta_col)) as u8;
+                    }
+                }
+            }
+            rule.next_with(cells.contains(&(row, col)), neighborhood)
+        })
+        .collect()
+}
+
+fn column_char(bits: u32) -> char {
+    std::char::from_digit(bits, 32).unwrap()
+}
+
+fn push_zeros(code: &mut String, mut zeros: u32) {
+    while zeros > 0 {
+        match zeros {
+            1 => code.push('0'),
+            2 => code.push('w'),
+            3 => code.push('x'),
+            _ => {
+                let run = zeros.min(39);
+                code.push('y');
+                code.push(std::char::from_digit(run - 4, 36).unwrap());
+                zeros -= run;
+                continue;
+            }
+        }
+        return;
+    }
+}
+
+// The Wechsler part of the code, for one phase in one orientation.
+fn wechsler(shape: &[(i64, i64)]) -> String {
+    let height = shape.iter().map(|&(row, _)| row + 1).max().unwrap_or(0);
+    let width = shape.iter().map(|&(_, col)| col + 1).max().unwrap_or(0) as usize;
+    let mut strips = Vec::new();
+    for strip in 0..(height + 4) / 5 {
+        let mut columns = vec![0; width];
+        for &(row, col) in shape.iter().filter(|&&(row, _)| row / 5 == strip) {
+            columns[col as usize] |= 1 << (row % 5);
+        }
+        // Empty columns at the end of a strip are left out.
+        let mut code = String::new();
+        let mut zeros = 0;
+        for bits in columns {
+            if bits == 0 {
+                zeros += 1;
+            } else {
+                push_zeros(&mut code, zeros);
+                zeros = 0;
+                code.push(column_char(bits));
+            }
+        }
+        strips.push(code);
+    }
+    strips.join("z")
+}
+
+// The eight rotations and reflections of a shape.
+fn orientations(shape: &[(i64, i64)]) -> Vec<Cells> {
+    type Orient = fn((i64, i64)) -> (i64, i64);
+    let maps: [Orient; 8] = [
+        |(r, c)| (r, c),
+        |(r, c)| (r, -c),
+        |(r, c)| (-r, c),
+        |(r, c)| (-r, -c),
+        |(r, c)| (c, r),
+        |(r, c)| (c, -r),
+        |(r, c)| (-c, r),
+        |(r, c)| (-c, -r),
+    ];
+    maps.iter()
+        .map(|map| normalize(&shape.iter().map(|&cell| map(cell)).collect()).0)
+        .collect()
+}
+
+// Names a pattern, see classify().
+pub fn encode(pattern: &Pattern, rule: &LifeRule) -> Result<String, Error> {
+    if rule.states() != 2 || rule.larger_than_life().is_some() {
+        return Err(Error::new("apgcodes need a two-state rule of the eight nearest neighbors"));
+    }
+    let cells: HashSet<(i64, i64)> =
+        pattern.live_cells().iter().map(|&(row, col)| (i64::from(row), i64::from(col))).collect();
+    if cells.is_empty() {
+        return Ok("xs0_0".to_string());
+    }
+    Ok(classify(cells, rule, MAX_PERIOD)?.code)
+}
+
+// What an object turned out to be when run on its own.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Classified {
+    pub code: String,
+    // 1 for still lifes.
+    pub period: u32,
+    // How far it moves every period, as (rows, columns). Nothing but
+    // spaceships move.
+    pub displacement: (i64, i64),
+}
+
+// Runs the cells until they come back, to tell still lifes, oscillators
+// and spaceships apart, and names them.
+pub(crate) fn classify(mut cells: HashSet<(i64, i64)>, rule: &LifeRule, max_period: u32) -> Result<Classified, Error> {
+    let (first, start) = normalize(&cells);
+    let mut phases = vec![first.clone()];
+    for period in 1..=max_period {
+        cells = step(&cells, rule);
+        let (shape, offset) = normalize(&cells);
+        if shape.is_empty() {
+            return Err(Error::new("the pattern dies out"));
+        }
+        if shape != first {
+            phases.push(shape);
+            continue;
+        }
+        let prefix = match (period, offset == start) {
+            (1, true) => format!("xs{}", first.len()),
+            (_, true) => format!("xp{}", period),
+            (_, false) => format!("xq{}", period),
+        };
+        let code = phases
+            .iter()
+            .flat_map(|phase| orientations(phase))
+            .map(|shape| wechsler(&shape))
+            .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+            .unwrap();
+        return Ok(Classified {
+            code: format!("{}_{}", prefix, code),
+            period,
+            displacement: (offset.0 - start.0, offset.1 - start.1),
+        });
+    }
+    Err(Error::new(&format!(
+        "the pattern is not a still life, oscillator or spaceship of period {} or less",
+        max_period
+    )))
+}
+
+// The cells of an apgcode, or of a bare Wechsler code, as a pattern in
+// the orientation and phase the code describes.
+pub fn decode(code: &str) -> Result<Pattern, Error> {
+    let code = code.trim();
+    let wechsler = match code.split_once('_') {
+        Some((prefix, wechsler)) => {
+            let known = ["xs", "xp", "xq"].iter().any(|kind| prefix.starts_with(kind));
+            if !known || prefix.len() < 3 || !prefix[2..].chars().all(|c| c.is_ascii_digit()) {
+                return Err(Error::new(&format!(
+                    "unknown apgcode prefix {:?}, only still lifes (xs), oscillators (xp) and spaceships (xq) have cells",
+                    prefix
+                )));
+            }
+            wechsler
+        }
+        None => code,
+    };
+
+    let mut cells = Vec::new();
+    let (mut strip, mut col) = (0, 0);
+    let mut chars = wechsler.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            'w' => col += 2,
+            'x' => col += 3,
+            'y' => {
+                let run = chars
+                    .next()
+                    .and_then(|c| c.to_digit(36))
+                    .ok_or_else(|| Error::new("y must be followed by 0-9 or a-z in an apgcode"))?;
+                col += 4 + run;
+            }
+            'z' => {
+                strip += 1;
+                col = 0;
+            }
+            _ => {
+                let bits = c
+                    .to_digit(32)
+                    .filter(|_| !c.is_ascii_uppercase())
+                    .ok_or_else(|| Error::new(&format!("unexpected {:?} in apgcode", c)))?;
+                for row in (0..5).filter(|row| bits & (1 << row) != 0) {
+                    cells.push((strip * 5 + row, col));
+                }
+                col += 1;
+            }
+        }
+    }
+
+    let height = cells.iter().map(|&(row, _)| row + 1).max().unwrap_or(0);
+    let width = cells.iter().map(|&(_, col)| col + 1).max().unwrap_or(0);
+    let flat: Vec<u32> = cells.iter().flat_map(|&(row, col)| [row, col]).collect();
+    Pattern::new(width, height, &flat)
+}
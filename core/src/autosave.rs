@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+
+use crate::snapshot::Snapshot;
+use crate::Turmite;
+
+// Keeps a universe recoverable without a full save every few
+// generations. Autosave holds one full snapshot, the base, and the
+// latest capture only as the cell words that differ from it:
+// (index, old ^ new) for every word where the two aren't equal. As
+// long as the universe stays the same size and setup, that is far
+// smaller than the cells themselves. Anything else, or a delta that
+// grew to be as big as a full save, makes the capture the new base.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Delta {
+    generation: u64,
+    random_state: u64,
+    turmites: Vec<Turmite>,
+    words: Vec<(u32, u32)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Autosave {
+    interval: u64,
+    // The generation of the last capture.
+    captured: u64,
+    base: Snapshot,
+    delta: Option<Delta>,
+}
+
+// Whether a delta against `base` can bring back `current`: everything
+// but the cells, the generation and the turmites must be the same.
+// Dying states and colors change about as often as the cells and
+// aren't kept in deltas, so rules and species with those always make
+// full captures.
+fn same_setup(base: &Snapshot, current: &Snapshot) -> bool {
+    base.width == current.width
+        && base.height == current.height
+        && base.rule == current.rule
+        && base.boundary == current.boundary
+        && base.neighborhood == current.neighborhood
+        && base.weighted == current.weighted
+        && base.engine == current.engine
+        && base.seed == current.seed
+        && base.stochastic == current.stochastic
+        && base.rule_table == current.rule_table
+        && base.species == current.species
+        && current.states.is_empty()
+        && current.colors.is_empty()
+}
+
+impl Autosave {
+    pub fn new(interval: u64, base: Snapshot) -> Autosave {
+        Autosave {
+            interval,
+            captured: base.generation,
+            base,
+            delta: None,
+        }
+    }
+
+    pub fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    // Whether enough generations went by for another capture.
+    pub fn is_due(&self, generation: u64) -> bool {
+        generation.abs_diff(self.captured) >= self.interval
+    }
+
+    pub fn capture(&mut self, current: Snapshot) {
+        self.captured = current.generation;
+        if !same_setup(&self.base, &current) {
+            self.base = current;
+            self.delta = None;
+            return;
+        }
+        let words: Vec<(u32, u32)> = self
+            .base
+            .cells
+            .iter()
+            .zip(&current.cells)
+            .enumerate()
+            .filter(|(_, (old, new))| old != new)
+            .map(|(index, (old, new))| (index as u32, old ^ new))
+            .collect();
+        // Each changed word takes twice the room of a word in the base.
+        if words.len() * 2 >= self.base.cells.len() {
+            self.base = current;
+            self.delta = None;
+        } else {
+            self.delta = Some(Delta {
+                generation: current.generation,
+                random_state: current.random_state,
+                turmites: current.turmites,
+                words,
+            });
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(&(self.base.to_bytes(), &self.delta)).expect("autosaves always serialize")
+    }
+
+    // The universe as it was at the last capture.
+    pub fn restore(bytes: &[u8]) -> Result<Snapshot, String> {
+        let (base, delta): (Vec<u8>, Option<Delta>) =
+            bincode::deserialize(bytes).map_err(|e| format!("invalid autosave: {}", e))?;
+        let mut snapshot = Snapshot::from_bytes(&base)?;
+        if let Some(delta) = delta {
+            for (index, diff) in delta.words {
+                let word = snapshot
+                    .cells
+                    .get_mut(index as usize)
+                    .ok_or("autosave changes cells outside the universe")?;
+                *word ^= diff;
+            }
+            snapshot.generation = delta.generation;
+            snapshot.random_state = delta.random_state;
+            snapshot.turmites = delta.turmites;
+        }
+        snapshot.checked()
+    }
+}
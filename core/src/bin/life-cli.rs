@@ -0,0 +1,105 @@
+// A terminal playground for the same Universe the browser runs:
+//
+//     cargo run --features cli --bin life-cli -- [width] [height]
+//
+// Arrow keys pan, space pauses, `n` steps once while paused, `r` starts
+// over from a fresh random soup and `q` (or Esc) quits.
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::{cursor, execute, queue, style, terminal};
+use game_of_life_core::{Engine, TextOptions, Universe};
+
+const FRAME: Duration = Duration::from_millis(50);
+
+fn soup(width: u32, height: u32) -> Universe {
+    let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.as_nanos() as u64);
+    let mut universe = Universe::random(width, height, 0.3, seed).expect("width and height are non-zero");
+    universe.set_engine(Engine::BitParallel);
+    // Nobody undoes anything here, so don't spend time recording it.
+    universe.set_history_depth(0);
+    universe
+}
+
+fn draw(out: &mut impl Write, universe: &Universe, (row, col): (u32, u32), paused: bool) -> io::Result<()> {
+    let (columns, rows) = terminal::size()?;
+    let mut options = TextOptions::new();
+    options.set_glyphs(" ", "█");
+    options.set_row_separator("\r\n", true);
+    options.set_viewport(row, col, u32::from(columns), u32::from(rows.saturating_sub(1)));
+
+    queue!(out, cursor::MoveTo(0, 0), terminal::Clear(terminal::ClearType::All))?;
+    queue!(out, style::Print(universe.render_text(&options)))?;
+    let status = format!(
+        "gen {}  pop {}  at ({}, {}){}  arrows pan, space pause, n step, r randomize, q quit",
+        universe.generation(),
+        universe.population(),
+        row,
+        col,
+        if paused { "  [paused]" } else { "" },
+    );
+    let status: String = status.chars().take(usize::from(columns)).collect();
+    queue!(out, cursor::MoveTo(0, rows.saturating_sub(1)), style::Print(status))?;
+    out.flush()
+}
+
+fn run(out: &mut impl Write, width: u32, height: u32) -> io::Result<()> {
+    let mut universe = soup(width, height);
+    let (mut row, mut col) = (0u32, 0u32);
+    let mut paused = false;
+
+    loop {
+        let frame_start = Instant::now();
+        draw(out, &universe, (row, col), paused)?;
+
+        while let Some(wait) = FRAME.checked_sub(frame_start.elapsed()) {
+            if !event::poll(wait)? {
+                break;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind == KeyEventKind::Release {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char(' ') => paused = !paused,
+                KeyCode::Char('n') if paused => universe.tick(),
+                KeyCode::Char('r') => universe = soup(width, height),
+                KeyCode::Up => row = row.saturating_sub(1),
+                KeyCode::Down => row = (row + 1).min(height - 1),
+                KeyCode::Left => col = col.saturating_sub(1),
+                KeyCode::Right => col = (col + 1).min(width - 1),
+                _ => continue,
+            }
+            draw(out, &universe, (row, col), paused)?;
+        }
+
+        if !paused {
+            universe.tick();
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut args = std::env::args().skip(1).map(|arg| arg.parse::<u32>());
+    let (width, height) = match (args.next(), args.next()) {
+        (None, None) => (256, 256),
+        (Some(Ok(width)), Some(Ok(height))) if width > 0 && height > 0 => (width, height),
+        _ => {
+            eprintln!("usage: life-cli [width height]");
+            std::process::exit(2);
+        }
+    };
+
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+    let result = run(&mut out, width, height);
+    execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
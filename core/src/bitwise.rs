@@ -0,0 +1,166 @@
+use fixedbitset::FixedBitSet;
+
+use crate::rule::LifeRule;
+use crate::Boundary;
+
+// The low `count` bits of the bit string in `words` starting at bit `bit`.
+fn load(words: &[u32], bit: usize, count: usize) -> u64 {
+    let (word, shift) = (bit / 32, bit % 32);
+    let mut value = 0u128;
+    for i in 0..3 {
+        if let Some(&w) = words.get(word + i) {
+            value |= u128::from(w) << (32 * i);
+        }
+    }
+    (value >> shift) as u64 & (u64::MAX >> (64 - count))
+}
+
+// ORs `bits` into the bit string in `words` starting at bit `bit`.
+fn store(words: &mut [u32], bit: usize, bits: u64) {
+    let (word, shift) = (bit / 32, bit % 32);
+    let value = u128::from(bits) << shift;
+    for i in 0..3 {
+        let part = (value >> (32 * i)) as u32;
+        if part != 0 {
+            words[word + i] |= part;
+        }
+    }
+}
+
+fn full_adder(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let partial = a ^ b;
+    (partial ^ c, (a & b) | (c & partial))
+}
+
+fn half_adder(a: u64, b: u64) -> (u64, u64) {
+    (a ^ b, a & b)
+}
+
+// One row of cells, 64 to a word, with whatever lies just past its
+// left and right ends.
+struct Row {
+    words: Vec<u64>,
+    left: bool,
+    right: bool,
+}
+
+impl Row {
+    fn new(words: usize) -> Row {
+        Row {
+            words: vec![0; words],
+            left: false,
+            right: false,
+        }
+    }
+
+    // The row with every cell moved one column to the right, so that
+    // each bit holds its western neighbor.
+    fn west(&self, i: usize) -> u64 {
+        let carry = if i == 0 { self.left as u64 } else { self.words[i - 1] >> 63 };
+        (self.words[i] << 1) | carry
+    }
+
+    // The same, the other way round. `width` marks where the row ends,
+    // since the last word is usually only partly used.
+    fn east(&self, i: usize, width: usize) -> u64 {
+        let last = self.words.len() - 1;
+        let carry = if i < last { self.words[i + 1] & 1 } else { 0 };
+        let mut bits = (self.words[i] >> 1) | (carry << 63);
+        if i == last && self.right {
+            bits |= 1 << ((width - 1) % 64);
+        }
+        bits
+    }
+}
+
+// Fills in `row` with row `r` of the universe, or with whatever the
+// boundary puts there when `r` is just outside it.
+fn fill(row: &mut Row, cells: &[u32], width: usize, height: usize, r: isize, boundary: Boundary) {
+    let r = match boundary {
+        Boundary::Torus => r.rem_euclid(height as isize),
+        Boundary::Mirror => r.clamp(0, height as isize - 1),
+        Boundary::Dead | Boundary::AliveWall if r < 0 || r >= height as isize => {
+            let alive = boundary == Boundary::AliveWall;
+            for (i, word) in row.words.iter_mut().enumerate() {
+                let count = (width - i * 64).min(64);
+                *word = if alive { u64::MAX >> (64 - count) } else { 0 };
+            }
+            row.left = alive;
+            row.right = alive;
+            return;
+        }
+        Boundary::Dead | Boundary::AliveWall => r,
+    } as usize;
+
+    let start = r * width;
+    for (i, word) in row.words.iter_mut().enumerate() {
+        *word = load(cells, start + i * 64, (width - i * 64).min(64));
+    }
+    let first = row.words[0] & 1 == 1;
+    let last = (row.words[row.words.len() - 1] >> ((width - 1) % 64)) & 1 == 1;
+    (row.left, row.right) = match boundary {
+        Boundary::Torus => (last, first),
+        Boundary::Mirror => (first, last),
+        Boundary::Dead => (false, false),
+        Boundary::AliveWall => (true, true),
+    };
+}
+
+// One generation, written into `next`. Each row is handled 64 cells at
+// a time: the eight neighbors are the rows above, at and below shifted
+// by one column either way, and adding them up with full and half
+// adders leaves every cell's neighbor count as four bit planes. The rule
+// then picks cells out of those planes with masks instead of branches.
+//
+// Callers keep width and height at 2 or more on a torus; narrower ones
+// count the same neighbor more than once, differently from Naive.
+pub fn step(cells: &FixedBitSet, next: &mut FixedBitSet, width: u32, height: u32, rule: LifeRule, boundary: Boundary) {
+    let (width, height) = (width as usize, height as usize);
+    let (birth, survival) = rule.masks();
+    let words = width.div_ceil(64);
+    let source = cells.as_slice();
+    next.clear();
+    let out = next.as_mut_slice();
+
+    let mut above = Row::new(words);
+    let mut middle = Row::new(words);
+    let mut below = Row::new(words);
+    fill(&mut above, source, width, height, -1, boundary);
+    fill(&mut middle, source, width, height, 0, boundary);
+
+    for r in 0..height {
+        fill(&mut below, source, width, height, r as isize + 1, boundary);
+        for i in 0..words {
+            let (ones_a, twos_a) = full_adder(above.west(i), above.words[i], above.east(i, width));
+            let (ones_b, twos_b) = full_adder(middle.west(i), middle.east(i, width), below.west(i));
+            let (ones_c, twos_c) = half_adder(below.words[i], below.east(i, width));
+            let (bit0, twos_d) = full_adder(ones_a, ones_b, ones_c);
+            let (partial, fours_a) = full_adder(twos_a, twos_b, twos_c);
+            let (bit1, fours_b) = half_adder(partial, twos_d);
+            let (bit2, bit3) = half_adder(fours_a, fours_b);
+
+            let alive = middle.words[i];
+            let mut result = 0;
+            for n in 0..=8 {
+                let wanted = ((birth >> n) & 1, (survival >> n) & 1);
+                if wanted == (0, 0) {
+                    continue;
+                }
+                let plane = |bit: u64, set: bool| if set { bit } else { !bit };
+                let count = plane(bit0, n & 1 != 0)
+                    & plane(bit1, n & 2 != 0)
+                    & plane(bit2, n & 4 != 0)
+                    & plane(bit3, n & 8 != 0);
+                let born = if wanted.0 == 1 { !alive } else { 0 };
+                let stays = if wanted.1 == 1 { alive } else { 0 };
+                result |= count & (born | stays);
+            }
+
+            // The last word's spare bits picked up neighbors past the end.
+            result &= u64::MAX >> (64 - (width - i * 64).min(64));
+            store(out, r * width + i * 64, result);
+        }
+        std::mem::swap(&mut above, &mut middle);
+        std::mem::swap(&mut middle, &mut below);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{Boundary, Engine, Error, Pattern, Universe};
+
+// What a built universe starts out with. Tutorial is the i % 2 == 0 ||
+// i % 7 == 0 pattern Universe::new() draws, Random a reproducible soup
+// like Universe::random(), and Pattern puts a pattern in the middle.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Seed {
+    Empty,
+    Tutorial,
+    Random { density: f32, seed: u64 },
+    Pattern(Pattern),
+}
+
+// Gathers everything a universe is made with and makes it in one go:
+//
+//     let universe = UniverseBuilder::new()
+//         .size(128, 96)
+//         .rule("B36/S23")
+//         .boundary(Boundary::Dead)
+//         .seed(Seed::Random { density: 0.3, seed: 7 })
+//         .build()?;
+//
+// Anything left out is as in Universe::new(): 64x64, Conway's Life on
+// a torus with the Naive engine, starting from the tutorial pattern.
+// Mistakes, like a rule that doesn't parse, only come out of build().
+#[derive(Clone, Debug, PartialEq)]
+pub struct UniverseBuilder {
+    width: u32,
+    height: u32,
+    rule: String,
+    boundary: Boundary,
+    engine: Engine,
+    seed: Seed,
+}
+
+impl Default for UniverseBuilder {
+    fn default() -> Self {
+        UniverseBuilder {
+            width: 64,
+            height: 64,
+            rule: "B3/S23".to_string(),
+            boundary: Boundary::Torus,
+            engine: Engine::Naive,
+            seed: Seed::Tutorial,
+        }
+    }
+}
+
+impl UniverseBuilder {
+    pub fn new() -> UniverseBuilder {
+        UniverseBuilder::default()
+    }
+
+    pub fn width(mut self, width: u32) -> UniverseBuilder {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> UniverseBuilder {
+        self.height = height;
+        self
+    }
+
+    pub fn size(self, width: u32, height: u32) -> UniverseBuilder {
+        self.width(width).height(height)
+    }
+
+    // Any rule Universe::with_rule() takes.
+    pub fn rule(mut self, rule: &str) -> UniverseBuilder {
+        self.rule = rule.to_string();
+        self
+    }
+
+    pub fn boundary(mut self, boundary: Boundary) -> UniverseBuilder {
+        self.boundary = boundary;
+        self
+    }
+
+    pub fn engine(mut self, engine: Engine) -> UniverseBuilder {
+        self.engine = engine;
+        self
+    }
+
+    pub fn seed(mut self, seed: Seed) -> UniverseBuilder {
+        self.seed = seed;
+        self
+    }
+
+    // The universe, seeded but with nothing to undo yet. The builder can
+    // be used again, e.g. for the same soup with another rule.
+    pub fn build(&self) -> Result<Universe, Error> {
+        let mut universe = Universe::with_rule(self.width, self.height, &self.rule)?;
+        match &self.seed {
+            Seed::Empty => {}
+            Seed::Tutorial => {
+                for i in 0..universe.cells.len() {
+                    universe.cells.set(i, i % 2 == 0 || i % 7 == 0);
+                }
+            }
+            Seed::Random { density, seed } => {
+                universe.set_seed(*seed);
+                universe.soup(*density)?;
+            }
+            Seed::Pattern(pattern) => {
+                let row = self.height.saturating_sub(pattern.height()) / 2;
+                let col = self.width.saturating_sub(pattern.width()) / 2;
+                universe.paste(pattern, row, col)?;
+            }
+        }
+        universe.recount();
+        universe.refresh_states();
+        universe.set_boundary(self.boundary);
+        universe.set_engine(self.engine);
+        Ok(universe)
+    }
+}
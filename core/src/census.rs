@@ -0,0 +1,233 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use crate::apgcode;
+use crate::objects::{self, live_cells};
+use crate::{Engine, Error, LifeRule, StopReason, Symmetry, Universe};
+
+// How many generations a soup runs between looks for spaceships that
+// have got away, and the longest period the whole universe may settle
+// into: 60 covers the usual mix of period 2, 3, 4, 5 and 15 ash.
+const CHECK_EVERY: u32 = 120;
+const MAX_PERIOD: u32 = 60;
+
+// The longest period a spaceship moving away may have: soups hardly
+// ever let go of anything slower than the period 4 gliders and
+// spaceships.
+const ESCAPE_PERIOD: u32 = 16;
+
+// The objects soups settled into, counted by apgcode, the way
+// apgsearch reports them: "xs4_33" is a block, "xp2_7" a blinker and
+// "xq4_153" a glider. Spaceships are counted as they leave. Anything
+// that doesn't come back to itself when run alone is "unidentified".
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Census {
+    pub soups: u32,
+    // Soups still changing after the search's max_generations, whose
+    // objects aren't counted.
+    pub unsettled: u32,
+    pub objects: BTreeMap<String, u64>,
+}
+
+impl Census {
+    pub fn count(&self, code: &str) -> u64 {
+        self.objects.get(code).copied().unwrap_or(0)
+    }
+
+    // The codes from the most common down.
+    pub fn by_count(&self) -> Vec<(&str, u64)> {
+        let mut objects: Vec<(&str, u64)> = self.objects.iter().map(|(code, &count)| (code.as_str(), count)).collect();
+        objects.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        objects
+    }
+}
+
+// A batch of soups, run one after another and taken a census of. Soup
+// n of a search is the universe's soup with seed `seed + n`, so any of
+// them can be brought back with set_seed() and randomize_symmetric()
+// on a universe of the same size to look at.
+//
+//     let mut search = SoupSearch::new("B3/S23", Symmetry::C1, 1000, 7)?;
+//     search.run_with(|done, total| println!("{}/{}", done, total))?;
+//     println!("{:?}", search.census().by_count());
+//
+// Soups run on a torus, big enough that ash rarely reaches round it.
+#[derive(Clone, Debug)]
+pub struct SoupSearch {
+    rule: LifeRule,
+    symmetry: Symmetry,
+    seed: u64,
+    total: u32,
+    size: u32,
+    max_generations: u32,
+    census: Census,
+}
+
+impl SoupSearch {
+    pub fn new(rule: &str, symmetry: Symmetry, soups: u32, seed: u64) -> Result<SoupSearch, Error> {
+        let rule: LifeRule = rule.parse()?;
+        if !rule.is_life_like() || rule.masks().0 & 1 != 0 {
+            return Err(Error::new(&format!("soup searches run B/S rules without B0, not {}", rule)));
+        }
+        Ok(SoupSearch {
+            rule,
+            symmetry,
+            seed,
+            total: soups,
+            size: 256,
+            max_generations: 20_000,
+            census: Census::default(),
+        })
+    }
+
+    // The universe every soup runs in, 256x256 unless set.
+    pub fn set_size(&mut self, size: u32) -> Result<(), Error> {
+        let (width, height) = self.symmetry.size();
+        if size < width.max(height) {
+            return Err(Error::new(&format!("a {} soup doesn't fit in {}x{}", self.symmetry, size, size)));
+        }
+        self.size = size;
+        Ok(())
+    }
+
+    // How long a soup may take to settle, 20000 generations unless set.
+    pub fn set_max_generations(&mut self, max_generations: u32) {
+        self.max_generations = max_generations;
+    }
+
+    pub fn done(&self) -> u32 {
+        self.census.soups
+    }
+
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.census.soups >= self.total
+    }
+
+    pub fn census(&self) -> &Census {
+        &self.census
+    }
+
+    // Runs up to `count` more soups, for callers that want to stop now
+    // and then, e.g. to let a page draw. Returns how many ran.
+    pub fn run(&mut self, count: u32) -> Result<u32, Error> {
+        let count = count.min(self.total - self.census.soups);
+        for _ in 0..count {
+            self.run_soup()?;
+        }
+        Ok(count)
+    }
+
+    // Runs every soup that is left, calling `progress` with the soups
+    // done and the total after each one.
+    pub fn run_with<F: FnMut(u32, u32)>(&mut self, mut progress: F) -> Result<&Census, Error> {
+        while !self.is_finished() {
+            self.run_soup()?;
+            progress(self.census.soups, self.total);
+        }
+        Ok(&self.census)
+    }
+
+    fn run_soup(&mut self) -> Result<(), Error> {
+        let mut universe = Universe::empty(self.size, self.size, self.rule)?;
+        universe.set_history_depth(0);
+        universe.set_engine(Engine::BitParallel);
+        universe.set_seed(self.seed.wrapping_add(u64::from(self.census.soups)));
+        universe.randomize_symmetric(self.symmetry, 0.5)?;
+        self.census.soups += 1;
+        let rule = self.rule;
+        match settle(&mut universe, &rule, self.max_generations, |code| self.count(Some(code))) {
+            Settled::DiedOut => {}
+            Settled::Cycle { period, .. } => self.count_ash(&mut universe, period),
+            Settled::Unsettled => self.census.unsettled += 1,
+        }
+        Ok(())
+    }
+
+    fn count(&mut self, code: Option<String>) {
+        let code = code.unwrap_or_else(|| "unidentified".to_string());
+        *self.census.objects.entry(code).or_insert(0) += 1;
+    }
+
+    // Everything left once the universe repeats itself. Objects are
+    // told apart on all the phases of the cycle put together, so that
+    // an oscillator passing close to a still life is seen to be part of
+    // the same thing.
+    fn count_ash(&mut self, universe: &mut Universe, period: u32) {
+        let now = live_cells(universe);
+        let mut phases = now.clone();
+        for _ in 0..period {
+            universe.tick();
+            phases.extend(live_cells(universe));
+        }
+        phases.sort_unstable();
+        phases.dedup();
+        let now: HashSet<(i64, i64)> = now.into_iter().collect();
+        for group in objects::split(&phases) {
+            let cells: HashSet<(i64, i64)> = group.into_iter().filter(|cell| now.contains(cell)).collect();
+            if !cells.is_empty() {
+                let found = apgcode::classify(cells, &self.rule, MAX_PERIOD);
+                self.count(found.ok().map(|found| found.code));
+            }
+        }
+    }
+}
+
+// How a universe run by settle() ended up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Settled {
+    DiedOut,
+    // Repeating itself every `period` generations since `since`.
+    Cycle { period: u32, since: u64 },
+    // Still changing after max_generations.
+    Unsettled,
+}
+
+// Runs a soup until it dies out or repeats itself. The spaceships it
+// sends off would never let it repeat itself, so every CHECK_EVERY
+// generations the ones that have got clear are taken out, and their
+// apgcodes handed to `escaped`.
+pub(crate) fn settle<F: FnMut(String)>(universe: &mut Universe, rule: &LifeRule, max_generations: u32, mut escaped: F) -> Settled {
+    loop {
+        let report = universe.run_until_stable(CHECK_EVERY, MAX_PERIOD);
+        match report.reason() {
+            StopReason::DiedOut => return Settled::DiedOut,
+            StopReason::Cycle => {
+                return Settled::Cycle {
+                    period: report.period().unwrap_or(1),
+                    since: report.since().unwrap_or(universe.generation),
+                }
+            }
+            StopReason::GenerationCap if universe.generation >= u64::from(max_generations) => return Settled::Unsettled,
+            StopReason::GenerationCap => remove_escapees(universe, rule, &mut escaped),
+        }
+    }
+}
+
+// Takes out the spaceships that are clear of everything else and moving
+// away from it, so they can't come back.
+fn remove_escapees<F: FnMut(String)>(universe: &mut Universe, rule: &LifeRule, escaped: &mut F) {
+    let groups = objects::split(&live_cells(universe));
+    for (i, group) in groups.iter().enumerate() {
+        let open = objects::open_sides(&groups, i);
+        if !open.contains(&true) {
+            continue;
+        }
+        let cells = group.iter().copied().collect();
+        let Ok(found) = apgcode::classify(cells, rule, ESCAPE_PERIOD) else { continue };
+        let (rows, cols) = found.displacement;
+        if objects::moving_away(open, (rows as i32, cols as i32)) {
+            for &(row, col) in group {
+                let idx = universe.get_index(row as u32, col as u32);
+                universe.cells.set(idx, false);
+            }
+            universe.recount();
+            escaped(found.code);
+        }
+    }
+}
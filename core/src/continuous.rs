@@ -0,0 +1,113 @@
+use crate::Error;
+
+// What Lenia and SmoothLife have in common: a torus of f32 cells between
+// 0 and 1, where every tick looks at weighted averages of the cells
+// around each cell. The renderer and anything else that only needs to
+// run and show such a universe can work with any of them through this.
+pub trait ContinuousEngine {
+    fn width(&self) -> u32;
+    fn height(&self) -> u32;
+    fn generation(&self) -> u64;
+
+    // Every cell's value, row by row.
+    fn cells(&self) -> &[f32];
+    fn cells_mut(&mut self) -> &mut [f32];
+
+    fn tick(&mut self);
+
+    fn tick_many(&mut self, n: u32) {
+        for _ in 0..n {
+            self.tick();
+        }
+    }
+
+    // The sum of all values, the continuous equivalent of the population.
+    fn mass(&self) -> f32 {
+        self.cells().iter().sum()
+    }
+
+    fn get_cell(&self, row: u32, col: u32) -> Result<f32, Error> {
+        Ok(self.cells()[index(self.width(), self.height(), row, col)?])
+    }
+
+    fn set_cell(&mut self, row: u32, col: u32, value: f32) -> Result<(), Error> {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(Error::new("cell values must be between 0 and 1"));
+        }
+        let idx = index(self.width(), self.height(), row, col)?;
+        self.cells_mut()[idx] = value;
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        self.cells_mut().iter_mut().for_each(|value| *value = 0.0);
+    }
+}
+
+fn index(width: u32, height: u32, row: u32, col: u32) -> Result<usize, Error> {
+    if row >= height || col >= width {
+        return Err(Error::new("cell is out of bounds"));
+    }
+    Ok((row * width + col) as usize)
+}
+
+// A kernel is the (row offset, column offset, weight) of every cell it
+// averages over, with the weights adding up to 1.
+pub type Kernel = Vec<(i32, i32, f32)>;
+
+// The kernel with weight(distance) around the middle, out to `radius`.
+pub fn kernel(radius: u32, weight: impl Fn(f32) -> f32) -> Kernel {
+    let radius = radius as i32;
+    let mut kernel = Vec::new();
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            let weight = weight(((dx * dx + dy * dy) as f32).sqrt());
+            if weight > 0.0 {
+                kernel.push((dy, dx, weight));
+            }
+        }
+    }
+    let total: f32 = kernel.iter().map(|&(_, _, weight)| weight).sum();
+    for (_, _, weight) in &mut kernel {
+        *weight /= total;
+    }
+    kernel
+}
+
+// A copy of the cells wrapped around with `radius` more cells on every
+// side, so that kernels never have to wrap while they are summed up.
+pub struct Padded {
+    values: Vec<f32>,
+    stride: usize,
+    radius: usize,
+}
+
+impl Padded {
+    pub fn new(cells: &[f32], width: u32, height: u32, radius: u32) -> Padded {
+        let (width, height, radius) = (width as usize, height as usize, radius as usize);
+        let stride = width + 2 * radius;
+        let mut values = vec![0.0; stride * (height + 2 * radius)];
+        for (y, padded_row) in values.chunks_exact_mut(stride).enumerate() {
+            let row = (y + height - radius % height) % height;
+            for (x, value) in padded_row.iter_mut().enumerate() {
+                *value = cells[row * width + (x + width - radius % width) % width];
+            }
+        }
+        Padded { values, stride, radius }
+    }
+
+    // The kernel's offsets from the top-left corner of a cell's window,
+    // for average().
+    pub fn offsets(&self, kernel: &Kernel) -> Vec<(usize, f32)> {
+        let radius = self.radius as i32;
+        kernel
+            .iter()
+            .map(|&(dy, dx, weight)| ((dy + radius) as usize * self.stride + (dx + radius) as usize, weight))
+            .collect()
+    }
+
+    pub fn average(&self, offsets: &[(usize, f32)], row: usize, col: usize) -> f32 {
+        let corner = row * self.stride + col;
+        offsets.iter().map(|&(offset, weight)| weight * self.values[corner + offset]).sum()
+    }
+}
@@ -0,0 +1,53 @@
+use crate::rule::LifeRule;
+use crate::Cell;
+
+// Rules written outside this crate. Implement next() and hand a boxed
+// rule to Universe::set_custom_rule(), and the universe runs it with
+// everything else working as usual: drawing, undo, statistics, cycle
+// detection and so on. Set the universe's own rule to one with enough
+// states first if next_state() uses more than two.
+//
+//     struct Replicator;
+//
+//     impl Rule for Replicator {
+//         fn next(&self, _cell: Cell, neighbors: Neighbors) -> Cell {
+//             if neighbors.count() % 2 == 1 { Cell::Alive } else { Cell::Dead }
+//         }
+//     }
+pub trait Rule {
+    fn next(&self, cell: Cell, neighbors: Neighbors) -> Cell;
+
+    // The same for rules with more than two states, where 0 is dead and
+    // 1 alive. By default, every other state counts as dead.
+    fn next_state(&self, state: u8, neighbors: Neighbors) -> u8 {
+        let cell = if state == 1 { Cell::Alive } else { Cell::Dead };
+        self.next(cell, neighbors) as u8
+    }
+}
+
+// Which of a cell's eight neighbors are alive, one bit each: NW N NE
+// W E SW S SE from the most significant bit down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Neighbors(pub u8);
+
+impl Neighbors {
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+}
+
+// The built-in rules work as custom ones too, e.g. to wrap them in
+// another rule.
+impl Rule for LifeRule {
+    fn next(&self, cell: Cell, neighbors: Neighbors) -> Cell {
+        if self.next_with(cell == Cell::Alive, neighbors.bits()) {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+
+use fixedbitset::FixedBitSet;
+use serde::Serialize;
+
+use crate::memory::deque_bytes;
+
+// How many consecutive generations are remembered, which is also the
+// longest period that can be detected.
+pub const WINDOW: usize = 1024;
+
+// A 64-bit FNV-1a over the cell words: good enough to tell generations
+// apart, and cheap compared with computing them.
+pub fn hash_cells(cells: &FixedBitSet) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    for &word in cells.as_slice() {
+        hash ^= u64::from(word);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+// The universe has been repeating itself every `period` generations
+// since generation `since`. A period of 1 is a still life; a universe
+// that died out is a still life too.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Cycle {
+    period: u32,
+    since: u64,
+}
+
+impl Cycle {
+    pub fn period(&self) -> u32 {
+        self.period
+    }
+
+    pub fn since(&self) -> u64 {
+        self.since
+    }
+}
+
+// Why Universe::run_until_stable stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum StopReason {
+    // Every cell is dead.
+    DiedOut,
+    // A still life or an oscillator; see the report's period.
+    Cycle,
+    // max_generations ran out first.
+    GenerationCap,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct StabilizationReport {
+    reason: StopReason,
+    generation: u64,
+    ticks: u32,
+    cycle: Option<Cycle>,
+}
+
+impl StabilizationReport {
+    pub fn new(reason: StopReason, generation: u64, ticks: u32, cycle: Option<Cycle>) -> StabilizationReport {
+        StabilizationReport {
+            reason,
+            generation,
+            ticks,
+            cycle,
+        }
+    }
+}
+
+impl StabilizationReport {
+    pub fn reason(&self) -> StopReason {
+        self.reason
+    }
+
+    // The generation the universe was left at.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    // How many generations the run itself ticked.
+    pub fn ticks(&self) -> u32 {
+        self.ticks
+    }
+
+    // Set when the reason is Cycle.
+    pub fn period(&self) -> Option<u32> {
+        self.cycle.map(|cycle| cycle.period)
+    }
+
+    pub fn since(&self) -> Option<u64> {
+        self.cycle.map(|cycle| cycle.since)
+    }
+}
+
+// Hashes of the last WINDOW generations, oldest first. Only an unbroken
+// run of ticks is kept: if the cells were edited between two ticks, the
+// state before the tick no longer matches the last one seen, and the
+// window starts over from there.
+#[derive(Clone, Debug, Default)]
+pub struct CycleDetector {
+    hashes: VecDeque<u64>,
+    // The generation of hashes[0].
+    first_generation: u64,
+}
+
+impl CycleDetector {
+    fn last_generation(&self) -> u64 {
+        self.first_generation + self.hashes.len() as u64 - 1
+    }
+
+    // Called after every step, with the hashes of the cells before and
+    // after it. Jumps of more than one generation restart the window.
+    pub fn observe(&mut self, before: u64, after: u64, generation_before: u64, generations: u64) {
+        let unbroken = generations == 1
+            && self.hashes.back() == Some(&before)
+            && self.last_generation() == generation_before;
+        if !unbroken {
+            self.hashes.clear();
+            self.first_generation = generation_before + generations;
+        }
+        if generations == 1 && self.hashes.is_empty() {
+            self.hashes.push_back(before);
+            self.first_generation = generation_before;
+        }
+        self.hashes.push_back(after);
+        if self.hashes.len() > WINDOW {
+            self.hashes.pop_front();
+            self.first_generation += 1;
+        }
+    }
+
+    // The hash of the newest generation seen, which is the current state
+    // as long as nothing was edited since.
+    pub fn latest(&self) -> Option<u64> {
+        self.hashes.back().copied()
+    }
+
+    // The shortest period of at most `max_period` with which the current
+    // state (hash `current` at `generation`) repeats an earlier one.
+    pub fn detect(&self, current: u64, generation: u64, max_period: u32) -> Option<Cycle> {
+        if self.hashes.back() != Some(&current) || self.last_generation() != generation {
+            return None;
+        }
+
+        let last = self.hashes.len() - 1;
+        let period = (1..=(max_period as usize).min(last)).find(|&p| self.hashes[last - p] == current)?;
+
+        // Walk back to the first generation that was already part of the cycle.
+        let mut start = last - period;
+        while start > 0 && self.hashes[start - 1] == self.hashes[start - 1 + period] {
+            start -= 1;
+        }
+        Some(Cycle {
+            period: period as u32,
+            since: self.first_generation + start as u64,
+        })
+    }
+
+    pub fn heap_bytes(&self) -> usize {
+        deque_bytes(&self.hashes)
+    }
+}
@@ -0,0 +1,162 @@
+use crate::random::Rng;
+use crate::{cell_count, Error, Neighborhood};
+
+// Cyclic cellular automata, a rock-paper-scissors game between `states`
+// colors: a cell in state s is eaten by state s + 1 (and the last state
+// by state 0) once at least `threshold` of its neighbors are in it. From
+// a random start, patches of color consume each other in turn until
+// spirals take over.
+//
+// Griffeath's "313" is 3 states and a threshold of 3 in the Moore
+// neighborhood; the original Fisch, Gravner and Griffeath demo is 14
+// states and a threshold of 1 with the four nearest neighbors. The
+// universe is a torus.
+#[derive(Clone, Debug)]
+pub struct CyclicUniverse {
+    width: u32,
+    height: u32,
+    states: u8,
+    threshold: u32,
+    neighborhood: Neighborhood,
+    // A state from 0 to states - 1 for every cell, row by row.
+    cells: Vec<u8>,
+    next: Vec<u8>,
+    generation: u64,
+}
+
+impl CyclicUniverse {
+    pub fn new(width: u32, height: u32, states: u8, threshold: u32) -> Result<CyclicUniverse, Error> {
+        if states < 2 {
+            return Err(Error::new("a cyclic automaton needs at least 2 states"));
+        }
+        let size = cell_count(&[width, height])?;
+        let mut universe = CyclicUniverse {
+            width,
+            height,
+            states,
+            threshold: 0,
+            neighborhood: Neighborhood::MOORE,
+            cells: vec![0; size],
+            next: vec![0; size],
+            generation: 0,
+        };
+        universe.set_threshold(threshold)?;
+        Ok(universe)
+    }
+
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    pub fn threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    pub fn set_threshold(&mut self, threshold: u32) -> Result<(), Error> {
+        if threshold == 0 || threshold > self.neighborhood.size() {
+            return Err(Error::new("the threshold must be between 1 and the number of neighbors"));
+        }
+        self.threshold = threshold;
+        Ok(())
+    }
+
+    pub fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+
+    pub fn set_neighborhood(&mut self, neighborhood: Neighborhood) -> Result<(), Error> {
+        if self.threshold > neighborhood.size() {
+            return Err(Error::new("the threshold must be between 1 and the number of neighbors"));
+        }
+        self.neighborhood = neighborhood;
+        Ok(())
+    }
+
+    pub fn tick(&mut self) {
+        let (width, height) = (i64::from(self.width), i64::from(self.height));
+        let radius = i64::from(self.neighborhood.radius());
+        let mut offsets = Vec::new();
+        for delta_row in -radius..=radius {
+            let reach = self.neighborhood.reach(delta_row);
+            for delta_col in -reach..=reach {
+                if (delta_row, delta_col) != (0, 0) {
+                    offsets.push((delta_row, delta_col));
+                }
+            }
+        }
+
+        let mut next = std::mem::take(&mut self.next);
+        for row in 0..height {
+            for col in 0..width {
+                let idx = (row * width + col) as usize;
+                let state = self.cells[idx];
+                let eater = (state + 1) % self.states;
+                let mut count = 0;
+                for &(delta_row, delta_col) in &offsets {
+                    let r = (row + delta_row).rem_euclid(height);
+                    let c = (col + delta_col).rem_euclid(width);
+                    if self.cells[(r * width + c) as usize] == eater {
+                        count += 1;
+                        if count == self.threshold {
+                            break;
+                        }
+                    }
+                }
+                next[idx] = if count == self.threshold { eater } else { state };
+            }
+        }
+        self.next = std::mem::replace(&mut self.cells, next);
+        self.generation += 1;
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        for _ in 0..n {
+            self.tick();
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    // One byte per cell, row by row.
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    fn index(&self, row: u32, col: u32) -> Result<usize, Error> {
+        if row >= self.height || col >= self.width {
+            return Err(Error::new("cell is out of bounds"));
+        }
+        Ok((row * self.width + col) as usize)
+    }
+
+    pub fn get_state(&self, row: u32, col: u32) -> Result<u8, Error> {
+        Ok(self.cells[self.index(row, col)?])
+    }
+
+    pub fn set_state(&mut self, row: u32, col: u32, state: u8) -> Result<(), Error> {
+        let idx = self.index(row, col)?;
+        if state >= self.states {
+            return Err(Error::new(&format!("states go from 0 to {}", self.states - 1)));
+        }
+        self.cells[idx] = state;
+        Ok(())
+    }
+
+    // Gives every cell a reproducible random state.
+    pub fn randomize(&mut self, seed: u64) {
+        let mut rng = Rng::new(seed);
+        for cell in self.cells.iter_mut() {
+            *cell = (rng.next_u64() % u64::from(self.states)) as u8;
+        }
+    }
+}
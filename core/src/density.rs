@@ -0,0 +1,63 @@
+use fixedbitset::FixedBitSet;
+
+use crate::memory::vec_bytes;
+
+// The side of the square blocks summed up at each level.
+pub const BLOCKS: [u32; 2] = [8, 64];
+
+// Live-cell counts per block, one grid of them for each entry of BLOCKS.
+// Blocks along the right and bottom edges can hang over the universe;
+// they only count the cells inside it.
+#[derive(Clone, Debug)]
+pub struct Density {
+    width: u32,
+    levels: Vec<Vec<u32>>,
+}
+
+impl Density {
+    pub fn new(cells: &FixedBitSet, width: u32, height: u32) -> Density {
+        let levels = BLOCKS
+            .iter()
+            .map(|&block| vec![0; (width.div_ceil(block) * height.div_ceil(block)) as usize])
+            .collect();
+        let mut density = Density { width, levels };
+        for idx in cells.ones() {
+            density.flip(idx, true);
+        }
+        density
+    }
+
+    // Counts one cell that was just born (or, with alive false, died).
+    pub fn flip(&mut self, idx: usize, alive: bool) {
+        let (row, col) = (idx as u32 / self.width, idx as u32 % self.width);
+        for (counts, &block) in self.levels.iter_mut().zip(BLOCKS.iter()) {
+            let i = (row / block * self.width.div_ceil(block) + col / block) as usize;
+            if alive {
+                counts[i] += 1;
+            } else {
+                counts[i] -= 1;
+            }
+        }
+    }
+
+    // Catches up with everything that changed between `previous` and `cells`,
+    // whether that took one generation or a thousand.
+    pub fn update(&mut self, previous: &FixedBitSet, cells: &FixedBitSet) {
+        for (word, (&old, &new)) in previous.as_slice().iter().zip(cells.as_slice()).enumerate() {
+            let mut diff = old ^ new;
+            while diff != 0 {
+                let bit = diff.trailing_zeros();
+                self.flip(word * 32 + bit as usize, new & (1 << bit) != 0);
+                diff &= diff - 1;
+            }
+        }
+    }
+
+    pub fn level(&self, level: usize) -> Option<&[u32]> {
+        self.levels.get(level).map(|counts| &counts[..])
+    }
+
+    pub fn heap_bytes(&self) -> usize {
+        vec_bytes(&self.levels) + self.levels.iter().map(vec_bytes).sum::<usize>()
+    }
+}
@@ -0,0 +1,47 @@
+use std::error;
+use std::fmt;
+
+use crate::rle::ParseRleError;
+use crate::rule::ParseRuleError;
+
+// Anything that can go wrong in a Universe call: a cell out of bounds,
+// a bad rule or pattern, a corrupt save. The message is meant for people;
+// the wasm crate turns it into a JavaScript Error as it is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    message: String,
+}
+
+impl Error {
+    pub fn new(message: &str) -> Error {
+        Error {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl error::Error for Error {}
+
+// For inspect_err() on the way out of a parser, so that patterns and
+// rules that didn't parse show up in the log along with why.
+pub(crate) fn parse_failed<E: fmt::Display>(what: &'static str) -> impl FnOnce(&E) {
+    move |err| log::warn!("could not parse {}: {}", what, err)
+}
+
+impl From<ParseRuleError> for Error {
+    fn from(err: ParseRuleError) -> Error {
+        Error::new(&err.to_string())
+    }
+}
+
+impl From<ParseRleError> for Error {
+    fn from(err: ParseRleError) -> Error {
+        Error::new(&err.to_string())
+    }
+}
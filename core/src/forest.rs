@@ -0,0 +1,203 @@
+use crate::random::Rng;
+use crate::stats::{PopulationHistory, TickStats};
+use crate::{cell_count, Error, NeighborhoodShape};
+
+// The Drossel-Schwabl forest-fire model. Every cell is empty, a tree or
+// on fire, and each tick:
+//
+// - a fire burns out, leaving the cell empty,
+// - a tree next to a fire catches fire,
+// - any other tree is struck by lightning with probability `lightning`,
+// - an empty cell grows a tree with probability `growth`.
+//
+// With growth much more likely than lightning, the forest fills up
+// until a strike sets off a fire that clears a whole region, and the
+// tree count, which is the population here, goes up and down in a saw
+// tooth. The universe is a torus, and a fire spreads to the four
+// nearest cells by default, or the eight around with Moore.
+#[derive(Clone, Debug)]
+pub struct ForestFire {
+    width: u32,
+    height: u32,
+    growth: f32,
+    lightning: f32,
+    shape: NeighborhoodShape,
+    // EMPTY, TREE or FIRE for every cell, row by row.
+    cells: Vec<u8>,
+    next: Vec<u8>,
+    rng: Rng,
+    generation: u64,
+    trees: u32,
+    last_step: TickStats,
+    populations: PopulationHistory,
+}
+
+pub const EMPTY: u8 = 0;
+pub const TREE: u8 = 1;
+pub const FIRE: u8 = 2;
+
+pub const DEFAULT_GROWTH: f32 = 0.01;
+pub const DEFAULT_LIGHTNING: f32 = 0.00001;
+
+impl ForestFire {
+    // An empty forest; trees start growing at the first tick.
+    pub fn new(width: u32, height: u32, seed: u64) -> Result<ForestFire, Error> {
+        let size = cell_count(&[width, height])?;
+        Ok(ForestFire {
+            width,
+            height,
+            growth: DEFAULT_GROWTH,
+            lightning: DEFAULT_LIGHTNING,
+            shape: NeighborhoodShape::VonNeumann,
+            cells: vec![EMPTY; size],
+            next: vec![EMPTY; size],
+            rng: Rng::new(seed),
+            generation: 0,
+            trees: 0,
+            last_step: TickStats::default(),
+            populations: PopulationHistory::default(),
+        })
+    }
+
+    pub fn growth(&self) -> f32 {
+        self.growth
+    }
+
+    pub fn lightning(&self) -> f32 {
+        self.lightning
+    }
+
+    pub fn set_probabilities(&mut self, growth: f32, lightning: f32) -> Result<(), Error> {
+        if !(0.0..=1.0).contains(&growth) || !(0.0..=1.0).contains(&lightning) {
+            return Err(Error::new("probabilities must be between 0 and 1"));
+        }
+        self.growth = growth;
+        self.lightning = lightning;
+        Ok(())
+    }
+
+    pub fn neighborhood_shape(&self) -> NeighborhoodShape {
+        self.shape
+    }
+
+    pub fn set_neighborhood_shape(&mut self, shape: NeighborhoodShape) {
+        self.shape = shape;
+    }
+
+    fn near_fire(&self, row: u32, col: u32) -> bool {
+        let (height, width) = (self.height as i64, self.width as i64);
+        let offsets: &[(i64, i64)] = match self.shape {
+            NeighborhoodShape::VonNeumann => &[(-1, 0), (0, -1), (0, 1), (1, 0)],
+            NeighborhoodShape::Moore => &[(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)],
+        };
+        offsets.iter().any(|&(delta_row, delta_col)| {
+            let r = (row as i64 + delta_row).rem_euclid(height);
+            let c = (col as i64 + delta_col).rem_euclid(width);
+            self.cells[(r * width + c) as usize] == FIRE
+        })
+    }
+
+    pub fn tick(&mut self) {
+        let mut next = std::mem::take(&mut self.next);
+        let (mut grown, mut burnt) = (0, 0);
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let idx = (row * self.width + col) as usize;
+                next[idx] = match self.cells[idx] {
+                    FIRE => EMPTY,
+                    TREE if self.near_fire(row, col) || self.rng.next_f32() < self.lightning => {
+                        burnt += 1;
+                        FIRE
+                    }
+                    TREE => TREE,
+                    _ if self.rng.next_f32() < self.growth => {
+                        grown += 1;
+                        TREE
+                    }
+                    _ => EMPTY,
+                };
+            }
+        }
+        self.next = std::mem::replace(&mut self.cells, next);
+        self.trees = self.trees + grown - burnt;
+        self.generation += 1;
+        self.last_step = TickStats::new(grown, burnt, self.trees);
+        self.populations.push(self.trees);
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        for _ in 0..n {
+            self.tick();
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    // The number of trees.
+    pub fn population(&self) -> u32 {
+        self.trees
+    }
+
+    pub fn fires(&self) -> u32 {
+        self.cells.iter().filter(|&&cell| cell == FIRE).count() as u32
+    }
+
+    // Trees grown and burnt by the last tick, as births and deaths.
+    pub fn tick_stats(&self) -> TickStats {
+        self.last_step
+    }
+
+    pub fn population_history(&self, n: u32) -> Vec<u32> {
+        self.populations.last(n as usize)
+    }
+
+    // One byte per cell, row by row: EMPTY, TREE or FIRE.
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    fn index(&self, row: u32, col: u32) -> Result<usize, Error> {
+        if row >= self.height || col >= self.width {
+            return Err(Error::new("cell is out of bounds"));
+        }
+        Ok((row * self.width + col) as usize)
+    }
+
+    pub fn get_state(&self, row: u32, col: u32) -> Result<u8, Error> {
+        Ok(self.cells[self.index(row, col)?])
+    }
+
+    // Plants a tree, lights a fire or clears a cell.
+    pub fn set_state(&mut self, row: u32, col: u32, state: u8) -> Result<(), Error> {
+        let idx = self.index(row, col)?;
+        if state > FIRE {
+            return Err(Error::new("cells are empty (0), trees (1) or on fire (2)"));
+        }
+        self.trees = self.trees - (self.cells[idx] == TREE) as u32 + (state == TREE) as u32;
+        self.cells[idx] = state;
+        Ok(())
+    }
+
+    // Replaces everything with a forest where each cell holds a tree with
+    // probability `density`, drawn from the forest's own dice.
+    pub fn plant(&mut self, density: f32) -> Result<(), Error> {
+        if !(0.0..=1.0).contains(&density) {
+            return Err(Error::new("density must be between 0 and 1"));
+        }
+        for cell in self.cells.iter_mut() {
+            *cell = if self.rng.next_f32() < density { TREE } else { EMPTY };
+        }
+        self.trees = self.cells.iter().filter(|&&cell| cell == TREE).count() as u32;
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use crate::rle::{self, RlePattern};
+use crate::{gzip, lif, macrocell, plaintext, Error};
+
+// The pattern file formats the universe can read and write.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatternFormat {
+    Rle,
+    Plaintext,
+    Life105,
+    Life106,
+}
+
+impl PatternFormat {
+    // Guesses the format of a pattern someone pasted in, from its header
+    // or, failing that, from what its first lines look like.
+    pub fn detect(text: &str) -> Option<PatternFormat> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        let first = lines.clone().next()?;
+        if first.starts_with("#Life 1.06") {
+            return Some(PatternFormat::Life106);
+        }
+        if first.starts_with("#Life 1.05") {
+            return Some(PatternFormat::Life105);
+        }
+        if first.starts_with('!') {
+            return Some(PatternFormat::Plaintext);
+        }
+        // RLE may have #N, #C and the like before its x = header.
+        let body = lines.find(|line| !line.starts_with('#'))?;
+        let is_number = |word: &str| word.parse::<i64>().is_ok();
+        if body.starts_with('x') && body.contains('=') {
+            Some(PatternFormat::Rle)
+        } else if body.chars().all(|c| matches!(c, '.' | 'O' | '*')) {
+            Some(PatternFormat::Plaintext)
+        } else if body.contains('$') || body.ends_with('!') {
+            // RLE without its header, like LifeWiki sometimes shows.
+            Some(PatternFormat::Rle)
+        } else if body.split_whitespace().count() == 2 && body.split_whitespace().all(is_number) {
+            Some(PatternFormat::Life106)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn parse(text: &str, format: PatternFormat) -> Result<RlePattern, Error> {
+    match format {
+        PatternFormat::Rle => Ok(rle::parse(text)?),
+        PatternFormat::Plaintext => plaintext::parse(text),
+        PatternFormat::Life105 => lif::parse_105(text),
+        PatternFormat::Life106 => lif::parse_106(text),
+    }
+}
+
+// Only the grid formats, plaintext and Life 1.05, can fail: they take a
+// byte for every dead cell up to the last live one in a row.
+pub fn write(pattern: &RlePattern, format: PatternFormat) -> Result<String, Error> {
+    match format {
+        PatternFormat::Rle => Ok(rle::write(pattern)),
+        PatternFormat::Plaintext => plaintext::write(pattern),
+        PatternFormat::Life105 => lif::write_105(pattern),
+        PatternFormat::Life106 => Ok(lif::write_106(pattern)),
+    }
+}
+
+// Reads a pattern in whatever format it is in.
+pub fn parse_any(text: &str) -> Result<RlePattern, Error> {
+    let format = PatternFormat::detect(text)
+        .ok_or_else(|| Error::new("not a pattern in any known format: RLE, plaintext, Life 1.05 or Life 1.06"))?;
+    parse(text, format)
+}
+
+// Reads a pattern file as it came off the disk: any of the formats
+// above or Macrocell, gzipped or not.
+pub fn parse_file(bytes: &[u8]) -> Result<RlePattern, Error> {
+    let inflated;
+    let bytes = if gzip::is_gzip(bytes) {
+        inflated = gzip::decompress(bytes).map_err(|e| Error::new(&e))?;
+        &inflated[..]
+    } else {
+        bytes
+    };
+    let text = std::str::from_utf8(bytes).map_err(|_| Error::new("the pattern file is not text"))?;
+    if text.trim_start().starts_with("[M2]") {
+        return macrocell::flatten(&macrocell::parse(text)?);
+    }
+    parse_any(text)
+}
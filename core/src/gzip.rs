@@ -0,0 +1,46 @@
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+
+// Pattern collections are often handed out gzipped (.rle.gz, .mc.gz).
+// A gzip file is a small header, raw deflate data and a checksum; the
+// data goes through the same inflater as share strings.
+
+// Refuse to inflate more than this, so a hostile file cannot make the
+// page allocate gigabytes.
+const MAX_INFLATED: usize = 64 << 20;
+
+const FHCRC: u8 = 1 << 1;
+const FEXTRA: u8 = 1 << 2;
+const FNAME: u8 = 1 << 3;
+const FCOMMENT: u8 = 1 << 4;
+
+pub fn is_gzip(bytes: &[u8]) -> bool {
+    bytes.starts_with(&[0x1f, 0x8b])
+}
+
+pub fn decompress(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let truncated = || "gzip file is truncated".to_string();
+    if !is_gzip(bytes) || bytes.len() < 10 {
+        return Err("not a gzip file".to_string());
+    }
+    if bytes[2] != 8 {
+        return Err("gzip file is not deflated".to_string());
+    }
+    let flags = bytes[3];
+    let mut start = 10;
+    if flags & FEXTRA != 0 {
+        let extra = bytes.get(start..start + 2).ok_or_else(truncated)?;
+        start += 2 + usize::from(u16::from_le_bytes([extra[0], extra[1]]));
+    }
+    // The file name and the comment are zero-terminated.
+    for flag in [FNAME, FCOMMENT].iter() {
+        if flags & flag != 0 {
+            let end = bytes.get(start..).ok_or_else(truncated)?.iter().position(|&b| b == 0).ok_or_else(truncated)?;
+            start += end + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        start += 2;
+    }
+    let data = bytes.get(start..).ok_or_else(truncated)?;
+    decompress_to_vec_with_limit(data, MAX_INFLATED).map_err(|e| format!("gzip file is corrupt: {:?}", e.status))
+}
@@ -0,0 +1,574 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+
+use fixedbitset::FixedBitSet;
+
+use crate::macrocell::MacroNode;
+use crate::memory::{bitset_bytes, map_bytes, vec_bytes};
+use crate::rule::LifeRule;
+
+// Hashlife: the pattern is stored as a quadtree in which identical
+// sub-squares are shared ("hash consing"), and the result of running
+// each sub-square forward is memoized. Large, regular patterns such as
+// guns and breeders reuse the same few nodes over and over, so the engine
+// can jump ahead by huge powers of two without visiting every cell.
+//
+// The tree lives on an unbounded plane. The universe's width x height grid
+// is a window onto it, with its top-left corner at plane coordinate (0, 0).
+// Cells that leave the window are not wrapped around; they keep evolving
+// outside of it.
+
+type NodeId = u32;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+// Past this many nodes the memo tables are thrown away and only the nodes
+// reachable from the root are kept.
+const MAX_NODES: usize = 1 << 20;
+
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    level: u8,
+    nw: NodeId,
+    ne: NodeId,
+    sw: NodeId,
+    se: NodeId,
+    population: u64,
+}
+
+// The keys are small integers, so a multiply-and-rotate hash is plenty
+// and a lot cheaper than the default SipHash.
+#[derive(Default)]
+struct IdHasher(u64);
+
+impl Hasher for IdHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.write_u64(u64::from(b));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.write_u64(u64::from(i));
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(u64::from(i));
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.0 = (self.0.rotate_left(5) ^ i).wrapping_mul(0x51_7C_C1_B7_27_22_0A_95);
+    }
+}
+
+type FastMap<K, V> = HashMap<K, V, BuildHasherDefault<IdHasher>>;
+
+pub struct Hashlife {
+    rule: LifeRule,
+    nodes: Vec<Node>,
+    join_cache: FastMap<(NodeId, NodeId, NodeId, NodeId), NodeId>,
+    step_cache: FastMap<(NodeId, u8), NodeId>,
+    empty: Vec<NodeId>,
+    root: NodeId,
+    // Plane coordinates of the root's top-left corner.
+    origin_x: i64,
+    origin_y: i64,
+    // What the window looked like the last time it was loaded or rendered.
+    // Diffing against it tells us which cells were edited in between.
+    window: FixedBitSet,
+}
+
+impl Hashlife {
+    pub fn new(rule: LifeRule, cells: &FixedBitSet, width: u32, height: u32) -> Hashlife {
+        let leaf = |population| Node {
+            level: 0,
+            nw: DEAD,
+            ne: DEAD,
+            sw: DEAD,
+            se: DEAD,
+            population,
+        };
+        let mut life = Hashlife {
+            rule,
+            nodes: vec![leaf(0), leaf(1)],
+            join_cache: FastMap::default(),
+            step_cache: FastMap::default(),
+            empty: vec![DEAD],
+            root: DEAD,
+            origin_x: 0,
+            origin_y: 0,
+            window: FixedBitSet::with_capacity(0),
+        };
+        life.load(cells, width, height);
+        life
+    }
+
+    // Replaces the whole plane with the contents of the window.
+    pub fn load(&mut self, cells: &FixedBitSet, width: u32, height: u32) {
+        let mut level = 1;
+        while (1u64 << level) < u64::from(width.max(height)) {
+            level += 1;
+        }
+        self.root = self.build(level, 0, 0, cells, width, height);
+        self.origin_x = 0;
+        self.origin_y = 0;
+        self.window = cells.clone();
+    }
+
+    // Applies whatever was edited in the window since it was last
+    // loaded or rendered.
+    pub fn sync(&mut self, cells: &FixedBitSet, width: u32) {
+        let edited: Vec<(usize, bool)> = self
+            .window
+            .as_slice()
+            .iter()
+            .zip(cells.as_slice())
+            .enumerate()
+            .flat_map(|(word, (&old, &new))| {
+                let mut diff = old ^ new;
+                let mut changes = Vec::new();
+                while diff != 0 {
+                    let bit = diff.trailing_zeros() as usize;
+                    let idx = word * 32 + bit;
+                    changes.push((idx, new & (1 << bit) != 0));
+                    diff &= diff - 1;
+                }
+                changes
+            })
+            .collect();
+
+        for (idx, alive) in edited {
+            let x = (idx as u32 % width) as i64;
+            let y = (idx as u32 / width) as i64;
+            self.set(x, y, alive);
+        }
+        self.window = cells.clone();
+    }
+
+    // Runs the pattern forward, one power of two at a time.
+    pub fn advance(&mut self, mut generations: u64) {
+        let mut j = 0;
+        while generations > 0 {
+            if generations & 1 == 1 {
+                self.step_pow2(j);
+            }
+            generations >>= 1;
+            j += 1;
+        }
+    }
+
+    // Writes the window's cells into `out`, which must hold width * height bits.
+    pub fn render(&mut self, width: u32, height: u32, out: &mut FixedBitSet) {
+        out.clear();
+        let root = self.root;
+        let (x, y) = (self.origin_x, self.origin_y);
+        self.render_node(root, x, y, width, height, out);
+        if self.window.len() == out.len() {
+            self.window.as_mut_slice().copy_from_slice(out.as_slice());
+        } else {
+            self.window = out.clone();
+        }
+    }
+
+    // -- Macrocell --------------------------------------------------------
+
+    // Replaces the plane with a tree read from a Macrocell file. Golly puts
+    // the origin at the center of the tree, and so does this, at the
+    // middle of the window.
+    pub fn load_macrocell(&mut self, nodes: &[MacroNode], width: u32, height: u32) {
+        let mut ids = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            let id = match node {
+                MacroNode::Leaf(rows) => self.leaf(rows, 3, 0, 0),
+                MacroNode::Inner(level, quarters) => {
+                    let mut children = [DEAD; 4];
+                    for (child, &quarter) in children.iter_mut().zip(quarters) {
+                        *child = match quarter {
+                            0 => self.empty(level - 1),
+                            _ => ids[quarter - 1],
+                        };
+                    }
+                    let [nw, ne, sw, se] = children;
+                    self.join(nw, ne, sw, se)
+                }
+            };
+            ids.push(id);
+        }
+        self.root = *ids.last().expect("a Macrocell tree has at least one node");
+        let half = 1i64 << (self.nodes[self.root as usize].level - 1);
+        self.origin_x = i64::from(width / 2) - half;
+        self.origin_y = i64::from(height / 2) - half;
+    }
+
+    // The whole plane as Macrocell nodes, every shared square only once.
+    // The tree is cut down to the smallest square around the live cells
+    // first, which is then what the file is centered on.
+    pub fn macrocell_nodes(&mut self) -> Vec<MacroNode> {
+        let mut top = self.root;
+        loop {
+            let n = self.nodes[top as usize];
+            if n.level <= 3 {
+                break;
+            }
+            let center = self.center(top);
+            let quarters = [n.nw, n.ne, n.sw, n.se, center];
+            match quarters.iter().find(|&&q| self.nodes[q as usize].population == n.population) {
+                Some(&inner) => top = inner,
+                None => break,
+            }
+        }
+
+        let root = self.nodes[top as usize];
+        let mut nodes = Vec::new();
+        if root.level < 3 {
+            // Too small for a leaf: put it in the middle of one.
+            let offset = (8 - (1 << root.level)) / 2;
+            nodes.push(MacroNode::Leaf(self.leaf_rows(top, offset)));
+        } else {
+            let mut written = FastMap::default();
+            self.write_node(top, top, &mut written, &mut nodes);
+        }
+        nodes
+    }
+
+    // Builds the level `level` square at (x, y) of an 8x8 leaf.
+    fn leaf(&mut self, rows: &[u8; 8], level: u8, x: u32, y: u32) -> NodeId {
+        if level == 0 {
+            return if rows[y as usize] & (1 << x) != 0 { ALIVE } else { DEAD };
+        }
+        let half = 1 << (level - 1);
+        let nw = self.leaf(rows, level - 1, x, y);
+        let ne = self.leaf(rows, level - 1, x + half, y);
+        let sw = self.leaf(rows, level - 1, x, y + half);
+        let se = self.leaf(rows, level - 1, x + half, y + half);
+        self.join(nw, ne, sw, se)
+    }
+
+    fn leaf_rows(&self, node: NodeId, offset: u32) -> [u8; 8] {
+        let mut out = FixedBitSet::with_capacity(64);
+        let offset = i64::from(offset);
+        self.render_node(node, offset, offset, 8, 8, &mut out);
+        let mut rows = [0; 8];
+        for idx in out.ones() {
+            rows[idx / 8] |= 1 << (idx % 8);
+        }
+        rows
+    }
+
+    // Writes the quarters first, so that every node only refers to
+    // earlier ones, and returns the node's number in the file.
+    fn write_node(&self, top: NodeId, node: NodeId, written: &mut FastMap<NodeId, usize>, nodes: &mut Vec<MacroNode>) -> usize {
+        let n = self.nodes[node as usize];
+        if n.population == 0 && node != top {
+            return 0;
+        }
+        if let Some(&number) = written.get(&node) {
+            return number;
+        }
+        let macro_node = if n.level == 3 {
+            MacroNode::Leaf(self.leaf_rows(node, 0))
+        } else {
+            let mut quarters = [0; 4];
+            for (quarter, &child) in quarters.iter_mut().zip(&[n.nw, n.ne, n.sw, n.se]) {
+                *quarter = self.write_node(top, child, written, nodes);
+            }
+            MacroNode::Inner(n.level, quarters)
+        };
+        nodes.push(macro_node);
+        written.insert(node, nodes.len());
+        nodes.len()
+    }
+
+    // -- Tree construction ------------------------------------------------
+
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        if let Some(&id) = self.join_cache.get(&(nw, ne, sw, se)) {
+            return id;
+        }
+        let n = &self.nodes;
+        let node = Node {
+            level: n[nw as usize].level + 1,
+            nw,
+            ne,
+            sw,
+            se,
+            population: n[nw as usize].population
+                + n[ne as usize].population
+                + n[sw as usize].population
+                + n[se as usize].population,
+        };
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(node);
+        self.join_cache.insert((nw, ne, sw, se), id);
+        id
+    }
+
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let e = *self.empty.last().unwrap();
+            let id = self.join(e, e, e, e);
+            self.empty.push(id);
+        }
+        self.empty[level as usize]
+    }
+
+    fn build(&mut self, level: u8, x: u32, y: u32, cells: &FixedBitSet, width: u32, height: u32) -> NodeId {
+        if x >= width || y >= height {
+            return self.empty(level);
+        }
+        if level == 0 {
+            return if cells[(y * width + x) as usize] { ALIVE } else { DEAD };
+        }
+        let half = 1 << (level - 1);
+        let nw = self.build(level - 1, x, y, cells, width, height);
+        let ne = self.build(level - 1, x + half, y, cells, width, height);
+        let sw = self.build(level - 1, x, y + half, cells, width, height);
+        let se = self.build(level - 1, x + half, y + half, cells, width, height);
+        self.join(nw, ne, sw, se)
+    }
+
+    // Sets a single cell, growing the tree if the cell lies outside it.
+    fn set(&mut self, x: i64, y: i64, alive: bool) {
+        loop {
+            let size = 1i64 << self.nodes[self.root as usize].level;
+            let inside = (self.origin_x..self.origin_x + size).contains(&x)
+                && (self.origin_y..self.origin_y + size).contains(&y);
+            if inside {
+                break;
+            }
+            self.expand();
+        }
+        let root = self.root;
+        self.root = self.set_in(root, x - self.origin_x, y - self.origin_y, alive);
+    }
+
+    fn set_in(&mut self, node: NodeId, x: i64, y: i64, alive: bool) -> NodeId {
+        let n = self.nodes[node as usize];
+        if n.level == 0 {
+            return if alive { ALIVE } else { DEAD };
+        }
+        let half = 1i64 << (n.level - 1);
+        let (mut nw, mut ne, mut sw, mut se) = (n.nw, n.ne, n.sw, n.se);
+        match (x < half, y < half) {
+            (true, true) => nw = self.set_in(nw, x, y, alive),
+            (false, true) => ne = self.set_in(ne, x - half, y, alive),
+            (true, false) => sw = self.set_in(sw, x, y - half, alive),
+            (false, false) => se = self.set_in(se, x - half, y - half, alive),
+        }
+        self.join(nw, ne, sw, se)
+    }
+
+    // Doubles the tree's size, keeping the current root in the middle.
+    fn expand(&mut self) {
+        let root = self.nodes[self.root as usize];
+        let e = self.empty(root.level - 1);
+        let nw = self.join(e, e, e, root.nw);
+        let ne = self.join(e, e, root.ne, e);
+        let sw = self.join(e, root.sw, e, e);
+        let se = self.join(root.se, e, e, e);
+        self.root = self.join(nw, ne, sw, se);
+        let quarter = 1i64 << (root.level - 1);
+        self.origin_x -= quarter;
+        self.origin_y -= quarter;
+    }
+
+    // The node of half the size sitting in the middle of `node`.
+    fn center(&mut self, node: NodeId) -> NodeId {
+        let n = self.nodes[node as usize];
+        let nw = self.nodes[n.nw as usize].se;
+        let ne = self.nodes[n.ne as usize].sw;
+        let sw = self.nodes[n.sw as usize].ne;
+        let se = self.nodes[n.se as usize].nw;
+        self.join(nw, ne, sw, se)
+    }
+
+    // -- Stepping ---------------------------------------------------------
+
+    fn step_pow2(&mut self, j: u8) {
+        if self.nodes.len() > MAX_NODES {
+            self.collect_garbage();
+        }
+
+        // To advance by 2^j generations we need a root of level j + 3 whose
+        // live cells all sit in the central quarter. The result is the
+        // middle half of the root, and at light speed nothing can escape it.
+        loop {
+            let root = self.nodes[self.root as usize];
+            if root.level >= j + 3 {
+                let c = self.center(self.root);
+                let cc = self.center(c);
+                if self.nodes[cc as usize].population == root.population {
+                    break;
+                }
+            }
+            self.expand();
+        }
+
+        let level = self.nodes[self.root as usize].level;
+        let root = self.root;
+        self.root = self.successor(root, j);
+        let quarter = 1i64 << (level - 2);
+        self.origin_x += quarter;
+        self.origin_y += quarter;
+
+        // Shrink back down so the tree doesn't keep growing around
+        // an empty border.
+        loop {
+            let root = self.nodes[self.root as usize];
+            if root.level <= 3 {
+                break;
+            }
+            let c = self.center(self.root);
+            if self.nodes[c as usize].population != root.population {
+                break;
+            }
+            self.root = c;
+            let quarter = 1i64 << (root.level - 2);
+            self.origin_x += quarter;
+            self.origin_y += quarter;
+        }
+    }
+
+    // The centered half-size node of `node`, 2^j generations later.
+    // Requires j <= level - 2.
+    fn successor(&mut self, node: NodeId, j: u8) -> NodeId {
+        let n = self.nodes[node as usize];
+        if n.population == 0 {
+            return self.empty(n.level - 1);
+        }
+        if let Some(&id) = self.step_cache.get(&(node, j)) {
+            return id;
+        }
+
+        let result = if n.level == 2 {
+            self.base_case(node)
+        } else {
+            let [nw, ne, sw, se] = [n.nw, n.ne, n.sw, n.se].map(|c| self.nodes[c as usize]);
+
+            // The nine overlapping sub-squares, each half the size of `node`.
+            let n00 = n.nw;
+            let n01 = self.join(nw.ne, ne.nw, nw.se, ne.sw);
+            let n02 = n.ne;
+            let n10 = self.join(nw.sw, nw.se, sw.nw, sw.ne);
+            let n11 = self.join(nw.se, ne.sw, sw.ne, se.nw);
+            let n12 = self.join(ne.sw, ne.se, se.nw, se.ne);
+            let n20 = n.sw;
+            let n21 = self.join(sw.ne, se.nw, sw.se, se.sw);
+            let n22 = n.se;
+            let squares = [n00, n01, n02, n10, n11, n12, n20, n21, n22];
+
+            // At full speed both halves of the recursion advance time;
+            // otherwise the first half only re-centers.
+            let full_speed = j == n.level - 2;
+            let mut first = [DEAD; 9];
+            for (out, &square) in first.iter_mut().zip(squares.iter()) {
+                *out = if full_speed {
+                    self.successor(square, j - 1)
+                } else {
+                    self.center(square)
+                };
+            }
+            let second_j = if full_speed { j - 1 } else { j };
+
+            let [c00, c01, c02, c10, c11, c12, c20, c21, c22] = first;
+            let a = self.join(c00, c01, c10, c11);
+            let b = self.join(c01, c02, c11, c12);
+            let c = self.join(c10, c11, c20, c21);
+            let d = self.join(c11, c12, c21, c22);
+            let a = self.successor(a, second_j);
+            let b = self.successor(b, second_j);
+            let c = self.successor(c, second_j);
+            let d = self.successor(d, second_j);
+            self.join(a, b, c, d)
+        };
+
+        self.step_cache.insert((node, j), result);
+        result
+    }
+
+    // A 4x4 square: run the rule once on its middle 2x2.
+    fn base_case(&mut self, node: NodeId) -> NodeId {
+        let mut grid = [[false; 4]; 4];
+        let n = self.nodes[node as usize];
+        for (qy, qx, child) in [(0, 0, n.nw), (0, 2, n.ne), (2, 0, n.sw), (2, 2, n.se)].iter().cloned() {
+            let c = self.nodes[child as usize];
+            grid[qy][qx] = c.nw == ALIVE;
+            grid[qy][qx + 1] = c.ne == ALIVE;
+            grid[qy + 1][qx] = c.sw == ALIVE;
+            grid[qy + 1][qx + 1] = c.se == ALIVE;
+        }
+
+        let mut next = [DEAD; 4];
+        for (i, &(y, x)) in [(1, 1), (1, 2), (2, 1), (2, 2)].iter().enumerate() {
+            let mut count = 0;
+            for dy in 0..3 {
+                for dx in 0..3 {
+                    if (dy, dx) != (1, 1) && grid[y + dy - 1][x + dx - 1] {
+                        count += 1;
+                    }
+                }
+            }
+            if self.rule.next(grid[y][x], count) {
+                next[i] = ALIVE;
+            }
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    // -- Reading the window back out --------------------------------------
+
+    fn render_node(&self, node: NodeId, x: i64, y: i64, width: u32, height: u32, out: &mut FixedBitSet) {
+        let n = self.nodes[node as usize];
+        let size = 1i64 << n.level;
+        let outside = x >= i64::from(width) || y >= i64::from(height) || x + size <= 0 || y + size <= 0;
+        if n.population == 0 || outside {
+            return;
+        }
+        if n.level == 0 {
+            out.insert((y as u32 * width + x as u32) as usize);
+            return;
+        }
+        let half = size / 2;
+        self.render_node(n.nw, x, y, width, height, out);
+        self.render_node(n.ne, x + half, y, width, height, out);
+        self.render_node(n.sw, x, y + half, width, height, out);
+        self.render_node(n.se, x + half, y + half, width, height, out);
+    }
+
+    // Rebuilds the node table with only what the current root still uses.
+    fn collect_garbage(&mut self) {
+        let old_nodes = std::mem::take(&mut self.nodes);
+        self.nodes = old_nodes[..2].to_vec();
+        self.join_cache.clear();
+        self.step_cache.clear();
+        self.empty = vec![DEAD];
+
+        let mut remap = FastMap::default();
+        remap.insert(DEAD, DEAD);
+        remap.insert(ALIVE, ALIVE);
+        self.root = self.copy_node(&old_nodes, self.root, &mut remap);
+    }
+
+    fn copy_node(&mut self, old: &[Node], node: NodeId, remap: &mut FastMap<NodeId, NodeId>) -> NodeId {
+        if let Some(&id) = remap.get(&node) {
+            return id;
+        }
+        let n = old[node as usize];
+        let nw = self.copy_node(old, n.nw, remap);
+        let ne = self.copy_node(old, n.ne, remap);
+        let sw = self.copy_node(old, n.sw, remap);
+        let se = self.copy_node(old, n.se, remap);
+        let id = self.join(nw, ne, sw, se);
+        remap.insert(node, id);
+        id
+    }
+
+    pub fn heap_bytes(&self) -> usize {
+        vec_bytes(&self.nodes) + map_bytes(&self.join_cache) + map_bytes(&self.step_cache) + vec_bytes(&self.empty) + bitset_bytes(&self.window)
+    }
+}
@@ -0,0 +1,251 @@
+use base64::alphabet::STANDARD;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::Engine as _;
+
+// Non-totalistic rules look at which neighbors are alive, not just how
+// many. A neighborhood is a byte with one bit per neighbor, from the
+// most significant bit down:
+//
+//     NW N NE       7 6 5
+//     W  .  E       4 . 3
+//     SW S SE       2 1 0
+//
+// and a rule part (birth or survival) is the set of neighborhoods that
+// trigger it: bit n of the 256-bit set for neighborhood n.
+pub type Neighborhoods = [u64; 4];
+
+// Hensel notation names every neighborhood up to rotation and
+// reflection with a neighbor count and a letter, e.g. "2a" for two
+// neighbors next to each other. These are one neighborhood of each kind
+// for counts 1 to 4; counts 5 to 7 use the same letters for the
+// complements of 3 to 1, so that "6i" is "2i" with dead and live swapped.
+const LETTERS: [&[(char, u8)]; 5] = [
+    &[],
+    &[('c', 0x01), ('e', 0x02)],
+    &[('c', 0x05), ('e', 0x0a), ('k', 0x0c), ('a', 0x03), ('i', 0x18), ('n', 0x24)],
+    &[
+        ('c', 0x25),
+        ('e', 0x1a),
+        ('k', 0x32),
+        ('a', 0x0b),
+        ('i', 0x07),
+        ('n', 0x0d),
+        ('y', 0x31),
+        ('q', 0x26),
+        ('j', 0x0e),
+        ('r', 0x19),
+    ],
+    &[
+        ('c', 0xa5),
+        ('e', 0x5a),
+        ('k', 0x33),
+        ('a', 0x0f),
+        ('i', 0x1d),
+        ('n', 0x27),
+        ('y', 0x35),
+        ('q', 0x36),
+        ('j', 0x3a),
+        ('r', 0x1b),
+        ('t', 0x39),
+        ('w', 0x2e),
+        ('z', 0x3c),
+    ],
+];
+
+// Where each bit of a neighborhood goes when it is turned a quarter
+// clockwise, and when it is mirrored left to right.
+const ROTATE: [u8; 8] = [2, 4, 7, 1, 6, 0, 3, 5];
+const MIRROR: [u8; 8] = [2, 1, 0, 4, 3, 7, 6, 5];
+
+fn permute(neighborhood: u8, to: &[u8; 8]) -> u8 {
+    (0..8).filter(|&bit| neighborhood & (1 << bit) != 0).fold(0, |out, bit| out | 1 << to[bit])
+}
+
+fn insert(set: &mut Neighborhoods, neighborhood: u8) {
+    set[usize::from(neighborhood / 64)] |= 1 << (neighborhood % 64);
+}
+
+pub fn contains(set: &Neighborhoods, neighborhood: u8) -> bool {
+    set[usize::from(neighborhood / 64)] & (1 << (neighborhood % 64)) != 0
+}
+
+// Every rotation and reflection of one neighborhood.
+fn orbit(neighborhood: u8) -> Neighborhoods {
+    let mut set = [0; 4];
+    let mut turned = neighborhood;
+    for _ in 0..4 {
+        insert(&mut set, turned);
+        insert(&mut set, permute(turned, &MIRROR));
+        turned = permute(turned, &ROTATE);
+    }
+    set
+}
+
+fn letters(count: u8) -> &'static [(char, u8)] {
+    LETTERS[usize::from(count.min(8 - count))]
+}
+
+// The neighborhoods a count and letter stand for.
+fn named(count: u8, letter: char) -> Option<Neighborhoods> {
+    let &(_, neighborhood) = letters(count).iter().find(|&&(l, _)| l == letter)?;
+    Some(if count > 4 { orbit(!neighborhood) } else { orbit(neighborhood) })
+}
+
+fn with_count(count: u8) -> Neighborhoods {
+    let mut set = [0; 4];
+    for neighborhood in 0..=255u8 {
+        if neighborhood.count_ones() == u32::from(count) {
+            insert(&mut set, neighborhood);
+        }
+    }
+    set
+}
+
+fn union(set: &mut Neighborhoods, other: &Neighborhoods) {
+    for (word, other) in set.iter_mut().zip(other) {
+        *word |= other;
+    }
+}
+
+fn is_subset(set: &Neighborhoods, of: &Neighborhoods) -> bool {
+    set.iter().zip(of).all(|(word, of)| word & !of == 0)
+}
+
+// Parses one part of a rule without its B or S, e.g. "2-a3" or "12ce":
+// each count stands for all its neighborhoods, unless it is followed by
+// letters picking some of them, or by a minus and letters leaving some out.
+pub fn parse_part(part: &str) -> Option<Neighborhoods> {
+    let mut set = [0; 4];
+    let mut chars = part.chars().peekable();
+    while let Some(digit) = chars.next() {
+        let count = digit.to_digit(10).filter(|&n| n <= 8)? as u8;
+        let minus = chars.peek() == Some(&'-');
+        if minus {
+            chars.next();
+        }
+        let mut picked = [0; 4];
+        let mut any = false;
+        while let Some(&letter) = chars.peek().filter(|c| c.is_ascii_lowercase()) {
+            union(&mut picked, &named(count, letter)?);
+            any = true;
+            chars.next();
+        }
+        if minus && !any {
+            return None;
+        }
+        if !any || minus {
+            let mut all = with_count(count);
+            for (word, picked) in all.iter_mut().zip(&picked) {
+                *word &= !picked;
+            }
+            picked = all;
+        }
+        union(&mut set, &picked);
+    }
+    Some(set)
+}
+
+// The neighbor counts of a part that only goes by counts, as a bitmask
+// like LifeRule's, or None if it picks out some neighborhoods of a count.
+pub fn totalistic(set: &Neighborhoods) -> Option<u16> {
+    let mut mask = 0;
+    for count in 0..=8 {
+        let all = with_count(count);
+        if is_subset(&all, set) {
+            mask |= 1 << count;
+        } else if all.iter().zip(set).any(|(all, word)| all & word != 0) {
+            return None;
+        }
+    }
+    Some(mask)
+}
+
+// The Hensel form of a part, or None if it can't be written that way
+// because it treats some rotation or reflection of a neighborhood
+// differently from the neighborhood itself.
+pub fn format_part(set: &Neighborhoods) -> Option<String> {
+    let mut out = String::new();
+    for count in 0..=8u8 {
+        let all = with_count(count);
+        if !all.iter().zip(set).any(|(all, word)| all & word != 0) {
+            continue;
+        }
+        out.push(char::from(b'0' + count));
+        if is_subset(&all, set) {
+            continue;
+        }
+        let (mut taken, mut left) = (String::new(), String::new());
+        for &(letter, _) in letters(count) {
+            let kind = named(count, letter).unwrap();
+            if is_subset(&kind, set) {
+                taken.push(letter);
+            } else if kind.iter().zip(set).any(|(kind, word)| kind & word != 0) {
+                return None;
+            } else {
+                left.push(letter);
+            }
+        }
+        if left.len() < taken.len() {
+            out.push('-');
+            out.push_str(&left);
+        } else {
+            out.push_str(&taken);
+        }
+    }
+    Some(out)
+}
+
+// MAP strings spell out any rule, isotropic or not, as the base64 of
+// a 512-bit table: bit k, counting from the most significant bit of the
+// first byte, says whether the middle cell of the 3x3 block k is alive
+// next, where k reads the block's cells row by row as a binary number.
+const MAP_LENGTH: usize = 86;
+
+const MAP_ENGINE: GeneralPurpose =
+    GeneralPurpose::new(&STANDARD, GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent));
+
+// The 9-bit block of a neighborhood around a dead or live cell.
+fn block(neighborhood: u8, alive: bool) -> usize {
+    let neighborhood = usize::from(neighborhood);
+    (neighborhood & 0xf0) << 1 | usize::from(alive) << 4 | neighborhood & 0x0f
+}
+
+// Splits "MAP<table>" off the front of a rule, returning the birth and
+// survival parts and whatever follows the table.
+pub fn parse_map(rule: &str) -> Option<Result<(Neighborhoods, Neighborhoods, &str), &'static str>> {
+    let rest = rule.strip_prefix("MAP")?;
+    let length = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '+' || c == '/'));
+    let (table, mut rest) = rest.split_at(length.unwrap_or(rest.len()).min(MAP_LENGTH));
+    rest = rest.trim_start_matches('=');
+    let bytes = match MAP_ENGINE.decode(table) {
+        Ok(bytes) if bytes.len() == 64 => bytes,
+        _ => return Some(Err("a MAP table must be 86 base64 characters")),
+    };
+    let (mut birth, mut survival) = ([0; 4], [0; 4]);
+    for neighborhood in 0..=255u8 {
+        for (alive, part) in [(false, &mut birth), (true, &mut survival)] {
+            let k = block(neighborhood, alive);
+            if bytes[k / 8] & (0x80 >> (k % 8)) != 0 {
+                insert(part, neighborhood);
+            }
+        }
+    }
+    Some(Ok((birth, survival, rest)))
+}
+
+pub fn format_map(birth: &Neighborhoods, survival: &Neighborhoods) -> String {
+    let mut bytes = [0u8; 64];
+    for neighborhood in 0..=255u8 {
+        for (alive, part) in [(false, birth), (true, survival)] {
+            if contains(part, neighborhood) {
+                let k = block(neighborhood, alive);
+                bytes[k / 8] |= 0x80 >> (k % 8);
+            }
+        }
+    }
+    let mut out = String::from("MAP");
+    MAP_ENGINE.encode_string(bytes, &mut out);
+    out.truncate(3 + MAP_LENGTH);
+    out
+}
@@ -0,0 +1,173 @@
+use std::collections::VecDeque;
+
+use fixedbitset::FixedBitSet;
+
+use crate::memory::{deque_bytes, vec_bytes};
+
+// One undoable step: the cells it flipped and the generation number before
+// and after. Flipping the same cells again undoes the step, and flipping
+// them once more redoes it, so a single list serves both directions.
+// With Generations rules, the states that changed go the same way, as
+// each cell's old state xor its new one.
+#[derive(Clone, Debug)]
+pub struct Change {
+    pub flipped: Vec<u32>,
+    pub states: Vec<(u32, u8)>,
+    pub generation_before: u64,
+    pub generation_after: u64,
+}
+
+impl Change {
+    // Compares two snapshots of the same universe word by word.
+    pub fn between(before: &FixedBitSet, after: &FixedBitSet, generation_before: u64, generation_after: u64) -> Change {
+        let mut flipped = Vec::new();
+        for (word, (&old, &new)) in before.as_slice().iter().zip(after.as_slice()).enumerate() {
+            let mut diff = old ^ new;
+            while diff != 0 {
+                flipped.push(word as u32 * 32 + diff.trailing_zeros());
+                diff &= diff - 1;
+            }
+        }
+        Change {
+            flipped,
+            states: Vec::new(),
+            generation_before,
+            generation_after,
+        }
+    }
+
+    // Adds the states that differ between two copies of the states.
+    pub fn with_states(mut self, before: &[u8], after: &[u8]) -> Change {
+        self.states = before
+            .iter()
+            .zip(after)
+            .enumerate()
+            .filter(|(_, (old, new))| old != new)
+            .map(|(idx, (old, new))| (idx as u32, old ^ new))
+            .collect();
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.flipped.is_empty() && self.states.is_empty() && self.generation_before == self.generation_after
+    }
+
+    // The flipped cells and states; the rest of a change is a few words
+    // kept on the stacks themselves.
+    pub fn heap_bytes(&self) -> usize {
+        vec_bytes(&self.flipped) + vec_bytes(&self.states)
+    }
+}
+
+// Undo and redo stacks. Only the most recent `depth` changes are kept;
+// a depth of zero turns history off. With a budget, the oldest changes
+// also go once the cells they flipped take up more than that many
+// bytes.
+#[derive(Clone, Debug)]
+pub struct History {
+    undo: VecDeque<Change>,
+    redo: Vec<Change>,
+    depth: usize,
+    budget: Option<usize>,
+}
+
+impl History {
+    pub fn new(depth: usize) -> History {
+        History {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            depth,
+            budget: None,
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+        while self.undo.len() > depth {
+            self.undo.pop_front();
+        }
+    }
+
+    pub fn budget(&self) -> Option<usize> {
+        self.budget
+    }
+
+    pub fn set_budget(&mut self, budget: Option<usize>) {
+        self.budget = budget;
+        self.trim();
+    }
+
+    // Drops changes, oldest undo first and the redo stack last, until
+    // the rest fit the budget. A single change bigger than the whole
+    // budget is not kept either.
+    fn trim(&mut self) {
+        let budget = match self.budget {
+            Some(budget) => budget,
+            None => return,
+        };
+        let mut bytes = self.undo.iter().chain(&self.redo).map(Change::heap_bytes).sum::<usize>();
+        while bytes > budget {
+            let change = match self.undo.pop_front() {
+                Some(change) => change,
+                None => self.redo.remove(0),
+            };
+            bytes -= change.heap_bytes();
+        }
+    }
+
+    // A new change makes whatever was undone unreachable.
+    pub fn push(&mut self, change: Change) {
+        if self.depth == 0 || change.is_empty() {
+            return;
+        }
+        self.redo.clear();
+        self.undo.push_back(change);
+        if self.undo.len() > self.depth {
+            self.undo.pop_front();
+        }
+        self.trim();
+    }
+
+    pub fn undo(&mut self) -> Option<&Change> {
+        let change = self.undo.pop_back()?;
+        self.redo.push(change);
+        self.redo.last()
+    }
+
+    pub fn redo(&mut self) -> Option<&Change> {
+        let change = self.redo.pop()?;
+        self.undo.push_back(change);
+        self.undo.back()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn heap_bytes(&self) -> usize {
+        let changes = self.undo.iter().chain(&self.redo).map(Change::heap_bytes).sum::<usize>();
+        deque_bytes(&self.undo) + vec_bytes(&self.redo) + changes
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        for change in self.undo.iter_mut().chain(&mut self.redo) {
+            change.flipped.shrink_to_fit();
+            change.states.shrink_to_fit();
+        }
+        self.undo.shrink_to_fit();
+        self.redo.shrink_to_fit();
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::rle;
+use crate::rule::LifeRule;
+use crate::{cell_count, Cell, Error};
+
+// Chunks are 64x64 cells, one u64 per row, bit i being column i.
+const CHUNK_BITS: u32 = 6;
+const CHUNK_SIZE: i64 = 1 << CHUNK_BITS;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Chunk {
+    rows: [u64; CHUNK_SIZE as usize],
+}
+
+impl Chunk {
+    const EMPTY: Chunk = Chunk {
+        rows: [0; CHUNK_SIZE as usize],
+    };
+
+    fn population(&self) -> u32 {
+        self.rows.iter().map(|row| row.count_ones()).sum()
+    }
+}
+
+// Splits a signed cell coordinate into (chunk coordinate, offset inside it).
+// Arithmetic shifts and masks round towards negative infinity,
+// so -1 lands in chunk -1 at offset 63.
+fn split(v: i64) -> (i64, u32) {
+    (v >> CHUNK_BITS, (v & (CHUNK_SIZE - 1)) as u32)
+}
+
+// An unbounded plane: nothing wraps, and gliders fly off forever.
+// Only the 64x64 chunks that actually contain live cells are stored,
+// keyed by their signed chunk coordinates, and new chunks are allocated
+// as patterns grow into them.
+pub struct InfiniteUniverse {
+    chunks: HashMap<(i64, i64), Chunk>,
+    rule: LifeRule,
+    generation: u64,
+    population: u32,
+}
+
+impl InfiniteUniverse {
+    fn get(&self, x: i64, y: i64) -> bool {
+        let (cx, ox) = split(x);
+        let (cy, oy) = split(y);
+        self.chunks
+            .get(&(cx, cy))
+            .is_some_and(|chunk| chunk.rows[oy as usize] & (1 << ox) != 0)
+    }
+
+    fn set(&mut self, x: i64, y: i64, alive: bool) {
+        if self.get(x, y) == alive {
+            return;
+        }
+        let (cx, ox) = split(x);
+        let (cy, oy) = split(y);
+        let chunk = self.chunks.entry((cx, cy)).or_insert(Chunk::EMPTY);
+        chunk.rows[oy as usize] ^= 1 << ox;
+        if alive {
+            self.population += 1;
+        } else {
+            self.population -= 1;
+            if chunk.population() == 0 {
+                self.chunks.remove(&(cx, cy));
+            }
+        }
+    }
+
+    // Row `row` of chunk (cx, cy), widened to 66 bits: bit 0 is the last
+    // column of the chunk to the left, bits 1..=64 are the chunk's own
+    // columns and bit 65 is the first column of the chunk to the right.
+    fn padded_row(&self, cx: i64, cy: i64, row: i64) -> u128 {
+        let (cy, row) = match row {
+            -1 => (cy - 1, CHUNK_SIZE - 1),
+            r if r == CHUNK_SIZE => (cy + 1, 0),
+            r => (cy, r),
+        };
+        let row_of = |cx| {
+            self.chunks
+                .get(&(cx, cy))
+                .map_or(0, |chunk| chunk.rows[row as usize])
+        };
+        let left = row_of(cx - 1) >> 63;
+        let middle = row_of(cx);
+        let right = row_of(cx + 1) & 1;
+        u128::from(left) | (u128::from(middle) << 1) | (u128::from(right) << 65)
+    }
+
+    fn next_chunk(&self, cx: i64, cy: i64) -> Chunk {
+        let mut next = Chunk::EMPTY;
+        let mut above = self.padded_row(cx, cy, -1);
+        let mut middle = self.padded_row(cx, cy, 0);
+        for row in 0..CHUNK_SIZE {
+            let below = self.padded_row(cx, cy, row + 1);
+            if above | middle | below != 0 {
+                let mut bits = 0u64;
+                for col in 0..CHUNK_SIZE as u32 {
+                    let count = ((above >> col) & 0b111).count_ones()
+                        + ((middle >> col) & 0b101).count_ones()
+                        + ((below >> col) & 0b111).count_ones();
+                    let alive = (middle >> (col + 1)) & 1 == 1;
+                    if self.rule.next(alive, count as u8) {
+                        bits |= 1 << col;
+                    }
+                }
+                next.rows[row as usize] = bits;
+            }
+            above = middle;
+            middle = below;
+        }
+        next
+    }
+}
+
+impl InfiniteUniverse {
+    pub fn new() -> InfiniteUniverse {
+        InfiniteUniverse {
+            chunks: HashMap::new(),
+            rule: LifeRule::conway(),
+            generation: 0,
+            population: 0,
+        }
+    }
+
+    pub fn with_rule(rule: &str) -> Result<InfiniteUniverse, Error> {
+        let mut universe = InfiniteUniverse::new();
+        universe.rule = rule.parse()?;
+        if !universe.rule.is_life_like() {
+            return Err(Error::new("infinite universes only run plain B/S rules"));
+        }
+        // With B0 every empty cell of the plane is born at once, and only
+        // the chunks near live cells would ever be looked at.
+        if universe.rule.masks().0 & 1 != 0 {
+            return Err(Error::new("infinite universes need a B/S rule without B0"));
+        }
+        Ok(universe)
+    }
+
+    pub fn rule(&self) -> String {
+        self.rule.to_string()
+    }
+
+    pub fn tick(&mut self) {
+        // Births can only happen in or right next to an occupied chunk.
+        let mut candidates = HashSet::new();
+        for &(cx, cy) in self.chunks.keys() {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    candidates.insert((cx + dx, cy + dy));
+                }
+            }
+        }
+
+        let mut next = HashMap::with_capacity(self.chunks.len());
+        let mut population = 0;
+        for (cx, cy) in candidates {
+            let chunk = self.next_chunk(cx, cy);
+            let alive = chunk.population();
+            if alive > 0 {
+                population += alive;
+                next.insert((cx, cy), chunk);
+            }
+        }
+
+        self.chunks = next;
+        self.population = population;
+        self.generation += 1;
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        for _ in 0..n {
+            self.tick();
+        }
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u32 {
+        self.population
+    }
+
+    // How many 64x64 chunks are currently allocated.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn get_cell(&self, x: i32, y: i32) -> Cell {
+        if self.get(x.into(), y.into()) {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+
+    pub fn set_cell(&mut self, x: i32, y: i32, cell: Cell) {
+        self.set(x.into(), y.into(), cell == Cell::Alive);
+    }
+
+    pub fn toggle_cell(&mut self, x: i32, y: i32) {
+        let alive = self.get(x.into(), y.into());
+        self.set(x.into(), y.into(), !alive);
+    }
+
+    // Pastes a Run Length Encoded pattern with its top-left corner at (x, y).
+    pub fn insert_rle(&mut self, rle: &str, x: i32, y: i32) -> Result<(), Error> {
+        let pattern = rle::parse(rle)?;
+        for (row, col) in pattern.cells {
+            self.set(i64::from(x) + i64::from(col), i64::from(y) + i64::from(row), true);
+        }
+        Ok(())
+    }
+
+    // The smallest rectangle containing every live cell, as
+    // [min_x, min_y, max_x, max_y] (inclusive), or empty if nothing is alive.
+    pub fn bounds(&self) -> Vec<i32> {
+        let mut bounds: Option<(i64, i64, i64, i64)> = None;
+        for (&(cx, cy), chunk) in self.chunks.iter() {
+            for (row, &bits) in chunk.rows.iter().enumerate() {
+                if bits == 0 {
+                    continue;
+                }
+                let y = cy * CHUNK_SIZE + row as i64;
+                let x0 = cx * CHUNK_SIZE + i64::from(bits.trailing_zeros());
+                let x1 = cx * CHUNK_SIZE + 63 - i64::from(bits.leading_zeros());
+                bounds = Some(match bounds {
+                    None => (x0, y, x1, y),
+                    Some((a, b, c, d)) => (a.min(x0), b.min(y), c.max(x1), d.max(y)),
+                });
+            }
+        }
+        match bounds {
+            None => Vec::new(),
+            Some((a, b, c, d)) => [a, b, c, d].iter().map(|&v| v as i32).collect(),
+        }
+    }
+
+    // The cells of a width x height window whose top-left corner is at
+    // (x, y), packed one bit per cell in the same layout as
+    // Universe::cells_bits(), so the same canvas code can draw it.
+    pub fn window_bits(&self, x: i32, y: i32, width: u32, height: u32) -> Result<Vec<u32>, Error> {
+        let len = cell_count(&[width, height])?;
+        let mut bits = vec![0u32; len.div_ceil(32)];
+        for row in 0..height {
+            for col in 0..width {
+                if self.get(i64::from(x) + i64::from(col), i64::from(y) + i64::from(row)) {
+                    let idx = (row * width + col) as usize;
+                    bits[idx / 32] |= 1 << (idx % 32);
+                }
+            }
+        }
+        Ok(bits)
+    }
+}
+
+impl Default for InfiniteUniverse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
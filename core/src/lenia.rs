@@ -0,0 +1,168 @@
+use crate::continuous::{self, ContinuousEngine, Kernel, Padded};
+use crate::random::Rng;
+use crate::{cell_count, Error};
+
+// Lenia: a continuous cousin of Life. Every cell holds a value between
+// 0 and 1 instead of being dead or alive, and "counting the neighbors"
+// becomes a weighted average over a ring around the cell. The average u
+// goes through a growth function, a bump that is 1 at u = mu, about 0 at
+// mu +- 2 sigma and -1 far away from it, and a small step dt of the growth
+// is added to the cell. The universe always wraps around like a torus.
+//
+// The defaults are those of Orbium, the glider of Lenia.
+pub const DEFAULT_RADIUS: u32 = 13;
+pub const DEFAULT_MU: f32 = 0.15;
+pub const DEFAULT_SIGMA: f32 = 0.015;
+pub const DEFAULT_DT: f32 = 0.1;
+
+pub const MAX_RADIUS: u32 = 64;
+
+#[derive(Clone, Debug)]
+pub struct Lenia {
+    width: u32,
+    height: u32,
+    cells: Vec<f32>,
+    next: Vec<f32>,
+    radius: u32,
+    mu: f32,
+    sigma: f32,
+    dt: f32,
+    kernel: Kernel,
+    generation: u64,
+}
+
+// The ring, as a function of the distance r from the middle in radii:
+// 0 at the middle and at the edge, 1 halfway.
+fn ring(r: f32) -> f32 {
+    if r <= 0.0 || r >= 1.0 {
+        return 0.0;
+    }
+    (4.0 - 1.0 / (r * (1.0 - r))).exp()
+}
+
+fn kernel(radius: u32) -> Kernel {
+    continuous::kernel(radius, |distance| ring(distance / radius as f32))
+}
+
+impl Lenia {
+    // An empty width x height universe with the default parameters.
+    pub fn new(width: u32, height: u32) -> Result<Lenia, Error> {
+        let size = cell_count(&[width, height])?;
+        Ok(Lenia {
+            width,
+            height,
+            cells: vec![0.0; size],
+            next: vec![0.0; size],
+            radius: DEFAULT_RADIUS,
+            mu: DEFAULT_MU,
+            sigma: DEFAULT_SIGMA,
+            dt: DEFAULT_DT,
+            kernel: kernel(DEFAULT_RADIUS),
+            generation: 0,
+        })
+    }
+
+    // Fills a centered square of side `size` with uniform random values,
+    // leaving the rest empty. The same seed always gives the same soup.
+    pub fn random(width: u32, height: u32, size: u32, seed: u64) -> Result<Lenia, Error> {
+        let mut lenia = Lenia::new(width, height)?;
+        let (rows, cols) = (size.min(height), size.min(width));
+        let (top, left) = ((height - rows) / 2, (width - cols) / 2);
+        let mut rng = Rng::new(seed);
+        for row in top..top + rows {
+            for col in left..left + cols {
+                lenia.cells[(row * width + col) as usize] = rng.next_f32();
+            }
+        }
+        Ok(lenia)
+    }
+
+    // The growth of a cell whose neighborhood averages to u.
+    fn growth(&self, u: f32) -> f32 {
+        let d = (u - self.mu) / self.sigma;
+        2.0 * (-d * d / 2.0).exp() - 1.0
+    }
+
+    pub fn radius(&self) -> u32 {
+        self.radius
+    }
+
+    // How far the ring reaches, in cells. Creatures scale with it, so a
+    // bigger radius gives the same shapes with finer detail.
+    pub fn set_radius(&mut self, radius: u32) -> Result<(), Error> {
+        if !(1..=MAX_RADIUS).contains(&radius) {
+            return Err(Error::new("the radius must be between 1 and 64"));
+        }
+        self.radius = radius;
+        self.kernel = kernel(radius);
+        Ok(())
+    }
+
+    pub fn mu(&self) -> f32 {
+        self.mu
+    }
+
+    pub fn sigma(&self) -> f32 {
+        self.sigma
+    }
+
+    // Where the growth function peaks, and how wide it is.
+    pub fn set_growth(&mut self, mu: f32, sigma: f32) -> Result<(), Error> {
+        if !(0.0..=1.0).contains(&mu) || sigma <= 0.0 || sigma > 1.0 {
+            return Err(Error::new("mu must be between 0 and 1, and sigma between 0 (excluded) and 1"));
+        }
+        self.mu = mu;
+        self.sigma = sigma;
+        Ok(())
+    }
+
+    pub fn dt(&self) -> f32 {
+        self.dt
+    }
+
+    // The time step: how much of the growth each tick adds.
+    pub fn set_dt(&mut self, dt: f32) -> Result<(), Error> {
+        if dt <= 0.0 || dt > 1.0 {
+            return Err(Error::new("the time step must be between 0 (excluded) and 1"));
+        }
+        self.dt = dt;
+        Ok(())
+    }
+}
+
+impl ContinuousEngine for Lenia {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn cells(&self) -> &[f32] {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut [f32] {
+        &mut self.cells
+    }
+
+    fn tick(&mut self) {
+        let padded = Padded::new(&self.cells, self.width, self.height, self.radius);
+        let offsets = padded.offsets(&self.kernel);
+        let width = self.width as usize;
+        for row in 0..self.height as usize {
+            for col in 0..width {
+                let u = padded.average(&offsets, row, col);
+                let idx = row * width + col;
+                self.next[idx] = (self.cells[idx] + self.dt * self.growth(u)).clamp(0.0, 1.0);
+            }
+        }
+        std::mem::swap(&mut self.cells, &mut self.next);
+        self.generation += 1;
+    }
+}
@@ -0,0 +1,2605 @@
+// The Game of Life itself, in plain Rust: universes, rules, engines, 
+// patterns and file formats, with no dependency on wasm-bindgen. 
+// The wasm-game-of-life crate wraps all of this for JavaScript, and 
+// native programs (a terminal front end, a Bevy game) can use it as is.
+
+#[cfg(any(feature = "gif", feature = "apng"))]
+mod animation;
+mod apgcode;
+mod autosave;
+mod bitwise;
+mod builder;
+mod census;
+mod continuous;
+mod custom;
+mod cycle;
+mod cyclic;
+mod density;
+mod error;
+mod forest;
+mod format;
+mod gzip;
+mod hashlife;
+mod hensel;
+mod history;
+mod infinite;
+mod lif;
+mod macrocell;
+mod lenia;
+mod ltl;
+mod margolus;
+mod memory;
+mod methuselah;
+mod neighborhood;
+mod noise;
+mod objects;
+mod parallel;
+mod patterns;
+mod perf;
+mod plaintext;
+mod player;
+mod random;
+mod rle;
+mod rule;
+mod share;
+mod simd;
+mod smoothlife;
+mod snapshot;
+mod species;
+mod stochastic;
+mod svg;
+mod stats;
+mod symmetry;
+mod table;
+mod text;
+mod topology;
+mod triangular;
+mod turmite;
+mod universe1d;
+mod universe3d;
+mod viewport;
+mod weighted;
+
+use cycle::CycleDetector;
+use density::Density;
+use error::parse_failed;
+use fixedbitset::FixedBitSet;
+use hashlife::Hashlife;
+use perf::Counters;
+use history::{Change, History};
+use random::Rng;
+use serde::{Deserialize, Serialize};
+use autosave::Autosave;
+use snapshot::Snapshot;
+use stats::{GenerationStats, PopulationHistory};
+
+pub use builder::{Seed, UniverseBuilder};
+pub use census::{Census, SoupSearch};
+pub use continuous::ContinuousEngine;
+pub use custom::{Neighbors, Rule};
+pub use cycle::{Cycle, StabilizationReport, StopReason};
+pub use cyclic::CyclicUniverse;
+pub use error::Error;
+pub use forest::ForestFire;
+pub use format::PatternFormat;
+pub use infinite::InfiniteUniverse;
+pub use lenia::Lenia;
+pub use margolus::{MargolusRule, MargolusUniverse};
+pub use memory::MemoryReport;
+pub use methuselah::{Candidate, MethuselahSearch};
+pub use neighborhood::{Neighborhood, NeighborhoodShape};
+pub use objects::{object_name, Heading, ObjectInfo, ObjectKind};
+pub use patterns::{list_patterns, Pattern, PatternInfo};
+pub use perf::{set_perf_clock, CountingAllocator, PerfStats};
+pub use player::{Player, PlayerState};
+pub use rle::ParseRleError;
+pub use rule::{LifeRule, ParseRuleError};
+pub use simd::simd_enabled;
+pub use smoothlife::SmoothLife;
+pub use species::Species;
+pub use stochastic::Stochastic;
+pub use stats::{StatsFormat, TickStats};
+pub use symmetry::Symmetry;
+pub use table::{RuleTable, TableNeighborhood};
+pub use svg::SvgOptions;
+pub use text::TextOptions;
+pub use topology::{TiledUniverse, Topology};
+pub use triangular::{Triangular, TriangularNeighborhood, TriangularUniverse};
+pub use turmite::{Direction, Turmite};
+pub use universe1d::Universe1D;
+pub use universe3d::{Axis, Rule3D, Universe3D};
+pub use weighted::WeightedRule;
+
+// ======================================================================
+// We have several ways of exposing the universe's cells to JavaScript. 
+// To begin, we will implement std::fmt::Display for Universe, 
+// which we can use to generate a Rust String of the cells rendered as text 
+// characters. This Rust String is then copied from the WebAssembly linear 
+// memory into a JavaScript String in the JavaScript's garbage-collected heap, 
+// and is then displayed by setting HTML textContent
+//
+
+// It is important that we have #[repr(u8)], 
+// so that each cell is represented as a single byte. 
+// It is also important that the Dead variant is 0 
+// and that the Alive variant is 1, 
+// so that we can easily count a cell's live neighbors with addition.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    Dead = 0,
+    Alive = 1,
+}
+
+// When a universe is resized, the anchor says which part of the 
+// existing pattern stays put. With TopLeft, growing adds dead cells 
+// to the right and bottom; with Center, the pattern stays in the middle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+// What lies beyond the edges of the universe. Torus wraps around, 
+// Dead and AliveWall pretend the outside is all dead or all alive, 
+// and Mirror reflects the edge row or column back in, 
+// as if the universe continued as its own mirror image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Boundary {
+    Torus,
+    Dead,
+    Mirror,
+    AliveWall,
+}
+
+// How the next generation gets computed. Naive visits every cell of 
+// the torus on every tick. Hashlife memoizes the evolution of repeated 
+// sub-squares on an unbounded plane, which lets tick_many fast-forward 
+// guns and breeders by millions of generations; the universe's 
+// width x height is then a window onto that plane and nothing wraps. 
+// BitParallel gives the same results as Naive, but packs each row into 
+// u64 words and works out 64 cells at a time with bitwise adders.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Engine {
+    Naive,
+    Hashlife,
+    BitParallel,
+}
+
+// How cells are colored in exports that have more than black and white. 
+// Age goes from amber for newborns to purple for old cells, like 
+// ages(), and Activity reddens dead cells that changed recently, like 
+// the activity() heatmap. 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Coloring {
+    Plain,
+    Age,
+    Activity,
+}
+
+// Automata that come with their own rule and their own idea of a good 
+// random start, for picking one from a menu. In Brian's Brain cells 
+// are ready (state 0), firing (1) or refractory (2). 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Preset {
+    Life,
+    BriansBrain,
+}
+
+impl Preset {
+    pub fn rule(self) -> LifeRule {
+        match self {
+            Preset::Life => LifeRule::conway(),
+            Preset::BriansBrain => LifeRule::brians_brain(),
+        }
+    }
+}
+
+// Next, let's define the universe. 
+// The universe has a width and a height, 
+// In Rust, you can use the fixedbitset crate and its FixedBitSet type 
+// to represent cells instead of Vec<Cell>
+pub struct Universe {
+    width: u32,
+    height: u32,
+    //cells: Vec<Cell>,
+    cells: FixedBitSet,
+    // The back buffer each generation is computed into before being 
+    // swapped with `cells`, and the buffer record() keeps the cells from 
+    // before a change in. Both are kept between calls so that ticking 
+    // doesn't allocate.
+    next: FixedBitSet,
+    before: FixedBitSet,
+    // The cells that differ from before the last tick, edit, undo or redo.
+    changed: FixedBitSet,
+    rule: LifeRule,
+    // How many ticks have run, and how many cells are alive right now. 
+    // The population is kept up to date as cells change, 
+    // so reading it never has to scan the grid.
+    generation: u64,
+    population: u32,
+    boundary: Boundary,
+    // Which cells count as neighbors. Only Moore with radius 1 runs on
+    // the fast engines; anything else goes through general_step_into.
+    neighborhood: Neighborhood,
+    // Replaces the rule's birth and survival counts with sums over a 
+    // weighted kernel while it is set.
+    weighted: Option<WeightedRule>,
+    engine: Engine,
+    // Only there while the Hashlife engine is selected.
+    hashlife: Option<Hashlife>,
+    history: History,
+    cycles: CycleDetector,
+    // How many generations in a row each cell has been alive (0 when dead). 
+    // Left empty, and not updated, until ages() is first asked for.
+    ages: Vec<u16>,
+    // Recent births and deaths per cell, fading by `activity_decay` 
+    // every generation. Also only tracked once asked for.
+    activity: Vec<f32>,
+    activity_decay: f32,
+    // Live counts per 8x8 and 64x64 block, for zoomed out views. 
+    // None until density_level() is first asked for.
+    density: Option<Density>,
+    // What the most recent step did, and the population after each step.
+    last_step: TickStats,
+    populations: PopulationHistory,
+    // Every step since record_stats(true), None while not recording.
+    recorded_stats: Option<Vec<GenerationStats>>,
+    // How long ticks take, and where the time goes.
+    perf: Counters,
+    // The color of every cell, 0 for dead ones and 1 up to 
+    // species.colors() for live ones. Empty with Species::Single.
+    species: Species,
+    colors: Vec<u8>,
+    // With a Generations rule, the state of every cell: 0 for dead, 
+    // 1 for alive and 2 up to rule.states() - 1 while dying. `cells` 
+    // only has the live ones. Empty with two-state rules. With 
+    // Wireworld, 1 are the heads, 2 the tails and 3 the wires.
+    states: Vec<u8>,
+    // Langton's Ants and friends, which move in turn before each step.
+    turmites: Vec<Turmite>,
+    // Random births, survivals and deaths, rolled with `rng`. 
+    stochastic: Option<Stochastic>,
+    // Everything random the universe does, soups, stochastic rules and 
+    // rule mutation alike, draws from this one generator, so that the 
+    // same seed and the same calls always give the same universe. 
+    seed: u64,
+    rng: Rng,
+    // A rule from outside, e.g. a JavaScript function, see set_custom_rule().
+    custom_rule: Option<Box<dyn Rule>>,
+    // A Golly rule table loaded with load_rule_file(), which replaces 
+    // the rule and brings its own number of states.
+    table: Option<RuleTable>,
+    // Captures the universe every so many generations once 
+    // enable_autosave() is called. 
+    autosave: Option<Autosave>,
+    // The rectangle the user picked for copying, as (row, col, width, 
+    // height). Dropped on resize.
+    selection: Option<(u32, u32, u32, u32)>,
+    // How many cells the per-cell buffers above are allocated for, at 
+    // least, as set by reserve(). 
+    reserved: usize,
+}
+
+// How much of a cell's activity is left after one generation.
+const DEFAULT_ACTIVITY_DECAY: f32 = 0.9;
+
+// How many edits and ticks can be undone, unless set_history_depth says otherwise.
+const DEFAULT_HISTORY_DEPTH: usize = 64;
+
+// The most cells any universe may have. Cells are indexed with u32s, 
+// and a few buffers of this many bits already take a good part of the 
+// 4GB a wasm module can address.
+const MAX_CELLS: u64 = 1 << 30;
+
+// How many cells a grid of these dimensions has, or why it can't be 
+// made: no cells at all, or more than MAX_CELLS. 
+pub(crate) fn cell_count(dimensions: &[u32]) -> Result<usize, Error> {
+    if dimensions.contains(&0) {
+        let sides = if dimensions.len() == 3 { "width, height and depth" } else { "width and height" };
+        return Err(Error::new(&format!("universe {} must be non-zero", sides)));
+    }
+    let cells = dimensions.iter().try_fold(1u64, |cells, &n| cells.checked_mul(u64::from(n)).filter(|&c| c <= MAX_CELLS));
+    let size = dimensions.iter().map(u32::to_string).collect::<Vec<_>>().join("x");
+    match cells {
+        Some(cells) => Ok(cells as usize),
+        None => Err(Error::new(&format!("a {} universe is too big, the most is {} cells", size, MAX_CELLS))),
+    }
+}
+
+// The most pixels render_viewport() draws at once: a 16K screen has 
+// fewer, and the RGBA bytes of this many already take a quarter of 
+// what a wasm module can address. 
+const MAX_VIEW_PIXELS: u64 = 1 << 28;
+
+// How many RGBA bytes a view of this size takes, or why it can't be drawn. 
+fn view_bytes(view_width: u32, view_height: u32) -> Result<usize, Error> {
+    let pixels = u64::from(view_width) * u64::from(view_height);
+    if pixels > MAX_VIEW_PIXELS {
+        return Err(Error::new(&format!(
+            "a {}x{} view is too big, the most is {} pixels",
+            view_width, view_height, MAX_VIEW_PIXELS
+        )));
+    }
+    Ok(pixels as usize * 4)
+}
+
+
+// To access the cell at a given row and column, 
+// we translate the row and column into an index 
+// into the cells vector, 
+impl Universe {
+    fn get_index(&self, row: u32, column: u32) -> usize {
+        (row * self.width + column) as usize
+    }
+
+    // In order to calculate the next state of a cell, 
+    // we need to get a count of how many of its neighbors are alive.
+    //
+    // The live_neighbor_count method uses deltas and modulo to avoid special 
+    // casing the edges of the universe with ifs. When applying a delta of -1, 
+    // we add self.height - 1 and let the modulo do its thing, rather than 
+    // attempting to subtract 1. row and column can be 0, and if we attempted 
+    // to subtract 1 from them, there would be an unsigned integer underflow.
+    fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
+        if self.neighborhood != Neighborhood::MOORE {
+            return self.extended_neighbor_count(row, column);
+        }
+        if self.boundary != Boundary::Torus {
+            return self.bounded_neighbor_count(row, column);
+        }
+
+        let mut count = 0;
+        for delta_row in [self.height - 1, 0, 1].iter().cloned() {
+            for delta_col in [self.width - 1, 0, 1].iter().cloned() {
+                if delta_row == 0 && delta_col == 0 {
+                    continue;
+                }
+
+                let neighbor_row = (row + delta_row) % self.height;
+                let neighbor_col = (column + delta_col) % self.width;
+                let idx = self.get_index(neighbor_row, neighbor_col);
+                count += self.cells[idx] as u8;
+            }
+        }
+        count
+    }
+
+    // An all-dead universe. Every constructor goes through here, so 
+    // there is a single place rejecting sizes that would break the 
+    // modulo arithmetic in live_neighbor_count.
+    fn empty(width: u32, height: u32, rule: LifeRule) -> Result<Universe, Error> {
+        let size = cell_count(&[width, height])?;
+
+        Ok(Universe {
+            width,
+            height,
+            cells: FixedBitSet::with_capacity(size),
+            next: FixedBitSet::with_capacity(size),
+            before: FixedBitSet::with_capacity(size),
+            changed: FixedBitSet::with_capacity(size),
+            rule,
+            generation: 0,
+            population: 0,
+            boundary: Boundary::Torus,
+            neighborhood: Neighborhood::MOORE,
+            weighted: None,
+            engine: Engine::Naive,
+            hashlife: None,
+            history: History::new(DEFAULT_HISTORY_DEPTH),
+            cycles: CycleDetector::default(),
+            ages: Vec::new(),
+            activity: Vec::new(),
+            activity_decay: DEFAULT_ACTIVITY_DECAY,
+            density: None,
+            last_step: TickStats::default(),
+            populations: PopulationHistory::default(),
+            recorded_stats: None,
+            perf: Counters::default(),
+            species: Species::Single,
+            colors: Vec::new(),
+            states: match rule.states() {
+                2 => Vec::new(),
+                _ => vec![0; size],
+            },
+            turmites: Vec::new(),
+            stochastic: None,
+            seed: 0,
+            rng: Rng::new(0),
+            custom_rule: None,
+            table: None,
+            autosave: None,
+            selection: None,
+            reserved: 0,
+        })
+    }
+
+    // Like get_index, but for coordinates coming from JavaScript, 
+    // which may well be outside the universe.
+    fn checked_index(&self, row: u32, column: u32) -> Result<usize, Error> {
+        if row >= self.height || column >= self.width {
+            return Err(Error::new(&format!(
+                "cell ({}, {}) is outside the {}x{} universe",
+                row, column, self.width, self.height
+            )));
+        }
+        Ok(self.get_index(row, column))
+    }
+
+    // Runs an edit or a tick and pushes what it changed onto the undo stack. 
+    // The change is found by comparing snapshots, so `op` doesn't have to 
+    // know about history at all.
+    fn record<R, F: FnOnce(&mut Universe) -> R>(&mut self, op: F) -> R {
+        let mut before = std::mem::take(&mut self.before);
+        if before.len() != self.cells.len() {
+            before = FixedBitSet::with_capacity(self.cells.len());
+        }
+        before.as_mut_slice().copy_from_slice(self.cells.as_slice());
+        // Dying cells can't be told from the cells, so with more than 
+        // two states those are kept for undo as well. 
+        let states_before = (self.history.depth() > 0 && !self.states.is_empty()).then(|| self.states.clone());
+        let generation = self.generation;
+        let result = op(self);
+        self.refresh_colors();
+        self.refresh_states();
+        if let Some(density) = &mut self.density {
+            density.update(&before, &self.cells);
+        }
+        if self.changed.len() != self.cells.len() {
+            self.changed = FixedBitSet::with_capacity(self.cells.len());
+        }
+        let words = before.as_slice().iter().zip(self.cells.as_slice());
+        for (changed, (&old, &new)) in self.changed.as_mut_slice().iter_mut().zip(words) {
+            *changed = old ^ new;
+        }
+        // Only the undo stack is skipped with history turned off; the 
+        // changed cells and the density counts are still needed.
+        if self.history.depth() > 0 {
+            let change = Change::between(&before, &self.cells, generation, self.generation);
+            match states_before {
+                Some(states) if states.len() == self.states.len() => self.history.push(change.with_states(&states, &self.states)),
+                _ => self.history.push(change),
+            }
+        }
+        self.before = before;
+        result
+    }
+
+    // record() for ticks, which also keeps the performance counters.
+    fn timed<R, F: FnOnce(&mut Universe) -> R>(&mut self, op: F) -> R {
+        self.perf.start();
+        let result = self.record(op);
+        self.perf.finish();
+        result
+    }
+
+    // Lends the back buffer to `op`, to be handed to step_into. 
+    // Whatever it holds afterwards is stale and gets overwritten next time.
+    fn with_next<R, F: FnOnce(&mut Universe, &mut FixedBitSet) -> R>(&mut self, op: F) -> R {
+        let mut next = std::mem::take(&mut self.next);
+        if next.len() != self.cells.len() {
+            next = FixedBitSet::with_capacity(self.cells.len());
+        }
+        let result = op(self, &mut next);
+        self.next = next;
+        result
+    }
+
+    // Flips the cells of an undone or redone change.
+    fn apply(&mut self, flipped: &[u32], generation: u64) {
+        for &idx in flipped {
+            let alive = !self.cells[idx as usize];
+            self.cells.set(idx as usize, alive);
+            if alive {
+                self.population += 1;
+            } else {
+                self.population -= 1;
+            }
+        }
+        self.generation = generation;
+    }
+
+    // apply() for undo and redo, which happen outside record() and so 
+    // have to keep the block counts and changed cells up to date themselves.
+    fn replay(&mut self, change: &Change, generation: u64) {
+        let flipped = &change.flipped;
+        self.apply(flipped, generation);
+        if self.states.len() == self.cells.len() {
+            for &(idx, diff) in &change.states {
+                self.states[idx as usize] ^= diff;
+            }
+        }
+        self.changed.clear();
+        for &idx in flipped {
+            self.changed.insert(idx as usize);
+        }
+        if let Some(density) = &mut self.density {
+            for &idx in flipped {
+                density.flip(idx as usize, self.cells[idx as usize]);
+            }
+        }
+        self.refresh_colors();
+        self.refresh_states();
+    }
+
+    // Edits that touch many cells at once just recount afterwards.
+    fn recount(&mut self) {
+        self.population = self.cells.count_ones(..) as u32;
+    }
+
+    // Copies a pattern's bounding box into the universe with its top-left 
+    // corner at (row, column), shifted by the pattern's offset. 
+    // Dead cells of the pattern overwrite live ones, and anything 
+    // hanging over an edge wraps around, just like the neighbors do.
+    fn paste(&mut self, pattern: &Pattern, row: u32, column: u32) -> Result<(), Error> {
+        if pattern.width() > self.width || pattern.height() > self.height {
+            return Err(Error::new("pattern is larger than the universe"));
+        }
+
+        let (offset_row, offset_col) = pattern.offset();
+        let top = (i64::from(row) + i64::from(offset_row)).rem_euclid(i64::from(self.height)) as u32;
+        let left = (i64::from(column) + i64::from(offset_col)).rem_euclid(i64::from(self.width)) as u32;
+        for r in 0..pattern.height() {
+            for c in 0..pattern.width() {
+                let idx = self.get_index((top + r) % self.height, (left + c) % self.width);
+                self.cells.set(idx, false);
+            }
+        }
+        for &(r, c) in pattern.live_cells() {
+            let idx = self.get_index((top + r) % self.height, (left + c) % self.width);
+            self.cells.set(idx, true);
+        }
+        self.recount();
+        Ok(())
+    }
+
+    // Without wrapping, the modulo trick doesn't apply: we step in signed 
+    // coordinates and decide what each neighbor beyond the edge is worth.
+    fn bounded_neighbor_count(&self, row: u32, column: u32) -> u8 {
+        let (height, width) = (i64::from(self.height), i64::from(self.width));
+        let mut count = 0;
+        for delta_row in -1..=1 {
+            for delta_col in -1..=1 {
+                if delta_row == 0 && delta_col == 0 {
+                    continue;
+                }
+
+                let mut r = i64::from(row) + delta_row;
+                let mut c = i64::from(column) + delta_col;
+                let outside = r < 0 || r >= height || c < 0 || c >= width;
+                if outside {
+                    match self.boundary {
+                        Boundary::Dead | Boundary::Torus => continue,
+                        Boundary::AliveWall => {
+                            count += 1;
+                            continue;
+                        }
+                        Boundary::Mirror => {
+                            r = r.clamp(0, height - 1);
+                            c = c.clamp(0, width - 1);
+                        }
+                    }
+                }
+                count += self.cells[self.get_index(r as u32, c as u32)] as u8;
+            }
+        }
+        count
+    }
+
+    // Any other neighborhood, row by row across its shape, with the 
+    // edges handled like in bounded_neighbor_count.
+    fn extended_neighbor_count(&self, row: u32, column: u32) -> u8 {
+        let (height, width) = (i64::from(self.height), i64::from(self.width));
+        let radius = i64::from(self.neighborhood.radius());
+        let mut count = 0;
+        for delta_row in -radius..=radius {
+            let reach = self.neighborhood.reach(delta_row);
+            for delta_col in -reach..=reach {
+                if delta_row == 0 && delta_col == 0 {
+                    continue;
+                }
+
+                let mut r = i64::from(row) + delta_row;
+                let mut c = i64::from(column) + delta_col;
+                if r < 0 || r >= height || c < 0 || c >= width {
+                    match self.boundary {
+                        Boundary::Dead => continue,
+                        Boundary::AliveWall => {
+                            count += 1;
+                            continue;
+                        }
+                        Boundary::Torus => {
+                            r = r.rem_euclid(height);
+                            c = c.rem_euclid(width);
+                        }
+                        Boundary::Mirror => {
+                            r = neighborhood::reflect(r, height);
+                            c = neighborhood::reflect(c, width);
+                        }
+                    }
+                }
+                count += self.cells[self.get_index(r as u32, c as u32)] as u8;
+            }
+        }
+        count
+    }
+
+    // Computes the next generation, calling `on_change(row, col, alive)` 
+    // for every cell whose state differs from the current generation.
+    fn step<F: FnMut(u32, u32, bool)>(&mut self, on_change: F) {
+        self.with_next(|universe, next| universe.step_into(next, on_change));
+    }
+
+    // The generation is written into `next`, which is then swapped with 
+    // the current cells. Every cell of `next` gets overwritten, 
+    // so callers can keep handing back the same buffer 
+    // instead of allocating a fresh one per generation.
+    fn step_into<F: FnMut(u32, u32, bool)>(&mut self, next: &mut FixedBitSet, mut on_change: F) {
+        self.move_turmites(&mut on_change);
+        if !self.simple_rule() {
+            return self.general_step_into(next, on_change);
+        }
+        match self.engine {
+            Engine::Naive => self.naive_step_into(next, on_change),
+            Engine::Hashlife if !self.hashlife_rule() => self.naive_step_into(next, on_change),
+            Engine::Hashlife => self.hashlife_step_into(1, next, on_change),
+            Engine::BitParallel => self.bitwise_step_into(next, on_change),
+        }
+    }
+
+    // Every turmite takes its step, in the order they were added, on 
+    // the cells as the ones before it left them. Then the rule runs on 
+    // the result. What they write is reported like any other change.
+    fn move_turmites<F: FnMut(u32, u32, bool)>(&mut self, on_change: &mut F) {
+        let mut turmites = std::mem::take(&mut self.turmites);
+        for turmite in turmites.iter_mut() {
+            let (row, col) = (turmite.row(), turmite.col());
+            let idx = self.get_index(row, col);
+            let alive = self.cells[idx];
+            let write = turmite.step(alive, self.width, self.height);
+            if write != alive {
+                self.cells.set(idx, write);
+                if write {
+                    self.population += 1;
+                } else {
+                    self.population -= 1;
+                }
+                on_change(row, col, write);
+            }
+        }
+        self.turmites = turmites;
+    }
+
+    fn bitwise_step_into<F: FnMut(u32, u32, bool)>(&mut self, next: &mut FixedBitSet, on_change: F) {
+        // A torus one cell across sees the same cells as neighbors a 
+        // different number of times than Naive does.
+        if self.boundary == Boundary::Torus && (self.width < 2 || self.height < 2) {
+            return self.naive_step_into(next, on_change);
+        }
+        let before = cycle::hash_cells(&self.cells);
+        bitwise::step(&self.cells, next, self.width, self.height, self.rule, self.boundary);
+        self.finish_step(next, before, 1, on_change);
+    }
+
+    fn naive_step_into<F: FnMut(u32, u32, bool)>(&mut self, next: &mut FixedBitSet, mut on_change: F) {
+        let before = cycle::hash_cells(&self.cells);
+        let (mut births, mut deaths) = (0, 0);
+        if simd::ENABLED && self.boundary == Boundary::Torus && self.width >= simd::MIN_WIDTH && self.height >= 2 {
+            simd::step_torus(&self.cells, next, self.width, self.height, self.rule);
+            self.finish_step(next, before, 1, on_change);
+            return;
+        }
+
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let idx = self.get_index(row, col);
+                let cell = self.cells[idx];
+                let live_neighbors = self.live_neighbor_count(row, col);
+
+               // let next_cell = match (cell, live_neighbors) {
+               //     // Rule 1: Any live cell with fewer than two live neighbours
+               //     // dies, as if caused by underpopulation.
+               //     (Cell::Alive, x) if x < 2 => Cell::Dead,
+               //     // Rule 2: Any live cell with two or three live neighbours
+               //     // lives on to the next generation.
+               //     (Cell::Alive, 2) | (Cell::Alive, 3) => Cell::Alive,
+               //     // Rule 3: Any live cell with more than three live
+               //     // neighbours dies, as if by overpopulation.
+               //     (Cell::Alive, x) if x > 3 => Cell::Dead,
+               //     // Rule 4: Any dead cell with exactly three live neighbours
+               //     // becomes a live cell, as if by reproduction.
+               //     (Cell::Dead, 3) => Cell::Alive,
+               //     // All other cells remain in the same state.
+               //     (otherwise, _) => otherwise,
+               // };
+               // let next_cell = match (cell, live_neighbors) {
+               //      (true, x) if x < 2 => false,
+               //      (true, 2) | (true, 3) => true,
+               //      (true, x) if x > 3 => false,
+               //      (false, 3) => true,
+               //      (otherwise, _) => otherwise
+               //  };
+               // The match arms above are Conway's B3/S23. The rule is now 
+               // data, so any Life-like rule can be plugged in.
+               let next_cell = self.rule.next(cell, live_neighbors);
+               next.set(idx, next_cell);
+
+               if next_cell != cell {
+                   if next_cell {
+                       self.population += 1;
+                       births += 1;
+                   } else {
+                       self.population -= 1;
+                       deaths += 1;
+                   }
+                   on_change(row, col, next_cell);
+               }
+
+               // next[idx] = next_cell;
+            }
+        }
+
+        self.end_step(next, before, 1, births, deaths);
+    }
+
+    // Whether the rule is one every engine can run.
+    fn simple_rule(&self) -> bool {
+        self.rule.is_life_like()
+            && self.neighborhood == Neighborhood::MOORE
+            && self.weighted.is_none()
+            && self.stochastic.is_none()
+            && self.custom_rule.is_none()
+            && self.table.is_none()
+    }
+
+    // Whether Hashlife can run the rule. It takes empty space to stay 
+    // empty, which B0 rules don't, so those run on the Naive loop. 
+    fn hashlife_rule(&self) -> bool {
+        self.simple_rule() && self.rule.masks().0 & 1 == 0
+    }
+
+    // Which of the eight neighbors are alive, one bit each, in the 
+    // layout LifeRule::next_with() expects: NW N NE W E SW S SE from 
+    // the most significant bit down. Edges are handled like in 
+    // bounded_neighbor_count.
+    fn neighbor_bits(&self, row: u32, column: u32) -> u8 {
+        let (height, width) = (i64::from(self.height), i64::from(self.width));
+        let mut neighborhood = 0;
+        for delta_row in -1..=1 {
+            for delta_col in -1..=1 {
+                if delta_row == 0 && delta_col == 0 {
+                    continue;
+                }
+
+                let (mut r, mut c) = (i64::from(row) + delta_row, i64::from(column) + delta_col);
+                let alive = if r < 0 || r >= height || c < 0 || c >= width {
+                    match self.boundary {
+                        Boundary::Torus => {
+                            r = r.rem_euclid(height);
+                            c = c.rem_euclid(width);
+                            self.cells[self.get_index(r as u32, c as u32)]
+                        }
+                        Boundary::Mirror => {
+                            r = r.clamp(0, height - 1);
+                            c = c.clamp(0, width - 1);
+                            self.cells[self.get_index(r as u32, c as u32)]
+                        }
+                        Boundary::Dead => false,
+                        Boundary::AliveWall => true,
+                    }
+                } else {
+                    self.cells[self.get_index(r as u32, c as u32)]
+                };
+                neighborhood = neighborhood << 1 | alive as u8;
+            }
+        }
+        neighborhood
+    }
+
+    // The states of the neighbors a rule table looks at, in its order, 
+    // with the edges handled like in neighbor_bits. 
+    // `states` are the ones from before the step, as the universe's own 
+    // get overwritten along the way.
+    fn table_neighbors(&self, states: &[u8], row: u32, column: u32, neighborhood: TableNeighborhood) -> [u8; 8] {
+        let (height, width) = (i64::from(self.height), i64::from(self.width));
+        let state = |idx: usize| states.get(idx).copied().unwrap_or(self.cells[idx] as u8);
+        let mut states = [0; 8];
+        for (slot, &(delta_row, delta_col)) in states.iter_mut().zip(neighborhood.offsets()) {
+            let (mut r, mut c) = (i64::from(row) + delta_row, i64::from(column) + delta_col);
+            *slot = if r < 0 || r >= height || c < 0 || c >= width {
+                match self.boundary {
+                    Boundary::Torus => {
+                        r = r.rem_euclid(height);
+                        c = c.rem_euclid(width);
+                        state(self.get_index(r as u32, c as u32))
+                    }
+                    Boundary::Mirror => {
+                        r = r.clamp(0, height - 1);
+                        c = c.clamp(0, width - 1);
+                        state(self.get_index(r as u32, c as u32))
+                    }
+                    Boundary::Dead => 0,
+                    Boundary::AliveWall => 1,
+                }
+            } else {
+                state(self.get_index(r as u32, c as u32))
+            };
+        }
+        states
+    }
+
+    // The naive loop again, for the rules the other engines can't run: 
+    // non-totalistic ones, which need to know which neighbors are alive, 
+    // Larger than Life, other neighborhoods than Moore's and weighted 
+    // kernels, which count other cells, and Generations 
+    // rules, where dying cells can't be born and move one state further 
+    // every generation until they are dead. Wireworld is a Generations 
+    // rule whose last state, the wire, never dies and is the only one 
+    // that can come alive. Stochastic rules roll their dice here too, 
+    // and custom rules get called for every cell, as do rule tables.
+    fn general_step_into<F: FnMut(u32, u32, bool)>(&mut self, next: &mut FixedBitSet, mut on_change: F) {
+        let before = cycle::hash_cells(&self.cells);
+        let (mut births, mut deaths) = (0, 0);
+        let states = self.state_count();
+        let wireworld = self.rule.is_wireworld();
+        let stochastic = self.stochastic.take();
+        let mut rng = self.rng.clone();
+        let custom = self.custom_rule.take();
+        let mut table = self.table.take();
+        let previous_states = match table {
+            Some(_) => self.states.clone(),
+            None => Vec::new(),
+        };
+        let larger = self.rule.larger_than_life().map(|larger| {
+            (*larger, larger.counts(&self.cells, self.width, self.height, self.boundary))
+        });
+        let weighted = self.weighted.as_ref().map(|weighted| {
+            (weighted, weighted.sums(&self.cells, self.width, self.height, self.boundary))
+        });
+        let next_with = |universe: &Universe, alive: bool, row: u32, col: u32, idx: usize| match (&weighted, &larger) {
+            (Some((weighted, sums)), _) => weighted.next(alive, sums[idx]),
+            (None, Some((larger, counts))) => larger.next(alive, counts[idx]),
+            (None, None) if universe.neighborhood != Neighborhood::MOORE => {
+                universe.rule.next(alive, universe.live_neighbor_count(row, col))
+            }
+            (None, None) => universe.rule.next_with(alive, universe.neighbor_bits(row, col)),
+        };
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let idx = self.get_index(row, col);
+                let cell = self.cells[idx];
+                let previous = self.states.get(idx).copied().unwrap_or(cell as u8);
+                // Custom rules see the eight nearest neighbors, whatever 
+                // the neighborhood, and states they make up mean dead. 
+                let state = match &custom {
+                    Some(rule) => Some(rule.next_state(previous, Neighbors(self.neighbor_bits(row, col))))
+                        .filter(|&state| state < states)
+                        .unwrap_or(0),
+                    None if table.is_some() => {
+                        let table = table.as_mut().unwrap();
+                        let neighbors = self.table_neighbors(&previous_states, row, col, table.neighborhood());
+                        table.next(previous, &neighbors[..table.neighborhood().offsets().len()])
+                    }
+                    None => match previous {
+                        0 if wireworld => 0,
+                        0 => next_with(self, false, row, col, idx) as u8,
+                        1 if next_with(self, true, row, col, idx) => 1,
+                        3 if wireworld => match next_with(self, false, row, col, idx) {
+                            true => 1,
+                            false => 3,
+                        },
+                        dying => (dying + 1) % states,
+                    },
+                };
+                // A survivor that loses its roll starts dying, a newborn 
+                // stays what it was. 
+                let lost = state == 1 && stochastic.as_ref().is_some_and(|dice| !dice.keeps(&mut rng, previous != 1));
+                let state = match (lost, previous) {
+                    (true, 1) => 2 % states,
+                    (true, _) => previous,
+                    (false, _) => state,
+                };
+                // Neighbors are read from `cells`, which stays as it 
+                // was until the end, so the state can change right away.
+                let next_cell = state == 1;
+                if let Some(old) = self.states.get_mut(idx) {
+                    *old = state;
+                }
+                next.set(idx, next_cell);
+                if next_cell != cell {
+                    if next_cell {
+                        self.population += 1;
+                        births += 1;
+                    } else {
+                        self.population -= 1;
+                        deaths += 1;
+                    }
+                    on_change(row, col, next_cell);
+                }
+            }
+        }
+
+        self.stochastic = stochastic;
+        self.rng = rng;
+        self.custom_rule = custom;
+        self.table = table;
+        self.end_step(next, before, 1, births, deaths);
+    }
+
+    // Hashlife can jump any number of generations at once. Edits made to 
+    // the window since the last step are pushed into the tree first, 
+    // then the window is read back out and compared with the old cells.
+    fn hashlife_step_into<F: FnMut(u32, u32, bool)>(&mut self, generations: u64, next: &mut FixedBitSet, on_change: F) {
+        let before = cycle::hash_cells(&self.cells);
+        let life = self.hashlife.as_mut().expect("Hashlife engine without a tree");
+        life.sync(&self.cells, self.width);
+        life.advance(generations);
+        life.render(self.width, self.height, next);
+        self.finish_step(next, before, generations, on_change);
+    }
+
+    // The bookkeeping shared by engines that compute the whole next 
+    // generation into `next` first: the population and on_change come 
+    // from comparing it with the current cells, word by word.
+    fn finish_step<F: FnMut(u32, u32, bool)>(&mut self, next: &mut FixedBitSet, before: u64, generations: u64, mut on_change: F) {
+        self.perf.counted();
+        let (mut births, mut deaths) = (0, 0);
+        for (word, (&old, &new)) in self.cells.as_slice().iter().zip(next.as_slice()).enumerate() {
+            let mut diff = old ^ new;
+            while diff != 0 {
+                let bit = diff.trailing_zeros();
+                let idx = word as u32 * 32 + bit;
+                let alive = new & (1 << bit) != 0;
+                if alive {
+                    self.population += 1;
+                    births += 1;
+                } else {
+                    self.population -= 1;
+                    deaths += 1;
+                }
+                on_change(idx / self.width, idx % self.width, alive);
+                diff &= diff - 1;
+            }
+        }
+
+        self.end_step(next, before, generations, births, deaths);
+    }
+
+    // Makes `next` the current generation and updates everything that 
+    // follows the cells from one step to the next. `before` is the hash 
+    // of the cells being replaced.
+    fn end_step(&mut self, next: &mut FixedBitSet, before: u64, generations: u64, births: u32, deaths: u32) {
+        self.perf.counted();
+        std::mem::swap(&mut self.cells, next);
+        self.color_cells(next);
+        self.cycles.observe(before, cycle::hash_cells(&self.cells), self.generation, generations);
+        self.age_cells(generations);
+        self.heat_cells(next, generations);
+        self.generation += generations;
+        self.last_step = TickStats::new(births, deaths, self.population);
+        self.populations.push(self.population);
+        if self.recorded_stats.is_some() {
+            self.record_step(births, deaths);
+        }
+        if self.autosave.as_ref().is_some_and(|autosave| autosave.is_due(self.generation)) {
+            // Not while a custom rule is set, which can't be saved: the 
+            // last capture from before it stays. 
+            if let (Ok(snapshot), Some(autosave)) = (self.snapshot(), &mut self.autosave) {
+                autosave.capture(snapshot);
+            }
+        }
+        self.perf.written();
+    }
+
+    // Called after each step: survivors get older, newborns start at 1 
+    // and the dead go back to 0. After a multi-generation Hashlife jump, 
+    // a cell alive at both ends is assumed to have been alive throughout.
+    fn age_cells(&mut self, generations: u64) {
+        if self.ages.is_empty() {
+            return;
+        }
+        let step = generations.min(u64::from(u16::MAX)) as u16;
+        for (idx, age) in self.ages.iter_mut().enumerate() {
+            *age = match (self.cells[idx], *age) {
+                (false, _) => 0,
+                (true, 0) => 1,
+                (true, age) => age.saturating_add(step),
+            };
+        }
+    }
+
+    // Called after each step with the previous generation: everything 
+    // fades, then every cell that was born or died gains one unit.
+    fn heat_cells(&mut self, previous: &FixedBitSet, generations: u64) {
+        if self.activity.is_empty() {
+            return;
+        }
+        let fade = self.activity_decay.powi(generations.min(i32::MAX as u64) as i32);
+        for heat in self.activity.iter_mut() {
+            *heat *= fade;
+        }
+        for (word, (&old, &new)) in previous.as_slice().iter().zip(self.cells.as_slice()).enumerate() {
+            let mut diff = old ^ new;
+            while diff != 0 {
+                self.activity[word * 32 + diff.trailing_zeros() as usize] += 1.0;
+                diff &= diff - 1;
+            }
+        }
+    }
+
+    // Called after each step with the previous generation. Newborns 
+    // are colored from the neighbors that were alive before the step, 
+    // which is exact for single ticks; after a multi-generation Hashlife 
+    // jump it is only a guess.
+    fn color_cells(&mut self, previous: &FixedBitSet) {
+        if self.colors.is_empty() {
+            return;
+        }
+        let (height, width) = (i64::from(self.height), i64::from(self.width));
+        for (word, (&old, &new)) in previous.as_slice().iter().zip(self.cells.as_slice()).enumerate() {
+            let mut born = !old & new;
+            while born != 0 {
+                let idx = word * 32 + born.trailing_zeros() as usize;
+                let (row, col) = ((idx as u32 / self.width) as i64, (idx as u32 % self.width) as i64);
+                let mut parents = [0; species::MAX_COLORS];
+                for delta_row in -1..=1 {
+                    for delta_col in -1..=1 {
+                        if delta_row == 0 && delta_col == 0 {
+                            continue;
+                        }
+
+                        let (mut r, mut c) = (row + delta_row, col + delta_col);
+                        if r < 0 || r >= height || c < 0 || c >= width {
+                            match self.boundary {
+                                Boundary::Torus => {
+                                    r = r.rem_euclid(height);
+                                    c = c.rem_euclid(width);
+                                }
+                                Boundary::Mirror => {
+                                    r = r.clamp(0, height - 1);
+                                    c = c.clamp(0, width - 1);
+                                }
+                                // Walls have no color.
+                                Boundary::Dead | Boundary::AliveWall => continue,
+                            }
+                        }
+                        let neighbor = self.get_index(r as u32, c as u32);
+                        // Newborns were dead in `previous`, so every color 
+                        // read here is still the one from before the step.
+                        if previous[neighbor] {
+                            parents[usize::from(self.colors[neighbor]) - 1] += 1;
+                        }
+                    }
+                }
+                self.colors[idx] = self.species.newborn(&parents);
+                born &= born - 1;
+            }
+        }
+        self.refresh_colors();
+    }
+
+    // Brings the colors in line with the cells: dead cells lose their 
+    // color, and cells that came alive without one, by an edit, undo 
+    // or redo, get the first color.
+    fn refresh_colors(&mut self) {
+        for (idx, color) in self.colors.iter_mut().enumerate() {
+            if !self.cells[idx] {
+                *color = 0;
+            } else if *color == 0 {
+                *color = 1;
+            }
+        }
+    }
+
+    // Brings the states in line with edits made since the last step: 
+    // cells drawn in are alive, and erased live cells are dead. Dying 
+    // cells that weren't touched keep dying.
+    fn refresh_states(&mut self) {
+        for (idx, state) in self.states.iter_mut().enumerate() {
+            if self.cells[idx] {
+                *state = 1;
+            } else if *state == 1 {
+                *state = 0;
+            }
+        }
+    }
+
+    // Brings the ages in line with edits made since the last step: 
+    // cells drawn in count as newborns, erased ones drop to 0.
+    fn refresh_ages(&mut self) {
+        let size = (self.width * self.height) as usize;
+        if self.ages.len() != size {
+            self.ages = self.cell_buffer(0);
+        }
+        for (idx, age) in self.ages.iter_mut().enumerate() {
+            if !self.cells[idx] {
+                *age = 0;
+            } else if *age == 0 {
+                *age = 1;
+            }
+        }
+    }
+
+}//^-- impl Universe
+
+// Now we have everything we need to compute the next generation from 
+// the current one! 
+
+
+// Each of the Game's rules follows a straightforward translation into a 
+// condition on a match expression. Additionally, because we want JavaScript 
+// to control when ticks happen, we will put this method inside 
+// a #[wasm_bindgen] block, so that it gets exposed to JavaScript.
+
+
+// Public methods, which the wasm crate exports to JavaScript.
+impl Universe {
+    pub fn tick(&mut self) {
+        self.timed(|universe| universe.step(|_, _, _| {}));
+    }
+
+    // Advances n generations in a single call, which saves the 
+    // JavaScript <-> wasm round trip of calling tick() n times.
+    // The whole batch is a single step as far as undo is concerned.
+    pub fn tick_many(&mut self, n: u32) {
+        self.timed(|universe| {
+            universe.with_next(|universe, next| {
+                if universe.engine == Engine::Hashlife && universe.hashlife_rule() && universe.turmites.is_empty() {
+                    universe.hashlife_step_into(u64::from(n), next, |_, _, _| {});
+                    return;
+                }
+                for _ in 0..n {
+                    universe.step_into(next, |_, _, _| {});
+                }
+            })
+        });
+    }
+
+    // Runs `generations` generations, calling `frame` with the universe 
+    // as it is now and then after every (frame_skip + 1)th of them. 
+    #[cfg(any(feature = "gif", feature = "apng"))]
+    fn record_frames(
+        &mut self,
+        generations: u32,
+        frame_skip: u32,
+        mut frame: impl FnMut(&mut Universe) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        frame(self)?;
+        let step = frame_skip.saturating_add(1);
+        let mut done = 0;
+        while done < generations {
+            let n = step.min(generations - done);
+            self.tick_many(n);
+            done += n;
+            frame(self)?;
+        }
+        Ok(())
+    }
+
+    // Runs `generations` generations and records them as a looping GIF, 
+    // drawn like the canvas with cell_size pixel cells. Only every 
+    // (frame_skip + 1)th generation is a frame, the first being the 
+    // universe as it is now. 
+    #[cfg(feature = "gif")]
+    pub fn record_gif(&mut self, generations: u32, cell_size: u32, frame_skip: u32) -> Result<Vec<u8>, Error> {
+        let mut gif = animation::GifRecorder::new(self.width, self.height, cell_size)?;
+        self.record_frames(generations, frame_skip, |universe| gif.add_frame(&universe.cells))?;
+        gif.finish()
+    }
+
+    // Like record_gif(), but as a full-color animated PNG, so cells can 
+    // be colored by age or activity. Both start being tracked with the 
+    // first frame if they weren't already. 
+    #[cfg(feature = "apng")]
+    pub fn record_apng(
+        &mut self,
+        generations: u32,
+        cell_size: u32,
+        frame_skip: u32,
+        coloring: Coloring,
+    ) -> Result<Vec<u8>, Error> {
+        let frames = 1 + generations.div_ceil(frame_skip.saturating_add(1));
+        let mut apng = Vec::new();
+        let mut recorder = animation::ApngRecorder::new(&mut apng, self.width, self.height, cell_size, frames)?;
+        self.record_frames(generations, frame_skip, |universe| {
+            match coloring {
+                Coloring::Plain => {}
+                Coloring::Age => universe.refresh_ages(),
+                Coloring::Activity => {
+                    universe.activity();
+                }
+            }
+            recorder.add_frame(&animation::cell_colors(coloring, &universe.cells, &universe.ages, &universe.activity))
+        })?;
+        recorder.finish()?;
+        Ok(apng)
+    }
+
+    // Keeps ticking until `budget` milliseconds, as measured by `now`, 
+    // are nearly spent: another generation only starts if the ones so 
+    // far say it will fit. At least one always runs. Returns how many 
+    // did, which as with tick_many() count as one step for undo. 
+    pub fn tick_for_budget(&mut self, budget: f64, mut now: impl FnMut() -> f64) -> u32 {
+        let start = now();
+        let mut ran = 0;
+        self.timed(|universe| {
+            universe.with_next(|universe, next| loop {
+                universe.step_into(next, |_, _, _| {});
+                ran += 1;
+                let elapsed = now() - start;
+                if elapsed + elapsed / f64::from(ran) > budget || ran == u32::MAX {
+                    break;
+                }
+            })
+        });
+        ran
+    }
+
+    // Like tick(), but the rows are split into up to `num_workers` bands 
+    // that are computed at the same time. With the `parallel` feature 
+    // (and, in the browser, a threaded build and a worker pool started by 
+    // init_thread_pool) each band runs on its own thread; otherwise they 
+    // run in turn. Only the Naive engine on a torus is split up; 
+    // anything else falls back to tick().
+    pub fn tick_parallel(&mut self, num_workers: u32) {
+        let splittable = self.engine == Engine::Naive
+            && self.simple_rule()
+            && self.turmites.is_empty()
+            && self.boundary == Boundary::Torus
+            && self.width >= simd::MIN_WIDTH
+            && self.height >= 2;
+        if !splittable {
+            return self.tick();
+        }
+
+        self.timed(|universe| {
+            universe.with_next(|universe, next| {
+                let before = cycle::hash_cells(&universe.cells);
+                parallel::step_torus(&universe.cells, next, universe.width, universe.height, universe.rule, num_workers);
+                universe.finish_step(next, before, 1, |_, _, _| {});
+            })
+        });
+    }
+
+    // Like tick(), but also reports which cells flipped this generation, 
+    // as a flat list of (row, col, new_state) triples. JavaScript receives 
+    // it as a Uint32Array and can repaint only those cells, 
+    // instead of the whole grid.
+    pub fn tick_delta(&mut self) -> Vec<u32> {
+        self.timed(|universe| {
+            let mut delta = Vec::new();
+            universe.step(|row, col, alive| {
+                delta.push(row);
+                delta.push(col);
+                delta.push(alive as u32);
+            });
+            delta
+        })
+    }
+
+    // We define a constructor that initializes the universe 
+    // with an interesting pattern of live and dead cells. 
+        //    pub fn new() -> Universe {
+        //        let width = 64;
+        //        let height = 64;
+        //
+        //        let cells = (0..width * height)
+        //            .map(|i| {
+        //                //if i % 2 == 0 || i % 7 == 0 {
+        //                if js_sys::Math::random() < 0.5 {
+        //                    Cell::Alive
+        //                } else {
+        //                    Cell::Dead
+        //                }
+        //            })
+        //            .collect();
+        //
+        //        Universe {
+        //            width,
+        //            height,
+        //            cells,
+        //        }
+        //    }
+    // The Universe constructor adjusted to use FixedBitSet. 
+    // There is no js_sys::Math::random() outside the browser, so this is 
+    // the fixed pattern again; the wasm crate's constructor starts from 
+    // a random soup instead.
+    pub fn new() -> Universe {
+        let mut universe = Universe::empty(64, 64, LifeRule::conway()).expect("64x64 is a valid size");
+
+        for i in 0..universe.cells.len() {
+            universe.cells.set(i, i % 2 == 0 || i % 7 == 0);
+            // cells.set(i, js_sys::Math::random() < 0.5);
+        }
+
+        universe.recount();
+        universe
+    }
+
+    // An empty universe of the given size running an arbitrary 
+    // Life-like rule, e.g. "B36/S23" (HighLife) or "B3678/S34678" 
+    // (Day & Night).
+    pub fn with_rule(width: u32, height: u32, rule: &str) -> Result<Universe, Error> {
+        Universe::empty(width, height, rule.parse().inspect_err(parse_failed("the rule"))?)
+    }
+
+    // A reproducible random soup: each cell is alive with probability 
+    // `density`, and the same seed always produces the same soup.
+    pub fn random(width: u32, height: u32, density: f32, seed: u64) -> Result<Universe, Error> {
+        if !(0.0..=1.0).contains(&density) {
+            return Err(Error::new("density must be between 0 and 1"));
+        }
+
+        let mut universe = Universe::empty(width, height, LifeRule::conway())?;
+        universe.set_seed(seed);
+        universe.soup(density)?;
+        Ok(universe)
+    }
+
+    // Fills the universe with a soup drawn from its own generator. 
+    pub(crate) fn soup(&mut self, density: f32) -> Result<(), Error> {
+        if !(0.0..=1.0).contains(&density) {
+            return Err(Error::new("density must be between 0 and 1"));
+        }
+        for i in 0..self.cells.len() {
+            self.cells.set(i, self.rng.next_f32() < density);
+        }
+        self.recount();
+        Ok(())
+    }
+
+    // A new soup in place of the cells, which can be undone. Soups go on 
+    // from wherever the generator is, so each one is different, and the 
+    // same after set_seed() with the same seed again. 
+    pub fn randomize(&mut self, density: f32) -> Result<(), Error> {
+        self.record(|universe| universe.soup(density))
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // Starts the universe's generator over from `seed`. 
+    pub fn set_seed(&mut self, seed: u64) {
+        log::info!("random seed {}", seed);
+        self.seed = seed;
+        self.rng = Rng::new(seed);
+    }
+
+    // Flips one of the B1 to B8 and S0 to S8 conditions of a B/S rule, 
+    // picked with the universe's generator. B0 is left alone, as it 
+    // makes the whole background flash. 
+    pub fn mutate_rule(&mut self) -> Result<(), Error> {
+        if !self.rule.is_life_like() || self.table.is_some() {
+            return Err(Error::new(&format!("only B/S rules can be mutated, not {}", self.rule())));
+        }
+        self.rule = self.rule.mutated(&mut self.rng);
+        log::info!("rule mutated to {}", self.rule);
+        self.cycles = CycleDetector::default();
+        if self.hashlife.is_some() {
+            self.hashlife = Some(Hashlife::new(self.rule, &self.cells, self.width, self.height));
+        }
+        Ok(())
+    }
+
+    // A reproducible random start for a preset. Life gets the usual soup 
+    // at half density. Brian's Brain gets a sparse one, with a tenth of 
+    // the cells firing and another tenth refractory, so that sparks 
+    // can be seen spreading out before they fill the universe. 
+    pub fn from_preset(width: u32, height: u32, preset: Preset, seed: u64) -> Result<Universe, Error> {
+        let mut universe = Universe::empty(width, height, preset.rule())?;
+        universe.set_seed(seed);
+        for idx in 0..universe.cells.len() {
+            let roll = universe.rng.next_f32();
+            let state = match preset {
+                Preset::Life => (roll < 0.5) as u8,
+                Preset::BriansBrain if roll < 0.1 => 1,
+                Preset::BriansBrain if roll < 0.2 => 2,
+                Preset::BriansBrain => 0,
+            };
+            universe.cells.set(idx, state == 1);
+            if let Some(old) = universe.states.get_mut(idx) {
+                *old = state;
+            }
+        }
+        universe.recount();
+        Ok(universe)
+    }
+
+    // Builds a universe from a Run Length Encoded pattern, as found on 
+    // LifeWiki or exported by Golly. The universe is exactly as large as 
+    // the pattern, unless the rule asks for a bigger torus 
+    // (e.g. "rule = B3/S23:T64,64"), in which case the pattern is centered.
+    pub fn from_rle(rle: &str) -> Result<Universe, Error> {
+        let pattern = rle::parse(rle).inspect_err(parse_failed("RLE"))?;
+        let (width, height) = pattern.torus.unwrap_or((pattern.width, pattern.height));
+        if width == 0 || height == 0 {
+            return Err(Error::new("RLE pattern is empty"));
+        }
+        if width < pattern.width || height < pattern.height {
+            return Err(Error::new("RLE pattern does not fit in its torus"));
+        }
+
+        let mut universe = Universe::empty(width, height, pattern.rule.unwrap_or_default())?;
+        let (row, col) = ((height - pattern.height) / 2, (width - pattern.width) / 2);
+        let states = pattern.states.clone();
+        universe.paste(&pattern.into(), row, col)?;
+        universe.refresh_states();
+        // Multi-state patterns, like Wireworld circuits, also have cells 
+        // in the other states. 
+        for (r, c, state) in states {
+            if state >= universe.rule.states() {
+                return Err(Error::new("RLE pattern has more states than its rule"));
+            }
+            let idx = universe.get_index(row + r, col + c);
+            universe.states[idx] = state;
+        }
+        Ok(universe)
+    }
+
+    // Builds a universe just large enough for a pattern in LifeWiki's 
+    // plaintext (.cells) format, running Conway's Life. 
+    pub fn from_plaintext(text: &str) -> Result<Universe, Error> {
+        Universe::fitting(plaintext::parse(text).inspect_err(parse_failed("plaintext"))?)
+    }
+
+    // Builds a universe from a pattern in any of the formats 
+    // PatternFormat knows, telling them apart by their headers. 
+    pub fn from_pattern_text(text: &str) -> Result<Universe, Error> {
+        match PatternFormat::detect(text) {
+            Some(PatternFormat::Rle) => Universe::from_rle(text),
+            _ => Universe::fitting(format::parse_any(text).inspect_err(parse_failed("the pattern"))?),
+        }
+    }
+
+    // The inverse of the Display output: a grid of ◻ and ◼, or of . and 
+    // O, one line per row, running Conway's Life. Handy for writing out 
+    // what a test expects, e.g. "...\nOOO\n...". 
+    pub fn from_text(text: &str) -> Result<Universe, Error> {
+        let (width, height, cells) = text::parse(text).inspect_err(parse_failed("the text grid"))?;
+        let mut universe = Universe::empty(width, height, LifeRule::default())?;
+        universe.cells = cells;
+        universe.recount();
+        Ok(universe)
+    }
+
+    // A Golly Macrocell (.mc) file, for patterns too big to ever fit a 
+    // grid. The universe runs on the Hashlife engine, and its width x 
+    // height window shows the middle of the pattern; the rest lives on 
+    // in the tree outside the window. Only plain B/S rules are supported.
+    pub fn from_macrocell(text: &str, width: u32, height: u32) -> Result<Universe, Error> {
+        let macrocell = macrocell::parse(text).inspect_err(parse_failed("Macrocell"))?;
+        let rule = macrocell.rule.unwrap_or_default();
+        if !rule.is_life_like() || rule.masks().0 & 1 != 0 {
+            return Err(Error::new("Macrocell patterns need a B/S rule without B0 to run on Hashlife"));
+        }
+        let mut universe = Universe::empty(width, height, rule)?;
+        universe.set_engine(Engine::Hashlife);
+        let life = universe.hashlife.as_mut().expect("Hashlife engine without a tree");
+        life.load_macrocell(&macrocell.nodes, width, height);
+        life.render(width, height, &mut universe.cells);
+        universe.recount();
+        universe.generation = macrocell.generation;
+        Ok(universe)
+    }
+
+    // The inverse of from_macrocell(). With Hashlife, this is the whole 
+    // plane, including what has left the window; other engines only 
+    // have the window. 
+    pub fn to_macrocell(&mut self) -> String {
+        let nodes = match self.hashlife.as_mut() {
+            Some(life) => {
+                life.sync(&self.cells, self.width);
+                life.macrocell_nodes()
+            }
+            None => Hashlife::new(self.rule, &self.cells, self.width, self.height).macrocell_nodes(),
+        };
+        macrocell::write(&macrocell::Macrocell {
+            rule: Some(self.rule),
+            generation: self.generation,
+            nodes,
+        })
+    }
+
+    // A universe exactly as large as the pattern, running its rule.
+    fn fitting(pattern: rle::RlePattern) -> Result<Universe, Error> {
+        if pattern.width == 0 || pattern.height == 0 {
+            return Err(Error::new("pattern is empty"));
+        }
+        let mut universe = Universe::empty(pattern.width, pattern.height, pattern.rule.unwrap_or_default())?;
+        universe.paste(&pattern.into(), 0, 0)?;
+        Ok(universe)
+    }
+
+    // Pastes a plaintext pattern with its top-left corner at (row, col).
+    pub fn insert_plaintext(&mut self, text: &str, row: u32, col: u32) -> Result<(), Error> {
+        let pattern = plaintext::parse(text).inspect_err(parse_failed("plaintext"))?;
+        self.record(|universe| universe.paste(&pattern.into(), row % universe.height, col % universe.width))
+    }
+
+    // Pastes a Run Length Encoded pattern with its top-left corner 
+    // at (row, col). The pattern's own rule, if any, is ignored.
+    pub fn insert_rle(&mut self, rle: &str, row: u32, col: u32) -> Result<(), Error> {
+        let pattern = rle::parse(rle).inspect_err(parse_failed("RLE"))?;
+        self.record(|universe| universe.paste(&pattern.into(), row % universe.height, col % universe.width))
+    }
+
+    // Pastes one of the built-in patterns (see list_patterns()) 
+    // with its top-left corner at (row, col).
+    pub fn insert_pattern(&mut self, name: &str, row: u32, col: u32) -> Result<(), Error> {
+        let pattern = Pattern::builtin(name)?;
+        self.stamp(&pattern, row, col)
+    }
+
+    // Stamps a Pattern with its top-left corner at (row, col), 
+    // after applying the pattern's own translation.
+    pub fn stamp(&mut self, pattern: &Pattern, row: u32, col: u32) -> Result<(), Error> {
+        self.record(|universe| universe.paste(pattern, row % universe.height, col % universe.width))
+    }
+
+    // Saves the whole simulation state (size, rule or rule table, 
+    // generation, boundary, neighborhood, weighted and stochastic rules, 
+    // engine, random seed, species, turmites, cells and their states) 
+    // as a compact binary blob, e.g. for IndexedDB or a file download. 
+    // A custom rule can't be saved, so neither can a universe with one. 
+    pub fn save(&self) -> Result<Vec<u8>, Error> {
+        Ok(self.snapshot()?.to_bytes())
+    }
+
+    fn snapshot(&self) -> Result<Snapshot, Error> {
+        if self.custom_rule.is_some() {
+            return Err(Error::new("a universe with a custom rule can't be saved"));
+        }
+        Ok(Snapshot {
+            version: snapshot::FORMAT_VERSION,
+            width: self.width,
+            height: self.height,
+            rule: self.rule.to_string(),
+            generation: self.generation,
+            boundary: self.boundary,
+            neighborhood: self.neighborhood,
+            weighted: self.weighted.clone(),
+            engine: self.engine,
+            seed: self.seed,
+            random_state: self.rng.state(),
+            stochastic: self.stochastic.clone(),
+            rule_table: self.table.as_ref().map(|table| table.source().to_string()),
+            species: self.species,
+            turmites: self.turmites.clone(),
+            cells: self.cells.as_slice().to_vec(),
+            states: self.states.clone(),
+            colors: self.colors.clone(),
+        })
+    }
+
+    // The inverse of save().
+    pub fn restore(bytes: &[u8]) -> Result<Universe, Error> {
+        let snapshot = Snapshot::from_bytes(bytes).map_err(|e| Error::new(&e))?;
+        Universe::from_snapshot(snapshot)
+    }
+
+    fn from_snapshot(snapshot: Snapshot) -> Result<Universe, Error> {
+        let mut universe = Universe::empty(snapshot.width, snapshot.height, snapshot.rule.parse()?)?;
+        universe.cells.as_mut_slice().copy_from_slice(&snapshot.cells);
+        universe.recount();
+        if let Some(table) = &snapshot.rule_table {
+            universe.load_rule_file(table)?;
+        }
+        universe.set_species(snapshot.species);
+        // Dying cells and colors as they were, then made to agree with 
+        // the cells in case the two were saved out of step. 
+        let states = universe.table.as_ref().map_or(universe.rule.states(), |table| table.states());
+        let colors = universe.species.colors();
+        for (name, saved, own, most) in [
+            ("states", snapshot.states, &mut universe.states, states - 1),
+            ("colors", snapshot.colors, &mut universe.colors, colors),
+        ] {
+            if saved.len() != own.len() || saved.iter().any(|&value| value > most) {
+                return Err(Error::new(&format!("the snapshot's {} don't fit its rule", name)));
+            }
+            *own = saved;
+        }
+        universe.refresh_states();
+        universe.refresh_colors();
+        if let Some(stochastic) = snapshot.stochastic {
+            universe.stochastic = Some(Stochastic::new(stochastic.birth(), stochastic.survival(), stochastic.death())?);
+        }
+        for turmite in snapshot.turmites {
+            universe.add_turmite(turmite.checked()?)?;
+        }
+        universe.generation = snapshot.generation;
+        universe.boundary = snapshot.boundary;
+        universe.set_neighborhood(snapshot.neighborhood)?;
+        universe.weighted = snapshot.weighted;
+        universe.set_engine(snapshot.engine);
+        universe.seed = snapshot.seed;
+        universe.rng = Rng::new(snapshot.random_state);
+        Ok(universe)
+    }
+
+    // Captures the universe every `interval` generations from now on, 
+    // starting with a full save right away, so a crashed tab can come 
+    // back with import_autosave(). Captures only keep the cells that 
+    // changed since the last full save, see autosave.rs. 
+    pub fn enable_autosave(&mut self, interval: u32) -> Result<(), Error> {
+        if interval == 0 {
+            return Err(Error::new("the autosave interval must be at least one generation"));
+        }
+        self.autosave = Some(Autosave::new(u64::from(interval), self.snapshot()?));
+        Ok(())
+    }
+
+    pub fn disable_autosave(&mut self) {
+        self.autosave = None;
+    }
+
+    // How many generations go by between captures, 0 while autosave is off.
+    pub fn autosave_interval(&self) -> u32 {
+        self.autosave.as_ref().map_or(0, |autosave| autosave.interval() as u32)
+    }
+
+    // The last capture, as a blob to keep e.g. in IndexedDB. 
+    pub fn export_autosave(&self) -> Result<Vec<u8>, Error> {
+        let autosave = self.autosave.as_ref().ok_or_else(|| Error::new("autosave is not enabled"))?;
+        Ok(autosave.to_bytes())
+    }
+
+    // The universe as export_autosave() last captured it. 
+    pub fn import_autosave(bytes: &[u8]) -> Result<Universe, Error> {
+        Universe::from_snapshot(Autosave::restore(bytes).map_err(|e| Error::new(&e))?)
+    }
+
+    // Serializes the live cells as RLE, cropped to their bounding box, 
+    // so an evolved pattern can be pasted into Golly or LifeWiki. 
+    // Cells in the other states of multi-state rules, like dying cells 
+    // or Wireworld's wires, are written too.
+    pub fn to_rle(&self) -> String {
+        rle::write(&self.cropped())
+    }
+
+    // The live cells in LifeWiki's plaintext format, cropped like 
+    // to_rle(). Plaintext has no rule and only two states. 
+    pub fn to_plaintext(&self) -> Result<String, Error> {
+        plaintext::write(&self.cropped())
+    }
+
+    // The live cells in any of the formats PatternFormat knows, cropped 
+    // like to_rle(). Only RLE keeps the other states. 
+    pub fn to_pattern_text(&self, format: PatternFormat) -> Result<String, Error> {
+        format::write(&self.cropped(), format)
+    }
+
+    // Every cell in a state other than 0, cropped to their bounding box.
+    fn cropped(&self) -> rle::RlePattern {
+        let mut live = Vec::new();
+        let mut others = Vec::new();
+        let (mut top, mut left, mut bottom, mut right) = (self.height, self.width, 0, 0);
+        let in_other_states = self.states.iter().enumerate().filter(|&(_, &state)| state > 1);
+        for idx in self.cells.ones().chain(in_other_states.map(|(idx, _)| idx)) {
+            let row = idx as u32 / self.width;
+            let col = idx as u32 % self.width;
+            top = top.min(row);
+            left = left.min(col);
+            bottom = bottom.max(row + 1);
+            right = right.max(col + 1);
+            if self.cells[idx] {
+                live.push((row, col));
+            } else {
+                others.push((row, col, self.states[idx]));
+            }
+        }
+
+        if live.is_empty() && others.is_empty() {
+            rle::RlePattern {
+                width: 0,
+                height: 0,
+                rule: Some(self.rule),
+                torus: None,
+                cells: live,
+                states: others,
+            }
+        } else {
+            rle::RlePattern {
+                width: right - left,
+                height: bottom - top,
+                rule: Some(self.rule),
+                torus: None,
+                cells: live.into_iter().map(|(row, col)| (row - top, col - left)).collect(),
+                states: others.into_iter().map(|(row, col, state)| (row - top, col - left, state)).collect(),
+            }
+        }
+    }
+
+    // Selects the rectangle of `width` x `height` cells with its top-left 
+    // corner at (row, col), which must be inside the universe. 
+    pub fn select(&mut self, row: u32, col: u32, width: u32, height: u32) -> Result<(), Error> {
+        if width == 0 || height == 0 {
+            return Err(Error::new("a selection must be at least one cell wide and high"));
+        }
+        let fits = |start: u32, length: u32, size: u32| u64::from(start) + u64::from(length) <= u64::from(size);
+        if !fits(row, height, self.height) || !fits(col, width, self.width) {
+            return Err(Error::new(&format!(
+                "a {}x{} selection at ({}, {}) does not fit in the universe",
+                width, height, row, col
+            )));
+        }
+        self.selection = Some((row, col, width, height));
+        Ok(())
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selection = None;
+    }
+
+    // (row, col, width, height), if anything is selected.
+    pub fn selection(&self) -> Option<(u32, u32, u32, u32)> {
+        self.selection
+    }
+
+    // The selected cells as RLE, as big as the selection, so pasting 
+    // them puts them back where they were relative to its corner. 
+    // Without a selection it is the whole universe, like to_rle(). 
+    pub fn selection_to_rle(&self) -> String {
+        let Some((top, left, width, height)) = self.selection else {
+            return self.to_rle();
+        };
+        let mut pattern = rle::RlePattern {
+            width,
+            height,
+            rule: Some(self.rule),
+            torus: None,
+            cells: Vec::new(),
+            states: Vec::new(),
+        };
+        for row in 0..height {
+            for col in 0..width {
+                let idx = self.get_index(top + row, left + col);
+                if self.cells[idx] {
+                    pattern.cells.push((row, col));
+                } else if self.states.get(idx).is_some_and(|&state| state > 1) {
+                    pattern.states.push((row, col, self.states[idx]));
+                }
+            }
+        }
+        rle::write(&pattern)
+    }
+
+    // A short, URL-safe string holding the size, rule and cells, 
+    // for "copy link to this universe": the whole grid as RLE, 
+    // deflated and base64-encoded. 
+    pub fn to_share_string(&self) -> String {
+        let pattern = rle::RlePattern {
+            width: self.width,
+            height: self.height,
+            rule: Some(self.rule),
+            torus: None,
+            cells: self
+                .cells
+                .ones()
+                .map(|idx| (idx as u32 / self.width, idx as u32 % self.width))
+                .collect(),
+            states: self
+                .states
+                .iter()
+                .enumerate()
+                .filter(|&(_, &state)| state > 1)
+                .map(|(idx, &state)| (idx as u32 / self.width, idx as u32 % self.width, state))
+                .collect(),
+        };
+        share::encode(&rle::write(&pattern))
+    }
+
+    // The inverse of to_share_string().
+    pub fn from_share_string(share: &str) -> Result<Universe, Error> {
+        let rle = share::decode(share).map_err(|e| Error::new(&e))?;
+        Universe::from_rle(&rle)
+    }
+
+    // Reading and editing single cells, e.g. from a click on the canvas. 
+    // Out-of-range coordinates are reported as an error instead of 
+    // panicking the whole wasm instance.
+    pub fn get_cell(&self, row: u32, col: u32) -> Result<Cell, Error> {
+        let idx = self.checked_index(row, col)?;
+        Ok(if self.cells[idx] { Cell::Alive } else { Cell::Dead })
+    }
+
+    pub fn set_cell(&mut self, row: u32, col: u32, cell: Cell) -> Result<(), Error> {
+        let idx = self.checked_index(row, col)?;
+        let alive = cell == Cell::Alive;
+        if self.cells[idx] != alive {
+            self.record(|universe| universe.apply(&[idx as u32], universe.generation));
+        }
+        Ok(())
+    }
+
+    pub fn toggle_cell(&mut self, row: u32, col: u32) -> Result<(), Error> {
+        let idx = self.checked_index(row, col)?;
+        let cell = if self.cells[idx] { Cell::Dead } else { Cell::Alive };
+        self.set_cell(row, col, cell)
+    }
+
+    // Turns many cells alive in a single call, for brush strokes and other 
+    // drawing tools. `cells` is a flat list of (row, col) pairs, 
+    // i.e. [row0, col0, row1, col1, ...]. Every pair is validated before 
+    // anything is written, so a bad coordinate leaves the universe untouched.
+    pub fn set_cells(&mut self, cells: &[u32]) -> Result<(), Error> {
+        if !cells.len().is_multiple_of(2) {
+            return Err(Error::new("set_cells expects (row, col) pairs"));
+        }
+
+        let indices = cells
+            .chunks(2)
+            .map(|pair| self.checked_index(pair[0], pair[1]))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.record(|universe| {
+            for idx in indices {
+                if !universe.cells.put(idx) {
+                    universe.population += 1;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    // Replaces every cell at once from packed words in the cells_bits() 
+    // layout, e.g. cells read back from a GpuUniverse.
+    pub fn set_cells_bits(&mut self, bits: &[u32]) -> Result<(), Error> {
+        if bits.len() != self.cells.as_slice().len() {
+            return Err(Error::new(&format!(
+                "expected {} words of cells, got {}",
+                self.cells.as_slice().len(),
+                bits.len()
+            )));
+        }
+        let used = (self.width * self.height) % 32;
+        if used != 0 && bits[bits.len() - 1] >> used != 0 {
+            return Err(Error::new("cell words have bits set past the last cell"));
+        }
+        self.record(|universe| {
+            universe.cells.as_mut_slice().copy_from_slice(bits);
+            universe.recount();
+        });
+        Ok(())
+    }
+
+    // Undo and redo cover ticks and edits alike. Resizing clears the 
+    // history, since old changes no longer line up with the new grid. 
+    // With the Hashlife engine, only the window is restored: 
+    // cells that already left it stay where they are.
+    pub fn undo(&mut self) -> bool {
+        match self.history.undo() {
+            Some(change) => {
+                let (change, generation) = (change.clone(), change.generation_before);
+                self.replay(&change, generation);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        match self.history.redo() {
+            Some(change) => {
+                let (change, generation) = (change.clone(), change.generation_after);
+                self.replay(&change, generation);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    // How many steps can be undone; 0 turns history off.
+    pub fn history_depth(&self) -> u32 {
+        self.history.depth() as u32
+    }
+
+    pub fn set_history_depth(&mut self, depth: u32) {
+        self.history.set_depth(depth as usize);
+    }
+
+    // The Hashlife engine always runs on an unbounded plane, 
+    // so the boundary only affects the Naive and BitParallel engines.
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
+        // The old generations evolved under different edges.
+        self.cycles = CycleDetector::default();
+    }
+
+    pub fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+
+    // Counts the rule's B and S digits over another neighborhood, 
+    // e.g. von Neumann's four cells. Only plain counting rules can: 
+    // isotropic and Larger than Life rules bring their own neighborhood.
+    pub fn set_neighborhood(&mut self, neighborhood: Neighborhood) -> Result<(), Error> {
+        if neighborhood != Neighborhood::MOORE && !self.rule.is_totalistic() {
+            return Err(Error::new("only B/S and Generations rules can count other neighborhoods"));
+        }
+        log::info!("neighborhood set to {:?}", neighborhood);
+        self.neighborhood = neighborhood;
+        self.cycles = CycleDetector::default();
+        Ok(())
+    }
+
+    pub fn weighted_rule(&self) -> Option<&WeightedRule> {
+        self.weighted.as_ref()
+    }
+
+    // Decides births and survivals with a weighted kernel instead of the 
+    // rule's neighbor counts, until it is set back to None. The rule still 
+    // says how many states cells go through.
+    pub fn set_weighted_rule(&mut self, weighted: Option<WeightedRule>) {
+        log::info!("weighted rule {}", if weighted.is_some() { "set" } else { "cleared" });
+        self.weighted = weighted;
+        self.cycles = CycleDetector::default();
+    }
+
+    pub fn has_custom_rule(&self) -> bool {
+        self.custom_rule.is_some()
+    }
+
+    // Hands every cell's next state over to `rule`, until it is set back 
+    // to None. It is much slower than the built-in rules, but anything 
+    // goes. The rule still says how many states there are. 
+    pub fn set_custom_rule(&mut self, rule: Option<Box<dyn Rule>>) {
+        log::info!("custom rule {}", if rule.is_some() { "set" } else { "cleared" });
+        self.custom_rule = rule;
+        self.cycles = CycleDetector::default();
+    }
+
+    pub fn stochastic(&self) -> Option<&Stochastic> {
+        self.stochastic.as_ref()
+    }
+
+    // Makes births, survivals and deaths random on top of the rule, 
+    // or deterministic again with None. 
+    pub fn set_stochastic(&mut self, stochastic: Option<Stochastic>) {
+        log::info!("stochastic rule {}", if stochastic.is_some() { "set" } else { "cleared" });
+        self.stochastic = stochastic;
+        self.cycles = CycleDetector::default();
+    }
+
+    // Reports whether the universe has settled into a still life or an 
+    // oscillator with a period of at most `max_period`, by comparing 
+    // the current state with the hashes of the last generations. 
+    // Returns undefined while it is still changing. Only uninterrupted 
+    // ticks count: editing a cell starts the watch over.
+    pub fn detect_cycle(&self, max_period: u32) -> Option<Cycle> {
+        self.cycles.detect(cycle::hash_cells(&self.cells), self.generation, max_period)
+    }
+
+    // Ticks until the universe dies out or settles into a cycle of at 
+    // most `max_period` generations, or until `max_generations` ticks 
+    // have run, whichever comes first. The whole run happens in wasm, 
+    // and is a single step for undo.
+    pub fn run_until_stable(&mut self, max_generations: u32, max_period: u32) -> StabilizationReport {
+        self.timed(|universe| universe.with_next(|universe, next| {
+            let mut ticks = 0;
+            let mut current = cycle::hash_cells(&universe.cells);
+            loop {
+                if universe.population == 0 {
+                    return StabilizationReport::new(StopReason::DiedOut, universe.generation, ticks, None);
+                }
+                if let Some(found) = universe.cycles.detect(current, universe.generation, max_period) {
+                    return StabilizationReport::new(StopReason::Cycle, universe.generation, ticks, Some(found));
+                }
+                if ticks == max_generations {
+                    return StabilizationReport::new(StopReason::GenerationCap, universe.generation, ticks, None);
+                }
+                universe.step_into(next, |_, _, _| {});
+                // Nothing else touches the cells during the run, 
+                // so the hash taken by the step is still current.
+                current = universe.cycles.latest().expect("a step was just observed");
+                ticks += 1;
+            }
+        }))
+    }
+
+    // An empty universe driven by the given engine.
+    pub fn with_engine(width: u32, height: u32, engine: Engine) -> Result<Universe, Error> {
+        let mut universe = Universe::empty(width, height, LifeRule::conway())?;
+        universe.set_engine(engine);
+        Ok(universe)
+    }
+
+    pub fn engine(&self) -> Engine {
+        self.engine
+    }
+
+    // Switching engines keeps the cells in the window. Going back from 
+    // Hashlife to Naive drops whatever has left the window.
+    pub fn set_engine(&mut self, engine: Engine) {
+        log::info!("using the {:?} engine", engine);
+        if !self.simple_rule() {
+            log::debug!("rule {} runs on the general loop whatever the engine", self.rule());
+        } else if engine == Engine::Hashlife && !self.hashlife_rule() {
+            log::debug!("rule {} has B0 and runs on the Naive loop instead of Hashlife", self.rule());
+        }
+        self.engine = engine;
+        self.hashlife = match engine {
+            Engine::Naive | Engine::BitParallel => None,
+            Engine::Hashlife => Some(Hashlife::new(self.rule, &self.cells, self.width, self.height)),
+        };
+    }
+
+    // The current rule, written back in canonical B/S notation, or the 
+    // name of the rule table in use.
+    pub fn rule(&self) -> String {
+        match &self.table {
+            Some(table) => table.name().to_string(),
+            None => self.rule.to_string(),
+        }
+    }
+
+    // Runs a Golly .rule file from now on, e.g. one of the many shared 
+    // on the Golly forums. Its @TABLE section replaces the rule, and 
+    // its @COLORS, if any, show up in table_colors(). Live cells stay 
+    // in state 1; with more than two states, only state 1 counts as 
+    // alive in cells() and the population. 
+    pub fn load_rule_file(&mut self, contents: &str) -> Result<(), Error> {
+        let table = RuleTable::parse(contents).inspect_err(parse_failed("the rule file"))?;
+        self.states = match table.states() {
+            2 => Vec::new(),
+            _ => (0..self.cells.len()).map(|idx| self.cells[idx] as u8).collect(),
+        };
+        log::info!("loaded rule table {} with {} states", table.name(), table.states());
+        self.table = Some(table);
+        self.set_engine(self.engine);
+        self.cycles = CycleDetector::default();
+        Ok(())
+    }
+
+    // Goes back to the rule the universe was made with. Cells in 
+    // states it doesn't have die. 
+    pub fn clear_rule_table(&mut self) {
+        if self.table.take().is_none() {
+            return;
+        }
+        log::info!("back to rule {}", self.rule);
+        let states = self.rule.states();
+        self.states = match states {
+            2 => Vec::new(),
+            _ => self.states.iter().map(|&state| if state < states { state } else { 0 }).collect(),
+        };
+        self.cycles = CycleDetector::default();
+    }
+
+    pub fn rule_table(&self) -> Option<&RuleTable> {
+        self.table.as_ref()
+    }
+
+
+    // Rendering to Canvas Directly from Memory
+    // Generating (and allocating) a String in Rust 
+    // and then having wasm-bindgen convert it to a valid JavaScript string 
+    // makes unnecessary copies of the universe's cells. 
+    // As the JavaScript code already knows the width and height of the universe, 
+    // and can read WebAssembly's linear memory that make up the cells directly, 
+    // we'll modify the render method to return a pointer to the start of 
+    // the cells array.
+    //
+    // Also, instead of rendering Unicode text, 
+    // we'll switch to using the Canvas API
+    // 
+    // old version 
+    // pub fn render(&self) -> String {
+    //    self.to_string()
+    // }
+   // 
+
+    // Text output is still handy, e.g. in a terminal-styled <pre>, 
+    // so render_text writes the cells out with configurable glyphs.
+    pub fn render_text(&self, options: &TextOptions) -> String {
+        text::render(&self.cells, self.width, self.height, options)
+    }
+
+    // A vector image of the live cells, for articles and slides that 
+    // want something crisper than a canvas screenshot. 
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        svg::render(&self.cells, self.width, self.height, options)
+    }
+
+    // An eight times denser text rendering, with a 2x4 block of cells 
+    // in each Unicode braille character. Good for a textContent fallback 
+    // and for logging snapshots.
+    pub fn render_braille(&self) -> String {
+        text::braille(&self.cells, self.width, self.height)
+    }
+
+   // To get the necessary information from the Rust implementation, 
+   // we'll need to add some more getter functions for a universe's 
+   // width, height, and pointer to its cells array. 
+   // All of these are exposed to JavaScript as well
+   pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // The generation number and the number of live cells, 
+    // for the "Gen 12,402 – 3,871 alive" line in the UI.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u32 {
+        self.population
+    }
+
+    // Births, deaths and population of the most recent step,
+    // for showing rates without walking the grid from JavaScript.
+    // Edits between ticks don't count; all zeros before the first tick.
+    pub fn tick_stats(&self) -> TickStats {
+        self.last_step
+    }
+
+    // How long the last tick took, the average over recent ones, and 
+    // how it split into counting and writing, see PerfStats. 
+    pub fn perf_stats(&self) -> PerfStats {
+        self.perf.stats()
+    }
+
+    // The population after each of the last `n` generations, oldest
+    // first, for plotting. At most 4096 are remembered.
+    pub fn population_history(&self, n: u32) -> Vec<u32> {
+        self.populations.last(n as usize)
+    }
+
+    // Starts or stops keeping the statistics of every step, for 
+    // export_stats(). Recording starts with a row for the universe as 
+    // it is, and stopping throws the rows away. 
+    pub fn record_stats(&mut self, enabled: bool) {
+        if !enabled {
+            self.recorded_stats = None;
+        } else if self.recorded_stats.is_none() {
+            self.recorded_stats = Some(Vec::new());
+            self.record_step(0, 0);
+        }
+    }
+
+    pub fn is_recording_stats(&self) -> bool {
+        self.recorded_stats.is_some()
+    }
+
+    fn record_step(&mut self, births: u32, deaths: u32) {
+        let row = GenerationStats {
+            generation: self.generation,
+            population: self.population,
+            births,
+            deaths,
+            bounds: stats::bounding_box(&self.cells, self.width),
+        };
+        if let Some(rows) = &mut self.recorded_stats {
+            rows.push(row);
+        }
+    }
+
+    // Generation, population, births, deaths and bounding box of every 
+    // recorded step, as CSV or JSON. Empty while not recording. 
+    pub fn export_stats(&self, format: StatsFormat) -> Vec<u8> {
+        match &self.recorded_stats {
+            Some(rows) => stats::export(rows, format).into_bytes(),
+            None => Vec::new(),
+        }
+    }
+
+    // Changing the size keeps the existing pattern: it is clipped when 
+    // shrinking and padded with dead cells when growing. 
+    // set_width and set_height keep the top-left corner in place.
+    pub fn set_width(&mut self, width: u32) -> Result<(), Error> {
+        self.resize(width, self.height, Anchor::TopLeft)
+    }
+
+    pub fn set_height(&mut self, height: u32) -> Result<(), Error> {
+        self.resize(self.width, height, Anchor::TopLeft)
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32, anchor: Anchor) -> Result<(), Error> {
+        let mut resized = Universe::empty(width, height, self.rule)?;
+        resized.reserved = self.reserved;
+        // The states, sized for a rule table's states if there is one.
+        resized.states = match self.states.is_empty() {
+            true => Vec::new(),
+            false => resized.cell_buffer(0),
+        };
+
+        // How far the old pattern moves, per axis. Each anchor pins 
+        // either the start, the middle or the end of an axis.
+        let shift = |old: u32, new: u32, pin: u8| -> i64 {
+            let grow = i64::from(new) - i64::from(old);
+            match pin {
+                0 => 0,
+                1 => grow / 2,
+                _ => grow,
+            }
+        };
+        let (pin_row, pin_col) = match anchor {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Top => (0, 1),
+            Anchor::TopRight => (0, 2),
+            Anchor::Left => (1, 0),
+            Anchor::Center => (1, 1),
+            Anchor::Right => (1, 2),
+            Anchor::BottomLeft => (2, 0),
+            Anchor::Bottom => (2, 1),
+            Anchor::BottomRight => (2, 2),
+        };
+        let row_shift = shift(self.height, height, pin_row);
+        let col_shift = shift(self.width, width, pin_col);
+
+        // Where a cell ends up, if it is still inside.
+        let moved = |idx: usize| {
+            let row = (idx as u32 / self.width) as i64 + row_shift;
+            let col = (idx as u32 % self.width) as i64 + col_shift;
+            let inside = (0..i64::from(height)).contains(&row) && (0..i64::from(width)).contains(&col);
+            Some((row as u32 * width + col as u32) as usize).filter(|_| inside)
+        };
+
+        resized.set_species(self.species);
+        for (idx, new_idx) in self.cells.ones().filter_map(|idx| Some((idx, moved(idx)?))) {
+            resized.cells.insert(new_idx);
+            if !self.colors.is_empty() {
+                resized.colors[new_idx] = self.colors[idx];
+            }
+        }
+        for (idx, &state) in self.states.iter().enumerate() {
+            if let Some(new_idx) = moved(idx) {
+                resized.states[new_idx] = state;
+            }
+        }
+        // Turmites come along if they are still inside.
+        for turmite in &self.turmites {
+            if let Some(new_idx) = moved(self.get_index(turmite.row(), turmite.col())) {
+                let mut turmite = turmite.clone();
+                turmite.move_to(new_idx as u32 / width, new_idx as u32 % width);
+                resized.turmites.push(turmite);
+            }
+        }
+
+        resized.generation = self.generation;
+        resized.recount();
+        resized.boundary = self.boundary;
+        resized.neighborhood = self.neighborhood;
+        resized.weighted = self.weighted.take();
+        resized.stochastic = self.stochastic.take();
+        resized.seed = self.seed;
+        resized.rng = self.rng.clone();
+        resized.custom_rule = self.custom_rule.take();
+        resized.table = self.table.take();
+        resized.autosave = self.autosave.take();
+        resized.history = std::mem::replace(&mut self.history, History::new(0));
+        resized.history.clear();
+        resized.set_engine(self.engine);
+        if !self.ages.is_empty() {
+            resized.refresh_ages();
+        }
+        if !self.activity.is_empty() {
+            resized.activity = resized.cell_buffer(0.0);
+        }
+        resized.activity_decay = self.activity_decay;
+        resized.populations = std::mem::take(&mut self.populations);
+        resized.recorded_stats = self.recorded_stats.take();
+        log::info!("resized from {}x{} to {}x{}", self.width, self.height, width, height);
+        *self = resized;
+        Ok(())
+    }
+    
+    //pub fn cells(&self) -> *const Cell {
+    //    self.cells.as_ptr()
+    //}
+
+    // The cells, packed: cell (row, col) is bit number 
+    // `row * row_stride() + col`, stored least-significant bit first 
+    // inside u32 words. Rows are not padded, so a row can start in the 
+    // middle of a word, and the last word is rounded up with dead cells. 
+    // Ticking swaps the cells with a second buffer instead of allocating, 
+    // so the slice lives at one of two addresses from one tick to the next.
+    pub fn cells(&self) -> &[u32] {
+        self.cells.as_slice()
+    }
+
+    // Which cells flipped in the last tick (all generations of a 
+    // tick_many() together), edit, undo or redo, as a bitmask in the same 
+    // layout as cells(). A renderer can repaint just those, and a 
+    // peer that already has the previous state only needs these bits.
+    pub fn changed_cells(&self) -> &[u32] {
+        self.changed.as_slice()
+    }
+
+    // How many cells flipped in each column in the last tick, edit, undo 
+    // or redo, left to right: where the action is, without unpacking 
+    // changed_cells(). 
+    pub fn changed_per_column(&self) -> Vec<u32> {
+        let mut columns = vec![0; self.width as usize];
+        for idx in self.changed.ones() {
+            columns[idx % self.width as usize] += 1;
+        }
+        columns
+    }
+
+    // Distance, in bits, between the start of one row and the next.
+    pub fn row_stride(&self) -> u32 {
+        self.width
+    }
+
+    // Draws part of the universe as RGBA pixels, ready for an ImageData:
+    // `view_width` x `view_height` pixels starting at cell (x, y), with
+    // `zoom` pixels per cell (below 1 to zoom out). Pixels past the edges
+    // come out grey. Only the visible cells are touched, so panning around
+    // a huge universe costs no more than the size of the view.
+    pub fn render_viewport(&self, x: i32, y: i32, view_width: u32, view_height: u32, zoom: f64) -> Result<Vec<u8>, Error> {
+        let mut pixels = vec![0; view_bytes(view_width, view_height)?];
+        self.render_viewport_into(x, y, view_width, view_height, zoom, &mut pixels)?;
+        Ok(pixels)
+    }
+
+    // Like render_viewport(), but paints into `out`, which must hold
+    // exactly view_width * view_height * 4 bytes, instead of allocating.
+    pub fn render_viewport_into(
+        &self,
+        x: i32,
+        y: i32,
+        view_width: u32,
+        view_height: u32,
+        zoom: f64,
+        out: &mut [u8],
+    ) -> Result<(), Error> {
+        if !(zoom.is_finite() && zoom > 0.0) {
+            return Err(Error::new("zoom must be a positive number"));
+        }
+        if out.len() != view_bytes(view_width, view_height)? {
+            return Err(Error::new("output buffer must hold 4 bytes per pixel of the view"));
+        }
+        viewport::render(&self.cells, (self.width, self.height), (x, y), (view_width, view_height), zoom, out);
+        Ok(())
+    }
+
+    // The live-cell counts of square blocks of cells, row by row, for 
+    // drawing a zoomed out universe without reading every cell: level 0 
+    // sums up 8x8 blocks and level 1 64x64 blocks, so there are 
+    // ceil(width / 8) x ceil(height / 8) counts at level 0. The counts 
+    // follow every tick and edit from the first call on.
+    pub fn density_level(&mut self, level: u32) -> Result<&[u32], Error> {
+        let (cells, width, height) = (&self.cells, self.width, self.height);
+        let density = self.density.get_or_insert_with(|| Density::new(cells, width, height));
+        let levels = density::BLOCKS.len();
+        density
+            .level(level as usize)
+            .ok_or_else(|| Error::new(&format!("there are only {} density levels", levels)))
+    }
+
+    // width * height ages, in the same row-major order as the cells: 
+    // how many generations in a row each cell has been alive, 0 for dead 
+    // cells and 1 for newborns. Ages are only tracked once this has been 
+    // called, so universes that never color by age don't pay for it.
+    pub fn ages(&mut self) -> &[u16] {
+        self.refresh_ages();
+        &self.ages
+    }
+
+    // width * height activity values, for a heatmap overlay: each birth 
+    // or death adds 1 to its cell, and every generation multiplies all 
+    // values by activity_decay(). Like ages(), tracking starts with the 
+    // first call, from all zeros.
+    pub fn activity(&mut self) -> &[f32] {
+        let size = (self.width * self.height) as usize;
+        if self.activity.len() != size {
+            self.activity = self.cell_buffer(0.0);
+        }
+        &self.activity
+    }
+
+    pub fn species(&self) -> Species {
+        self.species
+    }
+
+    // Switching to a multi-color species gives every live cell the 
+    // first color; switching back to Single forgets the colors.
+    pub fn set_species(&mut self, species: Species) {
+        self.species = species;
+        self.colors = match species {
+            Species::Single => Vec::new(),
+            _ => self.cell_buffer(0),
+        };
+        self.refresh_colors();
+    }
+
+    // width * height colors, in the same row-major order as the cells: 
+    // 0 for dead cells, 1 up to species().colors() for live ones. 
+    // Empty with Species::Single.
+    pub fn colors(&self) -> &[u8] {
+        &self.colors
+    }
+
+    // 0 if the cell is dead. Live cells are color 1 with Species::Single.
+    pub fn get_color(&self, row: u32, col: u32) -> Result<u8, Error> {
+        let idx = self.checked_index(row, col)?;
+        Ok(match self.colors.get(idx) {
+            Some(&color) => color,
+            None => self.cells[idx] as u8,
+        })
+    }
+
+    // Brings the cell to life with the given color, or kills it with 0.
+    pub fn set_cell_color(&mut self, row: u32, col: u32, color: u8) -> Result<(), Error> {
+        let idx = self.checked_index(row, col)?;
+        if color > self.species.colors() {
+            return Err(Error::new(&format!(
+                "color {} is out of range, the universe has {} colors",
+                color,
+                self.species.colors()
+            )));
+        }
+        if self.cells[idx] != (color > 0) {
+            self.record(|universe| universe.apply(&[idx as u32], universe.generation));
+        }
+        if let Some(cell) = self.colors.get_mut(idx) {
+            *cell = color;
+        }
+        Ok(())
+    }
+
+    // How many states cells go through: 2 unless the rule is a 
+    // Generations rule or a rule table with more.
+    pub fn state_count(&self) -> u8 {
+        match &self.table {
+            Some(table) => table.states(),
+            None => self.rule.states(),
+        }
+    }
+
+    // width * height states with a Generations rule, in the same 
+    // row-major order as the cells: 0 for dead, 1 for alive and 
+    // 2 up to state_count() - 1 for cells on their way out, e.g. for 
+    // drawing them in fading colors. Empty with two-state rules.
+    pub fn states(&self) -> &[u8] {
+        &self.states
+    }
+
+    pub fn get_state(&self, row: u32, col: u32) -> Result<u8, Error> {
+        let idx = self.checked_index(row, col)?;
+        Ok(match self.states.get(idx) {
+            Some(&state) => state,
+            None => self.cells[idx] as u8,
+        })
+    }
+
+    // Puts a cell in any state, e.g. to lay down Wireworld wires. 
+    // States 0 and 1 are the same as killing and reviving the cell. 
+    // Undoable like any other edit. 
+    pub fn set_state(&mut self, row: u32, col: u32, state: u8) -> Result<(), Error> {
+        let idx = self.checked_index(row, col)?;
+        if state >= self.state_count() {
+            return Err(Error::new(&format!(
+                "state {} is out of range, the rule has {} states",
+                state,
+                self.state_count()
+            )));
+        }
+        self.record(|universe| {
+            if universe.cells[idx] != (state == 1) {
+                universe.apply(&[idx as u32], universe.generation);
+            }
+            if let Some(old) = universe.states.get_mut(idx) {
+                *old = state;
+            }
+        });
+        Ok(())
+    }
+
+    // Puts a turmite on the grid, e.g. Turmite::ant(row, col, 
+    // Direction::North, "RL"). With the rule "B/S012345678" nothing but 
+    // the turmites changes the cells. 
+    pub fn add_turmite(&mut self, turmite: Turmite) -> Result<(), Error> {
+        self.checked_index(turmite.row(), turmite.col())?;
+        self.turmites.push(turmite);
+        self.cycles = CycleDetector::default();
+        Ok(())
+    }
+
+    // Takes the turmite at `index` off the grid. The ones after it 
+    // move down one place. 
+    pub fn remove_turmite(&mut self, index: usize) -> Result<Turmite, Error> {
+        if index >= self.turmites.len() {
+            return Err(Error::new(&format!(
+                "there is no turmite {}, the universe has {}",
+                index,
+                self.turmites.len()
+            )));
+        }
+        self.cycles = CycleDetector::default();
+        Ok(self.turmites.remove(index))
+    }
+
+    pub fn clear_turmites(&mut self) {
+        self.turmites.clear();
+        self.cycles = CycleDetector::default();
+    }
+
+    pub fn turmites(&self) -> &[Turmite] {
+        &self.turmites
+    }
+
+    // Whether the rule is Wireworld, whose states are drawn as 
+    // circuits rather than fading cells.
+    pub fn is_wireworld(&self) -> bool {
+        self.rule.is_wireworld()
+    }
+
+    pub fn activity_decay(&self) -> f32 {
+        self.activity_decay
+    }
+
+    // 0 forgets everything each generation, 1 never forgets.
+    pub fn set_activity_decay(&mut self, decay: f32) -> Result<(), Error> {
+        if !(0.0..=1.0).contains(&decay) {
+            return Err(Error::new("activity decay must be between 0 and 1"));
+        }
+        self.activity_decay = decay;
+        Ok(())
+    }
+    
+
+}//^-- impl Universe
+
+impl Default for Universe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// -- Now using Canvas in Js
+// The state of the universe is represented as a vector of cells. 
+// To make this human readable, let's implement a basic text renderer. 
+// The idea is to write the universe line by line as text, and for each cell 
+// that is alive, print the Unicode character ◼ ("black medium square"). 
+// For dead cells, we'll print ◻ (a "white medium square").
+// use std::fmt;
+// 
+// impl fmt::Display for Universe {
+//     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+//         for line in self.cells.as_slice().chunks(self.width as usize) {
+//             for &cell in line {
+//                 let symbol = if cell == Cell::Dead { '◻' } else { '◼' };
+//                 write!(f, "{}", symbol)?;
+//             }
+//             write!(f, "\n")?;
+//         }
+// 
+//         Ok(())
+//     }
+// }
+//
+// That renderer lives on in text.rs, where the glyphs, the row 
+// separators and the part of the universe to write are all options 
+// of render_text(). Display uses the defaults, which print the same.
+use std::fmt;
+
+impl fmt::Display for Universe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.render_text(&TextOptions::default()))
+    }
+}
+
@@ -0,0 +1,141 @@
+use crate::plaintext;
+use crate::rle::RlePattern;
+use crate::{Error, LifeRule};
+
+// The two .lif formats that came before RLE.
+//
+// Life 1.06 lists the live cells as "x y" pairs, one per line, after a
+// "#Life 1.06" header. Coordinates may be negative.
+//
+//     #Life 1.06
+//     0 -1
+//     1 0
+//     -1 1
+//     0 1
+//     1 1
+//
+// Life 1.05 draws blocks of rows, with `.` for dead and `*` for live
+// cells. Each block starts with "#P x y", the position of its top-left
+// corner. "#N" is Conway's rule and "#R 23/3" any other, survivals
+// first; "#D" lines are descriptions.
+//
+//     #Life 1.05
+//     #D Glider
+//     #N
+//     #P -1 -1
+//     .*
+//     ..*
+//     ***
+
+// Moves the cells so the top-left one is at (0, 0), and sizes the
+// pattern to fit them. Cells further apart than a pattern's u32 width
+// or height can reach are an error.
+pub(crate) fn cropped(cells: Vec<(i64, i64)>, rule: Option<LifeRule>) -> Result<RlePattern, Error> {
+    let top = cells.iter().map(|&(row, _)| row).min().unwrap_or(0);
+    let left = cells.iter().map(|&(_, col)| col).min().unwrap_or(0);
+    let bottom = cells.iter().map(|&(row, _)| row).max().unwrap_or(0);
+    let right = cells.iter().map(|&(_, col)| col).max().unwrap_or(0);
+    let fits = |low: i64, high: i64| i128::from(high) - i128::from(low) < i128::from(u32::MAX);
+    if !fits(top, bottom) || !fits(left, right) {
+        return Err(Error::new(&format!("the cells are more than {} apart", u32::MAX - 1)));
+    }
+    let mut cells: Vec<(u32, u32)> = cells
+        .into_iter()
+        .map(|(row, col)| ((row - top) as u32, (col - left) as u32))
+        .collect();
+    cells.sort_unstable();
+    cells.dedup();
+    Ok(RlePattern {
+        width: cells.iter().map(|&(_, col)| col + 1).max().unwrap_or(0),
+        height: cells.iter().map(|&(row, _)| row + 1).max().unwrap_or(0),
+        rule,
+        torus: None,
+        cells,
+        states: Vec::new(),
+    })
+}
+
+fn error(format: &str, number: usize, reason: &str) -> Error {
+    Error::new(&format!("invalid {} on line {}: {}", format, number + 1, reason))
+}
+
+pub fn parse_106(text: &str) -> Result<RlePattern, Error> {
+    let mut cells = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let coordinates: Vec<Option<i64>> = line.split_whitespace().map(|n| n.parse().ok()).collect();
+        match coordinates[..] {
+            [Some(x), Some(y)] => cells.push((y, x)),
+            _ => return Err(error("Life 1.06", number, "expected the x and y of a live cell")),
+        }
+    }
+    cropped(cells, None)
+}
+
+pub fn write_106(pattern: &RlePattern) -> String {
+    let mut out = String::from("#Life 1.06\n");
+    for &(row, col) in &pattern.cells {
+        out.push_str(&format!("{} {}\n", col, row));
+    }
+    out
+}
+
+pub fn parse_105(text: &str) -> Result<RlePattern, Error> {
+    let mut cells = Vec::new();
+    let mut rule = None;
+    // Where the next row of the current block goes.
+    let (mut row, mut left) = (0, 0);
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if let Some(position) = line.strip_prefix("#P") {
+            let position: Vec<Option<i64>> = position.split_whitespace().map(|n| n.parse().ok()).collect();
+            match position[..] {
+                [Some(x), Some(y)] => {
+                    row = y;
+                    left = x;
+                }
+                _ => return Err(error("Life 1.05", number, "expected #P x y")),
+            }
+        } else if line.starts_with("#N") {
+            rule = Some(LifeRule::conway());
+        } else if let Some(counts) = line.strip_prefix("#R") {
+            rule = Some(counts.trim().parse().map_err(|_| error("Life 1.05", number, "expected #R survivals/births"))?);
+        } else if line.starts_with('#') {
+            continue;
+        } else if !line.is_empty() {
+            for (col, c) in line.chars().enumerate() {
+                match c {
+                    '.' => {}
+                    '*' => match left.checked_add(col as i64) {
+                        Some(col) => cells.push((row, col)),
+                        None => return Err(error("Life 1.05", number, "the row runs off the plane")),
+                    },
+                    _ => return Err(error("Life 1.05", number, "cells are . or *")),
+                }
+            }
+            row = row.checked_add(1).ok_or_else(|| error("Life 1.05", number, "the block runs off the plane"))?;
+        }
+    }
+    cropped(cells, rule)
+}
+
+// One block, centered on the origin like Life 1.05 files usually are.
+pub fn write_105(pattern: &RlePattern) -> Result<String, Error> {
+    let mut out = String::from("#Life 1.05\n");
+    match pattern.rule {
+        Some(rule) if rule == LifeRule::conway() => out.push_str("#N\n"),
+        Some(rule) if rule.is_life_like() => {
+            // B3/S23 is written 23/3.
+            let rule = rule.to_string();
+            let (birth, survival) = rule.split_once('/').unwrap_or_default();
+            out.push_str(&format!("#R {}/{}\n", &survival[1..], &birth[1..]));
+        }
+        _ => {}
+    }
+    out.push_str(&format!("#P {} {}\n", -(pattern.width as i64 / 2), -(pattern.height as i64 / 2)));
+    out.push_str(&plaintext::rows(pattern, '*')?);
+    Ok(out)
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+
+use fixedbitset::FixedBitSet;
+
+use crate::neighborhood::{reflect, NeighborhoodShape};
+use crate::Boundary;
+
+// Larger than Life: totalistic rules over a big neighborhood, written
+// like "R5,C0,M1,S34..58,B34..45,NM" (Bosco's Rule). R is the radius,
+// C the number of states (0 or 1 for plain two-state rules, more for
+// Generations-style dying), M whether the cell counts itself, S and B
+// the ranges of live counts to survive and to be born, and N the shape:
+// M for the (2R+1) x (2R+1) square, N for the von Neumann diamond.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LargerThanLife {
+    radius: u32,
+    middle: bool,
+    survival: (u32, u32),
+    birth: (u32, u32),
+    shape: NeighborhoodShape,
+}
+
+pub const MAX_RADIUS: u32 = 500;
+
+impl LargerThanLife {
+    // Parses the rule and its number of states, or says it isn't
+    // Larger than Life at all.
+    pub fn parse(rule: &str) -> Option<Result<(LargerThanLife, u8), &'static str>> {
+        let upper = rule.to_ascii_uppercase();
+        if !upper.starts_with('R') || !upper[1..].starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        Some(parse_fields(&upper))
+    }
+
+    pub fn next(&self, alive: bool, count: u32) -> bool {
+        let (low, high) = if alive { self.survival } else { self.birth };
+        (low..=high).contains(&count)
+    }
+
+    // The live count of every cell's neighborhood, row by row. The
+    // neighborhood reaches `radius` cells past the edges, where the
+    // boundary decides what is there; see reflect() for Mirror.
+    pub fn counts(&self, cells: &FixedBitSet, width: u32, height: u32, boundary: Boundary) -> Vec<u32> {
+        let radius = self.radius as i64;
+        let (width, height) = (i64::from(width), i64::from(height));
+        let alive = |row: i64, col: i64| -> u32 {
+            let inside = (0..height).contains(&row) && (0..width).contains(&col);
+            let (row, col) = match boundary {
+                _ if inside => (row, col),
+                Boundary::Dead => return 0,
+                Boundary::AliveWall => return 1,
+                Boundary::Torus => (row.rem_euclid(height), col.rem_euclid(width)),
+                Boundary::Mirror => (reflect(row, height), reflect(col, width)),
+            };
+            cells[(row * width + col) as usize] as u32
+        };
+
+        // Prefix sums over rows of the universe padded with `radius`
+        // cells on every side: sums[y][x] adds up the first x cells of
+        // padded row y.
+        let (padded_width, padded_height) = (width + 2 * radius, height + 2 * radius);
+        let stride = padded_width as usize + 1;
+        let mut sums = vec![0u32; stride * padded_height as usize];
+        for y in 0..padded_height {
+            let row = &mut sums[y as usize * stride..(y as usize + 1) * stride];
+            for x in 0..padded_width {
+                row[x as usize + 1] = row[x as usize] + alive(y - radius, x - radius);
+            }
+        }
+        // For the square, summing the rows as well turns every window
+        // into four lookups.
+        if self.shape == NeighborhoodShape::Moore {
+            for y in 1..padded_height as usize {
+                for x in 0..stride {
+                    sums[y * stride + x] += sums[(y - 1) * stride + x];
+                }
+            }
+        }
+        let span = |y: i64, from: i64, to: i64| sums[y as usize * stride + to as usize] - sums[y as usize * stride + from as usize];
+
+        let mut counts = Vec::with_capacity((width * height) as usize);
+        for row in 0..height {
+            for col in 0..width {
+                // (row, col) sits at (row + radius, col + radius) in the padding.
+                let mut count = match self.shape {
+                    NeighborhoodShape::Moore => {
+                        let below = span(row + 2 * radius, col, col + 2 * radius + 1);
+                        let above = if row > 0 { span(row - 1, col, col + 2 * radius + 1) } else { 0 };
+                        below - above
+                    }
+                    NeighborhoodShape::VonNeumann => (-radius..=radius)
+                        .map(|dy| {
+                            let reach = radius - dy.abs();
+                            span(row + radius + dy, col + radius - reach, col + radius + reach + 1)
+                        })
+                        .sum(),
+                };
+                if !self.middle {
+                    count -= alive(row, col);
+                }
+                counts.push(count);
+            }
+        }
+        counts
+    }
+}
+
+fn parse_fields(rule: &str) -> Result<(LargerThanLife, u8), &'static str> {
+    const FORMAT: &str = "expected Larger than Life as R<radius>,C<states>,M<0|1>,S<min>..<max>,B<min>..<max>,N<M|N>";
+    let fields: Vec<&str> = rule.split(',').collect();
+    let field = |i: usize, tag: char| fields.get(i).and_then(|field| field.strip_prefix(tag)).ok_or(FORMAT);
+    let number = |s: &str| s.parse::<u32>().map_err(|_| FORMAT);
+    let range = |s: &str| -> Result<(u32, u32), &'static str> {
+        let (low, high) = s.split_once("..").ok_or(FORMAT)?;
+        Ok((number(low)?, number(high)?))
+    };
+    if fields.len() != 6 {
+        return Err(FORMAT);
+    }
+
+    let radius = number(field(0, 'R')?)?;
+    if !(1..=MAX_RADIUS).contains(&radius) {
+        return Err("the radius must be between 1 and 500");
+    }
+    let states = match number(field(1, 'C')?)? {
+        0 | 1 => 2,
+        states if states <= 255 => states as u8,
+        _ => return Err("the number of states must be between 2 and 255"),
+    };
+    let middle = match field(2, 'M')? {
+        "0" => false,
+        "1" => true,
+        _ => return Err(FORMAT),
+    };
+    let survival = range(field(3, 'S')?)?;
+    let birth = range(field(4, 'B')?)?;
+    let shape = match field(5, 'N')? {
+        "M" => NeighborhoodShape::Moore,
+        "N" => NeighborhoodShape::VonNeumann,
+        _ => return Err(FORMAT),
+    };
+    Ok((
+        LargerThanLife {
+            radius,
+            middle,
+            survival,
+            birth,
+            shape,
+        },
+        states,
+    ))
+}
+
+// Writes the rule back in canonical form, with `states` as the C field.
+pub fn format(rule: &LargerThanLife, states: u8, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+        f,
+        "R{},C{},M{},S{}..{},B{}..{},N{}",
+        rule.radius,
+        if states > 2 { states } else { 0 },
+        rule.middle as u8,
+        rule.survival.0,
+        rule.survival.1,
+        rule.birth.0,
+        rule.birth.1,
+        match rule.shape {
+            NeighborhoodShape::Moore => 'M',
+            NeighborhoodShape::VonNeumann => 'N',
+        }
+    )
+}
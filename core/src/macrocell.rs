@@ -0,0 +1,191 @@
+use crate::lif;
+use crate::rle::{RlePattern, MAX_CELLS};
+use crate::rule::LifeRule;
+use crate::Error;
+
+// Golly's Macrocell format writes out a Hashlife tree, so repeated
+// squares are only written once and patterns far too big for any grid,
+// like the metapixel galaxies, fit into a few kilobytes:
+//
+//     [M2] (golly 4.2)
+//     #R B3/S23
+//     .*$..*$***$
+//     4 0 0 0 1
+//     5 0 0 2 0
+//
+// Each line is a node, numbered from 1. The first kind is an 8x8 leaf:
+// `.` dead, `*` alive, `$` ends a row, trailing dead cells and rows
+// left out. The other kind is "level nw ne sw se", a square of 2^level
+// cells and the numbers of its four quarters, 0 being an empty one.
+// Leaves are level 3, and the last node is the whole pattern, centered
+// on the origin.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MacroNode {
+    // One byte per row, bit 0 the leftmost cell.
+    Leaf([u8; 8]),
+    // The level, and the four quarters as indices into the nodes
+    // before this one, starting at 1, with 0 for an empty quarter.
+    Inner(u8, [usize; 4]),
+}
+
+impl MacroNode {
+    fn level(&self) -> u8 {
+        match self {
+            MacroNode::Leaf(_) => 3,
+            MacroNode::Inner(level, _) => *level,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Macrocell {
+    pub rule: Option<LifeRule>,
+    pub generation: u64,
+    pub nodes: Vec<MacroNode>,
+}
+
+pub fn parse(text: &str) -> Result<Macrocell, Error> {
+    let error = |number: usize, reason: &str| Error::new(&format!("invalid Macrocell on line {}: {}", number + 1, reason));
+    let mut lines = text.lines().enumerate();
+    match lines.next() {
+        Some((_, header)) if header.starts_with("[M2]") => {}
+        _ => return Err(Error::new("a Macrocell file starts with [M2]")),
+    }
+
+    let mut macrocell = Macrocell {
+        rule: None,
+        generation: 0,
+        nodes: Vec::new(),
+    };
+    for (number, line) in lines {
+        let line = line.trim();
+        if let Some(rule) = line.strip_prefix("#R") {
+            macrocell.rule = Some(rule.trim().parse().map_err(|_| error(number, "unknown rule"))?);
+        } else if let Some(generation) = line.strip_prefix("#G") {
+            macrocell.generation = generation.trim().parse().map_err(|_| error(number, "bad generation"))?;
+        } else if line.is_empty() || line.starts_with('#') {
+            continue;
+        } else if line.starts_with(['.', '*', '$']) {
+            let mut rows = [0u8; 8];
+            let (mut x, mut y) = (0, 0);
+            for c in line.chars() {
+                match c {
+                    '$' => {
+                        x = 0;
+                        y += 1;
+                    }
+                    '.' | '*' if x < 8 && y < 8 => {
+                        if c == '*' {
+                            rows[y] |= 1 << x;
+                        }
+                        x += 1;
+                    }
+                    '.' | '*' => return Err(error(number, "a leaf is 8 by 8 cells")),
+                    _ => return Err(error(number, "leaf cells are . or *")),
+                }
+            }
+            macrocell.nodes.push(MacroNode::Leaf(rows));
+        } else {
+            let numbers: Vec<Option<usize>> = line.split_whitespace().map(|n| n.parse().ok()).collect();
+            let node = match numbers[..] {
+                [Some(level), Some(nw), Some(ne), Some(sw), Some(se)] if (4..64).contains(&level) => {
+                    MacroNode::Inner(level as u8, [nw, ne, sw, se])
+                }
+                [Some(_), Some(_), Some(_), Some(_), Some(_)] => {
+                    return Err(error(number, "only two-state patterns with 8x8 leaves are supported"))
+                }
+                _ => return Err(error(number, "expected a leaf or \"level nw ne sw se\"")),
+            };
+            if let MacroNode::Inner(level, quarters) = &node {
+                for &quarter in quarters.iter().filter(|&&quarter| quarter != 0) {
+                    match macrocell.nodes.get(quarter - 1) {
+                        Some(child) if child.level() + 1 == *level => {}
+                        Some(_) => return Err(error(number, "a quarter must be one level down")),
+                        None => return Err(error(number, "quarters must refer to earlier nodes")),
+                    }
+                }
+            }
+            macrocell.nodes.push(node);
+        }
+    }
+    if macrocell.nodes.is_empty() {
+        return Err(Error::new("the Macrocell file has no nodes"));
+    }
+    Ok(macrocell)
+}
+
+// Expands the tree into a plain list of cells, for files small enough
+// to be patterns stamped into a grid.
+pub fn flatten(macrocell: &Macrocell) -> Result<RlePattern, Error> {
+    let mut populations: Vec<u64> = Vec::with_capacity(macrocell.nodes.len());
+    for node in &macrocell.nodes {
+        let population = match node {
+            MacroNode::Leaf(rows) => rows.iter().map(|row| u64::from(row.count_ones())).sum(),
+            MacroNode::Inner(_, quarters) => {
+                quarters.iter().filter(|&&q| q != 0).map(|&q| populations[q - 1]).fold(0, u64::saturating_add)
+            }
+        };
+        populations.push(population);
+    }
+    if populations.last().copied().unwrap_or(0) > MAX_CELLS {
+        return Err(Error::new(
+            "the Macrocell pattern is too big to flatten, open it with Universe::from_macrocell instead",
+        ));
+    }
+
+    let mut cells = Vec::new();
+    let mut pending = vec![(macrocell.nodes.len(), 0i64, 0i64)];
+    while let Some((number, x, y)) = pending.pop() {
+        match &macrocell.nodes[number - 1] {
+            MacroNode::Leaf(rows) => {
+                for (row, &bits) in rows.iter().enumerate() {
+                    for col in (0..8).filter(|col| bits & (1 << col) != 0) {
+                        cells.push((y + row as i64, x + col as i64));
+                    }
+                }
+            }
+            MacroNode::Inner(level, quarters) => {
+                let half = 1i64 << (level - 1);
+                let corners = [(x, y), (x + half, y), (x, y + half), (x + half, y + half)];
+                // Empty quarters are skipped, and so are trees of nothing
+                // but empty leaves, which are tiny on disk but could have
+                // billions of squares to walk through.
+                for (&quarter, &(x, y)) in quarters.iter().zip(corners.iter()) {
+                    if quarter != 0 && populations[quarter - 1] > 0 {
+                        pending.push((quarter, x, y));
+                    }
+                }
+            }
+        }
+    }
+    lif::cropped(cells, macrocell.rule)
+}
+
+pub fn write(macrocell: &Macrocell) -> String {
+    let mut out = String::from("[M2] (wasm-game-of-life)\n");
+    if let Some(rule) = macrocell.rule {
+        out.push_str(&format!("#R {}\n", rule));
+    }
+    if macrocell.generation > 0 {
+        out.push_str(&format!("#G {}\n", macrocell.generation));
+    }
+    for node in &macrocell.nodes {
+        match node {
+            MacroNode::Leaf(rows) => {
+                // An empty leaf only ever comes up as a whole empty pattern.
+                let used = rows.iter().rposition(|&row| row != 0).map_or(1, |last| last + 1);
+                for &row in &rows[..used] {
+                    for x in 0..8 - row.leading_zeros() {
+                        out.push(if row & (1 << x) != 0 { '*' } else { '.' });
+                    }
+                    out.push('$');
+                }
+            }
+            MacroNode::Inner(level, [nw, ne, sw, se]) => {
+                out.push_str(&format!("{} {} {} {} {}", level, nw, ne, sw, se));
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
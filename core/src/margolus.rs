@@ -0,0 +1,227 @@
+use fixedbitset::FixedBitSet;
+
+use crate::random::Rng;
+use crate::{cell_count, Cell, Error};
+
+// Block cellular automata with the Margolus neighborhood. The grid is cut
+// into 2x2 blocks and every block is replaced as a whole by a lookup in a
+// 16-entry table; the next tick the cut moves one cell down and to the
+// right, so that blocks straddle the old ones and things can move across.
+// The universe wraps around like a torus, which needs an even width and
+// height for the blocks to tile it at both offsets.
+//
+// A block is a 4-bit number, one bit per cell:
+//
+//     1 2
+//     4 8
+//
+// When the table is a permutation, nothing is ever lost: every
+// generation can be computed back from the next, with tick_back().
+#[derive(Clone, Debug)]
+pub struct MargolusUniverse {
+    width: u32,
+    height: u32,
+    cells: FixedBitSet,
+    // The table for ticks starting at an even and at an odd generation.
+    tables: [[u8; 16]; 2],
+    generation: u64,
+}
+
+// Rules everybody knows.
+//
+// Critters leaves blocks with two live cells alone and turns every other
+// block inside out, also rotating it half a turn when it had three live
+// cells. In the billiard-ball model, a lone cell runs to the opposite
+// corner of its block, two cells on a diagonal bounce off to the other
+// diagonal and every other block stays as it is. Tron turns blocks that
+// are all dead or all alive inside out and leaves the rest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MargolusRule {
+    Critters,
+    BilliardBall,
+    Tron,
+}
+
+// Turns a block half a turn: top left with bottom right, top right with bottom left.
+fn rotate_half(block: u8) -> u8 {
+    (block & 1) << 3 | (block & 2) << 1 | (block & 4) >> 1 | (block & 8) >> 3
+}
+
+impl MargolusRule {
+    pub fn table(self) -> [u8; 16] {
+        let mut table = [0; 16];
+        for (block, next) in (0..16u8).zip(table.iter_mut()) {
+            *next = match self {
+                MargolusRule::Critters => match block.count_ones() {
+                    2 => block,
+                    3 => rotate_half(!block & 15),
+                    _ => !block & 15,
+                },
+                MargolusRule::BilliardBall => match block {
+                    1 | 2 | 4 | 8 => rotate_half(block),
+                    0b1001 => 0b0110,
+                    0b0110 => 0b1001,
+                    _ => block,
+                },
+                MargolusRule::Tron => match block {
+                    0 | 15 => !block & 15,
+                    _ => block,
+                },
+            };
+        }
+        table
+    }
+}
+
+impl MargolusUniverse {
+    pub fn new(width: u32, height: u32, rule: MargolusRule) -> Result<MargolusUniverse, Error> {
+        MargolusUniverse::with_tables(width, height, rule.table(), rule.table())
+    }
+
+    // Any rule, as the next block for each of the 16 blocks, for ticks
+    // starting at even and at odd generations.
+    pub fn with_tables(width: u32, height: u32, even: [u8; 16], odd: [u8; 16]) -> Result<MargolusUniverse, Error> {
+        if width == 0 || height == 0 || !width.is_multiple_of(2) || !height.is_multiple_of(2) {
+            return Err(Error::new("Margolus universes need an even, non-zero width and height"));
+        }
+        if even.iter().chain(&odd).any(|&block| block > 15) {
+            return Err(Error::new("blocks go from 0 to 15"));
+        }
+        let size = cell_count(&[width, height])?;
+        Ok(MargolusUniverse {
+            width,
+            height,
+            cells: FixedBitSet::with_capacity(size),
+            tables: [even, odd],
+            generation: 0,
+        })
+    }
+
+    fn get_index(&self, row: u32, col: u32) -> usize {
+        (row * self.width + col) as usize
+    }
+
+    // Replaces every block of the partition starting at `offset` through `table`.
+    fn apply(&mut self, table: &[u8; 16], offset: u32) {
+        for top in (offset..self.height + offset).step_by(2) {
+            for left in (offset..self.width + offset).step_by(2) {
+                let corners = [
+                    (top, left),
+                    (top, left + 1),
+                    (top + 1, left),
+                    (top + 1, left + 1),
+                ]
+                .map(|(row, col)| self.get_index(row % self.height, col % self.width));
+                let block = corners
+                    .iter()
+                    .enumerate()
+                    .fold(0, |block, (bit, &idx)| block | (self.cells[idx] as u8) << bit);
+                let next = table[usize::from(block)];
+                for (bit, &idx) in corners.iter().enumerate() {
+                    self.cells.set(idx, next & (1 << bit) != 0);
+                }
+            }
+        }
+    }
+
+    pub fn tick(&mut self) {
+        let phase = (self.generation % 2) as usize;
+        let table = self.tables[phase];
+        self.apply(&table, phase as u32);
+        self.generation += 1;
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        for _ in 0..n {
+            self.tick();
+        }
+    }
+
+    // Whether both tables are permutations, so that tick_back() works.
+    pub fn is_reversible(&self) -> bool {
+        self.tables.iter().all(|table| {
+            let mut seen = [false; 16];
+            table.iter().for_each(|&block| seen[usize::from(block)] = true);
+            seen.iter().all(|&seen| seen)
+        })
+    }
+
+    // Undoes one tick by running the inverse of its table, going back
+    // to the previous generation.
+    pub fn tick_back(&mut self) -> Result<(), Error> {
+        if !self.is_reversible() {
+            return Err(Error::new("this rule can't be run backwards"));
+        }
+        if self.generation == 0 {
+            return Err(Error::new("already at generation 0"));
+        }
+        let phase = ((self.generation - 1) % 2) as usize;
+        let mut inverse = [0; 16];
+        for (block, &next) in self.tables[phase].iter().enumerate() {
+            inverse[usize::from(next)] = block as u8;
+        }
+        self.apply(&inverse, phase as u32);
+        self.generation -= 1;
+        Ok(())
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u32 {
+        self.cells.count_ones(..) as u32
+    }
+
+    // The packed cells, in the same layout as Universe::cells().
+    pub fn cells(&self) -> &[u32] {
+        self.cells.as_slice()
+    }
+
+    fn checked_index(&self, row: u32, col: u32) -> Result<usize, Error> {
+        if row >= self.height || col >= self.width {
+            return Err(Error::new("cell is out of bounds"));
+        }
+        Ok(self.get_index(row, col))
+    }
+
+    pub fn get_cell(&self, row: u32, col: u32) -> Result<Cell, Error> {
+        Ok(if self.cells[self.checked_index(row, col)?] {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        })
+    }
+
+    pub fn set_cell(&mut self, row: u32, col: u32, cell: Cell) -> Result<(), Error> {
+        let idx = self.checked_index(row, col)?;
+        self.cells.set(idx, cell == Cell::Alive);
+        Ok(())
+    }
+
+    pub fn toggle_cell(&mut self, row: u32, col: u32) -> Result<(), Error> {
+        let idx = self.checked_index(row, col)?;
+        self.cells.set(idx, !self.cells[idx]);
+        Ok(())
+    }
+
+    // Replaces every cell with a reproducible random soup.
+    pub fn randomize(&mut self, density: f32, seed: u64) -> Result<(), Error> {
+        if !(0.0..=1.0).contains(&density) {
+            return Err(Error::new("density must be between 0 and 1"));
+        }
+        let mut rng = Rng::new(seed);
+        for idx in 0..self.cells.len() {
+            self.cells.set(idx, rng.next_f32() < density);
+        }
+        Ok(())
+    }
+}
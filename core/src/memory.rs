@@ -0,0 +1,132 @@
+use std::collections::{HashMap, VecDeque};
+use std::mem::{size_of, size_of_val};
+
+use fixedbitset::FixedBitSet;
+use serde::Serialize;
+
+use crate::hashlife::Hashlife;
+use crate::{cell_count, patterns, Error, Universe};
+
+// Roughly how many bytes of heap a universe holds on to, by what for.
+// Buffers are counted at their capacity rather than their length, as
+// that is what they take up, so the numbers can be a little over what
+// the universe is actually using. Hash maps count their buckets plus a
+// control byte each, which is close to what std's allocates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryReport {
+    // The current and next generations, the last generation for the
+    // diff, and the changed cells.
+    pub cells: usize,
+    // Undo and redo, the hashes cycle detection compares, the recent
+    // populations and any recorded statistics.
+    pub history: usize,
+    // Hashlife's nodes and its caches, once the engine has been used.
+    pub hashlife: usize,
+    // Ages, activity, colors, states and density levels, each only once
+    // something has asked for it.
+    pub overlays: usize,
+    // The built-in patterns, which are compiled in rather than on the
+    // heap, but are part of the module all the same.
+    pub patterns: usize,
+}
+
+impl MemoryReport {
+    pub fn total(&self) -> usize {
+        self.cells + self.history + self.hashlife + self.overlays + self.patterns
+    }
+}
+
+pub fn bitset_bytes(bits: &FixedBitSet) -> usize {
+    size_of_val(bits.as_slice())
+}
+
+pub fn vec_bytes<T>(vec: &Vec<T>) -> usize {
+    vec.capacity() * size_of::<T>()
+}
+
+pub fn deque_bytes<T>(deque: &VecDeque<T>) -> usize {
+    deque.capacity() * size_of::<T>()
+}
+
+pub fn map_bytes<K, V, S>(map: &HashMap<K, V, S>) -> usize {
+    map.capacity() * (size_of::<(K, V)>() + 1)
+}
+
+impl Universe {
+    pub fn memory_report(&self) -> MemoryReport {
+        let cells = [&self.cells, &self.next, &self.before, &self.changed].iter().map(|bits| bitset_bytes(bits)).sum();
+        let recorded = self.recorded_stats.as_ref().map_or(0, vec_bytes);
+        let history = self.history.heap_bytes() + self.cycles.heap_bytes() + self.populations.heap_bytes() + recorded;
+        let hashlife = self.hashlife.as_ref().map_or(0, |hashlife| hashlife.heap_bytes());
+        let density = self.density.as_ref().map_or(0, |density| density.heap_bytes());
+        let overlays = vec_bytes(&self.ages) + vec_bytes(&self.activity) + vec_bytes(&self.colors) + vec_bytes(&self.states) + density;
+        MemoryReport {
+            cells,
+            history,
+            hashlife,
+            overlays,
+            patterns: patterns::catalog_bytes(),
+        }
+    }
+    // A width * height buffer for one of the per-cell overlays, with
+    // room for the reserved size so that growing into it later doesn't
+    // go back to the allocator for a bigger block.
+    pub(crate) fn cell_buffer<T: Clone>(&self, value: T) -> Vec<T> {
+        let size = (self.width * self.height) as usize;
+        let mut buffer = Vec::with_capacity(size.max(self.reserved));
+        buffer.resize(size, value);
+        buffer
+    }
+
+    // Makes room for a universe of up to width x height: the ages,
+    // activity, colors and states, now and after every resize(), are
+    // allocated that big. The cells themselves are a bit each and are
+    // still allocated at their size.
+    pub fn reserve(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        self.reserved = cell_count(&[width, height])?;
+        let reserved = self.reserved;
+        let reserve = |len: usize| reserved.saturating_sub(len);
+        for buffer in [&mut self.colors, &mut self.states] {
+            if !buffer.is_empty() {
+                buffer.reserve_exact(reserve(buffer.len()));
+            }
+        }
+        if !self.ages.is_empty() {
+            self.ages.reserve_exact(reserve(self.ages.len()));
+        }
+        if !self.activity.is_empty() {
+            self.activity.reserve_exact(reserve(self.activity.len()));
+        }
+        Ok(())
+    }
+
+    // Gives back whatever isn't needed for the universe as it is: any
+    // reservation, spare capacity in the buffers and history, and
+    // Hashlife's caches, which start over from the current cells.
+    pub fn shrink_to_fit(&mut self) {
+        self.reserved = 0;
+        self.ages.shrink_to_fit();
+        self.activity.shrink_to_fit();
+        self.colors.shrink_to_fit();
+        self.states.shrink_to_fit();
+        self.turmites.shrink_to_fit();
+        if let Some(stats) = &mut self.recorded_stats {
+            stats.shrink_to_fit();
+        }
+        self.history.shrink_to_fit();
+        if self.hashlife.is_some() {
+            self.hashlife = Some(Hashlife::new(self.rule, &self.cells, self.width, self.height));
+        }
+    }
+
+    pub fn history_budget(&self) -> Option<usize> {
+        self.history.budget()
+    }
+
+    // At most this many bytes of flipped cells in the undo history, on
+    // top of the history_depth() limit. None for no limit.
+    pub fn set_history_budget(&mut self, bytes: Option<usize>) {
+        self.history.set_budget(bytes);
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::census::{settle, Settled};
+use crate::random::Rng;
+use crate::rle::{self, RlePattern};
+use crate::{Engine, Error, LifeRule, Universe};
+
+// How many of the longest lived patterns best() lists.
+const LEADERS: usize = 10;
+
+// A starting pattern that was tried, and how long it took to settle.
+// A methuselah is a small pattern that takes a long time: the
+// R-pentomino's 5 cells run for 1103 generations before they are down
+// to still lifes, oscillators and gliders flying off.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Candidate {
+    rle: String,
+    population: u32,
+    lifespan: u64,
+    final_population: u32,
+}
+
+impl Candidate {
+    // With the rule in the header, ready to paste into Golly.
+    pub fn rle(&self) -> &str {
+        &self.rle
+    }
+
+    // How many cells it starts with.
+    pub fn population(&self) -> u32 {
+        self.population
+    }
+
+    // The generation its ash started repeating itself, or died out.
+    // Escaping spaceships are only taken out now and then, so it can
+    // be a hundred or so generations late when a glider is the last
+    // thing to leave.
+    pub fn lifespan(&self) -> u64 {
+        self.lifespan
+    }
+
+    // The cells left once it settled, not counting escaped spaceships.
+    pub fn final_population(&self) -> u32 {
+        self.final_population
+    }
+}
+
+// Looks for methuselahs by evolving small random patterns: every round
+// keeps the longer lived half of the candidates and replaces the rest
+// with copies of them, each with a few cells flipped.
+//
+//     let mut search = MethuselahSearch::new("B3/S23", 100, 7)?;
+//     search.run_with(|done, total| println!("{}/{}", done, total))?;
+//     for candidate in search.best() {
+//         println!("{} generations:\n{}", candidate.lifespan(), candidate.rle());
+//     }
+//
+// Like a SoupSearch, it runs a round at a time for callers that want
+// to stop now and then, and the same seed always finds the same
+// patterns. Patterns that haven't settled after max_generations, which
+// may well be growing forever, don't count as long lived at all.
+#[derive(Clone, Debug)]
+pub struct MethuselahSearch {
+    rule: LifeRule,
+    rng: Rng,
+    total: u32,
+    done: u32,
+    width: u32,
+    height: u32,
+    candidates: usize,
+    size: u32,
+    max_generations: u32,
+    // Every pattern tried so far, moved to the top-left corner of the
+    // box, and how long it lived: None for the ones that never settled.
+    tried: HashMap<u64, Option<Candidate>>,
+    population: Vec<u64>,
+}
+
+impl MethuselahSearch {
+    pub fn new(rule: &str, rounds: u32, seed: u64) -> Result<MethuselahSearch, Error> {
+        let rule: LifeRule = rule.parse()?;
+        if !rule.is_life_like() || rule.masks().0 & 1 != 0 {
+            return Err(Error::new(&format!("methuselah searches run B/S rules without B0, not {}", rule)));
+        }
+        Ok(MethuselahSearch {
+            rule,
+            rng: Rng::new(seed),
+            total: rounds,
+            done: 0,
+            width: 6,
+            height: 6,
+            candidates: 24,
+            size: 256,
+            max_generations: 20_000,
+            tried: HashMap::new(),
+            population: Vec::new(),
+        })
+    }
+
+    // The box starting patterns are drawn in, 6x6 unless set. It can
+    // hold 2 to 64 cells: one cell alone has nothing to mutate into.
+    pub fn set_box(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        if !(2..=64).contains(&(u64::from(width) * u64::from(height))) {
+            return Err(Error::new(&format!("a methuselah box holds 2 to 64 cells, not {}x{}", width, height)));
+        }
+        if width > self.size || height > self.size {
+            return Err(Error::new(&format!("a {}x{} box doesn't fit in {}x{}", width, height, self.size, self.size)));
+        }
+        self.width = width;
+        self.height = height;
+        self.population.clear();
+        Ok(())
+    }
+
+    // How many patterns every round tries, 24 unless set.
+    pub fn set_candidates(&mut self, candidates: u32) -> Result<(), Error> {
+        if candidates < 2 {
+            return Err(Error::new("a methuselah search needs at least 2 candidates a round"));
+        }
+        self.candidates = candidates as usize;
+        self.population.clear();
+        Ok(())
+    }
+
+    // The universe every pattern runs in, 256x256 unless set.
+    pub fn set_size(&mut self, size: u32) -> Result<(), Error> {
+        if size < self.width.max(self.height) {
+            return Err(Error::new(&format!("a {}x{} box doesn't fit in {}x{}", self.width, self.height, size, size)));
+        }
+        self.size = size;
+        Ok(())
+    }
+
+    // How long a pattern may take to settle, 20000 generations unless set.
+    pub fn set_max_generations(&mut self, max_generations: u32) {
+        self.max_generations = max_generations;
+    }
+
+    pub fn done(&self) -> u32 {
+        self.done
+    }
+
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.done >= self.total
+    }
+
+    // How many different patterns have been run so far.
+    pub fn tried(&self) -> usize {
+        self.tried.len()
+    }
+
+    // The longest lived patterns found so far, the longest first.
+    pub fn best(&self) -> Vec<Candidate> {
+        let mut best: Vec<&Candidate> = self.tried.values().flatten().collect();
+        best.sort_by(|a, b| b.lifespan.cmp(&a.lifespan).then_with(|| a.population.cmp(&b.population)).then_with(|| a.rle.cmp(&b.rle)));
+        best.into_iter().take(LEADERS).cloned().collect()
+    }
+
+    // Runs up to `count` more rounds and returns how many ran.
+    pub fn run(&mut self, count: u32) -> Result<u32, Error> {
+        let count = count.min(self.total - self.done);
+        for _ in 0..count {
+            self.run_round()?;
+        }
+        Ok(count)
+    }
+
+    // Runs every round that is left, calling `progress` with the rounds
+    // done and the total after each one.
+    pub fn run_with<F: FnMut(u32, u32)>(&mut self, mut progress: F) -> Result<Vec<Candidate>, Error> {
+        while !self.is_finished() {
+            self.run_round()?;
+            progress(self.done, self.total);
+        }
+        Ok(self.best())
+    }
+
+    fn run_round(&mut self) -> Result<(), Error> {
+        if self.population.is_empty() {
+            self.population = (0..self.candidates).map(|_| self.random_pattern()).collect();
+        } else {
+            // The better half lives on, and the rest are mutants of it.
+            let survivors = self.candidates.div_ceil(2);
+            self.population.truncate(survivors);
+            while self.population.len() < self.candidates {
+                let parent = self.population[(self.rng.next_u64() % survivors as u64) as usize];
+                let child = self.mutate(parent);
+                self.population.push(child);
+            }
+        }
+        for i in 0..self.population.len() {
+            let bits = self.population[i];
+            if !self.tried.contains_key(&bits) {
+                let candidate = self.evaluate(bits)?;
+                self.tried.insert(bits, candidate);
+            }
+        }
+        let tried = &self.tried;
+        let lifespan = |bits: &u64| tried[bits].as_ref().map_or(0, |candidate| candidate.lifespan);
+        self.population.sort_by(|a, b| lifespan(b).cmp(&lifespan(a)).then_with(|| a.cmp(b)));
+        self.done += 1;
+        Ok(())
+    }
+
+    // Half the cells of the box alive, never none of them.
+    fn random_pattern(&mut self) -> u64 {
+        loop {
+            let bits = self.rng.next_u64() & self.mask();
+            if bits != 0 {
+                return self.normalize(bits);
+            }
+        }
+    }
+
+    // One to three cells flipped. With two cells or more in the box,
+    // some child is neither empty nor the parent again.
+    fn mutate(&mut self, parent: u64) -> u64 {
+        let cells = self.area();
+        loop {
+            let mut child = parent;
+            for _ in 0..=self.rng.next_u64() % 3 {
+                child ^= 1 << (self.rng.next_u64() % cells);
+            }
+            if child != 0 && child != parent {
+                return self.normalize(child);
+            }
+        }
+    }
+
+    fn area(&self) -> u64 {
+        u64::from(self.width) * u64::from(self.height)
+    }
+
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.area())
+    }
+
+    fn cell(&self, bits: u64, row: u32, col: u32) -> bool {
+        bits & (1 << (row * self.width + col)) != 0
+    }
+
+    // Moved up against the top and left of the box, so that the same
+    // pattern one cell over isn't tried again.
+    fn normalize(&self, bits: u64) -> u64 {
+        let cells: Vec<(u32, u32)> = self.cells(bits);
+        let top = cells.iter().map(|&(row, _)| row).min().unwrap_or(0);
+        let left = cells.iter().map(|&(_, col)| col).min().unwrap_or(0);
+        cells.iter().fold(0, |moved, &(row, col)| moved | 1 << ((row - top) * self.width + col - left))
+    }
+
+    fn cells(&self, bits: u64) -> Vec<(u32, u32)> {
+        (0..self.height)
+            .flat_map(|row| (0..self.width).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.cell(bits, row, col))
+            .collect()
+    }
+
+    fn evaluate(&self, bits: u64) -> Result<Option<Candidate>, Error> {
+        let cells = self.cells(bits);
+        let mut universe = Universe::empty(self.size, self.size, self.rule)?;
+        universe.set_history_depth(0);
+        universe.set_engine(Engine::BitParallel);
+        let (top, left) = ((self.size - self.height) / 2, (self.size - self.width) / 2);
+        for &(row, col) in &cells {
+            let idx = universe.get_index(top + row, left + col);
+            universe.cells.set(idx, true);
+        }
+        universe.recount();
+        let lifespan = match settle(&mut universe, &self.rule, self.max_generations, |_| {}) {
+            Settled::DiedOut => universe.generation,
+            Settled::Cycle { since, .. } => since,
+            Settled::Unsettled => return Ok(None),
+        };
+        let height = cells.iter().map(|&(row, _)| row + 1).max().unwrap_or(0);
+        let width = cells.iter().map(|&(_, col)| col + 1).max().unwrap_or(0);
+        let population = cells.len() as u32;
+        let rle = rle::write(&RlePattern {
+            width,
+            height,
+            rule: Some(self.rule),
+            torus: None,
+            cells,
+            states: Vec::new(),
+        });
+        Ok(Some(Candidate {
+            rle,
+            population,
+            lifespan,
+            final_population: universe.population,
+        }))
+    }
+}
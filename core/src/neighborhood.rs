@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+// Which cells around a cell count as its neighbors. Moore takes the
+// whole square of side 2 * radius + 1 around it, von Neumann only the
+// diamond of cells at most `radius` steps away, going along rows and
+// columns. Life is Moore with radius 1: the eight nearest cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Neighborhood {
+    shape: NeighborhoodShape,
+    radius: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NeighborhoodShape {
+    Moore,
+    VonNeumann,
+}
+
+// Counts have to fit in a u8: a radius 7 square has 224 neighbors.
+// Larger than Life rules reach much further with their own counting.
+pub const MAX_RADIUS: u32 = 7;
+
+impl Neighborhood {
+    pub const MOORE: Neighborhood = Neighborhood {
+        shape: NeighborhoodShape::Moore,
+        radius: 1,
+    };
+
+    pub fn new(shape: NeighborhoodShape, radius: u32) -> Result<Neighborhood, Error> {
+        if !(1..=MAX_RADIUS).contains(&radius) {
+            return Err(Error::new("the neighborhood radius must be between 1 and 7"));
+        }
+        Ok(Neighborhood { shape, radius })
+    }
+
+    pub fn shape(&self) -> NeighborhoodShape {
+        self.shape
+    }
+
+    pub fn radius(&self) -> u32 {
+        self.radius
+    }
+
+    // How many columns either side of the cell the neighborhood spans
+    // `delta_row` rows above or below it.
+    pub fn reach(&self, delta_row: i64) -> i64 {
+        match self.shape {
+            NeighborhoodShape::Moore => i64::from(self.radius),
+            NeighborhoodShape::VonNeumann => i64::from(self.radius) - delta_row.abs(),
+        }
+    }
+
+    // How many neighbors every cell has.
+    pub fn size(&self) -> u32 {
+        let radius = i64::from(self.radius);
+        let cells: i64 = (-radius..=radius).map(|delta_row| 2 * self.reach(delta_row) + 1).sum();
+        cells as u32 - 1
+    }
+}
+
+impl Default for Neighborhood {
+    fn default() -> Self {
+        Neighborhood::MOORE
+    }
+}
+
+// Folds a coordinate into 0..size for Boundary::Mirror: the universe is
+// reflected over and over, so the first cell outside is the edge cell
+// again, then the one next to it, and so on.
+pub fn reflect(position: i64, size: i64) -> i64 {
+    let folded = position.rem_euclid(2 * size);
+    if folded < size {
+        folded
+    } else {
+        2 * size - 1 - folded
+    }
+}
@@ -0,0 +1,95 @@
+use crate::random::Rng;
+use crate::{Error, Universe};
+
+// Perlin's gradient noise, in two dimensions. Every corner of the unit
+// grid gets one of eight directions, picked through a shuffled table,
+// and a point in between blends how far it lies along the directions
+// of the four corners around it. What comes out varies smoothly from
+// about -1 to 1, in blobs about a grid square across. Only adds,
+// multiplies and floor() go into it, so every platform gets the very
+// same numbers.
+struct Perlin {
+    // A shuffle of 0 to 255, twice over so that lookups can run past
+    // the end without wrapping.
+    permutation: [u8; 512],
+}
+
+impl Perlin {
+    fn new(rng: &mut Rng) -> Perlin {
+        let mut shuffled: Vec<u8> = (0..=255).collect();
+        for i in (1..shuffled.len()).rev() {
+            let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+            shuffled.swap(i, j);
+        }
+        let mut permutation = [0; 512];
+        for (i, slot) in permutation.iter_mut().enumerate() {
+            *slot = shuffled[i % 256];
+        }
+        Perlin { permutation }
+    }
+
+    fn at(&self, x: f32, y: f32) -> f32 {
+        let (left, top) = (x.floor(), y.floor());
+        let (x, y) = (x - left, y - top);
+        let (col, row) = ((left as i64 & 255) as usize, (top as i64 & 255) as usize);
+        let p = &self.permutation;
+        let corner = |dx: usize, dy: usize| p[p[col + dx] as usize + row + dy];
+        let (u, v) = (fade(x), fade(y));
+        let top = lerp(u, gradient(corner(0, 0), x, y), gradient(corner(1, 0), x - 1.0, y));
+        let bottom = lerp(u, gradient(corner(0, 1), x, y - 1.0), gradient(corner(1, 1), x - 1.0, y - 1.0));
+        lerp(v, top, bottom)
+    }
+}
+
+// 6t^5 - 15t^4 + 10t^3, which eases in and out of every grid square.
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f32, a: f32, b: f32) -> f32 {
+    a + t * (b - a)
+}
+
+// How far (x, y) lies along one of the four axes and four diagonals.
+fn gradient(hash: u8, x: f32, y: f32) -> f32 {
+    match hash & 7 {
+        0 => x + y,
+        1 => y - x,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+impl Universe {
+    // Fills the universe with blobs of live cells rather than an even
+    // soup: cells are alive where the noise is above `threshold`. The
+    // noise goes from about -1 to 1, so 0 makes roughly half the cells
+    // alive and higher thresholds fewer, in smaller islands.
+    // `frequency` is how far the noise moves on per cell: 0.05 to 0.2
+    // makes blobs some 5 to 20 cells across. The table behind the noise
+    // comes from the universe's generator, see set_seed().
+    pub fn randomize_noise(&mut self, frequency: f32, threshold: f32) -> Result<(), Error> {
+        if !(frequency > 0.0 && frequency.is_finite()) {
+            return Err(Error::new("the noise frequency must be more than 0"));
+        }
+        if !threshold.is_finite() {
+            return Err(Error::new("the noise threshold must be a number"));
+        }
+        self.record(|universe| {
+            let noise = Perlin::new(&mut universe.rng);
+            for row in 0..universe.height {
+                for col in 0..universe.width {
+                    let idx = universe.get_index(row, col);
+                    let alive = noise.at(col as f32 * frequency, row as f32 * frequency) > threshold;
+                    universe.cells.set(idx, alive);
+                }
+            }
+            universe.recount();
+        });
+        Ok(())
+    }
+}
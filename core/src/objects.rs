@@ -0,0 +1,351 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::apgcode::{self, Cells};
+use crate::{Error, Neighborhood, Universe};
+
+// Live cells at most this far apart, counting diagonals as one, belong
+// to the same object: two cells apart, they can still bring the cell
+// between them to life, three apart they can't see each other at all.
+const REACH: i64 = 2;
+
+// How long an object gets to come back to itself before it counts as
+// unidentified. Soups hardly ever leave anything of a longer period.
+const MAX_PERIOD: u32 = 60;
+
+// What Catagolue and LifeWiki call the objects soups leave most, by
+// apgcode.
+const NAMES: &[(&str, &str)] = &[
+    // Still lifes
+    ("xs4_33", "block"),
+    ("xs6_696", "beehive"),
+    ("xs7_2596", "loaf"),
+    ("xs5_253", "boat"),
+    ("xs4_252", "tub"),
+    ("xs6_356", "ship"),
+    ("xs8_6996", "pond"),
+    ("xs7_25ac", "long boat"),
+    ("xs6_25a4", "barge"),
+    ("xs8_69ic", "mango"),
+    ("xs7_178c", "eater 1"),
+    ("xs6_bd", "snake"),
+    ("xs6_39c", "aircraft carrier"),
+    ("xs8_25ak8", "long barge"),
+    ("xs8_35ac", "long ship"),
+    ("xs8_rr", "bi-block"),
+    ("xs11_g8o652z11", "boat-tie"),
+    ("xs12_g8o653z11", "ship-tie"),
+    // Oscillators
+    ("xp2_7", "blinker"),
+    ("xp2_7e", "toad"),
+    ("xp2_318c", "beacon"),
+    ("xp2_2a54", "clock"),
+    ("xp3_co9nas0san9oczgoldlo0oldlogz1047210127401", "pulsar"),
+    ("xp15_4r4z4r4", "pentadecathlon"),
+    // Spaceships
+    ("xq4_153", "glider"),
+    ("xq4_6frc", "lightweight spaceship"),
+    ("xq4_27dee6", "middleweight spaceship"),
+    ("xq4_27deee6", "heavyweight spaceship"),
+];
+
+// The usual name of the object with the given apgcode, if it has one
+// this knows of.
+pub fn object_name(code: &str) -> Option<&'static str> {
+    NAMES.iter().find(|&&(known, _)| known == code).map(|&(_, name)| name)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum ObjectKind {
+    StillLife,
+    Oscillator,
+    Spaceship,
+    // Still changing into something else, or of a period longer than
+    // identify_objects() waits for.
+    Unidentified,
+}
+
+// Which way a spaceship is going, as the nearest of eight compass
+// points, north being up the screen, towards row 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum Heading {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Heading {
+    const CLOCKWISE: [Heading; 8] = [
+        Heading::North,
+        Heading::NorthEast,
+        Heading::East,
+        Heading::SouthEast,
+        Heading::South,
+        Heading::SouthWest,
+        Heading::West,
+        Heading::NorthWest,
+    ];
+
+    // None for objects that stay put.
+    pub fn of(rows: i32, cols: i32) -> Option<Heading> {
+        if (rows, cols) == (0, 0) {
+            return None;
+        }
+        let eighths = (f64::from(cols).atan2(f64::from(-rows)) / std::f64::consts::FRAC_PI_4).round();
+        Some(Heading::CLOCKWISE[(eighths as i32).rem_euclid(8) as usize])
+    }
+}
+
+// One of the objects identify_objects() found: where it is, what it
+// is, and what it's called if it's a common one.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectInfo {
+    kind: ObjectKind,
+    code: Option<String>,
+    name: Option<&'static str>,
+    period: Option<u32>,
+    displacement: (i32, i32),
+    heading: Option<Heading>,
+    population: u32,
+    top: u32,
+    left: u32,
+    width: u32,
+    height: u32,
+}
+
+impl ObjectInfo {
+    pub fn kind(&self) -> ObjectKind {
+        self.kind
+    }
+
+    // The apgcode, e.g. "xs4_33", unless it's unidentified.
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    // E.g. "block", for the objects in the dictionary.
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    // 1 for still lifes.
+    pub fn period(&self) -> Option<u32> {
+        self.period
+    }
+
+    // How far a spaceship moves every period, as (rows, columns); (0, 0)
+    // for anything else. A glider heading south east moves (1, 1) every
+    // 4 generations.
+    pub fn displacement(&self) -> (i32, i32) {
+        self.displacement
+    }
+
+    // The displacement per generation, as (rows, columns).
+    pub fn velocity(&self) -> (f64, f64) {
+        let period = f64::from(self.period.unwrap_or(1));
+        (f64::from(self.displacement.0) / period, f64::from(self.displacement.1) / period)
+    }
+
+    // As a fraction of c, the speed of light of one cell per generation,
+    // counting a diagonal step as one: 1/4 for gliders, 1/2 for the
+    // light, middle and heavyweight spaceships.
+    pub fn speed(&self) -> f64 {
+        let (rows, cols) = self.velocity();
+        rows.abs().max(cols.abs())
+    }
+
+    pub fn heading(&self) -> Option<Heading> {
+        self.heading
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.displacement != (0, 0)
+    }
+
+    pub fn population(&self) -> u32 {
+        self.population
+    }
+
+    // The box around the object's cells right now.
+    pub fn top(&self) -> u32 {
+        self.top
+    }
+
+    pub fn left(&self) -> u32 {
+        self.left
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // Whether (row, col) is inside the object's box, for telling what
+    // is under the pointer.
+    pub fn contains(&self, row: u32, col: u32) -> bool {
+        (self.top..self.top + self.height).contains(&row) && (self.left..self.left + self.width).contains(&col)
+    }
+}
+
+impl Universe {
+    // Splits the live cells into separate objects and tells what each
+    // of them is, by running it on its own until it comes back to
+    // itself. Works best once the universe has settled: anything still
+    // in the middle of a reaction comes out unidentified. Objects come
+    // out from the top down, in the order of their first cells. One
+    // that reaches over the edge of a torus comes out as pieces.
+    pub fn identify_objects(&self) -> Result<Vec<ObjectInfo>, Error> {
+        self.check_identifiable()?;
+        Ok(split(&live_cells(self)).into_iter().map(|cells| self.identify(cells)).collect())
+    }
+
+    // The spaceships that have got away from everything else and are
+    // moving further away, so that nothing can ever catch them up:
+    // gliders leaving a settled soup, say, for the UI to draw arrows on.
+    pub fn escaping_objects(&self) -> Result<Vec<ObjectInfo>, Error> {
+        self.check_identifiable()?;
+        let groups = split(&live_cells(self));
+        let mut escaping = Vec::new();
+        for i in 0..groups.len() {
+            let open = open_sides(&groups, i);
+            if open.contains(&true) {
+                let object = self.identify(groups[i].clone());
+                if moving_away(open, object.displacement) {
+                    escaping.push(object);
+                }
+            }
+        }
+        Ok(escaping)
+    }
+
+    fn check_identifiable(&self) -> Result<(), Error> {
+        let plain = self.rule.states() == 2
+            && self.rule.larger_than_life().is_none()
+            && self.neighborhood == Neighborhood::MOORE
+            && self.weighted.is_none()
+            && self.stochastic.is_none()
+            && self.custom_rule.is_none()
+            && self.table.is_none();
+        if !plain {
+            return Err(Error::new(&format!(
+                "objects can only be identified under two-state rules of the eight nearest neighbors, not {}",
+                self.rule()
+            )));
+        }
+        Ok(())
+    }
+
+    fn identify(&self, cells: Cells) -> ObjectInfo {
+        let (top, left, bottom, right) = bounds(cells.iter().copied()).expect("objects have cells");
+        let population = cells.len() as u32;
+        let found = apgcode::classify(cells.into_iter().collect::<HashSet<_>>(), &self.rule, MAX_PERIOD).ok();
+        let kind = match &found {
+            None => ObjectKind::Unidentified,
+            Some(found) if found.displacement != (0, 0) => ObjectKind::Spaceship,
+            Some(found) if found.period == 1 => ObjectKind::StillLife,
+            Some(_) => ObjectKind::Oscillator,
+        };
+        let (rows, cols) = found.as_ref().map_or((0, 0), |found| found.displacement);
+        let displacement = (rows as i32, cols as i32);
+        ObjectInfo {
+            kind,
+            displacement,
+            heading: Heading::of(displacement.0, displacement.1),
+            name: found.as_ref().and_then(|found| object_name(&found.code)),
+            period: found.as_ref().map(|found| found.period),
+            code: found.map(|found| found.code),
+            population,
+            top: top as u32,
+            left: left as u32,
+            width: (right - left + 1) as u32,
+            height: (bottom - top + 1) as u32,
+        }
+    }
+}
+
+// How far a spaceship has to be from everything else before it counts
+// as getting away.
+const ESCAPE_MARGIN: i64 = 8;
+
+// (top, left, bottom, right), inclusive.
+fn bounds(cells: impl Iterator<Item = (i64, i64)>) -> Option<(i64, i64, i64, i64)> {
+    cells.fold(None, |bounds, (row, col)| match bounds {
+        None => Some((row, col, row, col)),
+        Some((top, left, bottom, right)) => Some((top.min(row), left.min(col), bottom.max(row), right.max(col))),
+    })
+}
+
+// Which sides of the box around all the other objects object `i` is
+// clear of, by ESCAPE_MARGIN or more: below, above, right and left.
+// Anything outside the box around the rest, moving away from it, can
+// never come back, since nothing in there can catch it up.
+pub(crate) fn open_sides(objects: &[Cells], i: usize) -> [bool; 4] {
+    let rest = objects.iter().enumerate().filter(|&(j, _)| j != i).flat_map(|(_, other)| other.iter().copied());
+    let (top, left, bottom, right) = bounds(objects[i].iter().copied()).expect("objects have cells");
+    match bounds(rest) {
+        None => [true; 4],
+        Some((rest_top, rest_left, rest_bottom, rest_right)) => [
+            top > rest_bottom + ESCAPE_MARGIN,
+            bottom < rest_top - ESCAPE_MARGIN,
+            left > rest_right + ESCAPE_MARGIN,
+            right < rest_left - ESCAPE_MARGIN,
+        ],
+    }
+}
+
+// Whether a displacement of (rows, columns) takes an object away from a
+// side open_sides() found it clear of.
+pub(crate) fn moving_away(open: [bool; 4], (rows, cols): (i32, i32)) -> bool {
+    (open[0] && rows > 0) || (open[1] && rows < 0) || (open[2] && cols > 0) || (open[3] && cols < 0)
+}
+
+// The live cells, as (row, col), in reading order.
+pub(crate) fn live_cells(universe: &Universe) -> Cells {
+    let width = universe.width as usize;
+    universe.cells.ones().map(|idx| ((idx / width) as i64, (idx % width) as i64)).collect()
+}
+
+// Splits live cells, as (row, col), into the objects they make up. Both
+// the objects and their cells come out in the order the cells went in.
+pub(crate) fn split(cells: &[(i64, i64)]) -> Vec<Cells> {
+    let index: HashMap<(i64, i64), usize> = cells.iter().enumerate().map(|(i, &cell)| (cell, i)).collect();
+    let mut parents: Vec<usize> = (0..cells.len()).collect();
+    fn root(parents: &mut [usize], mut i: usize) -> usize {
+        while parents[i] != i {
+            parents[i] = parents[parents[i]];
+            i = parents[i];
+        }
+        i
+    }
+    for (i, &(row, col)) in cells.iter().enumerate() {
+        for delta_row in -REACH..=REACH {
+            for delta_col in -REACH..=REACH {
+                if let Some(&j) = index.get(&(row + delta_row, col + delta_col)) {
+                    let (a, b) = (root(&mut parents, i), root(&mut parents, j));
+                    parents[a.max(b)] = a.min(b);
+                }
+            }
+        }
+    }
+    let mut objects: Vec<Cells> = Vec::new();
+    let mut slots: HashMap<usize, usize> = HashMap::new();
+    for (i, &cell) in cells.iter().enumerate() {
+        let group = root(&mut parents, i);
+        let slot = *slots.entry(group).or_insert_with(|| {
+            objects.push(Vec::new());
+            objects.len() - 1
+        });
+        objects[slot].push(cell);
+    }
+    objects
+}
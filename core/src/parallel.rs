@@ -0,0 +1,74 @@
+use std::ops::Range;
+
+use fixedbitset::FixedBitSet;
+
+use crate::rule::LifeRule;
+use crate::simd;
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// One band of rows and the words of the next generation it writes.
+struct Band<'a> {
+    words: &'a mut [u32],
+    first_word: usize,
+    rows: Range<u32>,
+}
+
+// More bands than this would only add overhead, whatever number of
+// workers is asked for.
+const MAX_BANDS: u32 = 256;
+
+// Splits the universe into at most `bands` bands of whole rows. Each band
+// has to start on a word boundary, so that no two bands ever write to the
+// same word; that only happens every 32 / gcd(width, 32) rows.
+fn split(words: &mut [u32], width: u32, height: u32, bands: u32) -> Vec<Band<'_>> {
+    let rows_per_unit = 32 / gcd(width, 32);
+    let units = height.div_ceil(rows_per_unit);
+    let bands = bands.clamp(1, units.min(MAX_BANDS));
+    // Where band number `band` starts, worked out in u64 since units
+    // times bands can be more than a u32 holds.
+    let boundary = |band: u32| (u64::from(band) * u64::from(units) / u64::from(bands) * u64::from(rows_per_unit)).min(u64::from(height)) as u32;
+
+    let mut split = Vec::with_capacity(bands as usize);
+    let mut rest = words;
+    let mut first_word = 0;
+    for band in 0..bands {
+        let (start, end) = (boundary(band), boundary(band + 1));
+        let len = if band + 1 == bands {
+            rest.len()
+        } else {
+            (end as usize * width as usize) / 32 - first_word
+        };
+        let (words, tail) = rest.split_at_mut(len);
+        split.push(Band {
+            words,
+            first_word,
+            rows: start..end,
+        });
+        first_word += len;
+        rest = tail;
+    }
+    split
+}
+
+// One generation on a torus, computed by up to `bands` workers at once.
+// Without the `parallel` feature, the bands simply run one after another.
+pub fn step_torus(cells: &FixedBitSet, next: &mut FixedBitSet, width: u32, height: u32, rule: LifeRule, bands: u32) {
+    next.clear();
+    let bands = split(next.as_mut_slice(), width, height, bands);
+    let run = |band: Band| simd::step_rows(cells, band.words, band.first_word, width, height, rule, band.rows);
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        bands.into_par_iter().for_each(run);
+    }
+    #[cfg(not(feature = "parallel"))]
+    bands.into_iter().for_each(run);
+}
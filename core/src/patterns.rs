@@ -0,0 +1,277 @@
+use crate::apgcode;
+use crate::error::parse_failed;
+use crate::format::{self, PatternFormat};
+use crate::plaintext;
+use crate::rle::{self, RlePattern};
+use crate::Error;
+
+use serde::Serialize;
+
+// A rectangular pattern that can be rotated, flipped and moved around
+// before being stamped into a universe with `Universe::stamp`.
+// Transforms return a new pattern, so one glider can be placed in all
+// four orientations without keeping four copies around in JavaScript.
+//
+// The offset is where the pattern's top-left corner lands relative to
+// the stamping position; translate() changes only that.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    width: u32,
+    height: u32,
+    offset_row: i32,
+    offset_col: i32,
+    // Live cells as (row, col) inside the width x height box.
+    cells: Vec<(u32, u32)>,
+}
+
+impl Pattern {
+    pub(crate) fn live_cells(&self) -> &[(u32, u32)] {
+        &self.cells
+    }
+
+    pub(crate) fn offset(&self) -> (i32, i32) {
+        (self.offset_row, self.offset_col)
+    }
+
+    fn map<F: Fn(u32, u32) -> (u32, u32)>(&self, width: u32, height: u32, f: F) -> Pattern {
+        let mut cells: Vec<_> = self.cells.iter().map(|&(r, c)| f(r, c)).collect();
+        cells.sort_unstable();
+        Pattern {
+            width,
+            height,
+            offset_row: self.offset_row,
+            offset_col: self.offset_col,
+            cells,
+        }
+    }
+}
+
+impl From<RlePattern> for Pattern {
+    fn from(rle: RlePattern) -> Pattern {
+        Pattern {
+            width: rle.width,
+            height: rle.height,
+            offset_row: 0,
+            offset_col: 0,
+            cells: rle.cells,
+        }
+    }
+}
+
+impl Pattern {
+    // `cells` is a flat list of (row, col) pairs, like Universe::set_cells.
+    pub fn new(width: u32, height: u32, cells: &[u32]) -> Result<Pattern, Error> {
+        if !cells.len().is_multiple_of(2) {
+            return Err(Error::new("Pattern expects (row, col) pairs"));
+        }
+        let mut live = Vec::with_capacity(cells.len() / 2);
+        for pair in cells.chunks(2) {
+            let (row, col) = (pair[0], pair[1]);
+            if row >= height || col >= width {
+                return Err(Error::new(&format!(
+                    "cell ({}, {}) is outside the {}x{} pattern",
+                    row, col, width, height
+                )));
+            }
+            live.push((row, col));
+        }
+        live.sort_unstable();
+        live.dedup();
+        Ok(Pattern {
+            width,
+            height,
+            offset_row: 0,
+            offset_col: 0,
+            cells: live,
+        })
+    }
+
+    pub fn from_rle(rle: &str) -> Result<Pattern, Error> {
+        Ok(rle::parse(rle).inspect_err(parse_failed("RLE"))?.into())
+    }
+
+    // A pattern in any of the formats PatternFormat knows.
+    pub fn parse(text: &str) -> Result<Pattern, Error> {
+        Ok(format::parse_any(text).inspect_err(parse_failed("the pattern"))?.into())
+    }
+
+    // A pattern file's raw bytes, e.g. dropped onto the page: RLE, 
+    // plaintext, Life 1.05 or 1.06, or Macrocell, and possibly gzipped. 
+    pub fn from_file(bytes: &[u8]) -> Result<Pattern, Error> {
+        Ok(format::parse_file(bytes).inspect_err(parse_failed("the pattern file"))?.into())
+    }
+
+    pub fn from_plaintext(text: &str) -> Result<Pattern, Error> {
+        Ok(plaintext::parse(text).inspect_err(parse_failed("plaintext"))?.into())
+    }
+
+    // The object an apgcode like "xq4_153" names, or the cells of a 
+    // bare Wechsler code like "153".
+    pub fn from_apgcode(code: &str) -> Result<Pattern, Error> {
+        apgcode::decode(code)
+    }
+
+    // One of the built-in patterns, by name.
+    pub fn builtin(name: &str) -> Result<Pattern, Error> {
+        get(name)
+            .map(Pattern::from)
+            .ok_or_else(|| Error::new(&format!("unknown pattern {:?}", name)))
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // The live cells as a flat list of (row, col) pairs.
+    pub fn cells(&self) -> Vec<u32> {
+        self.cells.iter().flat_map(|&(r, c)| [r, c]).collect()
+    }
+
+    // A quarter turn clockwise.
+    pub fn rotate90(&self) -> Pattern {
+        let height = self.height;
+        self.map(self.height, self.width, |r, c| (c, height - 1 - r))
+    }
+
+    // Mirrors left to right.
+    pub fn flip_h(&self) -> Pattern {
+        let width = self.width;
+        self.map(self.width, self.height, |r, c| (r, width - 1 - c))
+    }
+
+    // Mirrors top to bottom.
+    pub fn flip_v(&self) -> Pattern {
+        let height = self.height;
+        self.map(self.width, self.height, |r, c| (height - 1 - r, c))
+    }
+
+    // Moves the pattern relative to wherever it gets stamped.
+    pub fn translate(&self, rows: i32, cols: i32) -> Pattern {
+        let mut moved = self.clone();
+        moved.offset_row = moved.offset_row.saturating_add(rows);
+        moved.offset_col = moved.offset_col.saturating_add(cols);
+        moved
+    }
+
+    pub fn to_plaintext(&self) -> Result<String, Error> {
+        self.to_text(PatternFormat::Plaintext)
+    }
+
+    pub fn to_text(&self, format: PatternFormat) -> Result<String, Error> {
+        format::write(
+            &RlePattern {
+                width: self.width,
+                height: self.height,
+                rule: None,
+                torus: None,
+                cells: self.cells.clone(),
+                states: Vec::new(),
+            },
+            format,
+        )
+    }
+
+    // Names the pattern as Catagolue would under `rule`, e.g. "xs4_33" 
+    // for a block. The pattern is run until it comes back, so it has to 
+    // be a single still life, oscillator or spaceship.
+    pub fn apgcode(&self, rule: &str) -> Result<String, Error> {
+        apgcode::encode(self, &rule.parse()?)
+    }
+
+    pub fn to_rle(&self) -> String {
+        rle::write(&RlePattern {
+            width: self.width,
+            height: self.height,
+            rule: None,
+            torus: None,
+            cells: self.cells.clone(),
+            states: Vec::new(),
+        })
+    }
+}
+
+// Classic patterns compiled into the wasm, as RLE bodies.
+// The names are what `Universe::insert_pattern` accepts.
+const CATALOG: &[(&str, &str)] = &[
+    // Still lifes
+    ("block", "2o$2o!"),
+    ("beehive", "b2o$o2bo$b2o!"),
+    ("loaf", "b2o$o2bo$bobo$2bo!"),
+    ("boat", "2o$obo$bo!"),
+    // Oscillators
+    ("blinker", "3o!"),
+    ("toad", "b3o$3o!"),
+    ("beacon", "2o$2o$2b2o$2b2o!"),
+    (
+        "pulsar",
+        "2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+    ),
+    ("pentadecathlon", "2bo4bo$2ob4ob2o$2bo4bo!"),
+    // Spaceships
+    ("glider", "bo$2bo$3o!"),
+    ("lwss", "bo2bo$o$o3bo$4o!"),
+    ("mwss", "3bo$bo3bo$o$o4bo$5o!"),
+    ("hwss", "3b2o$bo4bo$o$o5bo$6o!"),
+    // Guns
+    (
+        "gosper-gun",
+        "24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+    ),
+    // Methuselahs
+    ("r-pentomino", "b2o$2o$bo!"),
+    ("acorn", "bo$3bo$2o2b3o!"),
+    ("diehard", "6bo$2o$bo3b3o!"),
+];
+
+// The names and RLE of the catalog, as memory_report() counts them.
+pub fn catalog_bytes() -> usize {
+    CATALOG.iter().map(|(name, body)| name.len() + body.len()).sum()
+}
+
+pub fn get(name: &str) -> Option<RlePattern> {
+    CATALOG
+        .iter()
+        .find(|&&(n, _)| n == name)
+        .map(|&(_, body)| rle::parse(body).expect("built-in patterns are valid RLE"))
+}
+
+// A catalog entry, as listed for the UI's pattern dropdown.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PatternInfo {
+    name: &'static str,
+    width: u32,
+    height: u32,
+}
+
+impl PatternInfo {
+    pub fn name(&self) -> String {
+        self.name.to_string()
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+// Every built-in pattern, in catalog order.
+pub fn list_patterns() -> Vec<PatternInfo> {
+    CATALOG
+        .iter()
+        .map(|&(name, _)| {
+            let pattern = get(name).unwrap();
+            PatternInfo {
+                name,
+                width: pattern.width,
+                height: pattern.height,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,135 @@
+use std::alloc::{GlobalAlloc, Layout};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+// How many ticks the rolling average is taken over.
+const AVERAGE_WINDOW: u64 = 60;
+
+// What the universe measured about its ticks. A tick is one call of
+// tick(), tick_many() and the like, however many generations it runs.
+// Counting is the time spent working out the next generations,
+// writing everything after: swapping them in, ages, activity, cycle
+// detection, statistics and the undo history. Times are in
+// milliseconds, as told by the clock given to set_perf_clock(), and
+// allocations are only counted with a CountingAllocator installed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerfStats {
+    pub ticks: u64,
+    pub last_tick_ms: f64,
+    // Over the last AVERAGE_WINDOW ticks, more or less: older ones fade
+    // out rather than drop off.
+    pub average_tick_ms: f64,
+    pub count_ms: f64,
+    pub write_ms: f64,
+    pub allocations: u64,
+}
+
+static CLOCK: OnceLock<fn() -> f64> = OnceLock::new();
+
+// Tells the counters how to read the time, in milliseconds from any
+// fixed point. Native programs don't have to, they get one based on
+// std::time::Instant; on wasm32 there is no clock until one is set and
+// every time reads 0. Only the first clock set counts.
+pub fn set_perf_clock(clock: fn() -> f64) {
+    let _ = CLOCK.set(clock);
+}
+
+fn now() -> f64 {
+    match CLOCK.get() {
+        Some(clock) => clock(),
+        None => default_clock(),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn default_clock() -> f64 {
+    static START: OnceLock<std::time::Instant> = OnceLock::new();
+    START.get_or_init(std::time::Instant::now).elapsed().as_secs_f64() * 1000.0
+}
+
+#[cfg(target_arch = "wasm32")]
+fn default_clock() -> f64 {
+    0.0
+}
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+// Wraps another allocator and counts how often it is asked for memory,
+// which PerfStats::allocations then reports per tick:
+//
+//     #[global_allocator]
+//     static ALLOC: CountingAllocator<System> = CountingAllocator(System);
+pub struct CountingAllocator<A>(pub A);
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        self.0.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        self.0.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        self.0.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.0.dealloc(ptr, layout)
+    }
+}
+
+// The universe's side of the counters. start() and finish() go around
+// a tick, counted() and written() around the writing part of each of
+// its generations.
+#[derive(Clone, Debug, Default)]
+pub struct Counters {
+    stats: PerfStats,
+    started: f64,
+    allocations: u64,
+    counting: f64,
+    // When the current counting phase began, None while writing.
+    mark: Option<f64>,
+}
+
+impl Counters {
+    pub fn stats(&self) -> PerfStats {
+        self.stats
+    }
+
+    pub fn start(&mut self) {
+        self.started = now();
+        self.allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        self.counting = 0.0;
+        self.mark = Some(self.started);
+    }
+
+    // A generation has been worked out and is about to be written.
+    // Engines that go through finish_step() get here twice.
+    pub fn counted(&mut self) {
+        if let Some(mark) = self.mark.take() {
+            self.counting += now() - mark;
+        }
+    }
+
+    pub fn written(&mut self) {
+        self.mark = Some(now());
+    }
+
+    pub fn finish(&mut self) {
+        let elapsed = now() - self.started;
+        let stats = &mut self.stats;
+        stats.ticks += 1;
+        stats.last_tick_ms = elapsed;
+        stats.average_tick_ms += (elapsed - stats.average_tick_ms) / stats.ticks.min(AVERAGE_WINDOW) as f64;
+        stats.count_ms = self.counting;
+        stats.write_ms = elapsed - self.counting;
+        stats.allocations = ALLOCATIONS.load(Ordering::Relaxed) - self.allocations;
+    }
+}
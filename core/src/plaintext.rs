@@ -0,0 +1,97 @@
+use crate::rle::RlePattern;
+use crate::Error;
+
+// The most text write() and Life 1.05 put out. A sparse pattern spans
+// far more rows and columns than it has cells, and each of those is a
+// byte of text.
+const MAX_BYTES: u64 = 1 << 26;
+
+// LifeWiki's plaintext format, the .cells files:
+//
+//     !Name: Glider
+//     !
+//     .O
+//     ..O
+//     OOO
+//
+// Lines starting with `!` are comments. Every other line is a row,
+// with `.` for a dead cell and `O` for a live one. Rows can stop short,
+// and an empty line is a row of dead cells. Some files use `*` for live
+// cells, which is read too.
+pub fn parse(text: &str) -> Result<RlePattern, Error> {
+    let mut pattern = RlePattern {
+        width: 0,
+        height: 0,
+        rule: None,
+        torus: None,
+        cells: Vec::new(),
+        states: Vec::new(),
+    };
+    let mut rows = 0;
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if line.starts_with('!') {
+            continue;
+        }
+        for (col, c) in line.chars().enumerate() {
+            match c {
+                '.' => {}
+                'O' | '*' => pattern.cells.push((rows, col as u32)),
+                _ => {
+                    return Err(Error::new(&format!(
+                        "invalid plaintext on line {}: unexpected {:?}, cells are . or O",
+                        number + 1,
+                        c
+                    )))
+                }
+            }
+        }
+        pattern.width = pattern.width.max(line.chars().count() as u32);
+        rows += 1;
+        // Empty lines at the end aren't rows.
+        if !line.is_empty() {
+            pattern.height = rows;
+        }
+    }
+    Ok(pattern)
+}
+
+// Writes the live cells back out, every row stopping at its last live
+// cell. Cells in other states can't be written and come out dead.
+pub fn write(pattern: &RlePattern) -> Result<String, Error> {
+    rows(pattern, 'O')
+}
+
+// The rows of a pattern as `.` for dead cells and `live` for live ones,
+// shared with Life 1.05, which lays its cells out the same way.
+pub(crate) fn rows(pattern: &RlePattern, live: char) -> Result<String, Error> {
+    let mut cells = pattern.cells.clone();
+    cells.sort_unstable();
+    cells.dedup();
+    // A newline for every row, and the cells up to the last live one.
+    let mut bytes = u64::from(pattern.height);
+    for (i, &(row, col)) in cells.iter().enumerate() {
+        if cells.get(i + 1).is_none_or(|&(next, _)| next != row) {
+            bytes += u64::from(col) + 1;
+        }
+    }
+    if bytes > MAX_BYTES {
+        return Err(Error::new(&format!(
+            "the pattern would take {} bytes of text, the most is {}",
+            bytes, MAX_BYTES
+        )));
+    }
+
+    let mut out = String::with_capacity(bytes as usize);
+    let mut cells = cells.into_iter().peekable();
+    for row in 0..pattern.height {
+        let mut col = 0;
+        while let Some((_, next)) = cells.next_if(|&(r, _)| r == row) {
+            out.extend(std::iter::repeat_n('.', (next - col) as usize));
+            out.push(live);
+            col = next + 1;
+        }
+        out.push('\n');
+    }
+    Ok(out)
+}
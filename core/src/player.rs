@@ -0,0 +1,125 @@
+use crate::{Error, Universe};
+
+// Generations per second that faster() and slower() step through.
+pub const SPEEDS: [f64; 10] = [1.0, 2.0, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0, 240.0, 960.0];
+
+// At most this much time counts towards a single advance(), so a tab
+// that spent a while in the background doesn't come back to minutes'
+// worth of generations.
+const MAX_ELAPSED_MS: f64 = 250.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayerState {
+    Paused,
+    Playing,
+}
+
+// Play, pause and step controls with a speed in generations per second.
+// The player keeps no clock of its own: whatever drives it, be it
+// requestAnimationFrame, a worker's timer or a test, passes the time in
+// milliseconds to advance(), which runs as many generations as are due.
+#[derive(Clone, Debug)]
+pub struct Player {
+    state: PlayerState,
+    speed: f64,
+    // When advance() was last called while playing, and the part of a
+    // generation that was due but not run yet.
+    last: Option<f64>,
+    owed: f64,
+}
+
+impl Default for Player {
+    fn default() -> Player {
+        Player {
+            state: PlayerState::Paused,
+            speed: 10.0,
+            last: None,
+            owed: 0.0,
+        }
+    }
+}
+
+impl Player {
+    pub fn new() -> Player {
+        Player::default()
+    }
+
+    pub fn state(&self) -> PlayerState {
+        self.state
+    }
+
+    pub fn play(&mut self) {
+        self.state = PlayerState::Playing;
+    }
+
+    // Pausing forgets the time, so playing again doesn't make up for the pause.
+    pub fn pause(&mut self) {
+        self.state = PlayerState::Paused;
+        self.last = None;
+        self.owed = 0.0;
+    }
+
+    pub fn toggle(&mut self) {
+        match self.state {
+            PlayerState::Paused => self.play(),
+            PlayerState::Playing => self.pause(),
+        }
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    // Any positive speed, not only the presets.
+    pub fn set_speed(&mut self, generations_per_second: f64) -> Result<(), Error> {
+        if !(generations_per_second.is_finite() && generations_per_second > 0.0) {
+            return Err(Error::new("speed must be a positive number of generations per second"));
+        }
+        self.speed = generations_per_second;
+        Ok(())
+    }
+
+    // The next preset up or down from the current speed.
+    pub fn faster(&mut self) {
+        if let Some(&speed) = SPEEDS.iter().find(|&&speed| speed > self.speed) {
+            self.speed = speed;
+        }
+    }
+
+    pub fn slower(&mut self) {
+        if let Some(&speed) = SPEEDS.iter().rev().find(|&&speed| speed < self.speed) {
+            self.speed = speed;
+        }
+    }
+
+    // Pauses and runs exactly one generation.
+    pub fn step(&mut self, universe: &mut Universe) {
+        self.pause();
+        universe.tick();
+    }
+
+    // Pauses and undoes the last step. A batch of generations advance()
+    // ran together goes back all at once. False if there is nothing to undo.
+    pub fn step_back(&mut self, universe: &mut Universe) -> bool {
+        self.pause();
+        universe.undo()
+    }
+
+    // Runs the generations due by `now`, in milliseconds, and says how
+    // many. Nothing runs while paused or on the first call after play().
+    pub fn advance(&mut self, universe: &mut Universe, now: f64) -> u32 {
+        if self.state == PlayerState::Paused {
+            return 0;
+        }
+        let elapsed = self.last.map_or(0.0, |last| (now - last).clamp(0.0, MAX_ELAPSED_MS));
+        self.last = Some(now);
+        self.owed += elapsed * self.speed / 1000.0;
+        let due = self.owed.floor();
+        self.owed -= due;
+        let due = due as u32;
+        if due > 0 {
+            universe.tick_many(due);
+        }
+        due
+    }
+}
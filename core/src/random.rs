@@ -0,0 +1,36 @@
+// A small, seedable pseudo-random number generator (SplitMix64).
+//
+// js_sys::Math::random() can't be seeded, so anything that must be
+// reproducible (random soups, for instance) draws from this instead.
+// It is fast, has no dependencies, and produces the same sequence on
+// every platform for the same seed.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    // Where the sequence is up to. Rng::new() with it carries on from
+    // exactly here.
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [0, 1), using the top 24 bits so every value is
+    // exactly representable as an f32.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
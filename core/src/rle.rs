@@ -0,0 +1,315 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::rule::{LifeRule, ParseRuleError};
+
+// A pattern decoded from the Run Length Encoded format used by Golly
+// and LifeWiki:
+//
+//     #N Glider
+//     x = 3, y = 3, rule = B3/S23
+//     bob$2bo$3o!
+//
+// `b` is a dead cell, `o` a live one, `$` ends a row and `!` ends the
+// pattern. Any of them may be preceded by a run count.
+//
+// Rules with more than two states, like Wireworld, write cells as `.`
+// for state 0 and `A` to `X` for states 1 to 24. Past that, a letter
+// from `p` to `y` goes in front and adds 24 times its rank: `pA` is
+// state 25.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RlePattern {
+    pub width: u32,
+    pub height: u32,
+    pub rule: Option<LifeRule>,
+    // Golly's bounded-grid suffix, e.g. "B3/S23:T64,64".
+    pub torus: Option<(u32, u32)>,
+    // Live cells as (row, col), relative to the pattern's top-left corner.
+    pub cells: Vec<(u32, u32)>,
+    // Cells in states 2 and up, as (row, col, state). Empty for
+    // two-state patterns.
+    pub states: Vec<(u32, u32, u8)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseRleError {
+    line: usize,
+    reason: String,
+}
+
+impl ParseRleError {
+    fn new<S: Into<String>>(line: usize, reason: S) -> ParseRleError {
+        ParseRleError {
+            line,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseRleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid RLE on line {}: {}", self.line, self.reason)
+    }
+}
+
+impl Error for ParseRleError {}
+
+// The most cells a pattern may have, live or in a higher state. A run
+// count lets a few bytes of RLE ask for billions of them.
+pub(crate) const MAX_CELLS: u64 = 1 << 22;
+
+pub fn parse(rle: &str) -> Result<RlePattern, ParseRleError> {
+    let mut pattern = RlePattern {
+        width: 0,
+        height: 0,
+        rule: None,
+        torus: None,
+        cells: Vec::new(),
+        states: Vec::new(),
+    };
+    let mut seen_header = false;
+
+    let mut row = 0;
+    let mut col = 0;
+    let mut run: Option<u32> = None;
+    let mut prefix: Option<u32> = None;
+    let mut finished = false;
+
+    for (number, line) in rle.lines().enumerate() {
+        let number = number + 1;
+        let line = line.trim();
+
+        if finished {
+            break;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !seen_header && (line.starts_with('x') || line.starts_with('X')) {
+            parse_header(line, number, &mut pattern)?;
+            seen_header = true;
+            continue;
+        }
+        seen_header = true;
+
+        for c in line.chars() {
+            if prefix.is_some() && !matches!(c, 'A'..='X') {
+                return Err(ParseRleError::new(number, "a state prefix must be followed by a state from A to X"));
+            }
+            match c {
+                '0'..='9' => {
+                    let digit = c.to_digit(10).unwrap();
+                    let count = run.unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|n| n.checked_add(digit))
+                        .ok_or_else(|| ParseRleError::new(number, "run count is too large"))?;
+                    run = Some(count);
+                }
+                'p'..='y' => {
+                    prefix = Some(c as u32 - 'p' as u32 + 1);
+                }
+                'b' | '.' => {
+                    col = advance(col, run.take(), number)?;
+                }
+                'o' | 'A'..='X' => {
+                    let state = match c {
+                        'o' => 1,
+                        _ => prefix.take().unwrap_or(0) * 24 + (c as u32 - 'A' as u32 + 1),
+                    };
+                    if state > 255 {
+                        return Err(ParseRleError::new(number, "cell states go up to 255"));
+                    }
+                    let count = run.take().unwrap_or(1);
+                    if (pattern.cells.len() + pattern.states.len()) as u64 + u64::from(count) > MAX_CELLS {
+                        return Err(ParseRleError::new(number, format!("patterns have at most {} cells", MAX_CELLS)));
+                    }
+                    for _ in 0..count {
+                        match state {
+                            1 => pattern.cells.push((row, col)),
+                            _ => pattern.states.push((row, col, state as u8)),
+                        }
+                        col = advance(col, None, number)?;
+                    }
+                    pattern.width = pattern.width.max(col);
+                    pattern.height = pattern.height.max(advance(row, None, number)?);
+                }
+                '$' => {
+                    row = advance(row, run.take(), number)?;
+                    col = 0;
+                }
+                '!' => {
+                    finished = true;
+                    break;
+                }
+                c if c.is_whitespace() => {}
+                c => {
+                    return Err(ParseRleError::new(number, format!("unexpected character {:?}", c)));
+                }
+            }
+        }
+    }
+
+    if prefix.is_some() {
+        return Err(ParseRleError::new(rle.lines().count(), "state prefix is not followed by a state"));
+    }
+    if run.is_some() {
+        return Err(ParseRleError::new(rle.lines().count(), "run count is not followed by a cell"));
+    }
+
+    Ok(pattern)
+}
+
+fn advance(position: u32, run: Option<u32>, line: usize) -> Result<u32, ParseRleError> {
+    position
+        .checked_add(run.unwrap_or(1))
+        .ok_or_else(|| ParseRleError::new(line, "pattern is too large"))
+}
+
+// The header is a comma separated list of `key = value` pairs. Only `x`
+// and `y` are required. `rule` comes last and its value may itself contain
+// a comma (the torus size), so it takes the rest of the line.
+fn parse_header(line: &str, number: usize, pattern: &mut RlePattern) -> Result<(), ParseRleError> {
+    let (fields, rule) = match line.find("rule").or_else(|| line.find("RULE")) {
+        Some(i) => (&line[..i], Some(&line[i + "rule".len()..])),
+        None => (line, None),
+    };
+
+    for field in fields.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+        let mut parts = field.splitn(2, '=');
+        let key = parts.next().unwrap_or("").trim();
+        let value = parts
+            .next()
+            .ok_or_else(|| ParseRleError::new(number, format!("header field {:?} has no value", key)))?
+            .trim();
+
+        match key {
+            "x" | "X" => pattern.width = parse_dimension(value, number)?,
+            "y" | "Y" => pattern.height = parse_dimension(value, number)?,
+            _ => return Err(ParseRleError::new(number, format!("unknown header field {:?}", key))),
+        }
+    }
+
+    if let Some(rule) = rule {
+        let value = rule
+            .trim_start()
+            .strip_prefix('=')
+            .ok_or_else(|| ParseRleError::new(number, "header field \"rule\" has no value"))?
+            .trim();
+        let mut rule_parts = value.splitn(2, ':');
+        let rule: LifeRule = rule_parts
+            .next()
+            .unwrap_or("")
+            .parse()
+            .map_err(|e: ParseRuleError| ParseRleError::new(number, e.to_string()))?;
+        pattern.rule = Some(rule);
+        if let Some(topology) = rule_parts.next() {
+            pattern.torus = Some(parse_torus(topology.trim(), number)?);
+        }
+    }
+    Ok(())
+}
+
+fn parse_dimension(value: &str, number: usize) -> Result<u32, ParseRleError> {
+    value
+        .parse()
+        .map_err(|_| ParseRleError::new(number, format!("{:?} is not a valid dimension", value)))
+}
+
+// Only Golly's torus grids ("T<width>,<height>") map onto our universe.
+fn parse_torus(topology: &str, number: usize) -> Result<(u32, u32), ParseRleError> {
+    let unsupported = || ParseRleError::new(number, format!("unsupported grid topology {:?}", topology));
+
+    if !topology.starts_with(['T', 't']) {
+        return Err(unsupported());
+    }
+    let mut dims = topology[1..].splitn(2, ',');
+    let width = dims.next().and_then(|w| w.trim().parse().ok()).ok_or_else(unsupported)?;
+    let height = dims.next().and_then(|h| h.trim().parse().ok()).ok_or_else(unsupported)?;
+    if width == 0 || height == 0 {
+        return Err(unsupported());
+    }
+    Ok((width, height))
+}
+
+// Golly and LifeWiki keep RLE lines at most 70 characters long.
+const MAX_LINE_LENGTH: usize = 70;
+
+// Writes a pattern back out as RLE. Trailing dead cells of each row are
+// dropped and runs of empty rows collapse into a single `n$`, which is
+// what other tools produce too.
+pub fn write(pattern: &RlePattern) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("x = {}, y = {}", pattern.width, pattern.height));
+    if let Some(rule) = pattern.rule {
+        out.push_str(&format!(", rule = {}", rule));
+    }
+    if let Some((width, height)) = pattern.torus {
+        out.push_str(&format!(":T{},{}", width, height));
+    }
+    out.push('\n');
+
+    // Every cell as (row, col, state), in order, rather than a list per
+    // row: the height can be far bigger than the number of cells.
+    // Multi-state letters are only used when there are cells in states
+    // past 1, so that two-state patterns come out exactly like
+    // everywhere else.
+    let multistate = !pattern.states.is_empty();
+    let mut cells: Vec<(u32, u32, u8)> = pattern.cells.iter().map(|&(row, col)| (row, col, 1)).collect();
+    cells.extend_from_slice(&pattern.states);
+    cells.sort_unstable();
+    cells.dedup_by_key(|&mut (row, col, _)| (row, col));
+
+    let mut line = String::new();
+    let mut previous_row = None;
+    for cells in cells.chunk_by(|a, b| a.0 == b.0) {
+        let row = cells[0].0;
+        let pending_rows = match previous_row {
+            Some(previous) => row - previous,
+            None => row,
+        };
+        if pending_rows > 0 {
+            push_token(pending_rows, "$", &mut line, &mut out);
+        }
+        previous_row = Some(row);
+
+        let mut col = 0;
+        let mut i = 0;
+        while i < cells.len() {
+            if cells[i].1 > col {
+                push_token(cells[i].1 - col, if multistate { "." } else { "b" }, &mut line, &mut out);
+            }
+            let (_, start, state) = cells[i];
+            while i + 1 < cells.len() && (cells[i + 1].1, cells[i + 1].2) == (cells[i].1 + 1, state) {
+                i += 1;
+            }
+            let tag = if multistate { state_tag(state) } else { "o".to_string() };
+            push_token(cells[i].1 - start + 1, &tag, &mut line, &mut out);
+            col = cells[i].1 + 1;
+            i += 1;
+        }
+    }
+    push_token(1, "!", &mut line, &mut out);
+    out.push_str(&line);
+    out.push('\n');
+    out
+}
+
+// `A` to `X`, with a `p` to `y` prefix past state 24.
+fn state_tag(state: u8) -> String {
+    let (prefix, letter) = ((state - 1) / 24, (state - 1) % 24);
+    let letter = char::from(b'A' + letter);
+    match prefix {
+        0 => letter.to_string(),
+        _ => format!("{}{}", char::from(b'p' + prefix - 1), letter),
+    }
+}
+
+fn push_token(count: u32, tag: &str, line: &mut String, out: &mut String) {
+    let token = if count == 1 { tag.to_string() } else { format!("{}{}", count, tag) };
+    if line.len() + token.len() > MAX_LINE_LENGTH {
+        out.push_str(line);
+        out.push('\n');
+        line.clear();
+    }
+    line.push_str(&token);
+}
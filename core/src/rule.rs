@@ -0,0 +1,312 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::hensel::{self, Neighborhoods};
+use crate::ltl::{self, LargerThanLife};
+use crate::random::Rng;
+
+// A Life-like rule in B/S notation, e.g. "B3/S23" for Conway's Life,
+// "B36/S23" for HighLife or "B3678/S34678" for Day & Night.
+//
+// Instead of hard-coding the match arms in tick(), we keep two bitsets:
+// bit n of `birth` is set when a dead cell with n live neighbors
+// becomes alive, and bit n of `survival` is set when a live cell
+// with n live neighbors stays alive. Neighbor counts go from 0 to 8,
+// so a u16 is plenty.
+//
+// Generations rules, like "23/3/8" (Star Wars) or "B2/S/C3" (Brian's
+// Brain), add a third number: the count of states. A live cell that
+// doesn't survive goes through states 2, 3, ... before it is dead again,
+// and while dying it neither counts as a neighbor nor can be reborn.
+// Plain Life-like rules have two states.
+//
+// Non-totalistic rules care about which neighbors are alive, as in
+// "B2-a/S12" (isotropic, in Hensel notation) or a MAP string (anything
+// at all). They keep the set of neighborhoods for birth and survival.
+// Larger than Life rules count over a bigger neighborhood instead; see
+// ltl.rs.
+//
+// Wireworld is a four-state rule for drawing circuits, numbered as in
+// Golly: 0 is empty, 1 an electron head, 2 its tail and 3 a wire. Heads
+// become tails, tails become wire again, and a piece of wire becomes a
+// head when one or two of its neighbors are heads. Empty cells stay
+// empty forever. Heads are the live cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LifeRule {
+    birth: u16,
+    survival: u16,
+    states: u8,
+    neighborhoods: Option<(Neighborhoods, Neighborhoods)>,
+    larger: Option<LargerThanLife>,
+    wireworld: bool,
+}
+
+impl LifeRule {
+    pub fn conway() -> LifeRule {
+        LifeRule {
+            birth: 1 << 3,
+            survival: (1 << 2) | (1 << 3),
+            states: 2,
+            neighborhoods: None,
+            larger: None,
+            wireworld: false,
+        }
+    }
+
+    // Wire turns into a head next to one or two heads, and heads don't
+    // survive, so the birth and survival masks already say it all; only
+    // the cycle of states is different.
+    pub fn wireworld() -> LifeRule {
+        LifeRule {
+            birth: (1 << 1) | (1 << 2),
+            survival: 0,
+            states: 4,
+            neighborhoods: None,
+            larger: None,
+            wireworld: true,
+        }
+    }
+
+    // B2/S/C3: ready cells fire with exactly two firing neighbors, and
+    // firing cells always spend the next generation refractory.
+    pub fn brians_brain() -> LifeRule {
+        LifeRule {
+            birth: 1 << 2,
+            survival: 0,
+            states: 3,
+            neighborhoods: None,
+            larger: None,
+            wireworld: false,
+        }
+    }
+
+    pub fn is_wireworld(&self) -> bool {
+        self.wireworld
+    }
+
+    // The whole rule boils down to a single bit lookup. Only meaningful
+    // for totalistic rules; the others need next_with().
+    pub fn next(&self, alive: bool, live_neighbors: u8) -> bool {
+        let mask = if alive { self.survival } else { self.birth };
+        live_neighbors < 16 && mask & (1 << live_neighbors) != 0
+    }
+
+    // The raw (birth, survival) bitmasks, for engines that apply the
+    // rule somewhere else, like a GPU shader.
+    pub fn masks(&self) -> (u16, u16) {
+        (self.birth, self.survival)
+    }
+
+    // 2 for Life-like rules, more for Generations rules.
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    // Whether this is a plain B/S rule: two states, and only the number
+    // of live cells among the eight nearest neighbors matters. That's
+    // all the fast engines, the GPU and infinite universes can run.
+    pub fn is_life_like(&self) -> bool {
+        self.states == 2 && self.neighborhoods.is_none() && self.larger.is_none()
+    }
+
+    // Whether only the number of live cells among the neighbors matters,
+    // however many states there are.
+    pub fn is_totalistic(&self) -> bool {
+        self.neighborhoods.is_none() && self.larger.is_none()
+    }
+
+    // The same rule with one of B1 to B8 or S0 to S8 flipped.
+    pub(crate) fn mutated(&self, rng: &mut Rng) -> LifeRule {
+        let condition = 1 + rng.next_u64() % 17;
+        let mut rule = *self;
+        match condition {
+            1..=8 => rule.birth ^= 1 << condition,
+            _ => rule.survival ^= 1 << (condition - 9),
+        }
+        rule
+    }
+
+    pub fn larger_than_life(&self) -> Option<&LargerThanLife> {
+        self.larger.as_ref()
+    }
+
+    // Like next(), for any rule, given which neighbors are alive in
+    // the bit layout described in hensel.rs.
+    pub fn next_with(&self, alive: bool, neighborhood: u8) -> bool {
+        match &self.neighborhoods {
+            None => self.next(alive, neighborhood.count_ones() as u8),
+            Some((birth, survival)) => hensel::contains(if alive { survival } else { birth }, neighborhood),
+        }
+    }
+}
+
+impl Default for LifeRule {
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseRuleError {
+    rule: String,
+    reason: &'static str,
+}
+
+impl fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid rule {:?}: {}", self.rule, self.reason)
+    }
+}
+
+impl Error for ParseRuleError {}
+
+// Accepts "B3/S23" (in either order, any case)
+// as well as the older "S/B" form "23/3". Generations rules have a
+// third part with the number of states: "B2/S/C3" or "23/3/8".
+// Counts can be followed by Hensel letters ("B2-a/S12"), and whole
+// rules can be given as a MAP string. Larger than Life rules have
+// their own "R5,C0,M1,S34..58,B34..45,NM" form, and Wireworld is
+// just "WireWorld".
+impl FromStr for LifeRule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |reason| ParseRuleError {
+            rule: s.to_string(),
+            reason,
+        };
+
+        if s.trim().eq_ignore_ascii_case("wireworld") {
+            return Ok(LifeRule::wireworld());
+        }
+
+        match LargerThanLife::parse(s.trim()) {
+            Some(Ok((larger, states))) => {
+                return Ok(LifeRule {
+                    birth: 0,
+                    survival: 0,
+                    states,
+                    neighborhoods: None,
+                    larger: Some(larger),
+                    wireworld: false,
+                })
+            }
+            Some(Err(reason)) => return Err(error(reason)),
+            None => {}
+        }
+
+        // A MAP table is base64, which can contain '/' itself.
+        let (map, rest) = match hensel::parse_map(s.trim()) {
+            Some(Ok((birth, survival, rest))) => (Some((birth, survival)), rest),
+            Some(Err(reason)) => return Err(error(reason)),
+            None => (None, s.trim()),
+        };
+        let mut parts = rest.split('/');
+        let (first, second, third) = match map {
+            Some(_) => match (parts.next(), parts.next(), parts.next()) {
+                (Some(""), states, None) => ("", "", states),
+                _ => return Err(error("a MAP table can only be followed by the number of states")),
+            },
+            None => match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(first), Some(second), third, None) => (first, second, third),
+                _ => return Err(error("expected two or three parts separated by '/'")),
+            },
+        };
+        let states = match third {
+            None => 2,
+            Some(part) => part
+                .strip_prefix(['C', 'c'])
+                .unwrap_or(part)
+                .parse()
+                .ok()
+                .filter(|&states| states >= 2)
+                .ok_or_else(|| error("the number of states must be between 2 and 255"))?,
+        };
+
+        let bad_counts = || error("neighbor counts must be digits 0-8, each optionally followed by Hensel letters");
+        let tagged = |part: &str| part.starts_with(['B', 'b', 'S', 's']);
+
+        let (birth, survival) = if let Some(map) = map {
+            map
+        } else if tagged(first) || tagged(second) {
+            let mut birth = None;
+            let mut survival = None;
+            for part in [first, second].iter() {
+                let slot = match part.chars().next() {
+                    Some('B') | Some('b') => &mut birth,
+                    Some('S') | Some('s') => &mut survival,
+                    _ => return Err(error("each part must start with 'B' or 'S'")),
+                };
+                if slot.is_some() {
+                    return Err(error("'B' and 'S' must each appear once"));
+                }
+                *slot = Some(hensel::parse_part(&part[1..]).ok_or_else(bad_counts)?);
+            }
+            (birth.unwrap_or_default(), survival.unwrap_or_default())
+        } else {
+            let survival = hensel::parse_part(first).ok_or_else(bad_counts)?;
+            let birth = hensel::parse_part(second).ok_or_else(bad_counts)?;
+            (birth, survival)
+        };
+
+        // Rules that turn out to only go by counts get the fast path.
+        Ok(match (hensel::totalistic(&birth), hensel::totalistic(&survival)) {
+            (Some(birth), Some(survival)) => LifeRule {
+                birth,
+                survival,
+                states,
+                neighborhoods: None,
+                larger: None,
+                wireworld: false,
+            },
+            _ => LifeRule {
+                birth: 0,
+                survival: 0,
+                states,
+                neighborhoods: Some((birth, survival)),
+                larger: None,
+                wireworld: false,
+            },
+        })
+    }
+}
+
+// Rules are always written back in the canonical "B.../S..." form
+// (except for Wireworld), or as a MAP string if there is no way to write them in Hensel notation.
+impl fmt::Display for LifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.wireworld {
+            return write!(f, "WireWorld");
+        }
+        if let Some(larger) = &self.larger {
+            return ltl::format(larger, self.states, f);
+        }
+        if let Some((birth, survival)) = &self.neighborhoods {
+            match (hensel::format_part(birth), hensel::format_part(survival)) {
+                (Some(birth), Some(survival)) => write!(f, "B{}/S{}", birth, survival)?,
+                _ => write!(f, "{}", hensel::format_map(birth, survival))?,
+            }
+            if self.states > 2 {
+                write!(f, "/C{}", self.states)?;
+            }
+            return Ok(());
+        }
+        write!(f, "B")?;
+        for n in 0..=8 {
+            if self.birth & (1 << n) != 0 {
+                write!(f, "{}", n)?;
+            }
+        }
+        write!(f, "/S")?;
+        for n in 0..=8 {
+            if self.survival & (1 << n) != 0 {
+                write!(f, "{}", n)?;
+            }
+        }
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+
+// Highest deflate level; share strings are made once and parsed once,
+// so there is no reason to trade size for speed.
+const LEVEL: u8 = 10;
+
+// Refuse to inflate more than this, so a hostile link cannot make the
+// page allocate gigabytes.
+const MAX_INFLATED: usize = 16 << 20;
+
+// Deflates `text` and encodes it as URL-safe base64 without padding,
+// so it can go straight into a query string or a #fragment.
+pub fn encode(text: &str) -> String {
+    URL_SAFE_NO_PAD.encode(compress_to_vec(text.as_bytes(), LEVEL))
+}
+
+pub fn decode(share: &str) -> Result<String, String> {
+    let compressed = URL_SAFE_NO_PAD
+        .decode(share.trim().trim_end_matches('='))
+        .map_err(|e| format!("share string is not valid base64: {}", e))?;
+    let bytes = decompress_to_vec_with_limit(&compressed, MAX_INFLATED)
+        .map_err(|e| format!("share string is corrupt: {:?}", e.status))?;
+    String::from_utf8(bytes).map_err(|_| "share string does not contain text".to_string())
+}
@@ -0,0 +1,135 @@
+use std::ops::Range;
+
+use fixedbitset::FixedBitSet;
+
+use crate::rule::LifeRule;
+
+// Whether this build counts neighbors with wasm SIMD. WebAssembly can't
+// probe for instructions at run time: a module using simd128 fails to
+// load at all in a browser without it. So the check happens in
+// JavaScript (WebAssembly.validate on a tiny SIMD module), which then
+// loads either this package built with the `simd` feature and
+// RUSTFLAGS="-C target-feature=+simd128", or the plain one.
+pub const ENABLED: bool = cfg!(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"));
+
+// Whether this build ended up with SIMD neighbor counting.
+pub fn simd_enabled() -> bool {
+    ENABLED
+}
+
+// Below this width a row is mostly scalar remainder anyway, and very
+// narrow tori count some neighbors twice in live_neighbor_count.
+pub const MIN_WIDTH: u32 = 16;
+
+// Unpacks one row into a byte per cell, with the wrapped-around
+// neighbor columns on either side: buf[0] is the last column and
+// buf[width + 1] the first one.
+fn unpack_row(cells: &FixedBitSet, width: usize, row: usize, buf: &mut [u8]) {
+    let start = row * width;
+    for col in 0..width {
+        buf[col + 1] = cells[start + col] as u8;
+    }
+    buf[0] = buf[width];
+    buf[width + 1] = buf[1];
+}
+
+// ORs the low `count` bits of `bits` into `words` starting at bit `idx`.
+fn or_bits(words: &mut [u32], idx: usize, bits: u32, count: usize) {
+    let (word, shift) = (idx / 32, idx % 32);
+    words[word] |= bits << shift;
+    if shift + count > 32 {
+        words[word + 1] |= bits >> (32 - shift);
+    }
+}
+
+// Byte n is 1 when the mask has bit n set, for the swizzle lookup below.
+fn table(mask: u16) -> [u8; 16] {
+    let mut table = [0; 16];
+    for (n, entry) in table.iter_mut().enumerate().take(9) {
+        *entry = (mask >> n) as u8 & 1;
+    }
+    table
+}
+
+// 16 cells of one row, starting at column `col`. Each neighbor count is
+// a sum of eight shifted byte vectors, and the rule is applied with a
+// table lookup per lane instead of a branch.
+#[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+fn step_16(rows: [&[u8]; 3], col: usize, birth: &[u8; 16], survival: &[u8; 16]) -> u32 {
+    use core::arch::wasm32::*;
+
+    // Safety: the caller keeps col + 18 within every row buffer.
+    let load = |row: &[u8], offset: usize| unsafe { v128_load(row[col + offset..].as_ptr() as *const v128) };
+    let [above, middle, below] = rows;
+    let mut count = u8x16_add(load(above, 0), load(above, 1));
+    count = u8x16_add(count, load(above, 2));
+    count = u8x16_add(count, load(middle, 0));
+    count = u8x16_add(count, load(middle, 2));
+    count = u8x16_add(count, load(below, 0));
+    count = u8x16_add(count, load(below, 1));
+    count = u8x16_add(count, load(below, 2));
+
+    let born = i8x16_swizzle(unsafe { v128_load(birth.as_ptr() as *const v128) }, count);
+    let stays = i8x16_swizzle(unsafe { v128_load(survival.as_ptr() as *const v128) }, count);
+    // 0xff in the lanes of live cells, 0 elsewhere.
+    let alive = i8x16_neg(load(middle, 1));
+    let next = v128_bitselect(stays, born, alive);
+    u32::from(i8x16_bitmask(i8x16_shl(next, 7)))
+}
+
+// One generation on a torus, written into `next`.
+pub fn step_torus(cells: &FixedBitSet, next: &mut FixedBitSet, width: u32, height: u32, rule: LifeRule) {
+    next.clear();
+    step_rows(cells, next.as_mut_slice(), 0, width, height, rule, 0..height);
+}
+
+// Like step_torus, but only for `rows`, whose cells are ORed into `words`,
+// a cleared slice of the next generation's words that starts at word
+// `first_word`. Disjoint row bands can run on different threads, as
+// long as each band's words don't overlap the next one's.
+pub fn step_rows(
+    cells: &FixedBitSet,
+    words: &mut [u32],
+    first_word: usize,
+    width: u32,
+    height: u32,
+    rule: LifeRule,
+    rows: Range<u32>,
+) {
+    let (width, height) = (width as usize, height as usize);
+    let (birth_mask, survival_mask) = rule.masks();
+    let (birth, survival) = (table(birth_mask), table(survival_mask));
+
+    let mut above = vec![0u8; width + 2];
+    let mut middle = vec![0u8; width + 2];
+    let mut below = vec![0u8; width + 2];
+    let first = rows.start as usize;
+    unpack_row(cells, width, (first + height - 1) % height, &mut above);
+    unpack_row(cells, width, first, &mut middle);
+
+    for row in rows.start as usize..rows.end as usize {
+        unpack_row(cells, width, (row + 1) % height, &mut below);
+        let start = row * width - first_word * 32;
+        let mut col = 0;
+
+        #[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+        while col + 16 <= width {
+            let bits = step_16([&above, &middle, &below], col, &birth, &survival);
+            or_bits(words, start + col, bits, 16);
+            col += 16;
+        }
+
+        while col < width {
+            let count = above[col] + above[col + 1] + above[col + 2] + middle[col] + middle[col + 2]
+                + below[col]
+                + below[col + 1]
+                + below[col + 2];
+            let lookup = if middle[col + 1] == 1 { &survival } else { &birth };
+            or_bits(words, start + col, u32::from(lookup[count as usize]), 1);
+            col += 1;
+        }
+
+        std::mem::swap(&mut above, &mut middle);
+        std::mem::swap(&mut middle, &mut below);
+    }
+}
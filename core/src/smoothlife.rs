@@ -0,0 +1,198 @@
+use crate::continuous::{self, ContinuousEngine, Kernel, Padded};
+use crate::random::Rng;
+use crate::{cell_count, Error};
+
+// SmoothLife, Stephan Rafler's continuous Life. Instead of one ring,
+// every cell looks at two averages: m over the disk of the inner radius
+// (the cell itself, sort of), and n over the ring between the inner and
+// the outer radius (its neighbors). Like in Life, a cell comes alive when
+// n is within the birth interval and stays alive while n is within the
+// death interval; only here "alive" is m, and the intervals and the
+// switch between them are smoothed out by sigmoids as wide as the two
+// steepness parameters. The universe always wraps around like a torus.
+//
+// The defaults are the ones from Rafler's paper.
+pub const DEFAULT_RADIUS: u32 = 12;
+pub const DEFAULT_BIRTH: (f32, f32) = (0.278, 0.365);
+pub const DEFAULT_DEATH: (f32, f32) = (0.267, 0.445);
+pub const DEFAULT_STEEPNESS: (f32, f32) = (0.028, 0.147);
+
+pub const MAX_RADIUS: u32 = 64;
+
+#[derive(Clone, Debug)]
+pub struct SmoothLife {
+    width: u32,
+    height: u32,
+    cells: Vec<f32>,
+    next: Vec<f32>,
+    // The outer radius; the inner one is a third of it.
+    radius: u32,
+    birth: (f32, f32),
+    death: (f32, f32),
+    // The widths of the sigmoids over n and over m.
+    steepness: (f32, f32),
+    inner: Kernel,
+    outer: Kernel,
+    generation: u64,
+}
+
+// The disk of the given radius, antialiased over the last cell so that
+// it grows smoothly with the radius.
+fn disk(radius: f32, distance: f32) -> f32 {
+    (radius + 0.5 - distance).clamp(0.0, 1.0)
+}
+
+fn kernels(radius: u32) -> (Kernel, Kernel) {
+    let (outer, inner) = (radius as f32, radius as f32 / 3.0);
+    (
+        continuous::kernel(radius, |distance| disk(inner, distance)),
+        continuous::kernel(radius + 1, |distance| disk(outer, distance) - disk(inner, distance)),
+    )
+}
+
+// A smooth step from 0 to 1 around a, about `width` wide.
+fn sigmoid(x: f32, a: f32, width: f32) -> f32 {
+    1.0 / (1.0 + (-(x - a) * 4.0 / width).exp())
+}
+
+impl SmoothLife {
+    // An empty width x height universe with the default parameters.
+    pub fn new(width: u32, height: u32) -> Result<SmoothLife, Error> {
+        let size = cell_count(&[width, height])?;
+        let (inner, outer) = kernels(DEFAULT_RADIUS);
+        Ok(SmoothLife {
+            width,
+            height,
+            cells: vec![0.0; size],
+            next: vec![0.0; size],
+            radius: DEFAULT_RADIUS,
+            birth: DEFAULT_BIRTH,
+            death: DEFAULT_DEATH,
+            steepness: DEFAULT_STEEPNESS,
+            inner,
+            outer,
+            generation: 0,
+        })
+    }
+
+    // Sprinkles `count` full disks of the outer radius at random places,
+    // the usual way to get SmoothLife going. The same seed always gives
+    // the same soup.
+    pub fn random(width: u32, height: u32, count: u32, seed: u64) -> Result<SmoothLife, Error> {
+        let mut life = SmoothLife::new(width, height)?;
+        let mut rng = Rng::new(seed);
+        let radius = life.radius as i32;
+        for _ in 0..count {
+            let row = (rng.next_f32() * height as f32) as i32;
+            let col = (rng.next_f32() * width as f32) as i32;
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    if dx * dx + dy * dy <= radius * radius {
+                        let r = (row + dy).rem_euclid(height as i32) as u32;
+                        let c = (col + dx).rem_euclid(width as i32) as u32;
+                        life.cells[(r * width + c) as usize] = 1.0;
+                    }
+                }
+            }
+        }
+        Ok(life)
+    }
+
+    // What a cell becomes with an inner average m and a ring average n.
+    fn transition(&self, n: f32, m: f32) -> f32 {
+        let (steepness_n, steepness_m) = self.steepness;
+        let alive = sigmoid(m, 0.5, steepness_m);
+        let low = self.birth.0 * (1.0 - alive) + self.death.0 * alive;
+        let high = self.birth.1 * (1.0 - alive) + self.death.1 * alive;
+        sigmoid(n, low, steepness_n) * (1.0 - sigmoid(n, high, steepness_n))
+    }
+
+    pub fn radius(&self) -> u32 {
+        self.radius
+    }
+
+    // The outer radius, in cells.
+    pub fn set_radius(&mut self, radius: u32) -> Result<(), Error> {
+        if !(3..=MAX_RADIUS).contains(&radius) {
+            return Err(Error::new("the radius must be between 3 and 64"));
+        }
+        self.radius = radius;
+        (self.inner, self.outer) = kernels(radius);
+        Ok(())
+    }
+
+    pub fn birth(&self) -> (f32, f32) {
+        self.birth
+    }
+
+    pub fn death(&self) -> (f32, f32) {
+        self.death
+    }
+
+    pub fn set_birth(&mut self, low: f32, high: f32) -> Result<(), Error> {
+        self.birth = interval(low, high)?;
+        Ok(())
+    }
+
+    pub fn set_death(&mut self, low: f32, high: f32) -> Result<(), Error> {
+        self.death = interval(low, high)?;
+        Ok(())
+    }
+
+    pub fn steepness(&self) -> (f32, f32) {
+        self.steepness
+    }
+
+    // How wide the sigmoids over the ring and over the inner disk are:
+    // smaller is closer to Life's hard thresholds.
+    pub fn set_steepness(&mut self, n: f32, m: f32) -> Result<(), Error> {
+        if n <= 0.0 || m <= 0.0 || n > 1.0 || m > 1.0 {
+            return Err(Error::new("the steepness must be between 0 (excluded) and 1"));
+        }
+        self.steepness = (n, m);
+        Ok(())
+    }
+}
+
+fn interval(low: f32, high: f32) -> Result<(f32, f32), Error> {
+    if !(0.0..=1.0).contains(&low) || !(low..=1.0).contains(&high) {
+        return Err(Error::new("intervals must go from low to high, within 0 and 1"));
+    }
+    Ok((low, high))
+}
+
+impl ContinuousEngine for SmoothLife {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn cells(&self) -> &[f32] {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut [f32] {
+        &mut self.cells
+    }
+
+    fn tick(&mut self) {
+        let padded = Padded::new(&self.cells, self.width, self.height, self.radius + 1);
+        let (inner, outer) = (padded.offsets(&self.inner), padded.offsets(&self.outer));
+        let width = self.width as usize;
+        for row in 0..self.height as usize {
+            for col in 0..width {
+                let (m, n) = (padded.average(&inner, row, col), padded.average(&outer, row, col));
+                self.next[row * width + col] = self.transition(n, m);
+            }
+        }
+        std::mem::swap(&mut self.cells, &mut self.next);
+        self.generation += 1;
+    }
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Boundary, Engine, Neighborhood, Species, Stochastic, Turmite, WeightedRule};
+
+// Bumped whenever the layout below changes, so old saves are rejected
+// with a clear message instead of being misread.
+pub const FORMAT_VERSION: u32 = 5;
+
+// Everything needed to bring a universe back exactly as it was:
+// not just the cells, but also the rule, the generation number and
+// the way it is being simulated. Undo history is not saved, and
+// neither can a custom rule be, so universes with one aren't saved.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub version: u32,
+    pub width: u32,
+    pub height: u32,
+    pub rule: String,
+    pub generation: u64,
+    pub boundary: Boundary,
+    pub neighborhood: Neighborhood,
+    pub weighted: Option<WeightedRule>,
+    pub engine: Engine,
+    // The random seed, and how far the generator has got since, so that
+    // soups and stochastic rules carry on where they left off.
+    pub seed: u64,
+    pub random_state: u64,
+    pub stochastic: Option<Stochastic>,
+    // The whole .rule file, when one was loaded with load_rule_file().
+    pub rule_table: Option<String>,
+    pub species: Species,
+    pub turmites: Vec<Turmite>,
+    // The packed cell words, as returned by Universe::cells_bits().
+    pub cells: Vec<u32>,
+    // As returned by Universe::states() and Universe::colors(), empty
+    // for two-state rules and Species::Single.
+    pub states: Vec<u8>,
+    pub colors: Vec<u8>,
+}
+
+impl Snapshot {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(self).expect("snapshots always serialize")
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Snapshot, String> {
+        let snapshot: Snapshot = bincode::deserialize(bytes).map_err(|e| format!("invalid snapshot: {}", e))?;
+        snapshot.checked()
+    }
+
+    // Makes sure the cells fit the size, for snapshots put together
+    // from more than one save.
+    pub fn checked(self) -> Result<Snapshot, String> {
+        if self.version != FORMAT_VERSION {
+            return Err(format!(
+                "snapshot format version {} is not supported (expected {})",
+                self.version, FORMAT_VERSION
+            ));
+        }
+        // In u64, as the size hasn't been checked yet and could overflow
+        // a wasm32 usize.
+        let size = u64::from(self.width)
+            .checked_mul(u64::from(self.height))
+            .ok_or("snapshot dimensions are too large")?;
+        let words = size.div_ceil(32);
+        if self.cells.len() as u64 != words {
+            return Err("snapshot cell data does not match its dimensions".to_string());
+        }
+        // Bits past the last cell must be clear, or they would be counted as alive.
+        let used = size % 32;
+        if used != 0 && self.cells[words as usize - 1] >> used != 0 {
+            return Err("snapshot has cells outside the universe".to_string());
+        }
+        for (name, len) in [("states", self.states.len()), ("colors", self.colors.len())] {
+            if len != 0 && len as u64 != size {
+                return Err(format!("snapshot {} do not match its dimensions", name));
+            }
+        }
+        Ok(self)
+    }
+}
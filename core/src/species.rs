@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+// Multi-color variants of Life. Which cells live and die is still up to
+// the rule; the species only decide what color every live cell has.
+// Colors are numbered from 1, so that 0 can stand for a dead cell.
+
+// Single is plain Life, with no colors at all. In Immigration, live
+// cells are one of two colors: survivors keep theirs and a newborn
+// takes the majority color of the live neighbors it was born from.
+// QuadLife works the same with four colors, except that a cell born
+// from three parents of three different colors takes the fourth one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Species {
+    Single,
+    Immigration,
+    QuadLife,
+}
+
+// The most colors any species has.
+pub const MAX_COLORS: usize = 4;
+
+impl Species {
+    // How many colors live cells can have.
+    pub fn colors(self) -> u8 {
+        match self {
+            Species::Single => 1,
+            Species::Immigration => 2,
+            Species::QuadLife => 4,
+        }
+    }
+
+    // The color of a cell born next to `parents[c]` live neighbors of
+    // color c + 1. Births from three parents are never a tie in
+    // Immigration, or in QuadLife once three different colors are taken
+    // care of; other birth counts can tie, and ties go to the lower color.
+    pub fn newborn(self, parents: &[u8; MAX_COLORS]) -> u8 {
+        match self {
+            Species::Single => 1,
+            Species::Immigration => {
+                if parents[1] > parents[0] {
+                    2
+                } else {
+                    1
+                }
+            }
+            Species::QuadLife => {
+                let most = *parents.iter().max().unwrap();
+                let tied = parents.iter().filter(|&&n| n == most).count();
+                // Three parents, all different: the color nobody has.
+                let wanted = if most == 1 && tied == 3 { 0 } else { most };
+                parents.iter().position(|&n| n == wanted).unwrap() as u8 + 1
+            }
+        }
+    }
+}
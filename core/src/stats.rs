@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+
+use fixedbitset::FixedBitSet;
+use serde::Serialize;
+
+use crate::memory::deque_bytes;
+
+// How many populations are remembered for population_history().
+pub const POPULATION_HISTORY: usize = 4096;
+
+// What the last step did: how many cells were born and died, and the
+// population it left behind. A Hashlife jump over many generations
+// counts as one step, so its births and deaths are the net difference
+// between the two ends of the jump.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TickStats {
+    births: u32,
+    deaths: u32,
+    population: u32,
+    changed: u32,
+}
+
+impl TickStats {
+    pub fn new(births: u32, deaths: u32, population: u32) -> TickStats {
+        TickStats {
+            births,
+            deaths,
+            population,
+            changed: births + deaths,
+        }
+    }
+}
+
+impl TickStats {
+    pub fn births(&self) -> u32 {
+        self.births
+    }
+
+    pub fn deaths(&self) -> u32 {
+        self.deaths
+    }
+
+    pub fn population(&self) -> u32 {
+        self.population
+    }
+
+    // Births plus deaths: how many cells flipped.
+    pub fn changed(&self) -> u32 {
+        self.changed
+    }
+}
+
+// The population after each of the last POPULATION_HISTORY steps, oldest first.
+#[derive(Clone, Debug, Default)]
+pub struct PopulationHistory {
+    populations: VecDeque<u32>,
+}
+
+impl PopulationHistory {
+    pub fn push(&mut self, population: u32) {
+        if self.populations.len() == POPULATION_HISTORY {
+            self.populations.pop_front();
+        }
+        self.populations.push_back(population);
+    }
+
+    // The last `n` populations (fewer if there haven't been that many steps).
+    pub fn last(&self, n: usize) -> Vec<u32> {
+        let skip = self.populations.len().saturating_sub(n);
+        self.populations.iter().skip(skip).copied().collect()
+    }
+
+    pub fn heap_bytes(&self) -> usize {
+        deque_bytes(&self.populations)
+    }
+}
+
+// The formats recorded statistics can be exported in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatsFormat {
+    Csv,
+    Json,
+}
+
+// One row of recorded statistics: a step and what it left behind. The
+// bounding box of the live cells is (top, left, width, height), None
+// when nothing is alive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GenerationStats {
+    pub generation: u64,
+    pub population: u32,
+    pub births: u32,
+    pub deaths: u32,
+    pub bounds: Option<(u32, u32, u32, u32)>,
+}
+
+pub fn bounding_box(cells: &FixedBitSet, width: u32) -> Option<(u32, u32, u32, u32)> {
+    let (mut top, mut left, mut bottom, mut right) = (u32::MAX, u32::MAX, 0, 0);
+    for idx in cells.ones() {
+        let (row, col) = (idx as u32 / width, idx as u32 % width);
+        top = top.min(row);
+        left = left.min(col);
+        bottom = bottom.max(row + 1);
+        right = right.max(col + 1);
+    }
+    (top != u32::MAX).then(|| (top, left, right - left, bottom - top))
+}
+
+// Writes the rows out for pandas and friends: CSV with a header line,
+// leaving the bounding box empty without live cells, or a JSON array
+// of objects with the same fields and nulls.
+pub fn export(rows: &[GenerationStats], format: StatsFormat) -> String {
+    let mut out = String::new();
+    match format {
+        StatsFormat::Csv => {
+            out.push_str("generation,population,births,deaths,top,left,width,height\n");
+            for row in rows {
+                out.push_str(&format!("{},{},{},{},", row.generation, row.population, row.births, row.deaths));
+                match row.bounds {
+                    Some((top, left, width, height)) => out.push_str(&format!("{},{},{},{}\n", top, left, width, height)),
+                    None => out.push_str(",,,\n"),
+                }
+            }
+        }
+        StatsFormat::Json => {
+            out.push('[');
+            for (i, row) in rows.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                let bounds = match row.bounds {
+                    Some((top, left, width, height)) => {
+                        format!("\"top\":{},\"left\":{},\"width\":{},\"height\":{}", top, left, width, height)
+                    }
+                    None => "\"top\":null,\"left\":null,\"width\":null,\"height\":null".to_string(),
+                };
+                out.push_str(&format!(
+                    "{{\"generation\":{},\"population\":{},\"births\":{},\"deaths\":{},{}}}",
+                    row.generation, row.population, row.births, row.deaths, bounds
+                ));
+            }
+            out.push_str("]\n");
+        }
+    }
+    out
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use crate::random::Rng;
+use crate::Error;
+
+// Noise on top of a rule: every birth the rule calls for only happens
+// with probability `birth`, every survival with probability `survival`,
+// and on top of that any cell that would be alive dies anyway with
+// probability `death`. "B3 with p=0.98, random death p=0.001" is
+// birth 0.98, survival 1 and death 0.001.
+//
+// The dice come from the universe's generator, see Universe::set_seed(),
+// and the cells are always visited in the same order, so a universe
+// started from the same cells and seed goes through the same history.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Stochastic {
+    birth: f32,
+    survival: f32,
+    death: f32,
+}
+
+impl Stochastic {
+    pub fn new(birth: f32, survival: f32, death: f32) -> Result<Stochastic, Error> {
+        if [birth, survival, death].iter().any(|p| !(0.0..=1.0).contains(p)) {
+            return Err(Error::new("probabilities must be between 0 and 1"));
+        }
+        Ok(Stochastic {
+            birth,
+            survival,
+            death,
+        })
+    }
+
+    pub fn birth(&self) -> f32 {
+        self.birth
+    }
+
+    pub fn survival(&self) -> f32 {
+        self.survival
+    }
+
+    pub fn death(&self) -> f32 {
+        self.death
+    }
+
+    // Rolls for a cell the rule wants alive: whether it really is.
+    pub(crate) fn keeps(&self, rng: &mut Rng, born: bool) -> bool {
+        let chance = if born { self.birth } else { self.survival } * (1.0 - self.death);
+        rng.next_f32() < chance
+    }
+}
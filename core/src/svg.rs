@@ -0,0 +1,131 @@
+use fixedbitset::FixedBitSet;
+
+// How Universe::to_svg draws the cells. The defaults give black 10px
+// cells on white, without a grid.
+#[derive(Clone, Debug)]
+pub struct SvgOptions {
+    cell_size: u32,
+    alive: String,
+    background: Option<String>,
+    grid: Option<String>,
+    // (row, col, width, height); None means the whole universe.
+    viewport: Option<(u32, u32, u32, u32)>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> SvgOptions {
+        SvgOptions {
+            cell_size: 10,
+            alive: "#000000".to_string(),
+            background: Some("#FFFFFF".to_string()),
+            grid: None,
+            viewport: None,
+        }
+    }
+}
+
+impl SvgOptions {
+    pub fn new() -> SvgOptions {
+        SvgOptions::default()
+    }
+
+    // How big a cell is at the image's natural size. It is a vector
+    // image, so this only sets its width and height attributes.
+    pub fn set_cell_size(&mut self, cell_size: u32) {
+        self.cell_size = cell_size.max(1);
+    }
+
+    // Any CSS color. An empty background leaves it transparent.
+    pub fn set_colors(&mut self, alive: &str, background: &str) {
+        self.alive = alive.to_string();
+        self.background = Some(background.to_string()).filter(|color| !color.is_empty());
+    }
+
+    // Draws 1px lines between the cells, whatever the image is scaled to.
+    pub fn set_grid(&mut self, color: &str) {
+        self.grid = Some(color.to_string());
+    }
+
+    pub fn clear_grid(&mut self) {
+        self.grid = None;
+    }
+
+    // Like TextOptions::set_viewport().
+    pub fn set_viewport(&mut self, row: u32, col: u32, width: u32, height: u32) {
+        self.viewport = Some((row, col, width, height));
+    }
+
+    pub fn clear_viewport(&mut self) {
+        self.viewport = None;
+    }
+}
+
+// For colors going into attribute values.
+fn escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;")
+}
+
+// The image is in cell units, one per cell, and the live cells are a
+// single path with one rectangle per run of live cells in a row:
+//
+//     <path fill="#000000" d="M1 0h1v1h-1zM2 1h1v1h-1zM0 2h3v1h-3z"/>
+pub fn render(cells: &FixedBitSet, width: u32, height: u32, options: &SvgOptions) -> String {
+    let (row, col, view_width, view_height) = options.viewport.unwrap_or((0, 0, width, height));
+    let rows = row.min(height)..row.saturating_add(view_height).min(height);
+    let cols = col.min(width)..col.saturating_add(view_width).min(width);
+    let (w, h) = (cols.len() as u32, rows.len() as u32);
+
+    let mut svg = format!(
+        concat!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" ",
+            "viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\n"
+        ),
+        u64::from(w) * u64::from(options.cell_size),
+        u64::from(h) * u64::from(options.cell_size),
+        w,
+        h
+    );
+    if let Some(background) = &options.background {
+        svg.push_str(&format!("<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>\n", w, h, escape(background)));
+    }
+
+    let mut path = String::new();
+    for (y, row) in rows.enumerate() {
+        let mut run_start = None;
+        for (x, col) in cols.clone().enumerate() {
+            let alive = cells[(row * width + col) as usize];
+            match (alive, run_start) {
+                (true, None) => run_start = Some(x),
+                (false, Some(start)) => {
+                    path.push_str(&format!("M{} {}h{}v1h-{}z", start, y, x - start, x - start));
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(start) = run_start {
+            let run = w as usize - start;
+            path.push_str(&format!("M{} {}h{}v1h-{}z", start, y, run, run));
+        }
+    }
+    if !path.is_empty() {
+        svg.push_str(&format!("<path fill=\"{}\" d=\"{}\"/>\n", escape(&options.alive), path));
+    }
+
+    if let Some(grid) = &options.grid {
+        let mut lines = String::new();
+        for y in 0..=h {
+            lines.push_str(&format!("M0 {}H{}", y, w));
+        }
+        for x in 0..=w {
+            lines.push_str(&format!("M{} 0V{}", x, h));
+        }
+        svg.push_str(&format!(
+            "<path fill=\"none\" stroke=\"{}\" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\" d=\"{}\"/>\n",
+            escape(grid),
+            lines
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
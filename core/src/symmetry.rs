@@ -0,0 +1,194 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Error, Universe};
+
+// The symmetries apgsearch runs soups with, under its names. C is for
+// rotations, D for rotations and reflections, and the number is how
+// many ways the soup maps onto itself. The suffix says where the
+// middle is: _1 on a cell, _2 on the edge between two cells, _4 on the
+// corner between four; + reflects across rows or columns and x across
+// diagonals.
+//
+// C1 is a plain 16x16 soup. The others start from the same and mirror
+// it into a 31 or 32 wide square, or a 16 high strip for D2_+1 and
+// D2_+2, a 16x16 square again for D2_x.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    C1,
+    C2_1,
+    C2_2,
+    C2_4,
+    C4_1,
+    C4_4,
+    D2_Plus1,
+    D2_Plus2,
+    D2_X,
+    D4_Plus1,
+    D4_Plus2,
+    D4_Plus4,
+    D4_X1,
+    D4_X4,
+    D8_1,
+    D8_4,
+}
+
+// A transformation of the soup, on coordinates measured from its middle
+// in half cells, so that middles between cells are whole numbers too.
+type Transform = fn(i32, i32) -> (i32, i32);
+
+const IDENTITY: Transform = |x, y| (x, y);
+const HALF_TURN: Transform = |x, y| (-x, -y);
+const QUARTER_TURN: Transform = |x, y| (-y, x);
+const THREE_QUARTER_TURN: Transform = |x, y| (y, -x);
+const MIRROR_COLUMNS: Transform = |x, y| (-x, y);
+const MIRROR_ROWS: Transform = |x, y| (x, -y);
+const DIAGONAL: Transform = |x, y| (y, x);
+const ANTIDIAGONAL: Transform = |x, y| (-y, -x);
+
+const ROTATIONS: &[Transform] = &[IDENTITY, QUARTER_TURN, HALF_TURN, THREE_QUARTER_TURN];
+const ORTHOGONAL: &[Transform] = &[IDENTITY, MIRROR_COLUMNS, MIRROR_ROWS, HALF_TURN];
+const DIAGONALS: &[Transform] = &[IDENTITY, DIAGONAL, ANTIDIAGONAL, HALF_TURN];
+const ALL: &[Transform] = &[
+    IDENTITY,
+    QUARTER_TURN,
+    HALF_TURN,
+    THREE_QUARTER_TURN,
+    MIRROR_COLUMNS,
+    MIRROR_ROWS,
+    DIAGONAL,
+    ANTIDIAGONAL,
+];
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 16] = [
+        Symmetry::C1,
+        Symmetry::C2_1,
+        Symmetry::C2_2,
+        Symmetry::C2_4,
+        Symmetry::C4_1,
+        Symmetry::C4_4,
+        Symmetry::D2_Plus1,
+        Symmetry::D2_Plus2,
+        Symmetry::D2_X,
+        Symmetry::D4_Plus1,
+        Symmetry::D4_Plus2,
+        Symmetry::D4_Plus4,
+        Symmetry::D4_X1,
+        Symmetry::D4_X4,
+        Symmetry::D8_1,
+        Symmetry::D8_4,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Symmetry::C1 => "C1",
+            Symmetry::C2_1 => "C2_1",
+            Symmetry::C2_2 => "C2_2",
+            Symmetry::C2_4 => "C2_4",
+            Symmetry::C4_1 => "C4_1",
+            Symmetry::C4_4 => "C4_4",
+            Symmetry::D2_Plus1 => "D2_+1",
+            Symmetry::D2_Plus2 => "D2_+2",
+            Symmetry::D2_X => "D2_x",
+            Symmetry::D4_Plus1 => "D4_+1",
+            Symmetry::D4_Plus2 => "D4_+2",
+            Symmetry::D4_Plus4 => "D4_+4",
+            Symmetry::D4_X1 => "D4_x1",
+            Symmetry::D4_X4 => "D4_x4",
+            Symmetry::D8_1 => "D8_1",
+            Symmetry::D8_4 => "D8_4",
+        }
+    }
+
+    // The soup's width and height.
+    pub fn size(self) -> (u32, u32) {
+        match self {
+            Symmetry::C1 | Symmetry::D2_X => (16, 16),
+            Symmetry::D2_Plus1 => (31, 16),
+            Symmetry::D2_Plus2 => (32, 16),
+            Symmetry::C2_2 | Symmetry::D4_Plus2 => (32, 31),
+            Symmetry::C2_1 | Symmetry::C4_1 | Symmetry::D4_Plus1 | Symmetry::D4_X1 | Symmetry::D8_1 => (31, 31),
+            Symmetry::C2_4 | Symmetry::C4_4 | Symmetry::D4_Plus4 | Symmetry::D4_X4 | Symmetry::D8_4 => (32, 32),
+        }
+    }
+
+    fn transforms(self) -> &'static [Transform] {
+        match self {
+            Symmetry::C1 => &[IDENTITY],
+            Symmetry::C2_1 | Symmetry::C2_2 | Symmetry::C2_4 => &[IDENTITY, HALF_TURN],
+            Symmetry::C4_1 | Symmetry::C4_4 => ROTATIONS,
+            Symmetry::D2_Plus1 | Symmetry::D2_Plus2 => &[IDENTITY, MIRROR_COLUMNS],
+            Symmetry::D2_X => &[IDENTITY, DIAGONAL],
+            Symmetry::D4_Plus1 | Symmetry::D4_Plus2 | Symmetry::D4_Plus4 => ORTHOGONAL,
+            Symmetry::D4_X1 | Symmetry::D4_X4 => DIAGONALS,
+            Symmetry::D8_1 | Symmetry::D8_4 => ALL,
+        }
+    }
+
+    // Which cell of the soup decides (row, col): the first one in
+    // reading order of all the cells the symmetry maps it onto.
+    fn representative(self, row: u32, col: u32) -> usize {
+        let (width, height) = self.size();
+        let (x, y) = (2 * col as i32 - (width as i32 - 1), 2 * row as i32 - (height as i32 - 1));
+        self.transforms()
+            .iter()
+            .map(|transform| {
+                let (x, y) = transform(x, y);
+                let (col, row) = ((x + width as i32 - 1) / 2, (y + height as i32 - 1) / 2);
+                row as usize * width as usize + col as usize
+            })
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Symmetry, Error> {
+        let name = name.trim();
+        Symmetry::ALL.iter().copied().find(|symmetry| symmetry.name() == name).ok_or_else(|| {
+            let names: Vec<&str> = Symmetry::ALL.iter().map(|symmetry| symmetry.name()).collect();
+            Error::new(&format!("unknown symmetry {:?}, expected one of {}", name, names.join(", ")))
+        })
+    }
+}
+
+impl Universe {
+    // Clears the universe and puts a soup with the given symmetry in
+    // the middle, e.g. for a census of D8_1 soups. Like randomize(), it
+    // draws from the universe's generator and can be undone.
+    pub fn randomize_symmetric(&mut self, symmetry: Symmetry, density: f32) -> Result<(), Error> {
+        if !(0.0..=1.0).contains(&density) {
+            return Err(Error::new("density must be between 0 and 1"));
+        }
+        let (width, height) = symmetry.size();
+        if width > self.width || height > self.height {
+            return Err(Error::new(&format!(
+                "a {} soup is {}x{}, which doesn't fit in the {}x{} universe",
+                symmetry, width, height, self.width, self.height
+            )));
+        }
+        self.record(|universe| {
+            let rolls: Vec<bool> = (0..width * height).map(|_| universe.rng.next_f32() < density).collect();
+            let (top, left) = ((universe.height - height) / 2, (universe.width - width) / 2);
+            universe.cells.clear();
+            for row in 0..height {
+                for col in 0..width {
+                    let idx = universe.get_index(top + row, left + col);
+                    universe.cells.set(idx, rolls[symmetry.representative(row, col)]);
+                }
+            }
+            universe.recount();
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,410 @@
+use std::collections::HashMap;
+
+use crate::Error;
+
+// A rule given as a Golly rule table, the format of most of the rules
+// shared by the Golly community in .rule files:
+//
+//     @RULE WireWorld
+//     @TABLE
+//     n_states:4
+//     neighborhood:Moore
+//     symmetries:rotate8
+//     var a={0,1,2,3}
+//     var b={0,1,2,3}
+//     ...
+//     # C,N,NE,E,SE,S,SW,W,NW,C'
+//     1,a,b,c,d,e,f,g,h,2
+//     ...
+//     @COLORS
+//     1 255 255 255
+//
+// Each transition lists the state of the cell, of its neighbors going
+// clockwise from north, and the state it turns into. A variable stands
+// for any of its values, but the same variable takes the same value
+// everywhere in a transition. The symmetries say which rotations and
+// reflections of every transition also apply. The first transition that
+// matches wins; cells that match none stay as they are.
+//
+// Only @TABLE rules with the Moore or von Neumann neighborhood are
+// supported, not @TREE rules nor hexagonal or one-dimensional ones.
+#[derive(Clone, Debug)]
+pub struct RuleTable {
+    name: String,
+    // The file it was parsed from, for saving the universe.
+    source: String,
+    states: u8,
+    neighborhood: TableNeighborhood,
+    transitions: Vec<Transition>,
+    // The @COLORS section, as RGB for each state it names.
+    colors: Vec<Option<[u8; 3]>>,
+    // Results already looked up, keyed by the cell and its neighbors.
+    cache: HashMap<[u8; 9], u8>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableNeighborhood {
+    Moore,
+    VonNeumann,
+}
+
+impl TableNeighborhood {
+    // Offsets (row, col) of the neighbors, in the order rule tables list them.
+    pub fn offsets(self) -> &'static [(i64, i64)] {
+        match self {
+            TableNeighborhood::Moore => &[(-1, 0), (-1, 1), (0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1)],
+            TableNeighborhood::VonNeumann => &[(-1, 0), (0, 1), (1, 0), (0, -1)],
+        }
+    }
+}
+
+// The states one position of a transition accepts, one bit each.
+type StateSet = [u64; 4];
+
+fn contains(set: &StateSet, state: u8) -> bool {
+    set[usize::from(state / 64)] & (1 << (state % 64)) != 0
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Input {
+    Any(StateSet),
+    // A variable: which one, and the states it stands for.
+    Bound(usize, StateSet),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Output {
+    State(u8),
+    Variable(usize),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Transition {
+    // The cell, then its neighbors.
+    inputs: Vec<Input>,
+    output: Output,
+    // With the "permute" symmetry the neighbors may come in any order.
+    // Rather than listing every ordering, matching looks for one.
+    permute: bool,
+}
+
+impl Transition {
+    fn apply(&self, cell: u8, neighbors: &[u8]) -> Option<u8> {
+        let mut bound: Vec<(usize, u8)> = Vec::new();
+        if !bind(&self.inputs[0], cell, &mut bound) {
+            return None;
+        }
+        if self.permute {
+            let mut used = vec![false; neighbors.len()];
+            if !permuted(&self.inputs[1..], neighbors, &mut used, &mut bound) {
+                return None;
+            }
+        } else if !self.inputs[1..].iter().zip(neighbors).all(|(input, &state)| bind(input, state, &mut bound)) {
+            return None;
+        }
+        Some(match self.output {
+            Output::State(state) => state,
+            Output::Variable(variable) => bound.iter().find(|(v, _)| *v == variable)?.1,
+        })
+    }
+}
+
+// Whether `state` fits `input`, binding its variable if it has one.
+fn bind(input: &Input, state: u8, bound: &mut Vec<(usize, u8)>) -> bool {
+    match input {
+        Input::Any(set) => contains(set, state),
+        Input::Bound(variable, set) if contains(set, state) => match bound.iter().find(|(v, _)| v == variable) {
+            Some(&(_, value)) => value == state,
+            None => {
+                bound.push((*variable, state));
+                true
+            }
+        },
+        Input::Bound(..) => false,
+    }
+}
+
+// Gives each neighbor in turn one of the inputs left, backing out of
+// choices that lead nowhere.
+fn permuted(inputs: &[Input], neighbors: &[u8], used: &mut [bool], bound: &mut Vec<(usize, u8)>) -> bool {
+    let (state, rest) = match neighbors.split_first() {
+        Some(split) => split,
+        None => return true,
+    };
+    for i in 0..inputs.len() {
+        if used[i] {
+            continue;
+        }
+        let bound_before = bound.len();
+        if bind(&inputs[i], *state, bound) {
+            used[i] = true;
+            if permuted(inputs, rest, used, bound) {
+                return true;
+            }
+            used[i] = false;
+        }
+        bound.truncate(bound_before);
+    }
+    false
+}
+
+impl RuleTable {
+    pub fn parse(contents: &str) -> Result<RuleTable, Error> {
+        let error = |number: usize, reason: &str| Error::new(&format!("invalid rule file on line {}: {}", number, reason));
+
+        let mut name = String::new();
+        let mut section = "";
+        let mut states: Option<u8> = None;
+        let mut neighborhood = None;
+        let mut symmetries = "none".to_string();
+        let mut variables: Vec<(String, StateSet)> = Vec::new();
+        let mut transitions = Vec::new();
+        let mut colors = Vec::new();
+        let mut seen_table = false;
+
+        for (number, line) in contents.lines().enumerate() {
+            let number = number + 1;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('@') {
+                let mut words = header.split_whitespace();
+                section = match words.next().unwrap_or("") {
+                    "RULE" => {
+                        name = words.next().unwrap_or("").to_string();
+                        "RULE"
+                    }
+                    "TABLE" => {
+                        seen_table = true;
+                        "TABLE"
+                    }
+                    "COLORS" => "COLORS",
+                    _ => "",
+                };
+                continue;
+            }
+
+            match section {
+                "TABLE" => {
+                    if let Some((key, value)) = line.split_once(':') {
+                        let value = value.trim();
+                        match key.trim() {
+                            "n_states" => {
+                                states = Some(
+                                    value
+                                        .parse()
+                                        .ok()
+                                        .filter(|&n| n >= 2)
+                                        .ok_or_else(|| error(number, "n_states must be between 2 and 255"))?,
+                                );
+                            }
+                            "neighborhood" => {
+                                neighborhood = Some(match value {
+                                    "Moore" => TableNeighborhood::Moore,
+                                    "vonNeumann" => TableNeighborhood::VonNeumann,
+                                    _ => return Err(error(number, "only the Moore and vonNeumann neighborhoods are supported")),
+                                });
+                            }
+                            "symmetries" => symmetries = value.to_string(),
+                            _ => return Err(error(number, "unknown table setting")),
+                        }
+                        continue;
+                    }
+                    let states = states.ok_or_else(|| error(number, "n_states must come before the transitions"))?;
+                    let neighborhood =
+                        neighborhood.ok_or_else(|| error(number, "neighborhood must come before the transitions"))?;
+
+                    if let Some(definition) = line.strip_prefix("var ") {
+                        let (variable, values) = definition
+                            .split_once('=')
+                            .ok_or_else(|| error(number, "expected var name={values}"))?;
+                        let values = values
+                            .trim()
+                            .strip_prefix('{')
+                            .and_then(|values| values.strip_suffix('}'))
+                            .ok_or_else(|| error(number, "variable values go between braces"))?;
+                        let mut set = [0; 4];
+                        for value in values.split(',').map(str::trim) {
+                            let values = lookup(value, states, &variables).ok_or_else(|| error(number, "unknown state"))?;
+                            for (word, bits) in set.iter_mut().zip(values) {
+                                *word |= bits;
+                            }
+                        }
+                        variables.retain(|(name, _)| name != variable.trim());
+                        variables.push((variable.trim().to_string(), set));
+                        continue;
+                    }
+
+                    let positions = neighborhood.offsets().len() + 2;
+                    let tokens: Vec<&str> = if line.contains(',') {
+                        line.split(',').map(str::trim).collect()
+                    } else if line.len() == positions && line.chars().all(|c| c.is_ascii_digit()) {
+                        (0..positions).map(|i| &line[i..i + 1]).collect()
+                    } else {
+                        line.split_whitespace().collect()
+                    };
+                    if tokens.len() != positions {
+                        return Err(error(number, "a transition needs the cell, every neighbor and the new state"));
+                    }
+                    // Variables are numbered by their first appearance in the transition.
+                    let mut names: Vec<&str> = Vec::new();
+                    let mut inputs = Vec::new();
+                    for &token in &tokens[..positions - 1] {
+                        let set = lookup(token, states, &variables).ok_or_else(|| error(number, "unknown state"))?;
+                        if token.parse::<u8>().is_ok() {
+                            inputs.push(Input::Any(set));
+                        } else {
+                            let variable = match names.iter().position(|&name| name == token) {
+                                Some(variable) => variable,
+                                None => {
+                                    names.push(token);
+                                    names.len() - 1
+                                }
+                            };
+                            inputs.push(Input::Bound(variable, set));
+                        }
+                    }
+                    let last = tokens[positions - 1];
+                    let output = match last.parse::<u8>() {
+                        Ok(state) if state < states => Output::State(state),
+                        Ok(_) => return Err(error(number, "the new state is out of range")),
+                        Err(_) => Output::Variable(
+                            names
+                                .iter()
+                                .position(|&name| name == last)
+                                .ok_or_else(|| error(number, "the new state must be a state or a variable used before"))?,
+                        ),
+                    };
+                    if symmetries == "permute" {
+                        transitions.push(Transition {
+                            inputs,
+                            output,
+                            permute: true,
+                        });
+                        continue;
+                    }
+                    // Symmetric transitions turn into the same one more than once.
+                    let first = transitions.len();
+                    for permutation in symmetry(&symmetries, neighborhood).ok_or_else(|| error(number, "unknown symmetries"))? {
+                        let mut permuted = vec![inputs[0].clone()];
+                        permuted.extend(permutation.iter().map(|&from| inputs[1 + from].clone()));
+                        let transition = Transition {
+                            inputs: permuted,
+                            output: output.clone(),
+                            permute: false,
+                        };
+                        if !transitions[first..].contains(&transition) {
+                            transitions.push(transition);
+                        }
+                    }
+                }
+                "COLORS" => {
+                    let numbers: Vec<Option<u8>> = line.split_whitespace().map(|n| n.parse().ok()).collect();
+                    match numbers[..] {
+                        [Some(state), Some(r), Some(g), Some(b)] => {
+                            let state = usize::from(state);
+                            if colors.len() <= state {
+                                colors.resize(state + 1, None);
+                            }
+                            colors[state] = Some([r, g, b]);
+                        }
+                        _ => return Err(error(number, "expected a state and its red, green and blue")),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if !seen_table {
+            return Err(Error::new("the rule file has no @TABLE section"));
+        }
+        let states = states.ok_or_else(|| Error::new("the rule table has no n_states"))?;
+        colors.truncate(usize::from(states));
+        Ok(RuleTable {
+            name,
+            source: contents.to_string(),
+            states,
+            neighborhood: neighborhood.ok_or_else(|| Error::new("the rule table has no neighborhood"))?,
+            transitions,
+            colors,
+            cache: HashMap::new(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    pub fn neighborhood(&self) -> TableNeighborhood {
+        self.neighborhood
+    }
+
+    // The color of every state named in @COLORS.
+    pub fn colors(&self) -> &[Option<[u8; 3]>] {
+        &self.colors
+    }
+
+    // The next state of a cell, given its neighbors in table order.
+    pub fn next(&mut self, cell: u8, neighbors: &[u8]) -> u8 {
+        let mut key = [0; 9];
+        key[0] = cell;
+        key[1..=neighbors.len()].copy_from_slice(neighbors);
+        if let Some(&state) = self.cache.get(&key) {
+            return state;
+        }
+        let state = self
+            .transitions
+            .iter()
+            .find_map(|transition| transition.apply(cell, neighbors))
+            .unwrap_or(cell);
+        self.cache.insert(key, state);
+        state
+    }
+}
+
+// A state number or a variable's name, as a set of states.
+fn lookup(token: &str, states: u8, variables: &[(String, StateSet)]) -> Option<StateSet> {
+    match token.parse::<u8>() {
+        Ok(state) if state < states => {
+            let mut set = [0; 4];
+            set[usize::from(state / 64)] |= 1 << (state % 64);
+            Some(set)
+        }
+        Ok(_) => None,
+        Err(_) => variables.iter().rev().find(|(name, _)| name == token).map(|(_, set)| *set),
+    }
+}
+
+// Every rearrangement of the neighbors a symmetry stands for, as the
+// position each neighbor is taken from.
+fn symmetry(name: &str, neighborhood: TableNeighborhood) -> Option<Vec<Vec<usize>>> {
+    let n = neighborhood.offsets().len();
+    // A quarter turn moves the neighbors two places along the Moore ring
+    // and one along von Neumann's.
+    let quarter = n / 4;
+    let rotate = |steps: usize| (0..n).map(|i| (i + steps) % n).collect::<Vec<_>>();
+    let reflect = |permutation: &Vec<usize>| permutation.iter().map(|&i| (n - i) % n).collect::<Vec<_>>();
+    let rotations = |step: usize| (0..n / step).map(|k| rotate(k * step)).collect::<Vec<_>>();
+
+    let permutations = match name {
+        "none" => vec![rotate(0)],
+        "rotate4" => rotations(quarter),
+        "rotate8" if neighborhood == TableNeighborhood::Moore => rotations(1),
+        "reflect_horizontal" => vec![rotate(0), reflect(&rotate(0))],
+        "rotate4reflect" => rotations(quarter).iter().flat_map(|p| vec![p.clone(), reflect(p)]).collect(),
+        "rotate8reflect" if neighborhood == TableNeighborhood::Moore => {
+            rotations(1).iter().flat_map(|p| vec![p.clone(), reflect(p)]).collect()
+        }
+        _ => return None,
+    };
+    Some(permutations)
+}
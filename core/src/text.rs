@@ -0,0 +1,144 @@
+use fixedbitset::FixedBitSet;
+
+use crate::Error;
+
+// How render_text() writes the universe out: the glyph (or glyphs) for
+// each dead and live cell, what goes between rows, and which part of the
+// universe to write. The defaults give the ◻/◼ squares of the original
+// text renderer, with a newline after every row.
+#[derive(Clone, Debug)]
+pub struct TextOptions {
+    dead: String,
+    alive: String,
+    row_separator: String,
+    trailing_separator: bool,
+    // (row, col, width, height); None means the whole universe.
+    viewport: Option<(u32, u32, u32, u32)>,
+}
+
+impl Default for TextOptions {
+    fn default() -> TextOptions {
+        TextOptions {
+            dead: "◻".to_string(),
+            alive: "◼".to_string(),
+            row_separator: "\n".to_string(),
+            trailing_separator: true,
+            viewport: None,
+        }
+    }
+}
+
+impl TextOptions {
+    pub fn new() -> TextOptions {
+        TextOptions::default()
+    }
+
+    // A glyph can be any string, e.g. "██" and "  " to make cells about
+    // as wide as they are tall in a monospace font.
+    pub fn set_glyphs(&mut self, dead: &str, alive: &str) {
+        self.dead = dead.to_string();
+        self.alive = alive.to_string();
+    }
+
+    // With `trailing` false, nothing follows the last row.
+    pub fn set_row_separator(&mut self, separator: &str, trailing: bool) {
+        self.row_separator = separator.to_string();
+        self.trailing_separator = trailing;
+    }
+
+    // Only writes the cells from (row, col) up to, but not including,
+    // (row + height, col + width). Whatever lies outside the universe
+    // is left out.
+    pub fn set_viewport(&mut self, row: u32, col: u32, width: u32, height: u32) {
+        self.viewport = Some((row, col, width, height));
+    }
+
+    pub fn clear_viewport(&mut self) {
+        self.viewport = None;
+    }
+}
+
+pub fn render(cells: &FixedBitSet, width: u32, height: u32, options: &TextOptions) -> String {
+    let (row, col, view_width, view_height) = options.viewport.unwrap_or((0, 0, width, height));
+    let rows = row.min(height)..row.saturating_add(view_height).min(height);
+    let cols = col.min(width)..col.saturating_add(view_width).min(width);
+
+    let mut text = String::new();
+    for (i, row) in rows.clone().enumerate() {
+        if i > 0 {
+            text.push_str(&options.row_separator);
+        }
+        for col in cols.clone() {
+            let alive = cells[(row * width + col) as usize];
+            text.push_str(if alive { &options.alive } else { &options.dead });
+        }
+    }
+    if options.trailing_separator && !rows.is_empty() {
+        text.push_str(&options.row_separator);
+    }
+    text
+}
+
+// Reads a grid written by render() with the default glyphs back in,
+// or one drawn with `.` and `O` like a plaintext pattern, so that tests
+// can spell out the cells they expect. The rows must all be the same
+// width; empty lines at the start and end are ignored.
+pub fn parse(text: &str) -> Result<(u32, u32, FixedBitSet), Error> {
+    let rows: Vec<&str> = text.trim_matches(|c| c == '\n' || c == '\r').lines().map(str::trim_end).collect();
+    let width = rows.first().map_or(0, |row| row.chars().count());
+    if width == 0 {
+        return Err(Error::new("text has no cells"));
+    }
+    let mut cells = FixedBitSet::with_capacity(width * rows.len());
+    for (number, row) in rows.iter().enumerate() {
+        if row.chars().count() != width {
+            return Err(Error::new(&format!(
+                "row {} is {} cells wide, the first one {}",
+                number + 1,
+                row.chars().count(),
+                width
+            )));
+        }
+        for (col, c) in row.chars().enumerate() {
+            match c {
+                '◻' | '.' => {}
+                '◼' | 'O' => cells.insert(number * width + col),
+                _ => {
+                    return Err(Error::new(&format!(
+                        "unexpected {:?} on row {}, cells are ◻ or ◼, . or O",
+                        c,
+                        number + 1
+                    )))
+                }
+            }
+        }
+    }
+    Ok((width as u32, rows.len() as u32, cells))
+}
+
+// The braille dot for each cell of a 2x4 block, by [row][col].
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+// Eight cells per character: each 2 wide, 4 tall block becomes one
+// braille pattern (U+2800 to U+28FF), with a newline after every row of
+// blocks. Blocks hanging over the right or bottom edge are padded with
+// dead cells.
+pub fn braille(cells: &FixedBitSet, width: u32, height: u32) -> String {
+    let mut text = String::new();
+    for block_row in (0..height).step_by(4) {
+        for block_col in (0..width).step_by(2) {
+            let mut dots = 0;
+            for (dy, row_dots) in BRAILLE_DOTS.iter().enumerate() {
+                for (dx, &dot) in row_dots.iter().enumerate() {
+                    let (row, col) = (block_row + dy as u32, block_col + dx as u32);
+                    if row < height && col < width && cells[(row * width + col) as usize] {
+                        dots |= dot;
+                    }
+                }
+            }
+            text.push(char::from_u32(0x2800 + dots).expect("braille patterns are all valid chars"));
+        }
+        text.push('\n');
+    }
+    text
+}
@@ -0,0 +1,147 @@
+use fixedbitset::FixedBitSet;
+
+use crate::random::Rng;
+use crate::{Cell, Error, LifeRule};
+
+// How the cells of a grid are laid out and which of them touch. Cells
+// are still addressed by (row, col) and stored row by row; a topology
+// only decides how many cells a row has and who the neighbors of each
+// cell are, wrapping around at the edges like a torus.
+pub trait Topology {
+    fn width(&self) -> u32;
+    fn height(&self) -> u32;
+
+    fn get_index(&self, row: u32, col: u32) -> usize {
+        (row * self.width() + col) as usize
+    }
+
+    // The most neighbors any cell has.
+    fn max_neighbors(&self) -> usize;
+
+    // Appends the indices of the cells next to (row, col) to `out`.
+    fn neighbors(&self, row: u32, col: u32, out: &mut Vec<usize>);
+}
+
+// A universe of two-state cells on any topology, running a Life-like
+// rule over however many neighbors the topology gives each cell. It
+// trades the tricks of the square Universe for a precomputed neighbor
+// table, which works for any shape of cell.
+#[derive(Clone, Debug)]
+pub struct TiledUniverse<T> {
+    topology: T,
+    rule: LifeRule,
+    cells: FixedBitSet,
+    next: FixedBitSet,
+    // neighbors[idx * stride..] lists the neighbors of cell idx, padded
+    // with usize::MAX up to stride = max_neighbors().
+    neighbors: Vec<usize>,
+    generation: u64,
+}
+
+impl<T: Topology> TiledUniverse<T> {
+    pub fn new(topology: T, rule: &str) -> Result<TiledUniverse<T>, Error> {
+        let rule: LifeRule = rule.parse()?;
+        if !rule.is_life_like() {
+            return Err(Error::new("tiled universes only run plain B/S rules"));
+        }
+        let stride = topology.max_neighbors();
+        let size = (topology.width() * topology.height()) as usize;
+        let mut neighbors = Vec::with_capacity(size * stride);
+        let mut cell = Vec::with_capacity(stride);
+        for row in 0..topology.height() {
+            for col in 0..topology.width() {
+                cell.clear();
+                topology.neighbors(row, col, &mut cell);
+                cell.resize(stride, usize::MAX);
+                neighbors.extend_from_slice(&cell);
+            }
+        }
+        Ok(TiledUniverse {
+            topology,
+            rule,
+            cells: FixedBitSet::with_capacity(size),
+            next: FixedBitSet::with_capacity(size),
+            neighbors,
+            generation: 0,
+        })
+    }
+
+    pub fn topology(&self) -> &T {
+        &self.topology
+    }
+
+    pub fn rule(&self) -> String {
+        self.rule.to_string()
+    }
+
+    pub fn tick(&mut self) {
+        let stride = self.topology.max_neighbors();
+        for idx in 0..self.cells.len() {
+            let live = self.neighbors[idx * stride..(idx + 1) * stride]
+                .iter()
+                .filter(|&&neighbor| neighbor != usize::MAX && self.cells[neighbor])
+                .count();
+            self.next.set(idx, self.rule.next(self.cells[idx], live as u8));
+        }
+        std::mem::swap(&mut self.cells, &mut self.next);
+        self.generation += 1;
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        for _ in 0..n {
+            self.tick();
+        }
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u32 {
+        self.cells.count_ones(..) as u32
+    }
+
+    // The packed cells, bit get_index(row, col), like Universe::cells().
+    pub fn cells(&self) -> &[u32] {
+        self.cells.as_slice()
+    }
+
+    fn index(&self, row: u32, col: u32) -> Result<usize, Error> {
+        if row >= self.topology.height() || col >= self.topology.width() {
+            return Err(Error::new("cell is out of bounds"));
+        }
+        Ok(self.topology.get_index(row, col))
+    }
+
+    pub fn get_cell(&self, row: u32, col: u32) -> Result<Cell, Error> {
+        Ok(if self.cells[self.index(row, col)?] {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        })
+    }
+
+    pub fn set_cell(&mut self, row: u32, col: u32, cell: Cell) -> Result<(), Error> {
+        let idx = self.index(row, col)?;
+        self.cells.set(idx, cell == Cell::Alive);
+        Ok(())
+    }
+
+    pub fn toggle_cell(&mut self, row: u32, col: u32) -> Result<(), Error> {
+        let idx = self.index(row, col)?;
+        self.cells.set(idx, !self.cells[idx]);
+        Ok(())
+    }
+
+    // Replaces every cell with a reproducible random soup, like Universe::random().
+    pub fn randomize(&mut self, density: f32, seed: u64) -> Result<(), Error> {
+        if !(0.0..=1.0).contains(&density) {
+            return Err(Error::new("density must be between 0 and 1"));
+        }
+        let mut rng = Rng::new(seed);
+        for idx in 0..self.cells.len() {
+            self.cells.set(idx, rng.next_f32() < density);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use crate::topology::{TiledUniverse, Topology};
+use crate::Error;
+
+// A grid of triangles. Each row alternates between triangles pointing up
+// and triangles pointing down, and (row, col) points up when row + col is
+// even, so the triangle below an upward one points down and the two share
+// their base:
+//
+//      /\  /\  /\
+//     /  \/  \/  \     row 0: up, down, up, down, ...
+//     \  /\  /\  /
+//      \/  \/  \/      row 1: down, up, down, up, ...
+//
+// Width and height must be even for the pattern to wrap around.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Triangular {
+    width: u32,
+    height: u32,
+    neighborhood: TriangularNeighborhood,
+}
+
+// Edges counts the three triangles sharing an edge with a cell; Vertices
+// the twelve sharing at least a corner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriangularNeighborhood {
+    Edges,
+    Vertices,
+}
+
+impl Triangular {
+    pub fn new(width: u32, height: u32, neighborhood: TriangularNeighborhood) -> Result<Triangular, Error> {
+        if width == 0 || height == 0 || !width.is_multiple_of(2) || !height.is_multiple_of(2) {
+            return Err(Error::new("triangular universes need an even, non-zero width and height"));
+        }
+        Ok(Triangular {
+            width,
+            height,
+            neighborhood,
+        })
+    }
+
+    pub fn neighborhood(&self) -> TriangularNeighborhood {
+        self.neighborhood
+    }
+
+    pub fn points_up(row: u32, col: u32) -> bool {
+        (row + col).is_multiple_of(2)
+    }
+}
+
+impl Topology for Triangular {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn max_neighbors(&self) -> usize {
+        match self.neighborhood {
+            TriangularNeighborhood::Edges => 3,
+            TriangularNeighborhood::Vertices => 12,
+        }
+    }
+
+    fn neighbors(&self, row: u32, col: u32, out: &mut Vec<usize>) {
+        // An upward triangle has its base, and its third edge neighbor,
+        // on the row below; a downward one on the row above.
+        let (base, apex) = if Triangular::points_up(row, col) { (1, -1) } else { (-1, 1) };
+        // (row offset, first and last column offset) of each strip of neighbors.
+        let strips: &[(i32, i32, i32)] = match self.neighborhood {
+            TriangularNeighborhood::Edges => &[(0, -1, -1), (0, 1, 1), (base, 0, 0)],
+            TriangularNeighborhood::Vertices => &[(0, -2, -1), (0, 1, 2), (base, -2, 2), (apex, -1, 1)],
+        };
+        let (width, height) = (self.width as i32, self.height as i32);
+        for &(dy, from, to) in strips {
+            let r = (row as i32 + dy).rem_euclid(height);
+            for dx in from..=to {
+                let c = (col as i32 + dx).rem_euclid(width);
+                out.push(self.get_index(r as u32, c as u32));
+            }
+        }
+    }
+}
+
+pub type TriangularUniverse = TiledUniverse<Triangular>;
+
+impl TriangularUniverse {
+    // An empty universe of triangles running a Life-like rule, with
+    // neighbor counts up to 3 or 12 depending on the neighborhood;
+    // "B4/S345" is a good start with twelve neighbors.
+    pub fn with_rule(
+        width: u32,
+        height: u32,
+        neighborhood: TriangularNeighborhood,
+        rule: &str,
+    ) -> Result<TriangularUniverse, Error> {
+        TiledUniverse::new(Triangular::new(width, height, neighborhood)?, rule)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.topology().width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.topology().height()
+    }
+}
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+// Turmites are little machines walking over the grid. Each tick a
+// turmite looks at the cell it stands on and, depending on that cell and
+// on its own state, writes a new value into the cell, turns, switches to
+// another state and moves one cell forward. They wrap around the edges
+// whatever the universe's boundary.
+//
+// Langton's Ant is the best known, with a single state: on a dead cell
+// it turns right, on a live one left, and it flips the cell either way.
+// That's the "RL" ant. After some 10,000 steps of mess it starts
+// building a diagonal highway.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Turmite {
+    row: u32,
+    col: u32,
+    direction: Direction,
+    state: u8,
+    // What to do in every state, on a dead and on a live cell.
+    table: Vec<[Action; 2]>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+// One entry of a turmite's table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Action {
+    write: bool,
+    // In quarter turns clockwise: 0 goes straight on, 1 turns right,
+    // 2 turns around and 3 turns left.
+    turn: u8,
+    next_state: u8,
+}
+
+impl Direction {
+    fn turned(self, quarter_turns: u8) -> Direction {
+        match (self as u8 + quarter_turns) % 4 {
+            0 => Direction::North,
+            1 => Direction::East,
+            2 => Direction::South,
+            _ => Direction::West,
+        }
+    }
+}
+
+impl Turmite {
+    // `table` is flat: for each state, the (write, turn, next state)
+    // triples for a dead and for a live cell, with write 0 or 1 and the
+    // turn in quarter turns clockwise. Langton's Ant is [1, 1, 0, 0, 3, 0].
+    pub fn new(row: u32, col: u32, direction: Direction, table: &[u8]) -> Result<Turmite, Error> {
+        if table.is_empty() || !table.len().is_multiple_of(6) || table.len() / 6 > 256 {
+            return Err(Error::new("a turmite table needs two (write, turn, next state) triples per state"));
+        }
+        let states = table.len() / 6;
+        let actions: Vec<Action> = table
+            .chunks(3)
+            .map(|triple| match *triple {
+                [write, turn, next_state] if write <= 1 && turn <= 3 && usize::from(next_state) < states => Ok(Action {
+                    write: write == 1,
+                    turn,
+                    next_state,
+                }),
+                _ => Err(Error::new(
+                    "writes are 0 or 1, turns 0 to 3, and next states must be in the table",
+                )),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Turmite {
+            row,
+            col,
+            direction,
+            state: 0,
+            table: actions.chunks(2).map(|pair| [pair[0], pair[1]]).collect(),
+        })
+    }
+
+    // An ant, given by its turns on dead and on live cells: "RL" is
+    // Langton's, "LR" its mirror image. N goes straight on and U turns
+    // around. An ant always flips the cell it leaves.
+    pub fn ant(row: u32, col: u32, direction: Direction, rule: &str) -> Result<Turmite, Error> {
+        let turns: Vec<Option<u8>> = rule
+            .chars()
+            .map(|c| match c.to_ascii_uppercase() {
+                'N' => Some(0),
+                'R' => Some(1),
+                'U' => Some(2),
+                'L' => Some(3),
+                _ => None,
+            })
+            .collect();
+        match turns[..] {
+            [Some(on_dead), Some(on_live)] => Turmite::new(row, col, direction, &[1, on_dead, 0, 0, on_live, 0]),
+            _ => Err(Error::new(
+                "an ant needs a turn for dead and for live cells, each one of L, R, N or U, like \"RL\"",
+            )),
+        }
+    }
+
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+
+    pub fn col(&self) -> u32 {
+        self.col
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn state(&self) -> u8 {
+        self.state
+    }
+
+    // Makes sure a turmite read back from a save only goes to states
+    // its table has, as Turmite::new() does.
+    pub(crate) fn checked(self) -> Result<Turmite, Error> {
+        let states = self.table.len();
+        let in_table = |state: u8| usize::from(state) < states;
+        let actions_ok = self.table.iter().flatten().all(|action| action.turn <= 3 && in_table(action.next_state));
+        if !(1..=256).contains(&states) || !in_table(self.state) || !actions_ok {
+            return Err(Error::new("the turmite's table doesn't hold together"));
+        }
+        Ok(self)
+    }
+
+    pub(crate) fn move_to(&mut self, row: u32, col: u32) {
+        self.row = row;
+        self.col = col;
+    }
+
+    // Does one step given the cell under the turmite, and returns what
+    // to write into it before moving away.
+    pub(crate) fn step(&mut self, alive: bool, width: u32, height: u32) -> bool {
+        let action = self.table[usize::from(self.state)][alive as usize];
+        self.state = action.next_state;
+        self.direction = self.direction.turned(action.turn);
+        match self.direction {
+            Direction::North => self.row = (self.row + height - 1) % height,
+            Direction::East => self.col = (self.col + 1) % width,
+            Direction::South => self.row = (self.row + 1) % height,
+            Direction::West => self.col = (self.col + width - 1) % width,
+        }
+        action.write
+    }
+}
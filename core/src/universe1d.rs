@@ -0,0 +1,173 @@
+use fixedbitset::FixedBitSet;
+
+use crate::neighborhood::reflect;
+use crate::random::Rng;
+use crate::{cell_count, Boundary, Cell, Error};
+
+// Wolfram's elementary cellular automata: a single row of cells, where
+// each cell's next state depends on itself and its two neighbors. The
+// three of them make a number from 0 to 7 (left is the high bit), and
+// bit n of the rule number is the next state for pattern n, so Rule 30
+// (0b00011110) turns "001", "010", "011" and "100" into live cells.
+//
+// The grid is the space-time diagram: row 0 is the current generation,
+// and every tick pushes the older ones one row down, dropping the
+// oldest off the bottom. The cells are packed like Universe::cells(),
+// so the same drawing code works.
+#[derive(Clone, Debug)]
+pub struct Universe1D {
+    width: u32,
+    height: u32,
+    rule: u8,
+    boundary: Boundary,
+    cells: FixedBitSet,
+    generation: u64,
+}
+
+impl Universe1D {
+    pub fn new(width: u32, height: u32, rule: u8) -> Result<Universe1D, Error> {
+        let size = cell_count(&[width, height])?;
+        Ok(Universe1D {
+            width,
+            height,
+            rule,
+            boundary: Boundary::Torus,
+            cells: FixedBitSet::with_capacity(size),
+            generation: 0,
+        })
+    }
+
+    pub fn rule(&self) -> u8 {
+        self.rule
+    }
+
+    pub fn set_rule(&mut self, rule: u8) {
+        self.rule = rule;
+    }
+
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    // What lies past both ends of the row, as for Universe.
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
+    }
+
+    // The current cell at `col`, which may be one past either end.
+    fn current(&self, col: i64) -> bool {
+        let width = i64::from(self.width);
+        let col = match self.boundary {
+            _ if (0..width).contains(&col) => col,
+            Boundary::Torus => col.rem_euclid(width),
+            Boundary::Mirror => reflect(col, width),
+            Boundary::Dead => return false,
+            Boundary::AliveWall => return true,
+        };
+        self.cells[col as usize]
+    }
+
+    pub fn tick(&mut self) {
+        let width = self.width as usize;
+        let next: Vec<bool> = (0..i64::from(self.width))
+            .map(|col| {
+                let pattern = (self.current(col - 1) as u8) << 2
+                    | (self.current(col) as u8) << 1
+                    | self.current(col + 1) as u8;
+                self.rule & (1 << pattern) != 0
+            })
+            .collect();
+        // Scroll the history down, starting from the bottom so that
+        // nothing is overwritten before it has been copied.
+        for idx in (width..self.cells.len()).rev() {
+            let above = self.cells[idx - width];
+            self.cells.set(idx, above);
+        }
+        for (idx, alive) in next.into_iter().enumerate() {
+            self.cells.set(idx, alive);
+        }
+        self.generation += 1;
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        for _ in 0..n {
+            self.tick();
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    // Live cells in the current generation only.
+    pub fn population(&self) -> u32 {
+        self.cells.count_ones(..self.width as usize) as u32
+    }
+
+    pub fn cells(&self) -> &[u32] {
+        self.cells.as_slice()
+    }
+
+    // Row 0 is now, row n is n generations ago.
+    pub fn get_cell(&self, row: u32, col: u32) -> Result<Cell, Error> {
+        if row >= self.height || col >= self.width {
+            return Err(Error::new("cell is out of bounds"));
+        }
+        Ok(if self.cells[(row * self.width + col) as usize] {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        })
+    }
+
+    // Edits go into the current generation; the history stays as it was.
+    pub fn set_cell(&mut self, col: u32, cell: Cell) -> Result<(), Error> {
+        if col >= self.width {
+            return Err(Error::new("cell is out of bounds"));
+        }
+        self.cells.set(col as usize, cell == Cell::Alive);
+        Ok(())
+    }
+
+    pub fn toggle_cell(&mut self, col: u32) -> Result<(), Error> {
+        if col >= self.width {
+            return Err(Error::new("cell is out of bounds"));
+        }
+        let alive = self.cells[col as usize];
+        self.cells.set(col as usize, !alive);
+        Ok(())
+    }
+
+    // Wipes the whole diagram and starts again from generation 0.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.generation = 0;
+    }
+
+    // The classic start: a single live cell in the middle of the row.
+    pub fn seed_center(&mut self) {
+        self.clear();
+        self.cells.insert(self.width as usize / 2);
+    }
+
+    // Starts again from a reproducible random row.
+    pub fn randomize(&mut self, density: f32, seed: u64) -> Result<(), Error> {
+        if !(0.0..=1.0).contains(&density) {
+            return Err(Error::new("density must be between 0 and 1"));
+        }
+        self.clear();
+        let mut rng = Rng::new(seed);
+        for col in 0..self.width as usize {
+            self.cells.set(col, rng.next_f32() < density);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt;
+
+use crate::random::Rng;
+use crate::{cell_count, Cell, Error};
+
+// Life in three dimensions, on a width x height x depth torus of cells
+// with 26 neighbors each: the 3x3x3 cube around them.
+//
+// Rules are written like Carter Bays did, as "4555": a live cell stays
+// alive with 4 to 5 live neighbors, and a dead one comes alive with 5
+// to 5. Since counts go up to 26, the four numbers can also be
+// separated by commas, as in "4,5,5,5" or "5,7,6,6" (which is "5766").
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule3D {
+    survival: (u8, u8),
+    birth: (u8, u8),
+}
+
+impl Rule3D {
+    pub fn next(&self, alive: bool, live_neighbors: u8) -> bool {
+        let (low, high) = if alive { self.survival } else { self.birth };
+        (low..=high).contains(&live_neighbors)
+    }
+}
+
+impl std::str::FromStr for Rule3D {
+    type Err = Error;
+
+    fn from_str(rule: &str) -> Result<Rule3D, Error> {
+        let numbers: Vec<Option<u8>> = if rule.contains(',') {
+            rule.split(',').map(|n| n.trim().parse().ok()).collect()
+        } else {
+            rule.chars().map(|c| c.to_digit(10).map(|n| n as u8)).collect()
+        };
+        match numbers[..] {
+            [Some(a), Some(b), Some(c), Some(d)] if a <= b && c <= d && b <= 26 && d <= 26 => Ok(Rule3D {
+                survival: (a, b),
+                birth: (c, d),
+            }),
+            _ => Err(Error::new(
+                "expected a 3D rule as four neighbor counts up to 26, like 4555 or 4,5,5,5",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Rule3D {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let numbers = [self.survival.0, self.survival.1, self.birth.0, self.birth.1];
+        if numbers.iter().all(|&n| n < 10) {
+            numbers.iter().try_for_each(|n| write!(f, "{}", n))
+        } else {
+            write!(f, "{},{},{},{}", numbers[0], numbers[1], numbers[2], numbers[3])
+        }
+    }
+}
+
+// The three directions of a 3D universe, for picking a slice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+#[derive(Clone, Debug)]
+pub struct Universe3D {
+    width: u32,
+    height: u32,
+    depth: u32,
+    rule: Rule3D,
+    // One byte per cell, 0 or 1, at x + width * (y + height * z): a
+    // stack of depth layers, each row by row, ready to be uploaded as is
+    // into a 3D texture.
+    cells: Vec<u8>,
+    generation: u64,
+}
+
+impl Universe3D {
+    pub fn with_rule(width: u32, height: u32, depth: u32, rule: &str) -> Result<Universe3D, Error> {
+        let size = cell_count(&[width, height, depth])?;
+        Ok(Universe3D {
+            width,
+            height,
+            depth,
+            rule: rule.parse()?,
+            cells: vec![0; size],
+            generation: 0,
+        })
+    }
+
+    pub fn rule(&self) -> String {
+        self.rule.to_string()
+    }
+
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), Error> {
+        self.rule = rule.parse()?;
+        Ok(())
+    }
+
+    fn get_index(&self, x: u32, y: u32, z: u32) -> usize {
+        (x + self.width * (y + self.height * z)) as usize
+    }
+
+    // Sums every cell with its two neighbors along one axis, `step`
+    // cells apart in the buffer with `size` cells along the axis.
+    fn sum_along(values: &[u8], size: u32, step: usize) -> Vec<u8> {
+        let size = size as usize;
+        let mut sums = vec![0; values.len()];
+        for (idx, sum) in sums.iter_mut().enumerate() {
+            let position = idx / step % size;
+            let line = idx - position * step;
+            let at = |p: usize| values[line + p % size * step];
+            *sum = at(position + size - 1) + at(position) + at(position + 1);
+        }
+        sums
+    }
+
+    pub fn tick(&mut self) {
+        // The 3x3x3 cube is a 3-cell sum along each axis in turn.
+        let rows = Universe3D::sum_along(&self.cells, self.width, 1);
+        let layers = Universe3D::sum_along(&rows, self.height, self.width as usize);
+        let cubes = Universe3D::sum_along(&layers, self.depth, (self.width * self.height) as usize);
+        for (cell, cube) in self.cells.iter_mut().zip(cubes) {
+            *cell = self.rule.next(*cell == 1, cube - *cell) as u8;
+        }
+        self.generation += 1;
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        for _ in 0..n {
+            self.tick();
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u32 {
+        self.cells.iter().map(|&cell| u32::from(cell)).sum()
+    }
+
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    fn index(&self, x: u32, y: u32, z: u32) -> Result<usize, Error> {
+        if x >= self.width || y >= self.height || z >= self.depth {
+            return Err(Error::new("cell is out of bounds"));
+        }
+        Ok(self.get_index(x, y, z))
+    }
+
+    pub fn get_cell(&self, x: u32, y: u32, z: u32) -> Result<Cell, Error> {
+        Ok(if self.cells[self.index(x, y, z)?] == 1 {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        })
+    }
+
+    pub fn set_cell(&mut self, x: u32, y: u32, z: u32, cell: Cell) -> Result<(), Error> {
+        let idx = self.index(x, y, z)?;
+        self.cells[idx] = cell as u8;
+        Ok(())
+    }
+
+    pub fn toggle_cell(&mut self, x: u32, y: u32, z: u32) -> Result<(), Error> {
+        let idx = self.index(x, y, z)?;
+        self.cells[idx] ^= 1;
+        Ok(())
+    }
+
+    // Replaces every cell with a reproducible random soup.
+    pub fn randomize(&mut self, density: f32, seed: u64) -> Result<(), Error> {
+        if !(0.0..=1.0).contains(&density) {
+            return Err(Error::new("density must be between 0 and 1"));
+        }
+        let mut rng = Rng::new(seed);
+        for cell in &mut self.cells {
+            *cell = (rng.next_f32() < density) as u8;
+        }
+        Ok(())
+    }
+
+    // The 2D cross-section at `index` along `axis`, one byte per cell, row
+    // by row: (y, z) for X, (x, z) for Y and (x, y) for Z, with the first
+    // coordinate going along the rows.
+    pub fn slice(&self, axis: Axis, index: u32) -> Result<Vec<u8>, Error> {
+        let size = match axis {
+            Axis::X => self.width,
+            Axis::Y => self.height,
+            Axis::Z => self.depth,
+        };
+        if index >= size {
+            return Err(Error::new("slice is out of bounds"));
+        }
+        Ok(match axis {
+            Axis::X => (0..self.depth)
+                .flat_map(|z| (0..self.height).map(move |y| (index, y, z)))
+                .map(|(x, y, z)| self.cells[self.get_index(x, y, z)])
+                .collect(),
+            Axis::Y => (0..self.depth)
+                .flat_map(|z| (0..self.width).map(move |x| (x, index, z)))
+                .map(|(x, y, z)| self.cells[self.get_index(x, y, z)])
+                .collect(),
+            Axis::Z => {
+                let layer = (self.width * self.height) as usize;
+                let start = index as usize * layer;
+                self.cells[start..start + layer].to_vec()
+            }
+        })
+    }
+}
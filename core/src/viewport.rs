@@ -0,0 +1,47 @@
+use fixedbitset::FixedBitSet;
+
+// The same colors as Renderer's defaults, as RGBA.
+const ALIVE: [u8; 4] = [0x00, 0x00, 0x00, 0xFF];
+const DEAD: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+// Pixels that fall outside the universe.
+const OUTSIDE: [u8; 4] = [0xCC, 0xCC, 0xCC, 0xFF];
+
+// Which cell (along one axis) each pixel shows, or None past the edges.
+fn cells_along(start: i32, pixels: u32, zoom: f64, cells: u32) -> Vec<Option<usize>> {
+    (0..pixels)
+        .map(|p| {
+            let cell = f64::from(start) + (f64::from(p) / zoom).floor();
+            if cell >= 0.0 && cell < f64::from(cells) {
+                Some(cell as usize)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+// Paints a `view_width` x `view_height` pixel view into `out`, four bytes
+// per pixel, starting at cell (x, y) with `zoom` pixels per cell. Below
+// a zoom of 1 each pixel shows the cell under its top left corner.
+pub fn render(
+    cells: &FixedBitSet,
+    (width, height): (u32, u32),
+    (x, y): (i32, i32),
+    (view_width, view_height): (u32, u32),
+    zoom: f64,
+    out: &mut [u8],
+) {
+    let cols = cells_along(x, view_width, zoom, width);
+    let rows = cells_along(y, view_height, zoom, height);
+    let mut pixels = out.chunks_exact_mut(4);
+    for row in &rows {
+        for col in &cols {
+            let color = match (row, col) {
+                (Some(row), Some(col)) if cells[row * width as usize + col] => ALIVE,
+                (Some(_), Some(_)) => DEAD,
+                _ => OUTSIDE,
+            };
+            pixels.next().expect("out holds every pixel").copy_from_slice(&color);
+        }
+    }
+}
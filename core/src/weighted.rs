@@ -0,0 +1,121 @@
+use fixedbitset::FixedBitSet;
+use serde::{Deserialize, Serialize};
+
+use crate::neighborhood::reflect;
+use crate::{Boundary, Error};
+
+// Weighted Life: instead of counting the live cells around a cell, every
+// cell of a small square kernel centered on it adds its own weight when
+// it is alive, and the total decides. A dead cell is born when the sum is
+// one of `birth`, a live one survives when it is one of `survival`.
+// Weights can be negative, to make some neighbors inhibit, and the middle
+// weight counts the cell itself.
+//
+// Life is the 3x3 kernel of ones with a zero in the middle, birth [3]
+// and survival [2, 3]. Giving the middle a weight of 9 instead turns
+// "alive with 2 or 3 neighbors" into the sums 11 and 12, so a single
+// list of sums can also do the job of both.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeightedRule {
+    size: u32,
+    // Row by row, size * size of them.
+    weights: Vec<i8>,
+    birth: Vec<i32>,
+    survival: Vec<i32>,
+}
+
+pub const MAX_SIZE: u32 = 7;
+
+impl WeightedRule {
+    pub fn new(size: u32, weights: &[i8], birth: &[i32], survival: &[i32]) -> Result<WeightedRule, Error> {
+        if size.is_multiple_of(2) || size > MAX_SIZE {
+            return Err(Error::new("the kernel must be 1x1, 3x3, 5x5 or 7x7"));
+        }
+        if weights.len() != (size * size) as usize {
+            return Err(Error::new("the kernel needs size * size weights"));
+        }
+        let sorted = |sums: &[i32]| {
+            let mut sums = sums.to_vec();
+            sums.sort_unstable();
+            sums.dedup();
+            sums
+        };
+        Ok(WeightedRule {
+            size,
+            weights: weights.to_vec(),
+            birth: sorted(birth),
+            survival: sorted(survival),
+        })
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    pub fn weights(&self) -> &[i8] {
+        &self.weights
+    }
+
+    pub fn birth(&self) -> &[i32] {
+        &self.birth
+    }
+
+    pub fn survival(&self) -> &[i32] {
+        &self.survival
+    }
+
+    pub fn next(&self, alive: bool, sum: i32) -> bool {
+        let sums = if alive { &self.survival } else { &self.birth };
+        sums.binary_search(&sum).is_ok()
+    }
+
+    // The weighted sum around every cell, row by row, with whatever the
+    // boundary puts past the edges.
+    pub fn sums(&self, cells: &FixedBitSet, width: u32, height: u32, boundary: Boundary) -> Vec<i32> {
+        let (width, height) = (i64::from(width), i64::from(height));
+        let radius = i64::from(self.size / 2);
+        // The kernel without its zero weights, as (row offset, column offset, weight).
+        let taps: Vec<(i64, i64, i32)> = self
+            .weights
+            .iter()
+            .enumerate()
+            .filter(|&(_, &weight)| weight != 0)
+            .map(|(i, &weight)| {
+                let (row, col) = (i as i64 / (2 * radius + 1), i as i64 % (2 * radius + 1));
+                (row - radius, col - radius, i32::from(weight))
+            })
+            .collect();
+
+        let mut sums = Vec::with_capacity((width * height) as usize);
+        for row in 0..height {
+            for col in 0..width {
+                let mut sum = 0;
+                for &(delta_row, delta_col, weight) in &taps {
+                    let (mut r, mut c) = (row + delta_row, col + delta_col);
+                    if r < 0 || r >= height || c < 0 || c >= width {
+                        match boundary {
+                            Boundary::Dead => continue,
+                            Boundary::AliveWall => {
+                                sum += weight;
+                                continue;
+                            }
+                            Boundary::Torus => {
+                                r = r.rem_euclid(height);
+                                c = c.rem_euclid(width);
+                            }
+                            Boundary::Mirror => {
+                                r = reflect(r, height);
+                                c = reflect(c, width);
+                            }
+                        }
+                    }
+                    if cells[(r * width + c) as usize] {
+                        sum += weight;
+                    }
+                }
+                sums.push(sum);
+            }
+        }
+        sums
+    }
+}
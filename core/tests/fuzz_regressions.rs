@@ -0,0 +1,22 @@
+// Inputs that once crashed or hung the parsers, run the way the fuzz
+// targets in fuzz/ run them. A new one found with cargo fuzz goes here
+// too, so it stays fixed without the nightly toolchain.
+
+use game_of_life_core::{Pattern, Universe};
+
+#[test]
+fn rle_rows_as_far_as_they_count() {
+    assert!(Universe::from_rle("4294967295$o!").is_err());
+    assert!(Pattern::from_rle("4294967295$o!").is_err());
+    assert!(Pattern::from_file(b"4294967295$o!").is_err());
+}
+
+#[test]
+fn macrocell_trees_of_empty_leaves() {
+    let mut file = String::from("[M2]\n$\n");
+    for level in 4..=30 {
+        let below = level - 3;
+        file.push_str(&format!("{} {} {} {} {}\n", level, below, below, below, below));
+    }
+    assert_eq!(Pattern::from_file(file.as_bytes()).unwrap().width(), 0);
+}
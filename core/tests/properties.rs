@@ -0,0 +1,92 @@
+// Invariants that should hold for any universe, checked on random ones
+// with proptest. Small and lopsided sizes are where the modulo
+// arithmetic at the edges goes wrong, so that is what gets generated.
+
+use game_of_life_core::{Cell, Engine, Seed, Universe, UniverseBuilder};
+use proptest::prelude::*;
+
+// Rules without B0, so that nothing is born out of nothing.
+const RULES: [&str; 5] = ["B3/S23", "B36/S23", "B3678/S34678", "B2/S", "B1357/S1357"];
+
+fn engine() -> impl Strategy<Value = Engine> {
+    prop_oneof![Just(Engine::Naive), Just(Engine::BitParallel)]
+}
+
+// A builder rather than the universe itself, which proptest couldn't
+// print when a case fails.
+fn soup() -> impl Strategy<Value = UniverseBuilder> {
+    (3..40u32, 3..40u32, 0.0..=1.0f32, any::<u64>(), 0..RULES.len(), engine()).prop_map(|(width, height, density, seed, rule, engine)| {
+        UniverseBuilder::new().size(width, height).rule(RULES[rule]).engine(engine).seed(Seed::Random { density, seed })
+    })
+}
+
+fn live_cells(universe: &Universe) -> Vec<(u32, u32)> {
+    let mut cells = Vec::new();
+    for row in 0..universe.height() {
+        for col in 0..universe.width() {
+            if universe.get_cell(row, col).unwrap() == Cell::Alive {
+                cells.push((row, col));
+            }
+        }
+    }
+    cells
+}
+
+// The same universe with every cell moved down and right, wrapping
+// around the torus.
+fn translated(universe: &Universe, down: u32, right: u32) -> Universe {
+    let (width, height) = (universe.width(), universe.height());
+    let mut moved = Universe::with_rule(width, height, &universe.rule()).unwrap();
+    moved.set_engine(universe.engine());
+    let pairs: Vec<u32> = live_cells(universe).iter().flat_map(|&(row, col)| vec![(row + down) % height, (col + right) % width]).collect();
+    moved.set_cells(&pairs).unwrap();
+    moved
+}
+
+proptest! {
+    #[test]
+    fn ticking_keeps_the_size_and_the_buffer(soup in soup(), ticks in 1..8u32) {
+        let mut universe = soup.build().unwrap();
+        let (width, height, words) = (universe.width(), universe.height(), universe.cells().len());
+        universe.tick_many(ticks);
+        prop_assert_eq!((universe.width(), universe.height(), universe.cells().len()), (width, height, words));
+        prop_assert_eq!(universe.population() as usize, live_cells(&universe).len());
+    }
+
+    #[test]
+    fn dead_universes_stay_dead(width in 1..40u32, height in 1..40u32, rule in 0..RULES.len(), engine in engine(), ticks in 1..8u32) {
+        let mut universe = Universe::with_rule(width, height, RULES[rule]).unwrap();
+        universe.set_engine(engine);
+        universe.tick_many(ticks);
+        prop_assert_eq!(universe.population(), 0);
+        prop_assert!(universe.cells().iter().all(|&word| word == 0));
+    }
+
+    #[test]
+    fn rle_round_trips_keep_every_cell(soup in soup()) {
+        let universe = soup.build().unwrap();
+        // An empty RLE has no size to make a universe of.
+        prop_assume!(universe.population() > 0);
+        let copy = Universe::from_rle(&universe.to_rle()).unwrap();
+        prop_assert_eq!(copy.to_rle(), universe.to_rle());
+        // The RLE is cropped to the live cells, so compare them from the
+        // corner of their bounding box.
+        let from_corner = |cells: Vec<(u32, u32)>| {
+            let top = cells.iter().map(|cell| cell.0).min().unwrap_or(0);
+            let left = cells.iter().map(|cell| cell.1).min().unwrap_or(0);
+            cells.into_iter().map(|(row, col)| (row - top, col - left)).collect::<Vec<_>>()
+        };
+        prop_assert_eq!(from_corner(live_cells(&copy)), from_corner(live_cells(&universe)));
+    }
+
+    #[test]
+    fn moving_around_the_torus_commutes_with_ticking(soup in soup(), down in 0..40u32, right in 0..40u32) {
+        let universe = soup.build().unwrap();
+        let mut moved_then_ticked = translated(&universe, down, right);
+        moved_then_ticked.tick();
+        let mut ticked = translated(&universe, 0, 0);
+        ticked.tick();
+        let ticked_then_moved = translated(&ticked, down, right);
+        prop_assert_eq!(live_cells(&moved_then_ticked), live_cells(&ticked_then_moved));
+    }
+}
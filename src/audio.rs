@@ -0,0 +1,131 @@
+use js_sys::Promise;
+use wasm_bindgen::prelude::*;
+use web_sys::{AudioContext, BiquadFilterNode, BiquadFilterType, GainNode, OscillatorType};
+
+use crate::{Error, Universe};
+
+// A major pentatonic scale, in semitones above the root, so that any
+// notes played together sound fine.
+const SCALE: [u32; 5] = [0, 2, 4, 7, 9];
+const OCTAVES: u32 = 3;
+// A3, the lowest note, for the leftmost column.
+const ROOT: f64 = 220.0;
+// The low-pass filter opens up from MIN_CUTOFF for an empty universe
+// to MAX_CUTOFF for a full one.
+const MIN_CUTOFF: f64 = 200.0;
+const MAX_CUTOFF: f64 = 8000.0;
+// How long a note rings, in seconds.
+const NOTE_LENGTH: f64 = 0.3;
+
+fn audio_error(what: &str) -> impl Fn(JsValue) -> Error + '_ {
+    move |e| Error::new(&format!("{}: {:?}", what, e))
+}
+
+// Spreads the columns over the notes of the scale.
+fn pitch(col: usize, width: usize) -> f64 {
+    let notes = SCALE.len() * OCTAVES as usize;
+    let note = col * notes / width.max(1);
+    let semitones = (note / SCALE.len()) as u32 * 12 + SCALE[note % SCALE.len()];
+    ROOT * 2f64.powf(f64::from(semitones) / 12.0)
+}
+
+// Plays a universe as it runs. Each play() after a tick turns births
+// into notes, pitched by the columns where most cells changed, left
+// to right from low to high, while the population sets how bright
+// everything sounds:
+//
+//     const sonifier = new Sonifier();
+//     button.onclick = () => sonifier.resume();
+//     universe.tick();
+//     sonifier.play(universe);
+#[wasm_bindgen]
+pub struct Sonifier {
+    context: AudioContext,
+    filter: BiquadFilterNode,
+    output: GainNode,
+    // The most notes a single tick plays.
+    voices: u32,
+}
+
+#[wasm_bindgen]
+impl Sonifier {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Result<Sonifier, Error> {
+        let context = AudioContext::new().map_err(audio_error("could not create an AudioContext"))?;
+        let filter = context.create_biquad_filter().map_err(audio_error("could not create a filter"))?;
+        filter.set_type(BiquadFilterType::Lowpass);
+        filter.frequency().set_value(MIN_CUTOFF as f32);
+        let output = context.create_gain().map_err(audio_error("could not create a gain node"))?;
+        output.gain().set_value(0.3);
+        filter
+            .connect_with_audio_node(&output)
+            .and_then(|_| output.connect_with_audio_node(&context.destination()))
+            .map_err(audio_error("could not connect the audio nodes"))?;
+        Ok(Sonifier {
+            context,
+            filter,
+            output,
+            voices: 4,
+        })
+    }
+
+    // Browsers keep audio suspended until the page is clicked or
+    // typed into, so call this from such an event handler.
+    pub fn resume(&self) -> Result<Promise, Error> {
+        self.context.resume().map_err(audio_error("could not resume the audio"))
+    }
+
+    pub fn set_voices(&mut self, voices: u32) {
+        self.voices = voices;
+    }
+
+    // From 0, silent, to 1.
+    pub fn set_volume(&self, volume: f32) {
+        self.output.gain().set_value(volume.clamp(0.0, 1.0));
+    }
+
+    // Sounds the universe's last tick.
+    pub fn play(&self, universe: &Universe) -> Result<(), Error> {
+        let universe = &universe.inner;
+        let now = self.context.current_time();
+        let stats = universe.tick_stats();
+        let cells = f64::from(universe.width()) * f64::from(universe.height());
+        let fullness = if cells > 0.0 { f64::from(stats.population()) / cells } else { 0.0 };
+        let cutoff = MIN_CUTOFF + (MAX_CUTOFF - MIN_CUTOFF) * fullness.sqrt();
+        self.filter
+            .frequency()
+            .set_target_at_time(cutoff as f32, now, 0.05)
+            .map_err(audio_error("could not move the filter"))?;
+
+        // The busiest columns get a note each, one per birth at most.
+        let columns = universe.changed_per_column();
+        let mut busiest: Vec<usize> = (0..columns.len()).filter(|&col| columns[col] > 0).collect();
+        busiest.sort_by_key(|&col| std::cmp::Reverse(columns[col]));
+        busiest.truncate(self.voices.min(stats.births()) as usize);
+        let level = 1.0 / busiest.len().max(1) as f32;
+        for col in busiest {
+            self.note(pitch(col, columns.len()), level, now)?;
+        }
+        Ok(())
+    }
+}
+
+impl Sonifier {
+    // A short plucked note through the filter.
+    fn note(&self, frequency: f64, level: f32, now: f64) -> Result<(), Error> {
+        let failed = audio_error("could not play a note");
+        let oscillator = self.context.create_oscillator().map_err(&failed)?;
+        oscillator.set_type(OscillatorType::Triangle);
+        oscillator.frequency().set_value(frequency as f32);
+        let envelope = self.context.create_gain().map_err(&failed)?;
+        let gain = envelope.gain();
+        gain.set_value_at_time(0.0, now).map_err(&failed)?;
+        gain.linear_ramp_to_value_at_time(level, now + 0.01).map_err(&failed)?;
+        gain.exponential_ramp_to_value_at_time(0.001, now + NOTE_LENGTH).map_err(&failed)?;
+        oscillator.connect_with_audio_node(&envelope).map_err(&failed)?;
+        envelope.connect_with_audio_node(&self.filter).map_err(&failed)?;
+        oscillator.start_with_when(now).map_err(&failed)?;
+        oscillator.stop_with_when(now + NOTE_LENGTH).map_err(&failed)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{life, Boundary, Engine, Error, Pattern, Universe};
+
+// Every setter hands the builder back, so calls chain:
+//
+//     const universe = new UniverseBuilder()
+//       .size(128, 96)
+//       .rule("B36/S23")
+//       .seed_random(0.3, 7n)
+//       .build();
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct UniverseBuilder {
+    inner: life::UniverseBuilder,
+}
+
+#[wasm_bindgen]
+impl UniverseBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> UniverseBuilder {
+        UniverseBuilder::default()
+    }
+
+    pub fn width(self, width: u32) -> UniverseBuilder {
+        UniverseBuilder { inner: self.inner.width(width) }
+    }
+
+    pub fn height(self, height: u32) -> UniverseBuilder {
+        UniverseBuilder { inner: self.inner.height(height) }
+    }
+
+    pub fn size(self, width: u32, height: u32) -> UniverseBuilder {
+        UniverseBuilder { inner: self.inner.size(width, height) }
+    }
+
+    pub fn rule(self, rule: &str) -> UniverseBuilder {
+        UniverseBuilder { inner: self.inner.rule(rule) }
+    }
+
+    pub fn boundary(self, boundary: Boundary) -> UniverseBuilder {
+        UniverseBuilder { inner: self.inner.boundary(boundary.into()) }
+    }
+
+    pub fn engine(self, engine: Engine) -> UniverseBuilder {
+        UniverseBuilder { inner: self.inner.engine(engine.into()) }
+    }
+
+    pub fn seed_empty(self) -> UniverseBuilder {
+        UniverseBuilder { inner: self.inner.seed(life::Seed::Empty) }
+    }
+
+    pub fn seed_tutorial(self) -> UniverseBuilder {
+        UniverseBuilder { inner: self.inner.seed(life::Seed::Tutorial) }
+    }
+
+    pub fn seed_random(self, density: f32, seed: u64) -> UniverseBuilder {
+        UniverseBuilder { inner: self.inner.seed(life::Seed::Random { density, seed }) }
+    }
+
+    pub fn seed_pattern(self, pattern: &Pattern) -> UniverseBuilder {
+        UniverseBuilder { inner: self.inner.seed(life::Seed::Pattern(pattern.inner().clone())) }
+    }
+
+    pub fn build(&self) -> Result<Universe, Error> {
+        Ok(self.inner.build()?.into())
+    }
+}
@@ -0,0 +1,127 @@
+use js_sys::Function;
+use wasm_bindgen::prelude::*;
+
+use crate::{life, options, Error};
+
+#[wasm_bindgen(typescript_custom_section)]
+const CENSUS_JSON: &str = r#"
+export interface CensusJSON {
+    soups: number;
+    unsettled: number;
+    objects: Record<string, number>;
+}
+"#;
+
+// How many of each object, by apgcode, the soups of a SoupSearch left.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Census {
+    inner: life::Census,
+}
+
+impl From<life::Census> for Census {
+    fn from(inner: life::Census) -> Census {
+        Census { inner }
+    }
+}
+
+#[wasm_bindgen]
+impl Census {
+    pub fn soups(&self) -> u32 {
+        self.inner.soups
+    }
+
+    pub fn unsettled(&self) -> u32 {
+        self.inner.unsettled
+    }
+
+    // How many times `code`, e.g. "xs4_33" for blocks, turned up.
+    pub fn count(&self, code: &str) -> f64 {
+        self.inner.count(code) as f64
+    }
+
+    // The apgcodes from the most common down.
+    pub fn codes(&self) -> Vec<String> {
+        self.inner.by_count().into_iter().map(|(code, _)| code.to_string()).collect()
+    }
+
+    #[wasm_bindgen(js_name = toJSON, unchecked_return_type = "CensusJSON")]
+    pub fn to_json(&self) -> JsValue {
+        options::to_js(&self.inner)
+    }
+}
+
+// Runs a batch of soups and takes a census of what they settle into:
+//
+//     const search = new SoupSearch("B3/S23", "C1", 1000, 7n);
+//     search.set_on_progress((done, total) => bar.value = done / total);
+//     while (!search.is_finished()) {
+//         search.run(10);
+//         await new Promise(requestAnimationFrame);
+//     }
+//     console.log(search.census().toJSON());
+#[wasm_bindgen]
+pub struct SoupSearch {
+    inner: life::SoupSearch,
+    on_progress: Option<Function>,
+}
+
+#[wasm_bindgen]
+impl SoupSearch {
+    // `symmetry` is one of apgsearch's, "C1" for plain 16x16 soups.
+    #[wasm_bindgen(constructor)]
+    pub fn new(rule: &str, symmetry: &str, soups: u32, seed: u64) -> Result<SoupSearch, Error> {
+        Ok(SoupSearch {
+            inner: life::SoupSearch::new(rule, symmetry.parse()?, soups, seed)?,
+            on_progress: None,
+        })
+    }
+
+    pub fn set_size(&mut self, size: u32) -> Result<(), Error> {
+        Ok(self.inner.set_size(size)?)
+    }
+
+    pub fn set_max_generations(&mut self, max_generations: u32) {
+        self.inner.set_max_generations(max_generations);
+    }
+
+    // Called with the soups done so far and the total after each soup.
+    pub fn set_on_progress(&mut self, callback: Option<Function>) {
+        self.on_progress = callback;
+    }
+
+    pub fn done(&self) -> u32 {
+        self.inner.done()
+    }
+
+    pub fn total(&self) -> u32 {
+        self.inner.total()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+
+    // Runs up to `count` more soups and returns how many ran, so that a
+    // long search can hand the page back now and then.
+    pub fn run(&mut self, count: u32) -> Result<u32, Error> {
+        let mut ran = 0;
+        while ran < count && !self.inner.is_finished() {
+            ran += self.inner.run(1)?;
+            if let Some(callback) = &self.on_progress {
+                let _ = callback.call2(&JsValue::NULL, &self.inner.done().into(), &self.inner.total().into());
+            }
+        }
+        Ok(ran)
+    }
+
+    // Every soup that is left, in one go.
+    pub fn run_all(&mut self) -> Result<Census, Error> {
+        self.run(u32::MAX)?;
+        Ok(self.census())
+    }
+
+    pub fn census(&self) -> Census {
+        self.inner.census().clone().into()
+    }
+}
@@ -0,0 +1,44 @@
+use js_sys::Promise;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+use web_sys::Clipboard;
+
+use crate::{Error, Pattern, Universe};
+
+fn clipboard() -> Result<Clipboard, Error> {
+    let window = web_sys::window().ok_or_else(|| Error::new("no window to reach the clipboard from"))?;
+    Ok(window.navigator().clipboard())
+}
+
+fn clipboard_error(what: &str) -> impl Fn(JsValue) -> Error + '_ {
+    move |e| Error::new(&format!("{}: {:?}", what, e))
+}
+
+#[wasm_bindgen]
+impl Universe {
+    // Puts the selection, or the whole universe when nothing is
+    // selected, on the clipboard as RLE, which Golly and LifeViewer
+    // paste as well. The text is taken right away.
+    pub fn copy_selection_to_clipboard(&self) -> Promise {
+        let rle = self.inner.selection_to_rle();
+        future_to_promise(async move {
+            let written = clipboard()?.write_text(&rle);
+            JsFuture::from(written).await.map_err(clipboard_error("could not copy to the clipboard"))?;
+            Ok(JsValue::UNDEFINED)
+        })
+    }
+}
+
+// Reads whatever pattern is on the clipboard, in any format Pattern.parse
+// knows. A universe can't be held on to while the clipboard is read, so
+// pasting at (row, col) takes two steps:
+//
+//     universe.stamp(await pattern_from_clipboard(), row, col);
+#[wasm_bindgen]
+pub async fn pattern_from_clipboard() -> Result<Pattern, Error> {
+    let text = JsFuture::from(clipboard()?.read_text())
+        .await
+        .map_err(clipboard_error("could not read the clipboard"))?;
+    let text = text.as_string().ok_or_else(|| Error::new("the clipboard holds no text"))?;
+    Pattern::parse(&text)
+}
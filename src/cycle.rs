@@ -0,0 +1,99 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{life, options};
+
+mirror_enum! {
+    pub enum StopReason {
+        DiedOut,
+        Cycle,
+        GenerationCap,
+    }
+}
+
+// What toJSON() gives for a Cycle and a StabilizationReport. The
+// reason is the StopReason's name rather than its number.
+#[wasm_bindgen(typescript_custom_section)]
+const CYCLE_JSON: &str = r#"
+export interface CycleJSON {
+    period: number;
+    since: number;
+}
+
+export interface StabilizationReportJSON {
+    reason: "DiedOut" | "Cycle" | "GenerationCap";
+    generation: number;
+    ticks: number;
+    cycle: CycleJSON | null;
+}
+"#;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    inner: life::Cycle,
+}
+
+impl From<life::Cycle> for Cycle {
+    fn from(inner: life::Cycle) -> Cycle {
+        Cycle { inner }
+    }
+}
+
+#[wasm_bindgen]
+impl Cycle {
+    pub fn period(&self) -> u32 {
+        self.inner.period()
+    }
+
+    pub fn since(&self) -> u64 {
+        self.inner.since()
+    }
+
+    #[wasm_bindgen(js_name = toJSON, unchecked_return_type = "CycleJSON")]
+    pub fn to_json(&self) -> JsValue {
+        options::to_js(&self.inner)
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StabilizationReport {
+    inner: life::StabilizationReport,
+}
+
+impl From<life::StabilizationReport> for StabilizationReport {
+    fn from(inner: life::StabilizationReport) -> StabilizationReport {
+        StabilizationReport { inner }
+    }
+}
+
+#[wasm_bindgen]
+impl StabilizationReport {
+    pub fn reason(&self) -> StopReason {
+        self.inner.reason().into()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    pub fn ticks(&self) -> u32 {
+        self.inner.ticks()
+    }
+
+    // Comes out as undefined unless the reason is Cycle.
+    pub fn period(&self) -> Option<u32> {
+        self.inner.period()
+    }
+
+    pub fn since(&self) -> Option<u64> {
+        self.inner.since()
+    }
+
+    // { reason, generation, ticks, cycle }, the reason by name and the
+    // cycle null unless there is one.
+    #[wasm_bindgen(js_name = toJSON, unchecked_return_type = "StabilizationReportJSON")]
+    pub fn to_json(&self) -> JsValue {
+        options::to_js(&self.inner)
+    }
+}
@@ -0,0 +1,86 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{life, Error, NeighborhoodShape};
+
+#[wasm_bindgen]
+pub struct CyclicUniverse {
+    inner: life::CyclicUniverse,
+}
+
+impl CyclicUniverse {
+    pub(crate) fn inner(&self) -> &life::CyclicUniverse {
+        &self.inner
+    }
+}
+
+#[wasm_bindgen]
+impl CyclicUniverse {
+    // All cells start in state 0; randomize() to get things going.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u32, height: u32, states: u8, threshold: u32) -> Result<CyclicUniverse, Error> {
+        Ok(CyclicUniverse {
+            inner: life::CyclicUniverse::new(width, height, states, threshold)?,
+        })
+    }
+
+    pub fn states(&self) -> u8 {
+        self.inner.states()
+    }
+
+    pub fn threshold(&self) -> u32 {
+        self.inner.threshold()
+    }
+
+    pub fn set_threshold(&mut self, threshold: u32) -> Result<(), Error> {
+        Ok(self.inner.set_threshold(threshold)?)
+    }
+
+    pub fn neighborhood_shape(&self) -> NeighborhoodShape {
+        self.inner.neighborhood().shape().into()
+    }
+
+    pub fn neighborhood_radius(&self) -> u32 {
+        self.inner.neighborhood().radius()
+    }
+
+    pub fn set_neighborhood(&mut self, shape: NeighborhoodShape, radius: u32) -> Result<(), Error> {
+        Ok(self.inner.set_neighborhood(life::Neighborhood::new(shape.into(), radius)?)?)
+    }
+
+    pub fn tick(&mut self) {
+        self.inner.tick();
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        self.inner.tick_many(n);
+    }
+
+    pub fn width(&self) -> u32 {
+        self.inner.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.inner.height()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    // width * height u8 states for a Uint8Array.
+    pub fn cells(&self) -> *const u8 {
+        self.inner.cells().as_ptr()
+    }
+
+    pub fn get_state(&self, row: u32, col: u32) -> Result<u8, Error> {
+        Ok(self.inner.get_state(row, col)?)
+    }
+
+    pub fn set_state(&mut self, row: u32, col: u32, state: u8) -> Result<(), Error> {
+        Ok(self.inner.set_state(row, col, state)?)
+    }
+
+    pub fn randomize(&mut self, seed: u64) {
+        self.inner.randomize(seed);
+    }
+}
@@ -0,0 +1,84 @@
+use std::cell::RefCell;
+use std::panic::Location;
+
+use js_sys::{Function, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::life;
+
+thread_local! {
+    static ERROR_CALLBACK: RefCell<Option<Function>> = RefCell::new(None);
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const ERROR_REPORT: &str = r#"
+export interface ErrorReport {
+    message: string;
+    location: string | undefined;
+    panic: boolean;
+}
+"#;
+
+// Calls `callback` with { message, location, panic } for every error
+// thrown to JavaScript and for every panic, which otherwise only shows
+// up as "unreachable executed". The location is the line of Rust the
+// error came from. Pass undefined to stop.
+#[wasm_bindgen]
+pub fn set_error_callback(
+    #[wasm_bindgen(unchecked_param_type = "((report: ErrorReport) => void) | undefined")] callback: Option<Function>,
+) {
+    ERROR_CALLBACK.with(|current| *current.borrow_mut() = callback);
+}
+
+pub fn report(message: &str, location: Option<&Location>, panic: bool) {
+    ERROR_CALLBACK.with(|callback| {
+        // A callback that panics, or throws, must not call itself.
+        let callback = match callback.try_borrow() {
+            Ok(callback) => callback,
+            Err(_) => return,
+        };
+        if let Some(callback) = callback.as_ref() {
+            let report = Object::new();
+            let location = location.map(|location| location.to_string());
+            let _ = Reflect::set(&report, &"message".into(), &message.into());
+            let _ = Reflect::set(&report, &"location".into(), &location.into());
+            let _ = Reflect::set(&report, &"panic".into(), &panic.into());
+            let _ = callback.call1(&JsValue::NULL, &report);
+        }
+    });
+}
+
+// What the exported functions throw: a JavaScript Error, like JsError,
+// that the error callback also hears about on its way out. Errors from
+// the engine turn into one with `?`, which tells the callback the line
+// of the `?`.
+#[derive(Debug)]
+pub struct Error(JsError);
+
+impl Error {
+    #[track_caller]
+    pub fn new(message: &str) -> Error {
+        report(message, Some(Location::caller()), false);
+        Error(JsError::new(message))
+    }
+}
+
+impl From<Error> for JsValue {
+    fn from(error: Error) -> JsValue {
+        error.0.into()
+    }
+}
+
+impl From<life::Error> for Error {
+    #[track_caller]
+    fn from(error: life::Error) -> Error {
+        Error::new(&error.to_string())
+    }
+}
+
+impl From<life::ParseRuleError> for Error {
+    #[track_caller]
+    fn from(error: life::ParseRuleError) -> Error {
+        Error::new(&error.to_string())
+    }
+}
@@ -0,0 +1,38 @@
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Response;
+
+use crate::{Error, Pattern};
+
+fn fetch_error(url: &str) -> impl Fn(JsValue) -> Error + '_ {
+    move |e| Error::new(&format!("could not fetch {}: {:?}", url, e))
+}
+
+// Downloads a pattern file and reads it like a dropped one, so it can be
+// in any format Pattern.from_file knows, gzipped or not:
+//
+//     const glider = await load_pattern_from_url("https://conwaylife.com/patterns/glider.rle");
+//
+// The server has to allow the page's origin, as with any fetch().
+#[wasm_bindgen]
+pub async fn load_pattern_from_url(url: String) -> Result<Pattern, Error> {
+    let window = web_sys::window().ok_or_else(|| Error::new("no window to fetch patterns from"))?;
+    let response: Response = JsFuture::from(window.fetch_with_str(&url))
+        .await
+        .map_err(fetch_error(&url))?
+        .unchecked_into();
+    if !response.ok() {
+        return Err(Error::new(&format!(
+            "could not fetch {}: {} {}",
+            url,
+            response.status(),
+            response.status_text()
+        )));
+    }
+    let buffer = JsFuture::from(response.array_buffer().map_err(fetch_error(&url))?)
+        .await
+        .map_err(fetch_error(&url))?;
+    Pattern::from_file(&Uint8Array::new(&buffer).to_vec())
+}
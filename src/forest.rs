@@ -0,0 +1,100 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{life, Error, NeighborhoodShape, TickStats};
+
+#[wasm_bindgen]
+pub struct ForestFire {
+    inner: life::ForestFire,
+}
+
+impl ForestFire {
+    pub(crate) fn inner(&self) -> &life::ForestFire {
+        &self.inner
+    }
+}
+
+#[wasm_bindgen]
+impl ForestFire {
+    // An empty forest. Growth and lightning start at 0.01 and 0.00001.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u32, height: u32, seed: u64) -> Result<ForestFire, Error> {
+        Ok(ForestFire {
+            inner: life::ForestFire::new(width, height, seed)?,
+        })
+    }
+
+    pub fn growth(&self) -> f32 {
+        self.inner.growth()
+    }
+
+    pub fn lightning(&self) -> f32 {
+        self.inner.lightning()
+    }
+
+    pub fn set_probabilities(&mut self, growth: f32, lightning: f32) -> Result<(), Error> {
+        Ok(self.inner.set_probabilities(growth, lightning)?)
+    }
+
+    pub fn neighborhood_shape(&self) -> NeighborhoodShape {
+        self.inner.neighborhood_shape().into()
+    }
+
+    pub fn set_neighborhood_shape(&mut self, shape: NeighborhoodShape) {
+        self.inner.set_neighborhood_shape(shape.into());
+    }
+
+    pub fn tick(&mut self) {
+        self.inner.tick();
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        self.inner.tick_many(n);
+    }
+
+    pub fn width(&self) -> u32 {
+        self.inner.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.inner.height()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    // The number of trees.
+    pub fn population(&self) -> u32 {
+        self.inner.population()
+    }
+
+    pub fn fires(&self) -> u32 {
+        self.inner.fires()
+    }
+
+    // Trees grown and burnt by the last tick, as births and deaths.
+    pub fn tick_stats(&self) -> TickStats {
+        self.inner.tick_stats().into()
+    }
+
+    pub fn population_history(&self, n: u32) -> Vec<u32> {
+        self.inner.population_history(n)
+    }
+
+    // width * height u8 cells for a Uint8Array: 0 empty, 1 tree, 2 fire.
+    pub fn cells(&self) -> *const u8 {
+        self.inner.cells().as_ptr()
+    }
+
+    pub fn get_state(&self, row: u32, col: u32) -> Result<u8, Error> {
+        Ok(self.inner.get_state(row, col)?)
+    }
+
+    pub fn set_state(&mut self, row: u32, col: u32, state: u8) -> Result<(), Error> {
+        Ok(self.inner.set_state(row, col, state)?)
+    }
+
+    pub fn plant(&mut self, density: f32) -> Result<(), Error> {
+        Ok(self.inner.plant(density)?)
+    }
+}
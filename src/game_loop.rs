@@ -0,0 +1,185 @@
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use js_sys::Function;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::{Error, MemoryReport, PerfStats, Renderer, Universe};
+
+// The requestAnimationFrame loop every page used to write for itself:
+// each frame ticks the universe, draws it and calls back into
+// JavaScript, e.g. to show the generation.
+//
+//     const gameLoop = new GameLoop(universe, renderer);
+//     gameLoop.set_on_frame(() => drawStats());
+//     gameLoop.start();
+//
+// The loop owns the universe from then on; into_universe() hands it
+// back.
+#[wasm_bindgen]
+pub struct GameLoop {
+    state: Rc<RefCell<State>>,
+    frames: Rc<RefCell<Frames>>,
+}
+
+struct State {
+    universe: Universe,
+    renderer: Renderer,
+    ticks_per_frame: u32,
+    running: bool,
+    on_frame: Option<Function>,
+}
+
+// The closure requestAnimationFrame calls and the frame it was asked
+// for, if any. A frame still pending when the loop goes away is
+// cancelled, or it would call a closure that is gone.
+struct Frames {
+    callback: Option<Closure<dyn FnMut()>>,
+    pending: Option<i32>,
+}
+
+impl Frames {
+    fn request(&mut self) -> Result<(), JsValue> {
+        if let (None, Some(callback)) = (self.pending, &self.callback) {
+            let window = web_sys::window().ok_or("no window to animate in")?;
+            self.pending = Some(window.request_animation_frame(callback.as_ref().unchecked_ref())?);
+        }
+        Ok(())
+    }
+
+    fn cancel(&mut self) {
+        if let (Some(frame), Some(window)) = (self.pending.take(), web_sys::window()) {
+            let _ = window.cancel_animation_frame(frame);
+        }
+    }
+}
+
+impl Drop for Frames {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+// One frame. The callback runs after the state is let go of, so it may
+// call back into the loop, even to stop it or take the universe back.
+// `frames`, on the other hand, is held on to until the end, so that this
+// closure isn't dropped while it runs.
+fn frame(weak_state: &Weak<RefCell<State>>, frames: &Weak<RefCell<Frames>>) {
+    let (Some(state), Some(frames)) = (weak_state.upgrade(), frames.upgrade()) else {
+        return;
+    };
+    frames.borrow_mut().pending = None;
+    let on_frame = {
+        let mut state = state.borrow_mut();
+        let State {
+            universe,
+            renderer,
+            ticks_per_frame,
+            ..
+        } = &mut *state;
+        universe.tick_many(*ticks_per_frame);
+        renderer.draw(universe);
+        state.on_frame.clone()
+    };
+    drop(state);
+    if let Some(on_frame) = on_frame {
+        let _ = on_frame.call0(&JsValue::NULL);
+    }
+    if weak_state.upgrade().is_some_and(|state| state.borrow().running) {
+        let _ = frames.borrow_mut().request();
+    }
+}
+
+#[wasm_bindgen]
+impl GameLoop {
+    // Draws the universe right away, so the first frame on screen is
+    // generation 0 and not the one after it.
+    #[wasm_bindgen(constructor)]
+    pub fn new(universe: Universe, mut renderer: Renderer) -> GameLoop {
+        renderer.draw(&universe);
+        let state = Rc::new(RefCell::new(State {
+            universe,
+            renderer,
+            ticks_per_frame: 1,
+            running: false,
+            on_frame: None,
+        }));
+        let frames = Rc::new(RefCell::new(Frames {
+            callback: None,
+            pending: None,
+        }));
+        let (weak_state, weak_frames) = (Rc::downgrade(&state), Rc::downgrade(&frames));
+        frames.borrow_mut().callback = Some(Closure::new(move || frame(&weak_state, &weak_frames)));
+        GameLoop { state, frames }
+    }
+
+    pub fn start(&mut self) -> Result<(), Error> {
+        self.state.borrow_mut().running = true;
+        self.frames
+            .borrow_mut()
+            .request()
+            .map_err(|e| Error::new(&format!("could not start the game loop: {:?}", e)))
+    }
+
+    pub fn stop(&mut self) {
+        self.state.borrow_mut().running = false;
+        self.frames.borrow_mut().cancel();
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state.borrow().running
+    }
+
+    // How many generations each frame advances, 0 to only redraw.
+    pub fn set_ticks_per_frame(&mut self, n: u32) {
+        self.state.borrow_mut().ticks_per_frame = n;
+    }
+
+    pub fn ticks_per_frame(&self) -> u32 {
+        self.state.borrow().ticks_per_frame
+    }
+
+    // Called with no arguments after every frame is drawn.
+    pub fn set_on_frame(&mut self, callback: Option<Function>) {
+        self.state.borrow_mut().on_frame = callback;
+    }
+
+    // Flips a cell and redraws, for clicks on the canvas.
+    pub fn toggle_cell(&mut self, row: u32, col: u32) -> Result<(), Error> {
+        let mut state = self.state.borrow_mut();
+        let State { universe, renderer, .. } = &mut *state;
+        universe.toggle_cell(row, col)?;
+        renderer.draw(universe);
+        Ok(())
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.state.borrow().universe.generation()
+    }
+
+    pub fn population(&self) -> u32 {
+        self.state.borrow().universe.population()
+    }
+
+    pub fn perf_stats(&self) -> PerfStats {
+        self.state.borrow().universe.perf_stats()
+    }
+
+    pub fn memory_report(&self) -> MemoryReport {
+        self.state.borrow().universe.memory_report()
+    }
+
+    pub fn to_share_string(&self) -> String {
+        self.state.borrow().universe.to_share_string()
+    }
+
+    // Stops the loop and gives the universe back.
+    pub fn into_universe(mut self) -> Universe {
+        self.stop();
+        match Rc::try_unwrap(self.state) {
+            Ok(state) => state.into_inner().universe,
+            Err(_) => unreachable!("only the loop holds on to its state"),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{life, Cell, Error};
+
+#[wasm_bindgen]
+pub struct InfiniteUniverse {
+    inner: life::InfiniteUniverse,
+}
+
+#[wasm_bindgen]
+impl InfiniteUniverse {
+    pub fn new() -> InfiniteUniverse {
+        InfiniteUniverse {
+            inner: life::InfiniteUniverse::new(),
+        }
+    }
+
+    pub fn with_rule(rule: &str) -> Result<InfiniteUniverse, Error> {
+        Ok(InfiniteUniverse {
+            inner: life::InfiniteUniverse::with_rule(rule)?,
+        })
+    }
+
+    pub fn rule(&self) -> String {
+        self.inner.rule()
+    }
+
+    pub fn tick(&mut self) {
+        self.inner.tick();
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        self.inner.tick_many(n);
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    pub fn population(&self) -> u32 {
+        self.inner.population()
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.inner.chunk_count()
+    }
+
+    pub fn get_cell(&self, x: i32, y: i32) -> Cell {
+        self.inner.get_cell(x, y).into()
+    }
+
+    pub fn set_cell(&mut self, x: i32, y: i32, cell: Cell) {
+        self.inner.set_cell(x, y, cell.into());
+    }
+
+    pub fn toggle_cell(&mut self, x: i32, y: i32) {
+        self.inner.toggle_cell(x, y);
+    }
+
+    pub fn insert_rle(&mut self, rle: &str, x: i32, y: i32) -> Result<(), Error> {
+        Ok(self.inner.insert_rle(rle, x, y)?)
+    }
+
+    // [min_x, min_y, max_x, max_y], or an empty array when nothing is alive.
+    pub fn bounds(&self) -> Vec<i32> {
+        self.inner.bounds()
+    }
+
+    pub fn window_bits(&self, x: i32, y: i32, width: u32, height: u32) -> Result<Vec<u32>, Error> {
+        Ok(self.inner.window_bits(x, y, width, height)?)
+    }
+}
+
+impl Default for InfiniteUniverse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
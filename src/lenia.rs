@@ -0,0 +1,103 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{life, Error};
+use life::ContinuousEngine;
+
+#[wasm_bindgen]
+pub struct Lenia {
+    inner: life::Lenia,
+}
+
+impl Lenia {
+    pub(crate) fn inner(&self) -> &life::Lenia {
+        &self.inner
+    }
+}
+
+impl From<life::Lenia> for Lenia {
+    fn from(inner: life::Lenia) -> Lenia {
+        Lenia { inner }
+    }
+}
+
+#[wasm_bindgen]
+impl Lenia {
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u32, height: u32) -> Result<Lenia, Error> {
+        Ok(life::Lenia::new(width, height)?.into())
+    }
+
+    pub fn random(width: u32, height: u32, size: u32, seed: u64) -> Result<Lenia, Error> {
+        Ok(life::Lenia::random(width, height, size, seed)?.into())
+    }
+
+    pub fn tick(&mut self) {
+        self.inner.tick();
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        self.inner.tick_many(n);
+    }
+
+    pub fn width(&self) -> u32 {
+        self.inner.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.inner.height()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    // A pointer to width * height f32 values, row by row, for a
+    // Float32Array over the wasm memory.
+    pub fn cells(&self) -> *const f32 {
+        self.inner.cells().as_ptr()
+    }
+
+    pub fn mass(&self) -> f32 {
+        self.inner.mass()
+    }
+
+    pub fn get_cell(&self, row: u32, col: u32) -> Result<f32, Error> {
+        Ok(self.inner.get_cell(row, col)?)
+    }
+
+    pub fn set_cell(&mut self, row: u32, col: u32, value: f32) -> Result<(), Error> {
+        Ok(self.inner.set_cell(row, col, value)?)
+    }
+
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    pub fn radius(&self) -> u32 {
+        self.inner.radius()
+    }
+
+    pub fn set_radius(&mut self, radius: u32) -> Result<(), Error> {
+        Ok(self.inner.set_radius(radius)?)
+    }
+
+    pub fn mu(&self) -> f32 {
+        self.inner.mu()
+    }
+
+    pub fn sigma(&self) -> f32 {
+        self.inner.sigma()
+    }
+
+    pub fn set_growth(&mut self, mu: f32, sigma: f32) -> Result<(), Error> {
+        Ok(self.inner.set_growth(mu, sigma)?)
+    }
+
+    pub fn dt(&self) -> f32 {
+        self.inner.dt()
+    }
+
+    pub fn set_dt(&mut self, dt: f32) -> Result<(), Error> {
+        Ok(self.inner.set_dt(dt)?)
+    }
+}
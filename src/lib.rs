@@ -1,244 +1,956 @@
+// The JavaScript face of game-of-life-core. Everything that makes up the
+// Game of Life lives in that crate, in plain Rust; this one gives each
+// of its types a #[wasm_bindgen] twin that forwards to it, turns errors
+// into JavaScript Errors and hands out raw pointers into wasm memory
+// where JavaScript wants to read the cells without copying them.
+
+// wasm_bindgen can't be put on another crate's enums, so each one is
+// declared again here, variant for variant, with conversions both ways.
+macro_rules! mirror_enum {
+    ($(#[$attr:meta])* pub enum $name:ident { $($variant:ident $(= $value:expr)?,)* }) => {
+        $(#[$attr])*
+        #[wasm_bindgen]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum $name {
+            $($variant $(= $value)?,)*
+        }
+
+        impl From<$name> for $crate::life::$name {
+            fn from(value: $name) -> $crate::life::$name {
+                match value {
+                    $($name::$variant => $crate::life::$name::$variant,)*
+                }
+            }
+        }
+
+        impl From<$crate::life::$name> for $name {
+            fn from(value: $crate::life::$name) -> $name {
+                match value {
+                    $($crate::life::$name::$variant => $name::$variant,)*
+                }
+            }
+        }
+    };
+}
+
+#[cfg(feature = "audio")]
+mod audio;
+mod builder;
+mod census;
+#[cfg(feature = "clipboard")]
+mod clipboard;
+mod cycle;
+mod cyclic;
+mod error;
+#[cfg(feature = "fetch")]
+mod fetch;
+mod forest;
+mod game_loop;
+mod infinite;
+mod lenia;
+#[cfg(feature = "console_log")]
+mod logging;
+mod margolus;
+mod memory;
+mod methuselah;
+mod objects;
+mod options;
+mod patterns;
+mod perf;
+mod player;
+mod render;
+mod smoothlife;
+mod svg;
+mod stats;
+#[cfg(feature = "storage")]
+mod storage;
+mod text;
+mod triangular;
+mod universe1d;
+mod universe3d;
 mod utils;
+mod views;
+#[cfg(feature = "webgl")]
+mod webgl;
+#[cfg(feature = "webgpu")]
+mod webgpu;
+
+use std::fmt;
 
+use game_of_life_core as life;
 use wasm_bindgen::prelude::*;
-use js_sys;
-use fixedbitset::FixedBitSet;
+use utils::Timer;
+
+#[cfg(feature = "audio")]
+pub use audio::Sonifier;
+pub use builder::UniverseBuilder;
+pub use census::{Census, SoupSearch};
+#[cfg(feature = "clipboard")]
+pub use clipboard::pattern_from_clipboard;
+pub use cycle::{Cycle, StabilizationReport, StopReason};
+pub use cyclic::CyclicUniverse;
+pub use error::{set_error_callback, Error};
+#[cfg(feature = "fetch")]
+pub use fetch::load_pattern_from_url;
+pub use forest::ForestFire;
+pub use game_loop::GameLoop;
+pub use infinite::InfiniteUniverse;
+pub use lenia::Lenia;
+#[cfg(feature = "console_log")]
+pub use logging::init_logging;
+pub use margolus::{MargolusRule, MargolusUniverse};
+pub use memory::{wasm_memory_bytes, MemoryReport};
+pub use methuselah::{Candidate, MethuselahSearch};
+pub use life::{LifeRule, ParseRleError, ParseRuleError};
+pub use objects::{object_name, Heading, ObjectInfo, ObjectKind};
+pub use patterns::{list_patterns, Pattern, PatternInfo};
+pub use perf::PerfStats;
+pub use player::Player;
+pub use render::Renderer;
+pub use smoothlife::SmoothLife;
+pub use stats::TickStats;
+#[cfg(feature = "storage")]
+pub use storage::{delete_saved_universe, list_saved_universes, load_universe, save_universe, SavedUniverse};
+pub use svg::SvgOptions;
+pub use text::TextOptions;
+pub use triangular::{TriangularNeighborhood, TriangularUniverse};
+pub use universe1d::Universe1D;
+pub use universe3d::{Axis, Universe3D};
+pub use utils::set_panic_hook;
+pub use views::set_memory_grow_callback;
+#[cfg(all(feature = "parallel", target_arch = "wasm32"))]
+pub use wasm_bindgen_rayon::init_thread_pool;
+#[cfg(feature = "webgl")]
+pub use webgl::WebGlRenderer;
+#[cfg(feature = "webgpu")]
+pub use webgpu::GpuUniverse;
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
-// allocator.
+// allocator. Either way, allocations are counted for perf_stats().
 #[cfg(feature = "wee_alloc")]
 #[global_allocator]
-static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
-
-// ======================================================================
-// We have several ways of exposing the universe's cells to JavaScript. 
-// To begin, we will implement std::fmt::Display for Universe, 
-// which we can use to generate a Rust String of the cells rendered as text 
-// characters. This Rust String is then copied from the WebAssembly linear 
-// memory into a JavaScript String in the JavaScript's garbage-collected heap, 
-// and is then displayed by setting HTML textContent
-//
-
-// It is important that we have #[repr(u8)], 
-// so that each cell is represented as a single byte. 
-// It is also important that the Dead variant is 0 
-// and that the Alive variant is 1, 
-// so that we can easily count a cell's live neighbors with addition.
+static ALLOC: life::CountingAllocator<wee_alloc::WeeAlloc> = life::CountingAllocator(wee_alloc::WeeAlloc::INIT);
+
+#[cfg(not(feature = "wee_alloc"))]
+#[global_allocator]
+static ALLOC: life::CountingAllocator<std::alloc::System> = life::CountingAllocator(std::alloc::System);
+
+// Runs when the module is instantiated, so that panics are reported
+// from the start, and the very first tick is timed already.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(start)]
+fn start() {
+    set_panic_hook();
+    life::set_perf_clock(perf::now);
+}
+
+mirror_enum! {
+    #[repr(u8)]
+    pub enum Cell {
+        Dead = 0,
+        Alive = 1,
+    }
+}
+
+mirror_enum! {
+    pub enum Anchor {
+        TopLeft,
+        Top,
+        TopRight,
+        Left,
+        Center,
+        Right,
+        BottomLeft,
+        Bottom,
+        BottomRight,
+    }
+}
+
+mirror_enum! {
+    pub enum Boundary {
+        Torus,
+        Dead,
+        Mirror,
+        AliveWall,
+    }
+}
+
+mirror_enum! {
+    pub enum NeighborhoodShape {
+        Moore,
+        VonNeumann,
+    }
+}
+
+mirror_enum! {
+    pub enum Engine {
+        Naive,
+        Hashlife,
+        BitParallel,
+    }
+}
+
+mirror_enum! {
+    pub enum Direction {
+        North,
+        East,
+        South,
+        West,
+    }
+}
+
+mirror_enum! {
+    pub enum Coloring {
+        Plain,
+        Age,
+        Activity,
+    }
+}
+
+mirror_enum! {
+    pub enum PlayerState {
+        Paused,
+        Playing,
+    }
+}
+
+mirror_enum! {
+    pub enum StatsFormat {
+        Csv,
+        Json,
+    }
+}
+
+mirror_enum! {
+    pub enum PatternFormat {
+        Rle,
+        Plaintext,
+        Life105,
+        Life106,
+    }
+}
+
+mirror_enum! {
+    pub enum Preset {
+        Life,
+        BriansBrain,
+    }
+}
+
+mirror_enum! {
+    pub enum Species {
+        Single,
+        Immigration,
+        QuadLife,
+    }
+}
+
+// Lets JavaScript check which build it ended up loading; see the `simd` feature.
 #[wasm_bindgen]
-#[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum Cell {
-    Dead = 0,
-    Alive = 1,
+pub fn simd_enabled() -> bool {
+    life::simd_enabled()
+}
+
+// A JavaScript function as a rule, for Universe::set_custom_rule().
+struct FunctionRule {
+    function: js_sys::Function,
+}
+
+impl life::Rule for FunctionRule {
+    fn next(&self, cell: life::Cell, neighbors: life::Neighbors) -> life::Cell {
+        if self.next_state(cell as u8, neighbors) == 1 {
+            life::Cell::Alive
+        } else {
+            life::Cell::Dead
+        }
+    }
+
+    fn next_state(&self, state: u8, neighbors: life::Neighbors) -> u8 {
+        let (count, bits) = (neighbors.count(), neighbors.bits());
+        self.function
+            .call3(&JsValue::NULL, &state.into(), &count.into(), &bits.into())
+            .ok()
+            .and_then(|next| next.as_f64().or_else(|| next.as_bool().map(f64::from)))
+            .map_or(0, |next| next as u8)
+    }
 }
 
-// Next, let's define the universe. 
-// The universe has a width and a height, 
-// In Rust, you can use the fixedbitset crate and its FixedBitSet type 
-// to represent cells instead of Vec<Cell>
 #[wasm_bindgen]
 pub struct Universe {
-    width: u32,
-    height: u32,
-    //cells: Vec<Cell>,
-    cells: FixedBitSet,
+    inner: life::Universe,
+}
+
+impl Universe {
+    // The core universe, for the renderers.
+    pub(crate) fn inner(&self) -> &life::Universe {
+        &self.inner
+    }
 }
 
+impl From<life::Universe> for Universe {
+    fn from(inner: life::Universe) -> Universe {
+        Universe { inner }
+    }
+}
 
-// To access the cell at a given row and column, 
-// we translate the row and column into an index 
-// into the cells vector, 
+#[wasm_bindgen]
 impl Universe {
-    fn get_index(&self, row: u32, column: u32) -> usize {
-        (row * self.width + column) as usize
-    }
-
-    // In order to calculate the next state of a cell, 
-    // we need to get a count of how many of its neighbors are alive.
-    //
-    // The live_neighbor_count method uses deltas and modulo to avoid special 
-    // casing the edges of the universe with ifs. When applying a delta of -1, 
-    // we add self.height - 1 and let the modulo do its thing, rather than 
-    // attempting to subtract 1. row and column can be 0, and if we attempted 
-    // to subtract 1 from them, there would be an unsigned integer underflow.
-    fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
-        let mut count = 0;
-        for delta_row in [self.height - 1, 0, 1].iter().cloned() {
-            for delta_col in [self.width - 1, 0, 1].iter().cloned() {
-                if delta_row == 0 && delta_col == 0 {
-                    continue;
-                }
+    // A 64x64 soup, different on every page load.
+    pub fn new() -> Universe {
+        let seed = (js_sys::Math::random() * 2f64.powi(53)) as u64;
+        life::Universe::random(64, 64, 0.5, seed)
+            .expect("64x64 at half density is a valid soup")
+            .into()
+    }
 
-                let neighbor_row = (row + delta_row) % self.height;
-                let neighbor_col = (column + delta_col) % self.width;
-                let idx = self.get_index(neighbor_row, neighbor_col);
-                count += self.cells[idx] as u8;
-            }
-        }
-        count
+    pub fn with_rule(width: u32, height: u32, rule: &str) -> Result<Universe, Error> {
+        Ok(life::Universe::with_rule(width, height, rule)?.into())
     }
 
-}//^-- impl Universe
+    pub fn random(width: u32, height: u32, density: f32, seed: u64) -> Result<Universe, Error> {
+        Ok(life::Universe::random(width, height, density, seed)?.into())
+    }
 
-// Now we have everything we need to compute the next generation from 
-// the current one! 
+    // A random start picked for the automaton, e.g. Preset.BriansBrain.
+    pub fn from_preset(width: u32, height: u32, preset: Preset, seed: u64) -> Result<Universe, Error> {
+        Ok(life::Universe::from_preset(width, height, preset.into(), seed)?.into())
+    }
 
+    pub fn with_engine(width: u32, height: u32, engine: Engine) -> Result<Universe, Error> {
+        Ok(life::Universe::with_engine(width, height, engine.into())?.into())
+    }
 
-// Each of the Game's rules follows a straightforward translation into a 
-// condition on a match expression. Additionally, because we want JavaScript 
-// to control when ticks happen, we will put this method inside 
-// a #[wasm_bindgen] block, so that it gets exposed to JavaScript.
+    pub fn from_rle(rle: &str) -> Result<Universe, Error> {
+        let _timer = Timer::new("Universe::from_rle");
+        Ok(life::Universe::from_rle(rle)?.into())
+    }
 
+    // LifeWiki's .cells files, with . for dead and O for live cells.
+    pub fn from_plaintext(text: &str) -> Result<Universe, Error> {
+        Ok(life::Universe::from_plaintext(text)?.into())
+    }
+
+    // What render_text() writes with the default glyphs, or the same
+    // grid with . and O.
+    pub fn from_text(text: &str) -> Result<Universe, Error> {
+        Ok(life::Universe::from_text(text)?.into())
+    }
+
+    // RLE, plaintext, Life 1.05 or Life 1.06, whichever it is.
+    pub fn from_pattern_text(text: &str) -> Result<Universe, Error> {
+        Ok(life::Universe::from_pattern_text(text)?.into())
+    }
+
+    // Golly's .mc files, which may be far bigger than width x height:
+    // the universe runs Hashlife and the window shows their middle.
+    pub fn from_macrocell(text: &str, width: u32, height: u32) -> Result<Universe, Error> {
+        Ok(life::Universe::from_macrocell(text, width, height)?.into())
+    }
+
+    pub fn from_share_string(share: &str) -> Result<Universe, Error> {
+        let _timer = Timer::new("Universe::from_share_string");
+        Ok(life::Universe::from_share_string(share)?.into())
+    }
+
+    pub fn restore(bytes: &[u8]) -> Result<Universe, Error> {
+        let _timer = Timer::new("Universe::restore");
+        Ok(life::Universe::restore(bytes)?.into())
+    }
+
+    pub fn save(&self) -> Result<Vec<u8>, Error> {
+        let _timer = Timer::new("Universe::save");
+        Ok(self.inner.save()?)
+    }
+
+    pub fn enable_autosave(&mut self, interval: u32) -> Result<(), Error> {
+        Ok(self.inner.enable_autosave(interval)?)
+    }
+
+    pub fn disable_autosave(&mut self) {
+        self.inner.disable_autosave()
+    }
+
+    pub fn autosave_interval(&self) -> u32 {
+        self.inner.autosave_interval()
+    }
+
+    pub fn export_autosave(&self) -> Result<Vec<u8>, Error> {
+        Ok(self.inner.export_autosave()?)
+    }
+
+    pub fn import_autosave(bytes: &[u8]) -> Result<Universe, Error> {
+        Ok(life::Universe::import_autosave(bytes)?.into())
+    }
+
+    pub fn to_rle(&self) -> String {
+        let _timer = Timer::new("Universe::to_rle");
+        self.inner.to_rle()
+    }
+
+    pub fn to_plaintext(&self) -> Result<String, Error> {
+        Ok(self.inner.to_plaintext()?)
+    }
+
+    pub fn to_pattern_text(&self, format: PatternFormat) -> Result<String, Error> {
+        Ok(self.inner.to_pattern_text(format.into())?)
+    }
+
+    pub fn to_macrocell(&mut self) -> String {
+        self.inner.to_macrocell()
+    }
+
+    pub fn to_share_string(&self) -> String {
+        let _timer = Timer::new("Universe::to_share_string");
+        self.inner.to_share_string()
+    }
 
-// Public methods, exported to JavaScript.
-#[wasm_bindgen]
-impl Universe {
     pub fn tick(&mut self) {
-        let mut next = self.cells.clone();
-
-        for row in 0..self.height {
-            for col in 0..self.width {
-                let idx = self.get_index(row, col);
-                let cell = self.cells[idx];
-                let live_neighbors = self.live_neighbor_count(row, col);
-
-               // let next_cell = match (cell, live_neighbors) {
-               //     // Rule 1: Any live cell with fewer than two live neighbours
-               //     // dies, as if caused by underpopulation.
-               //     (Cell::Alive, x) if x < 2 => Cell::Dead,
-               //     // Rule 2: Any live cell with two or three live neighbours
-               //     // lives on to the next generation.
-               //     (Cell::Alive, 2) | (Cell::Alive, 3) => Cell::Alive,
-               //     // Rule 3: Any live cell with more than three live
-               //     // neighbours dies, as if by overpopulation.
-               //     (Cell::Alive, x) if x > 3 => Cell::Dead,
-               //     // Rule 4: Any dead cell with exactly three live neighbours
-               //     // becomes a live cell, as if by reproduction.
-               //     (Cell::Dead, 3) => Cell::Alive,
-               //     // All other cells remain in the same state.
-               //     (otherwise, _) => otherwise,
-               // };
-               next.set(idx, match (cell, live_neighbors) {
-                    (true, x) if x < 2 => false,
-                    (true, 2) | (true, 3) => true,
-                    (true, x) if x > 3 => false,
-                    (false, 3) => true,
-                    (otherwise, _) => otherwise
-                });
-
-               // next[idx] = next_cell;
-            }
-        }
+        let _timer = Timer::new("Universe::tick");
+        self.inner.tick();
+        views::check_memory();
+    }
 
-        self.cells = next;
-    }
-
-    // We define a constructor that initializes the universe 
-    // with an interesting pattern of live and dead cells. 
-        //    pub fn new() -> Universe {
-        //        let width = 64;
-        //        let height = 64;
-        //
-        //        let cells = (0..width * height)
-        //            .map(|i| {
-        //                //if i % 2 == 0 || i % 7 == 0 {
-        //                if js_sys::Math::random() < 0.5 {
-        //                    Cell::Alive
-        //                } else {
-        //                    Cell::Dead
-        //                }
-        //            })
-        //            .collect();
-        //
-        //        Universe {
-        //            width,
-        //            height,
-        //            cells,
-        //        }
-        //    }
-    // The Universe constructor adjusted to use FixedBitSet
-    pub fn new() -> Universe {
-        let width = 64;
-        let height = 64;
+    pub fn tick_many(&mut self, n: u32) {
+        let _timer = Timer::new("Universe::tick_many");
+        self.inner.tick_many(n);
+        views::check_memory();
+    }
 
-        let size = (width * height) as usize;
-        let mut cells = FixedBitSet::with_capacity(size);
+    // E.g. tick_for_budget(12) for as many generations as fit in 12ms, 
+    // timed with performance.now(). 
+    pub fn tick_for_budget(&mut self, ms: f64) -> u32 {
+        let _timer = Timer::new("Universe::tick_for_budget");
+        views::stepped(self.inner.tick_for_budget(ms, perf::now))
+    }
 
-        for i in 0..size {
-            // cells.set(i, i % 2 == 0 || i % 7 == 0);
-            cells.set(i, js_sys::Math::random() < 0.5);
-        }
+    // E.g. `new Blob([universe.record_gif(200, 4, 0)], { type: "image/gif" })`.
+    #[cfg(feature = "gif")]
+    pub fn record_gif(&mut self, generations: u32, cell_size: u32, frame_skip: u32) -> Result<Vec<u8>, Error> {
+        Ok(views::stepped(self.inner.record_gif(generations, cell_size, frame_skip))?)
+    }
 
-        Universe {
-            width,
-            height,
-            cells,
-        }
+    #[cfg(feature = "apng")]
+    pub fn record_apng(&mut self, generations: u32, cell_size: u32, frame_skip: u32, coloring: Coloring) -> Result<Vec<u8>, Error> {
+        Ok(views::stepped(self.inner.record_apng(generations, cell_size, frame_skip, coloring.into()))?)
+    }
+
+    // In the browser, the bands only run on separate threads with a
+    // threaded build and a pool started by init_thread_pool().
+    pub fn tick_parallel(&mut self, num_workers: u32) {
+        let _timer = Timer::new("Universe::tick_parallel");
+        self.inner.tick_parallel(num_workers);
+        views::check_memory();
+    }
+
+    // Comes out as a Uint32Array of (row, col, new_state) triples.
+    pub fn tick_delta(&mut self) -> Vec<u32> {
+        let _timer = Timer::new("Universe::tick_delta");
+        views::stepped(self.inner.tick_delta())
+    }
+
+    pub fn run_until_stable(&mut self, max_generations: u32, max_period: u32) -> StabilizationReport {
+        views::stepped(self.inner.run_until_stable(max_generations, max_period)).into()
+    }
+
+    pub fn detect_cycle(&self, max_period: u32) -> Option<Cycle> {
+        self.inner.detect_cycle(max_period).map(Cycle::from)
+    }
+
+    // Every separate object in the universe and what it is, e.g. for a
+    // tooltip over whatever is under the pointer, see ObjectInfo.contains().
+    pub fn identify_objects(&self) -> Result<Vec<ObjectInfo>, Error> {
+        Ok(self.inner.identify_objects()?.into_iter().map(ObjectInfo::from).collect())
+    }
+
+    // The spaceships that are clear of everything else and moving away
+    // from it, and will never come back.
+    pub fn escaping_objects(&self) -> Result<Vec<ObjectInfo>, Error> {
+        Ok(self.inner.escaping_objects()?.into_iter().map(ObjectInfo::from).collect())
+    }
+
+    pub fn insert_rle(&mut self, rle: &str, row: u32, col: u32) -> Result<(), Error> {
+        Ok(self.inner.insert_rle(rle, row, col)?)
+    }
+
+    pub fn insert_plaintext(&mut self, text: &str, row: u32, col: u32) -> Result<(), Error> {
+        Ok(self.inner.insert_plaintext(text, row, col)?)
+    }
+
+    pub fn insert_pattern(&mut self, name: &str, row: u32, col: u32) -> Result<(), Error> {
+        Ok(self.inner.insert_pattern(name, row, col)?)
+    }
+
+    pub fn stamp(&mut self, pattern: &Pattern, row: u32, col: u32) -> Result<(), Error> {
+        Ok(self.inner.stamp(pattern.inner(), row, col)?)
+    }
+
+    pub fn select(&mut self, row: u32, col: u32, width: u32, height: u32) -> Result<(), Error> {
+        Ok(self.inner.select(row, col, width, height)?)
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.inner.clear_selection()
+    }
+
+    // [row, col, width, height], or undefined when nothing is selected.
+    pub fn selection(&self) -> Option<Vec<u32>> {
+        self.inner.selection().map(|(row, col, width, height)| vec![row, col, width, height])
+    }
+
+    pub fn selection_to_rle(&self) -> String {
+        self.inner.selection_to_rle()
+    }
+
+    pub fn get_cell(&self, row: u32, col: u32) -> Result<Cell, Error> {
+        Ok(self.inner.get_cell(row, col)?.into())
+    }
+
+    pub fn set_cell(&mut self, row: u32, col: u32, cell: Cell) -> Result<(), Error> {
+        Ok(self.inner.set_cell(row, col, cell.into())?)
+    }
+
+    pub fn toggle_cell(&mut self, row: u32, col: u32) -> Result<(), Error> {
+        Ok(self.inner.toggle_cell(row, col)?)
+    }
+
+    pub fn set_cells(&mut self, cells: &[u32]) -> Result<(), Error> {
+        Ok(self.inner.set_cells(cells)?)
+    }
+
+    pub fn set_cells_bits(&mut self, bits: &[u32]) -> Result<(), Error> {
+        Ok(self.inner.set_cells_bits(bits)?)
+    }
+
+    pub fn undo(&mut self) -> bool {
+        self.inner.undo()
+    }
+
+    pub fn redo(&mut self) -> bool {
+        self.inner.redo()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.inner.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.inner.can_redo()
+    }
+
+    pub fn history_depth(&self) -> u32 {
+        self.inner.history_depth()
+    }
+
+    pub fn set_history_depth(&mut self, depth: u32) {
+        self.inner.set_history_depth(depth);
+    }
+
+    pub fn history_budget(&self) -> Option<usize> {
+        self.inner.history_budget()
+    }
+
+    // Bytes of undo history to keep at most, or undefined for no limit.
+    pub fn set_history_budget(&mut self, bytes: Option<usize>) {
+        self.inner.set_history_budget(bytes);
+    }
+
+    pub fn boundary(&self) -> Boundary {
+        self.inner.boundary().into()
+    }
+
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.inner.set_boundary(boundary.into());
+    }
+
+    pub fn neighborhood_shape(&self) -> NeighborhoodShape {
+        self.inner.neighborhood().shape().into()
+    }
+
+    pub fn neighborhood_radius(&self) -> u32 {
+        self.inner.neighborhood().radius()
+    }
+
+    pub fn set_neighborhood(&mut self, shape: NeighborhoodShape, radius: u32) -> Result<(), Error> {
+        Ok(self.inner.set_neighborhood(life::Neighborhood::new(shape.into(), radius)?)?)
+    }
+
+    // An odd size x size kernel of weights, row by row, and the weighted
+    // sums under which dead cells are born and live ones survive; see the
+    // core crate's WeightedRule.
+    pub fn set_weighted_rule(&mut self, size: u32, weights: &[i8], birth: &[i32], survival: &[i32]) -> Result<(), Error> {
+        let weighted = life::WeightedRule::new(size, weights, birth, survival)?;
+        self.inner.set_weighted_rule(Some(weighted));
+        Ok(())
+    }
+
+    // Goes back to the rule's own neighbor counts.
+    pub fn clear_weighted_rule(&mut self) {
+        self.inner.set_weighted_rule(None);
+    }
+
+    pub fn has_weighted_rule(&self) -> bool {
+        self.inner.weighted_rule().is_some()
+    }
+
+    // Runs every cell through `rule(state, count, bits)`: its state, how
+    // many of its eight neighbors are alive, and which ones, one bit each
+    // from NW (128) down to SE (1). It returns the next state, as a number
+    // or a boolean. Much slower than the built-in rules, but handy for
+    // trying out ideas; if it throws or returns anything else, the cell
+    // dies.
+    pub fn set_custom_rule(&mut self, rule: js_sys::Function) {
+        self.inner.set_custom_rule(Some(Box::new(FunctionRule { function: rule })));
+    }
+
+    pub fn clear_custom_rule(&mut self) {
+        self.inner.set_custom_rule(None);
+    }
+
+    pub fn has_custom_rule(&self) -> bool {
+        self.inner.has_custom_rule()
+    }
+
+    // Runs the @TABLE of a Golly .rule file, e.g. read from a file input.
+    // Its @COLORS are used by Renderer::draw.
+    pub fn load_rule_file(&mut self, contents: &str) -> Result<(), Error> {
+        Ok(self.inner.load_rule_file(contents)?)
+    }
+
+    pub fn clear_rule_table(&mut self) {
+        self.inner.clear_rule_table();
+    }
+
+    pub fn has_rule_table(&self) -> bool {
+        self.inner.rule_table().is_some()
     }
 
+    // Births the rule calls for happen with probability `birth`,
+    // survivals with `survival`, and any cell left alive still dies with
+    // `death`. The dice come from set_seed(), so the same seed and cells
+    // always give the same history.
+    pub fn set_stochastic(&mut self, birth: f32, survival: f32, death: f32) -> Result<(), Error> {
+        let stochastic = life::Stochastic::new(birth, survival, death)?;
+        self.inner.set_stochastic(Some(stochastic));
+        Ok(())
+    }
+
+    pub fn clear_stochastic(&mut self) {
+        self.inner.set_stochastic(None);
+    }
+
+    pub fn is_stochastic(&self) -> bool {
+        self.inner.stochastic().is_some()
+    }
+
+    // Soups, stochastic rules and mutate_rule() all draw from the one
+    // generator, which this starts over. The seed goes into save() too,
+    // so a shared save carries on exactly as it would have here.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.inner.set_seed(seed);
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.inner.seed()
+    }
+
+    // A fresh soup from the generator, undoable like any edit.
+    pub fn randomize(&mut self, density: f32) -> Result<(), Error> {
+        Ok(self.inner.randomize(density)?)
+    }
+
+    // A soup with one of apgsearch's symmetries, by its name, e.g.
+    // "D8_1" or "D4_+2", in the middle of an otherwise empty universe.
+    pub fn randomize_symmetric(&mut self, symmetry: &str, density: f32) -> Result<(), Error> {
+        Ok(self.inner.randomize_symmetric(symmetry.parse()?, density)?)
+    }
+
+    // Clustered blobs of live cells from gradient noise, where it is
+    // above `threshold`, between about -1 and 1. The smaller the
+    // `frequency`, the bigger the blobs; 0.1 is a good start.
+    pub fn randomize_noise(&mut self, frequency: f32, threshold: f32) -> Result<(), Error> {
+        Ok(self.inner.randomize_noise(frequency, threshold)?)
+    }
+
+    // Flips one birth or survival condition of a B/S rule at random.
+    pub fn mutate_rule(&mut self) -> Result<(), Error> {
+        Ok(self.inner.mutate_rule()?)
+    }
+
+    pub fn engine(&self) -> Engine {
+        self.inner.engine().into()
+    }
+
+    pub fn set_engine(&mut self, engine: Engine) {
+        self.inner.set_engine(engine.into());
+    }
 
-    // Rendering to Canvas Directly from Memory
-    // Generating (and allocating) a String in Rust 
-    // and then having wasm-bindgen convert it to a valid JavaScript string 
-    // makes unnecessary copies of the universe's cells. 
-    // As the JavaScript code already knows the width and height of the universe, 
-    // and can read WebAssembly's linear memory that make up the cells directly, 
-    // we'll modify the render method to return a pointer to the start of 
-    // the cells array.
-    //
-    // Also, instead of rendering Unicode text, 
-    // we'll switch to using the Canvas API
-    // 
-    // old version 
-    // pub fn render(&self) -> String {
-    //    self.to_string()
-    // }
-   // 
-   // To get the necessary information from the Rust implementation, 
-   // we'll need to add some more getter functions for a universe's 
-   // width, height, and pointer to its cells array. 
-   // All of these are exposed to JavaScript as well
-   pub fn width(&self) -> u32 {
-        self.width
+    pub fn rule(&self) -> String {
+        self.inner.rule()
+    }
+
+    pub fn render_text(&self, options: &TextOptions) -> String {
+        self.inner.render_text(options.inner())
+    }
+
+    // E.g. for `new Blob([universe.to_svg(options)], { type: "image/svg+xml" })`.
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        self.inner.to_svg(options.inner())
+    }
+
+    pub fn render_braille(&self) -> String {
+        self.inner.render_braille()
+    }
+
+    pub fn render_viewport(&self, x: i32, y: i32, view_width: u32, view_height: u32, zoom: f64) -> Result<Vec<u8>, Error> {
+        Ok(self.inner.render_viewport(x, y, view_width, view_height, zoom)?)
+    }
+
+    // `out` is copied back into the JavaScript array after the call.
+    pub fn render_viewport_into(
+        &self,
+        x: i32,
+        y: i32,
+        view_width: u32,
+        view_height: u32,
+        zoom: f64,
+        out: &mut [u8],
+    ) -> Result<(), Error> {
+        Ok(self.inner.render_viewport_into(x, y, view_width, view_height, zoom, out)?)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.inner.width()
     }
 
     pub fn height(&self) -> u32 {
-        self.height
+        self.inner.height()
+    }
+
+    pub fn set_width(&mut self, width: u32) -> Result<(), Error> {
+        Ok(self.inner.set_width(width)?)
+    }
+
+    pub fn set_height(&mut self, height: u32) -> Result<(), Error> {
+        Ok(self.inner.set_height(height)?)
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32, anchor: Anchor) -> Result<(), Error> {
+        Ok(self.inner.resize(width, height, anchor.into())?)
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.inner.generation()
     }
-    
-    //pub fn cells(&self) -> *const Cell {
-    //    self.cells.as_ptr()
-    //}
 
-    // To pass a pointer to the start of the bits to JavaScript, 
-    // you can convert the FixedBitSet to a slice and then convert the slice 
-    // to a pointer
+    pub fn population(&self) -> u32 {
+        self.inner.population()
+    }
+
+    pub fn tick_stats(&self) -> TickStats {
+        self.inner.tick_stats().into()
+    }
+
+    pub fn perf_stats(&self) -> PerfStats {
+        self.inner.perf_stats().into()
+    }
+
+    // The bytes held by the cells, undo history, Hashlife and the rest,
+    // for when a big universe runs into wasm's memory limit.
+    pub fn memory_report(&self) -> MemoryReport {
+        self.inner.memory_report().into()
+    }
+
+    // For a universe that is going to be resized up to width x height.
+    pub fn reserve(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        Ok(self.inner.reserve(width, height)?)
+    }
+
+    // Hands memory back after a big experiment; wasm memory itself
+    // doesn't shrink, but the space can be used for something else.
+    pub fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit();
+    }
+
+    pub fn population_history(&self, n: u32) -> Vec<u32> {
+        self.inner.population_history(n)
+    }
+
+    pub fn record_stats(&mut self, enabled: bool) {
+        self.inner.record_stats(enabled)
+    }
+
+    pub fn is_recording_stats(&self) -> bool {
+        self.inner.is_recording_stats()
+    }
+
+    // UTF-8 bytes, ready for `new Blob([bytes], { type: "text/csv" })`.
+    pub fn export_stats(&self, format: StatsFormat) -> Vec<u8> {
+        self.inner.export_stats(format.into())
+    }
+
+    // The pointers below are for typed arrays laid over wasm memory,
+    // which the *_view() methods in views.rs also make ready-made.
+    // They are only good until the next call into wasm: ticking swaps
+    // the cells between two buffers, and anything that allocates can
+    // move memory around, so fetch them again every frame.
+
+    // A pointer to the packed cells, in the layout described by the
+    // core crate's Universe::cells(): bit `row * row_stride() + col`,
+    // least-significant bit first inside little-endian u32 words.
     pub fn cells(&self) -> *const u32 {
-        self.cells.as_slice().as_ptr()
-    }
-    
-
-}//^-- impl Universe
-
-// -- Now using Canvas in Js
-// The state of the universe is represented as a vector of cells. 
-// To make this human readable, let's implement a basic text renderer. 
-// The idea is to write the universe line by line as text, and for each cell 
-// that is alive, print the Unicode character ◼ ("black medium square"). 
-// For dead cells, we'll print ◻ (a "white medium square").
-// use std::fmt;
-// 
-// impl fmt::Display for Universe {
-//     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-//         for line in self.cells.as_slice().chunks(self.width as usize) {
-//             for &cell in line {
-//                 let symbol = if cell == Cell::Dead { '◻' } else { '◼' };
-//                 write!(f, "{}", symbol)?;
-//             }
-//             write!(f, "\n")?;
-//         }
-// 
-//         Ok(())
-//     }
-// }
+        self.inner.cells().as_ptr()
+    }
+
+    // The same pointer again, under the name the renderers know it by.
+    pub fn cells_bits(&self) -> *const u32 {
+        self.cells()
+    }
+
+    // Number of bytes JavaScript should overlay at `cells_bits()`.
+    // This is rounded up to whole u32 words, so it is not always
+    // width * height / 8.
+    pub fn cells_bits_len(&self) -> usize {
+        self.inner.cells().len() * 4
+    }
+
+    pub fn row_stride(&self) -> u32 {
+        self.inner.row_stride()
+    }
+
+    // The cells flipped by the last tick, edit, undo or redo, laid out like cells_bits().
+    pub fn changed_cells(&self) -> *const u32 {
+        self.inner.changed_cells().as_ptr()
+    }
+
+    pub fn changed_cells_len(&self) -> usize {
+        self.inner.changed_cells().len() * 4
+    }
+
+    pub fn changed_per_column(&self) -> Vec<u32> {
+        self.inner.changed_per_column()
+    }
+
+    // u32 live counts of 8x8 (level 0) or 64x64 (level 1) blocks,
+    // ceil(width / block) x ceil(height / block) of them.
+    pub fn density_level(&mut self, level: u32) -> Result<*const u32, Error> {
+        Ok(self.inner.density_level(level)?.as_ptr())
+    }
+
+    // width * height u16 ages, for a Uint16Array.
+    pub fn ages(&mut self) -> *const u16 {
+        self.inner.ages().as_ptr()
+    }
+
+    // width * height f32 activity values, for a Float32Array heatmap.
+    pub fn activity(&mut self) -> *const f32 {
+        self.inner.activity().as_ptr()
+    }
+
+    pub fn species(&self) -> Species {
+        self.inner.species().into()
+    }
+
+    pub fn set_species(&mut self, species: Species) {
+        self.inner.set_species(species.into());
+    }
+
+    // width * height u8 colors, for a Uint8Array: 0 for dead cells,
+    // 1 and up for the live ones. With Species::Single there are no
+    // colors, and nothing to read at this pointer.
+    pub fn colors(&self) -> *const u8 {
+        self.inner.colors().as_ptr()
+    }
+
+    pub fn get_color(&self, row: u32, col: u32) -> Result<u8, Error> {
+        Ok(self.inner.get_color(row, col)?)
+    }
+
+    pub fn set_cell_color(&mut self, row: u32, col: u32, color: u8) -> Result<(), Error> {
+        Ok(self.inner.set_cell_color(row, col, color)?)
+    }
+
+    pub fn state_count(&self) -> u8 {
+        self.inner.state_count()
+    }
+
+    // width * height u8 states with a Generations rule, for a
+    // Uint8Array: 0 dead, 1 alive, 2 and up dying. With Wireworld, 0
+    // empty, 1 head, 2 tail and 3 wire. Nothing to read here with
+    // two-state rules.
+    pub fn states(&self) -> *const u8 {
+        self.inner.states().as_ptr()
+    }
+
+    pub fn get_state(&self, row: u32, col: u32) -> Result<u8, Error> {
+        Ok(self.inner.get_state(row, col)?)
+    }
+
+    // E.g. 3 to lay down wire with the "WireWorld" rule.
+    pub fn set_state(&mut self, row: u32, col: u32, state: u8) -> Result<(), Error> {
+        Ok(self.inner.set_state(row, col, state)?)
+    }
+
+    pub fn is_wireworld(&self) -> bool {
+        self.inner.is_wireworld()
+    }
+
+    // Adds an ant given by its turns on dead and live cells, "RL" for
+    // Langton's.
+    pub fn add_ant(&mut self, row: u32, col: u32, direction: Direction, rule: &str) -> Result<(), Error> {
+        Ok(self.inner.add_turmite(life::Turmite::ant(row, col, direction.into(), rule)?)?)
+    }
 
+    // Adds a turmite with any table: for each state, (write, turn, next
+    // state) for a dead cell and then for a live one, with turns in
+    // quarter turns clockwise.
+    pub fn add_turmite(&mut self, row: u32, col: u32, direction: Direction, table: &[u8]) -> Result<(), Error> {
+        Ok(self.inner.add_turmite(life::Turmite::new(row, col, direction.into(), table)?)?)
+    }
+
+    pub fn remove_turmite(&mut self, index: usize) -> Result<(), Error> {
+        self.inner.remove_turmite(index)?;
+        Ok(())
+    }
+
+    pub fn clear_turmites(&mut self) {
+        self.inner.clear_turmites()
+    }
+
+    pub fn turmite_count(&self) -> usize {
+        self.inner.turmites().len()
+    }
+
+    // Where the turmites are, for drawing them over the cells: a flat
+    // list of (row, col, direction, state), with directions numbered
+    // like Direction.
+    pub fn turmites(&self) -> Vec<u32> {
+        self.inner
+            .turmites()
+            .iter()
+            .flat_map(|turmite| {
+                let direction = Direction::from(turmite.direction()) as u32;
+                [turmite.row(), turmite.col(), direction, u32::from(turmite.state())]
+            })
+            .collect()
+    }
+
+    pub fn activity_decay(&self) -> f32 {
+        self.inner.activity_decay()
+    }
+
+    pub fn set_activity_decay(&mut self, decay: f32) -> Result<(), Error> {
+        Ok(self.inner.set_activity_decay(decay)?)
+    }
+}
+
+impl Default for Universe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Universe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
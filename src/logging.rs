@@ -0,0 +1,14 @@
+use std::str::FromStr;
+
+use wasm_bindgen::prelude::*;
+
+use crate::Error;
+
+// Sends the engine's log messages at `level` and up, one of "error",
+// "warn", "info", "debug" or "trace", to the browser console. There
+// can only be one logger, so calling it again is an error.
+#[wasm_bindgen]
+pub fn init_logging(level: &str) -> Result<(), Error> {
+    let level = log::Level::from_str(level).map_err(|_| Error::new(&format!("unknown log level {:?}", level)))?;
+    console_log::init_with_level(level).map_err(|_| Error::new("logging is already set up"))
+}
@@ -0,0 +1,101 @@
+use std::convert::TryInto;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{life, Cell, Error};
+
+mirror_enum! {
+    pub enum MargolusRule {
+        Critters,
+        BilliardBall,
+        Tron,
+    }
+}
+
+#[wasm_bindgen]
+pub struct MargolusUniverse {
+    inner: life::MargolusUniverse,
+}
+
+impl MargolusUniverse {
+    pub(crate) fn inner(&self) -> &life::MargolusUniverse {
+        &self.inner
+    }
+}
+
+#[wasm_bindgen]
+impl MargolusUniverse {
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u32, height: u32, rule: MargolusRule) -> Result<MargolusUniverse, Error> {
+        Ok(MargolusUniverse {
+            inner: life::MargolusUniverse::new(width, height, rule.into())?,
+        })
+    }
+
+    // `tables` holds the next block for each of the 16 blocks: 16 entries
+    // to use at every tick, or 32 with those for even generations first.
+    pub fn with_tables(width: u32, height: u32, tables: &[u8]) -> Result<MargolusUniverse, Error> {
+        let table = |entries: &[u8]| -> [u8; 16] { entries.try_into().unwrap() };
+        let (even, odd) = match tables.len() {
+            16 => (table(tables), table(tables)),
+            32 => (table(&tables[..16]), table(&tables[16..])),
+            _ => return Err(Error::new("expected 16 or 32 table entries")),
+        };
+        Ok(MargolusUniverse {
+            inner: life::MargolusUniverse::with_tables(width, height, even, odd)?,
+        })
+    }
+
+    pub fn tick(&mut self) {
+        self.inner.tick();
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        self.inner.tick_many(n);
+    }
+
+    pub fn is_reversible(&self) -> bool {
+        self.inner.is_reversible()
+    }
+
+    pub fn tick_back(&mut self) -> Result<(), Error> {
+        Ok(self.inner.tick_back()?)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.inner.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.inner.height()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    pub fn population(&self) -> u32 {
+        self.inner.population()
+    }
+
+    // A pointer to the packed cells, in the same layout as Universe::cells().
+    pub fn cells(&self) -> *const u32 {
+        self.inner.cells().as_ptr()
+    }
+
+    pub fn get_cell(&self, row: u32, col: u32) -> Result<Cell, Error> {
+        Ok(self.inner.get_cell(row, col)?.into())
+    }
+
+    pub fn set_cell(&mut self, row: u32, col: u32, cell: Cell) -> Result<(), Error> {
+        Ok(self.inner.set_cell(row, col, cell.into())?)
+    }
+
+    pub fn toggle_cell(&mut self, row: u32, col: u32) -> Result<(), Error> {
+        Ok(self.inner.toggle_cell(row, col)?)
+    }
+
+    pub fn randomize(&mut self, density: f32, seed: u64) -> Result<(), Error> {
+        Ok(self.inner.randomize(density, seed)?)
+    }
+}
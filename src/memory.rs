@@ -0,0 +1,68 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::{life, options};
+
+#[wasm_bindgen(typescript_custom_section)]
+const MEMORY_REPORT_JSON: &str = r#"
+export interface MemoryReportJSON {
+    cells: number;
+    history: number;
+    hashlife: number;
+    overlays: number;
+    patterns: number;
+}
+"#;
+
+// How much wasm memory there is altogether, in bytes. It only ever
+// grows, so after a big universe has been dropped this stays as big as
+// it got, while memory_report() shows what is still in use.
+#[wasm_bindgen]
+pub fn wasm_memory_bytes() -> f64 {
+    let memory = wasm_bindgen::memory().unchecked_into::<js_sys::WebAssembly::Memory>();
+    memory.buffer().unchecked_into::<js_sys::ArrayBuffer>().byte_length() as f64
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryReport {
+    inner: life::MemoryReport,
+}
+
+impl From<life::MemoryReport> for MemoryReport {
+    fn from(inner: life::MemoryReport) -> MemoryReport {
+        MemoryReport { inner }
+    }
+}
+
+#[wasm_bindgen]
+impl MemoryReport {
+    pub fn cells(&self) -> usize {
+        self.inner.cells
+    }
+
+    pub fn history(&self) -> usize {
+        self.inner.history
+    }
+
+    pub fn hashlife(&self) -> usize {
+        self.inner.hashlife
+    }
+
+    pub fn overlays(&self) -> usize {
+        self.inner.overlays
+    }
+
+    pub fn patterns(&self) -> usize {
+        self.inner.patterns
+    }
+
+    pub fn total(&self) -> usize {
+        self.inner.total()
+    }
+
+    #[wasm_bindgen(js_name = toJSON, unchecked_return_type = "MemoryReportJSON")]
+    pub fn to_json(&self) -> JsValue {
+        options::to_js(&self.inner)
+    }
+}
@@ -0,0 +1,142 @@
+use js_sys::Function;
+use wasm_bindgen::prelude::*;
+
+use crate::{life, options, Error};
+
+#[wasm_bindgen(typescript_custom_section)]
+const CANDIDATE_JSON: &str = r#"
+export interface CandidateJSON {
+    rle: string;
+    population: number;
+    lifespan: number;
+    finalPopulation: number;
+}
+"#;
+
+// A starting pattern a MethuselahSearch tried, and how long it lived.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    inner: life::Candidate,
+}
+
+impl From<life::Candidate> for Candidate {
+    fn from(inner: life::Candidate) -> Candidate {
+        Candidate { inner }
+    }
+}
+
+#[wasm_bindgen]
+impl Candidate {
+    pub fn rle(&self) -> String {
+        self.inner.rle().to_string()
+    }
+
+    pub fn population(&self) -> u32 {
+        self.inner.population()
+    }
+
+    // The generation it settled into still lifes, oscillators and
+    // escaping spaceships.
+    pub fn lifespan(&self) -> u64 {
+        self.inner.lifespan()
+    }
+
+    pub fn final_population(&self) -> u32 {
+        self.inner.final_population()
+    }
+
+    #[wasm_bindgen(js_name = toJSON, unchecked_return_type = "CandidateJSON")]
+    pub fn to_json(&self) -> JsValue {
+        options::to_js(&self.inner)
+    }
+}
+
+// Evolves small patterns towards ones that take a long time to settle:
+//
+//     const search = new MethuselahSearch("B3/S23", 200, 7n);
+//     search.set_on_progress((done, total) => bar.value = done / total);
+//     while (!search.is_finished()) {
+//         search.run(1);
+//         await new Promise(requestAnimationFrame);
+//     }
+//     for (const candidate of search.best()) console.log(candidate.toJSON());
+#[wasm_bindgen]
+pub struct MethuselahSearch {
+    inner: life::MethuselahSearch,
+    on_progress: Option<Function>,
+}
+
+#[wasm_bindgen]
+impl MethuselahSearch {
+    #[wasm_bindgen(constructor)]
+    pub fn new(rule: &str, rounds: u32, seed: u64) -> Result<MethuselahSearch, Error> {
+        Ok(MethuselahSearch {
+            inner: life::MethuselahSearch::new(rule, rounds, seed)?,
+            on_progress: None,
+        })
+    }
+
+    // The box the starting patterns fit in, 6x6 unless set.
+    pub fn set_box(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        Ok(self.inner.set_box(width, height)?)
+    }
+
+    // How many patterns a round tries.
+    pub fn set_candidates(&mut self, candidates: u32) -> Result<(), Error> {
+        Ok(self.inner.set_candidates(candidates)?)
+    }
+
+    pub fn set_size(&mut self, size: u32) -> Result<(), Error> {
+        Ok(self.inner.set_size(size)?)
+    }
+
+    pub fn set_max_generations(&mut self, max_generations: u32) {
+        self.inner.set_max_generations(max_generations);
+    }
+
+    // Called with the rounds done so far and the total after each round.
+    pub fn set_on_progress(&mut self, callback: Option<Function>) {
+        self.on_progress = callback;
+    }
+
+    pub fn done(&self) -> u32 {
+        self.inner.done()
+    }
+
+    pub fn total(&self) -> u32 {
+        self.inner.total()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+
+    // How many different patterns have been run.
+    pub fn tried(&self) -> u32 {
+        self.inner.tried() as u32
+    }
+
+    // Runs up to `count` more rounds and returns how many ran.
+    pub fn run(&mut self, count: u32) -> Result<u32, Error> {
+        let mut ran = 0;
+        while ran < count && !self.inner.is_finished() {
+            ran += self.inner.run(1)?;
+            if let Some(callback) = &self.on_progress {
+                let _ = callback.call2(&JsValue::NULL, &self.inner.done().into(), &self.inner.total().into());
+            }
+        }
+        Ok(ran)
+    }
+
+    // Every round that is left, in one go.
+    pub fn run_all(&mut self) -> Result<Vec<Candidate>, Error> {
+        self.run(u32::MAX)?;
+        Ok(self.best())
+    }
+
+    // The longest lived patterns so far, the longest first.
+    pub fn best(&self) -> Vec<Candidate> {
+        self.inner.best().into_iter().map(Candidate::from).collect()
+    }
+}
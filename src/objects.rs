@@ -0,0 +1,137 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{life, options};
+
+mirror_enum! {
+    pub enum ObjectKind {
+        StillLife,
+        Oscillator,
+        Spaceship,
+        Unidentified,
+    }
+}
+
+mirror_enum! {
+    pub enum Heading {
+        North,
+        NorthEast,
+        East,
+        SouthEast,
+        South,
+        SouthWest,
+        West,
+        NorthWest,
+    }
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const OBJECT_INFO_JSON: &str = r#"
+export interface ObjectInfoJSON {
+    kind: "StillLife" | "Oscillator" | "Spaceship" | "Unidentified";
+    code: string | null;
+    name: string | null;
+    period: number | null;
+    // [rows, columns] per period, [0, 0] unless it's a spaceship.
+    displacement: [number, number];
+    heading: "North" | "NorthEast" | "East" | "SouthEast" | "South" | "SouthWest" | "West" | "NorthWest" | null;
+    population: number;
+    top: number;
+    left: number;
+    width: number;
+    height: number;
+}
+"#;
+
+// One of the objects Universe.identify_objects() found.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObjectInfo {
+    inner: life::ObjectInfo,
+}
+
+impl From<life::ObjectInfo> for ObjectInfo {
+    fn from(inner: life::ObjectInfo) -> ObjectInfo {
+        ObjectInfo { inner }
+    }
+}
+
+#[wasm_bindgen]
+impl ObjectInfo {
+    pub fn kind(&self) -> ObjectKind {
+        self.inner.kind().into()
+    }
+
+    // The apgcode, e.g. "xs4_33"; undefined if it's unidentified.
+    pub fn code(&self) -> Option<String> {
+        self.inner.code().map(str::to_string)
+    }
+
+    // E.g. "block", for the common objects.
+    pub fn name(&self) -> Option<String> {
+        self.inner.name().map(str::to_string)
+    }
+
+    pub fn period(&self) -> Option<u32> {
+        self.inner.period()
+    }
+
+    // How many rows and columns a spaceship moves per generation, e.g.
+    // for the arrow on a glider: 0.25 and 0.25 going south east.
+    pub fn velocity_rows(&self) -> f64 {
+        self.inner.velocity().0
+    }
+
+    pub fn velocity_cols(&self) -> f64 {
+        self.inner.velocity().1
+    }
+
+    // As a fraction of c: 0.25 for gliders.
+    pub fn speed(&self) -> f64 {
+        self.inner.speed()
+    }
+
+    // Undefined for objects that stay put.
+    pub fn heading(&self) -> Option<Heading> {
+        self.inner.heading().map(Heading::from)
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.inner.is_moving()
+    }
+
+    pub fn population(&self) -> u32 {
+        self.inner.population()
+    }
+
+    pub fn top(&self) -> u32 {
+        self.inner.top()
+    }
+
+    pub fn left(&self) -> u32 {
+        self.inner.left()
+    }
+
+    pub fn width(&self) -> u32 {
+        self.inner.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.inner.height()
+    }
+
+    pub fn contains(&self, row: u32, col: u32) -> bool {
+        self.inner.contains(row, col)
+    }
+
+    #[wasm_bindgen(js_name = toJSON, unchecked_return_type = "ObjectInfoJSON")]
+    pub fn to_json(&self) -> JsValue {
+        options::to_js(&self.inner)
+    }
+}
+
+// What an apgcode is usually called, e.g. "glider" for "xq4_153", for
+// labelling a census.
+#[wasm_bindgen]
+pub fn object_name(code: &str) -> Option<String> {
+    life::object_name(code).map(str::to_string)
+}
@@ -0,0 +1,174 @@
+use js_sys::Object;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::{life, Error, Universe};
+
+// Plain objects handed back to JavaScript: camelCase keys, numbers
+// rather than BigInts and nothing missing turned into undefined, so
+// they go through JSON.stringify() as they are.
+pub fn to_js<T: Serialize>(value: &T) -> JsValue {
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    value.serialize(&serializer).unwrap_or(JsValue::UNDEFINED)
+}
+
+// Reads an options object. serde-wasm-bindgen only looks up the keys it
+// knows, so a misspelt one would quietly be left out; it is checked for
+// here instead.
+fn from_options<T: DeserializeOwned>(options: JsValue, keys: &[&str]) -> Result<T, Error> {
+    if let Some(object) = options.dyn_ref::<Object>() {
+        for key in Object::keys(object).iter().filter_map(|key| key.as_string()) {
+            if !keys.contains(&key.as_str()) {
+                return Err(Error::new(&format!("unknown option {:?}, expected one of {}", key, keys.join(", "))));
+            }
+        }
+    }
+    Ok(serde_wasm_bindgen::from_value(options)?)
+}
+
+impl From<serde_wasm_bindgen::Error> for Error {
+    #[track_caller]
+    fn from(error: serde_wasm_bindgen::Error) -> Error {
+        // Its messages come as JavaScript errors, already with a prefix.
+        let message = error.to_string();
+        Error::new(&format!("invalid options: {}", message.trim_start_matches("Error: ")))
+    }
+}
+
+// What Universe.create() takes. The boundary and engine are their
+// names, as in { boundary: "Dead", engine: "Hashlife" }. A seed makes
+// a random soup of the given density (a half if none is given), a
+// pattern is any text Pattern.parse() reads, put in the middle.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateOptions {
+    width: Option<u32>,
+    height: Option<u32>,
+    rule: Option<String>,
+    boundary: Option<life::Boundary>,
+    engine: Option<life::Engine>,
+    seed: Option<u64>,
+    density: Option<f32>,
+    pattern: Option<String>,
+}
+
+impl CreateOptions {
+    fn builder(self) -> Result<life::UniverseBuilder, Error> {
+        let mut builder = life::UniverseBuilder::new();
+        if let Some(width) = self.width {
+            builder = builder.width(width);
+        }
+        if let Some(height) = self.height {
+            builder = builder.height(height);
+        }
+        if let Some(rule) = &self.rule {
+            builder = builder.rule(rule);
+        }
+        if let Some(boundary) = self.boundary {
+            builder = builder.boundary(boundary);
+        }
+        if let Some(engine) = self.engine {
+            builder = builder.engine(engine);
+        }
+        let seed = match (self.seed, self.density, self.pattern) {
+            (Some(_), _, Some(_)) => return Err(Error::new("give either a seed or a pattern, not both")),
+            (None, Some(_), _) => return Err(Error::new("a density needs a seed to go with it")),
+            (Some(seed), density, None) => life::Seed::Random { density: density.unwrap_or(0.5), seed },
+            (None, None, Some(pattern)) => life::Seed::Pattern(life::Pattern::parse(&pattern)?),
+            (None, None, None) => return Ok(builder),
+        };
+        Ok(builder.seed(seed))
+    }
+}
+
+#[cfg(any(feature = "gif", feature = "apng"))]
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Format {
+    Gif,
+    Apng,
+}
+
+// What Universe.record() takes: { format: "gif" | "apng", generations,
+// cellSize, frameSkip, coloring }. Cells are 4 pixels and every
+// generation is a frame unless told otherwise; coloring is "Plain",
+// "Age" or "Activity" and only makes a difference to an APNG.
+#[cfg(any(feature = "gif", feature = "apng"))]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordOptions {
+    format: Format,
+    generations: u32,
+    #[serde(default = "default_cell_size")]
+    cell_size: u32,
+    #[serde(default)]
+    frame_skip: u32,
+    #[cfg_attr(not(feature = "apng"), allow(dead_code))]
+    coloring: Option<life::Coloring>,
+}
+
+#[cfg(any(feature = "gif", feature = "apng"))]
+fn default_cell_size() -> u32 {
+    4
+}
+
+// The same two objects for TypeScript, which would otherwise only see
+// `any`. Boundary and Engine names are spelt out by hand, so a new
+// variant has to be added here as well.
+#[wasm_bindgen(typescript_custom_section)]
+const OPTIONS: &str = r#"
+export interface UniverseOptions {
+    width?: number;
+    height?: number;
+    rule?: string;
+    boundary?: "Torus" | "Dead" | "Mirror" | "AliveWall";
+    engine?: "Naive" | "Hashlife" | "BitParallel";
+    seed?: number | bigint;
+    density?: number;
+    pattern?: string;
+}
+
+export interface RecordOptions {
+    format: "gif" | "apng";
+    generations: number;
+    cellSize?: number;
+    frameSkip?: number;
+    coloring?: "Plain" | "Age" | "Activity";
+}
+"#;
+
+#[wasm_bindgen]
+impl Universe {
+    // Every setting of a new universe in one object, e.g.
+    //
+    //     Universe.create({ width: 128, height: 96, rule: "B36/S23",
+    //                       boundary: "Dead", seed: 7, density: 0.3 })
+    //
+    // Anything left out is as with UniverseBuilder. A misspelt key is an
+    // error rather than silently ignored.
+    pub fn create(#[wasm_bindgen(unchecked_param_type = "UniverseOptions")] options: JsValue) -> Result<Universe, Error> {
+        let keys = ["width", "height", "rule", "boundary", "engine", "seed", "density", "pattern"];
+        let options: CreateOptions = from_options(options, &keys)?;
+        Ok(options.builder()?.build()?.into())
+    }
+
+    #[cfg(any(feature = "gif", feature = "apng"))]
+    pub fn record(&mut self, #[wasm_bindgen(unchecked_param_type = "RecordOptions")] options: JsValue) -> Result<Vec<u8>, Error> {
+        let keys = ["format", "generations", "cellSize", "frameSkip", "coloring"];
+        let options: RecordOptions = from_options(options, &keys)?;
+        let RecordOptions { generations, cell_size, frame_skip, .. } = options;
+        match options.format {
+            #[cfg(feature = "gif")]
+            Format::Gif => Ok(self.inner.record_gif(generations, cell_size, frame_skip)?),
+            #[cfg(feature = "apng")]
+            Format::Apng => {
+                let coloring = options.coloring.unwrap_or(life::Coloring::Plain);
+                Ok(self.inner.record_apng(generations, cell_size, frame_skip, coloring)?)
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(Error::new("this build can't record in that format")),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{life, options, Error, PatternFormat};
+
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    inner: life::Pattern,
+}
+
+impl Pattern {
+    pub(crate) fn inner(&self) -> &life::Pattern {
+        &self.inner
+    }
+}
+
+impl From<life::Pattern> for Pattern {
+    fn from(inner: life::Pattern) -> Pattern {
+        Pattern { inner }
+    }
+}
+
+#[wasm_bindgen]
+impl Pattern {
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u32, height: u32, cells: &[u32]) -> Result<Pattern, Error> {
+        Ok(life::Pattern::new(width, height, cells)?.into())
+    }
+
+    pub fn from_rle(rle: &str) -> Result<Pattern, Error> {
+        Ok(life::Pattern::from_rle(rle)?.into())
+    }
+
+    // Whatever the format, see PatternFormat.
+    pub fn parse(text: &str) -> Result<Pattern, Error> {
+        Ok(life::Pattern::parse(text)?.into())
+    }
+
+    // The bytes of a dropped or downloaded file, e.g. from
+    // `new Uint8Array(await file.arrayBuffer())`, gzipped or not.
+    pub fn from_file(bytes: &[u8]) -> Result<Pattern, Error> {
+        Ok(life::Pattern::from_file(bytes)?.into())
+    }
+
+    pub fn from_plaintext(text: &str) -> Result<Pattern, Error> {
+        Ok(life::Pattern::from_plaintext(text)?.into())
+    }
+
+    // E.g. "xq4_153", a glider, as found on Catagolue.
+    pub fn from_apgcode(code: &str) -> Result<Pattern, Error> {
+        Ok(life::Pattern::from_apgcode(code)?.into())
+    }
+
+    pub fn builtin(name: &str) -> Result<Pattern, Error> {
+        Ok(life::Pattern::builtin(name)?.into())
+    }
+
+    pub fn width(&self) -> u32 {
+        self.inner.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.inner.height()
+    }
+
+    pub fn cells(&self) -> Vec<u32> {
+        self.inner.cells()
+    }
+
+    pub fn rotate90(&self) -> Pattern {
+        self.inner.rotate90().into()
+    }
+
+    pub fn flip_h(&self) -> Pattern {
+        self.inner.flip_h().into()
+    }
+
+    pub fn flip_v(&self) -> Pattern {
+        self.inner.flip_v().into()
+    }
+
+    pub fn translate(&self, rows: i32, cols: i32) -> Pattern {
+        self.inner.translate(rows, cols).into()
+    }
+
+    pub fn to_rle(&self) -> String {
+        self.inner.to_rle()
+    }
+
+    pub fn to_plaintext(&self) -> Result<String, Error> {
+        Ok(self.inner.to_plaintext()?)
+    }
+
+    pub fn to_text(&self, format: PatternFormat) -> Result<String, Error> {
+        Ok(self.inner.to_text(format.into())?)
+    }
+
+    pub fn apgcode(&self, rule: &str) -> Result<String, Error> {
+        Ok(self.inner.apgcode(rule)?)
+    }
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const PATTERN_INFO_JSON: &str = r#"
+export interface PatternInfoJSON {
+    name: string;
+    width: number;
+    height: number;
+}
+"#;
+
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternInfo {
+    inner: life::PatternInfo,
+}
+
+#[wasm_bindgen]
+impl PatternInfo {
+    pub fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    pub fn width(&self) -> u32 {
+        self.inner.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.inner.height()
+    }
+
+    #[wasm_bindgen(js_name = toJSON, unchecked_return_type = "PatternInfoJSON")]
+    pub fn to_json(&self) -> JsValue {
+        options::to_js(&self.inner)
+    }
+}
+
+#[wasm_bindgen]
+pub fn list_patterns() -> Vec<PatternInfo> {
+    life::list_patterns().into_iter().map(|inner| PatternInfo { inner }).collect()
+}
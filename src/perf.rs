@@ -0,0 +1,73 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{life, options};
+
+thread_local! {
+    static PERFORMANCE: Option<web_sys::Performance> = web_sys::window().and_then(|window| window.performance());
+}
+
+// performance.now(), or Date.now() where there is no window to ask.
+pub fn now() -> f64 {
+    PERFORMANCE.with(|performance| match performance {
+        Some(performance) => performance.now(),
+        None => js_sys::Date::now(),
+    })
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const PERF_STATS_JSON: &str = r#"
+export interface PerfStatsJSON {
+    ticks: number;
+    lastTickMs: number;
+    averageTickMs: number;
+    countMs: number;
+    writeMs: number;
+    allocations: number;
+}
+"#;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PerfStats {
+    inner: life::PerfStats,
+}
+
+impl From<life::PerfStats> for PerfStats {
+    fn from(inner: life::PerfStats) -> PerfStats {
+        PerfStats { inner }
+    }
+}
+
+#[wasm_bindgen]
+impl PerfStats {
+    pub fn ticks(&self) -> u64 {
+        self.inner.ticks
+    }
+
+    pub fn last_tick_ms(&self) -> f64 {
+        self.inner.last_tick_ms
+    }
+
+    pub fn average_tick_ms(&self) -> f64 {
+        self.inner.average_tick_ms
+    }
+
+    pub fn count_ms(&self) -> f64 {
+        self.inner.count_ms
+    }
+
+    pub fn write_ms(&self) -> f64 {
+        self.inner.write_ms
+    }
+
+    pub fn allocations(&self) -> u64 {
+        self.inner.allocations
+    }
+
+    // So that JSON.stringify(universe.perf_stats()) has the numbers in
+    // it. The counts are plain numbers there, as JSON has no BigInt.
+    #[wasm_bindgen(js_name = toJSON, unchecked_return_type = "PerfStatsJSON")]
+    pub fn to_json(&self) -> JsValue {
+        options::to_js(&self.inner)
+    }
+}
@@ -0,0 +1,95 @@
+use js_sys::Function;
+use wasm_bindgen::prelude::*;
+
+use crate::{life, views, Error, PlayerState, Universe};
+
+// Play, pause and step controls that work the same with a canvas, in a
+// worker or headless: the page passes in the universe and the time.
+//
+//     const player = new Player();
+//     player.set_on_state_change(state => button.textContent = state === PlayerState.Playing ? "⏸" : "▶");
+//     const frame = now => {
+//         player.advance(universe, now);
+//         renderer.draw(universe);
+//         requestAnimationFrame(frame);
+//     };
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct Player {
+    inner: life::Player,
+    on_state_change: Option<Function>,
+}
+
+impl Player {
+    // Runs `change`, then tells JavaScript if the state is any different.
+    fn changing<T>(&mut self, change: impl FnOnce(&mut life::Player) -> T) -> T {
+        let before = self.inner.state();
+        let result = change(&mut self.inner);
+        let after = self.inner.state();
+        if let (true, Some(callback)) = (before != after, &self.on_state_change) {
+            let _ = callback.call1(&JsValue::NULL, &PlayerState::from(after).into());
+        }
+        result
+    }
+}
+
+#[wasm_bindgen]
+impl Player {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Player {
+        Player::default()
+    }
+
+    pub fn state(&self) -> PlayerState {
+        self.inner.state().into()
+    }
+
+    // Called with the new PlayerState whenever it changes.
+    pub fn set_on_state_change(&mut self, callback: Option<Function>) {
+        self.on_state_change = callback;
+    }
+
+    pub fn play(&mut self) {
+        self.changing(|player| player.play())
+    }
+
+    pub fn pause(&mut self) {
+        self.changing(|player| player.pause())
+    }
+
+    pub fn toggle(&mut self) {
+        self.changing(|player| player.toggle())
+    }
+
+    // In generations per second.
+    pub fn speed(&self) -> f64 {
+        self.inner.speed()
+    }
+
+    pub fn set_speed(&mut self, generations_per_second: f64) -> Result<(), Error> {
+        Ok(self.inner.set_speed(generations_per_second)?)
+    }
+
+    pub fn faster(&mut self) {
+        self.inner.faster();
+    }
+
+    pub fn slower(&mut self) {
+        self.inner.slower();
+    }
+
+    pub fn step(&mut self, universe: &mut Universe) {
+        self.changing(|player| player.step(&mut universe.inner));
+        views::check_memory();
+    }
+
+    pub fn step_back(&mut self, universe: &mut Universe) -> bool {
+        views::stepped(self.changing(|player| player.step_back(&mut universe.inner)))
+    }
+
+    // `now` is in milliseconds, e.g. the requestAnimationFrame timestamp
+    // or performance.now().
+    pub fn advance(&mut self, universe: &mut Universe, now: f64) -> u32 {
+        views::stepped(self.inner.advance(&mut universe.inner, now))
+    }
+}
@@ -0,0 +1,324 @@
+use fixedbitset::FixedBitSet;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+use crate::life::ContinuousEngine;
+use crate::utils::Timer;
+use crate::{life, CyclicUniverse, Error, ForestFire, Lenia, MargolusUniverse, SmoothLife, TriangularUniverse, Universe, Universe1D};
+
+const GRID_COLOR: &str = "#CCCCCC";
+const DEAD_COLOR: &str = "#FFFFFF";
+const ALIVE_COLOR: &str = "#000000";
+// Live cells of color 1, 2, ... when the universe has several species.
+const SPECIES_COLORS: [&str; 4] = ["#000000", "#D62728", "#2CA02C", "#1F77B4"];
+// How many shades continuous values are rounded to, from dead to alive.
+const LEVELS: u32 = 32;
+
+// Draws a universe onto a 2d canvas: one cell_size square per cell,
+// with a 1px grid line around each of them, like the original demo.
+//
+// The renderer remembers what is already on the canvas, so each draw()
+// only repaints the cells that changed since the previous one, however
+// many ticks, edits or undos happened in between.
+#[wasm_bindgen]
+pub struct Renderer {
+    canvas: HtmlCanvasElement,
+    ctx: CanvasRenderingContext2d,
+    cell_size: u32,
+    alive_color: String,
+    dead_color: String,
+    grid_color: String,
+    // What the canvas currently shows, and for which size of universe.
+    // None means the next draw() has to start from scratch.
+    drawn: Option<(u32, u32, FixedBitSet)>,
+    species_colors: Vec<String>,
+    // The same for universes with colors or dying cells, keeping the
+    // color or state of each cell.
+    drawn_indexed: Option<(u32, u32, Vec<u8>)>,
+}
+
+impl Renderer {
+    fn fill_cells<I: Iterator<Item = usize>>(&self, cells: I, width: u32, color: &str) {
+        self.ctx.set_fill_style_str(color);
+        let pitch = f64::from(self.cell_size + 1);
+        let size = f64::from(self.cell_size);
+        for idx in cells {
+            let row = idx as u32 / width;
+            let col = idx as u32 % width;
+            self.ctx
+                .fill_rect(f64::from(col) * pitch + 1.0, f64::from(row) * pitch + 1.0, size, size);
+        }
+    }
+
+    // Sizes the canvas for the universe and draws every line of the grid.
+    fn draw_grid(&self, width: u32, height: u32) {
+        let pitch = f64::from(self.cell_size + 1);
+        let (w, h) = (f64::from(width) * pitch + 1.0, f64::from(height) * pitch + 1.0);
+        self.canvas.set_width(w as u32);
+        self.canvas.set_height(h as u32);
+
+        self.ctx.begin_path();
+        self.ctx.set_stroke_style_str(&self.grid_color);
+        for i in 0..=width {
+            let x = f64::from(i) * pitch + 1.0;
+            self.ctx.move_to(x, 0.0);
+            self.ctx.line_to(x, h);
+        }
+        for j in 0..=height {
+            let y = f64::from(j) * pitch + 1.0;
+            self.ctx.move_to(0.0, y);
+            self.ctx.line_to(w, y);
+        }
+        self.ctx.stroke();
+    }
+
+    // Draws a width x height universe with a color or state per cell,
+    // `values`, filling each cell with the CSS color at its value in `palette`.
+    fn draw_indexed(&mut self, width: u32, height: u32, values: &[u8], palette: &[String]) {
+        let mut drawn = match self.drawn_indexed.take() {
+            Some((w, h, colors)) if (w, h) == (width, height) => colors,
+            _ => {
+                self.draw_grid(width, height);
+                self.fill_cells(0..(width * height) as usize, width, &self.dead_color);
+                vec![0; (width * height) as usize]
+            }
+        };
+
+        // Everything that differs from the canvas, by new value.
+        let mut repaint: Vec<Vec<usize>> = Vec::new();
+        for (idx, (old, &new)) in drawn.iter_mut().zip(values).enumerate() {
+            if *old != new {
+                if repaint.len() <= usize::from(new) {
+                    repaint.resize(usize::from(new) + 1, Vec::new());
+                }
+                repaint[usize::from(new)].push(idx);
+                *old = new;
+            }
+        }
+        for (value, cells) in repaint.into_iter().enumerate() {
+            let fill = palette.get(value).unwrap_or(&self.alive_color);
+            self.fill_cells(cells.into_iter(), width, fill);
+        }
+
+        self.drawn_indexed = Some((width, height, drawn));
+    }
+
+    // Draws a width x height universe of two-state cells, packed like
+    // Universe::cells(), repainting only what changed since last time.
+    fn draw_cells(&mut self, width: u32, height: u32, cells: &[u32]) {
+        self.drawn_indexed = None;
+        let mut drawn = match self.drawn.take() {
+            Some((w, h, cells)) if (w, h) == (width, height) => cells,
+            _ => {
+                self.draw_grid(width, height);
+                self.fill_cells(0..(width * height) as usize, width, &self.dead_color);
+                FixedBitSet::with_capacity((width * height) as usize)
+            }
+        };
+
+        // Everything that differs from the canvas, split into births and deaths.
+        let mut born = Vec::new();
+        let mut died = Vec::new();
+        for (word, (&old, &new)) in drawn.as_slice().iter().zip(cells).enumerate() {
+            let mut diff = old ^ new;
+            while diff != 0 {
+                let bit = diff.trailing_zeros();
+                let idx = word * 32 + bit as usize;
+                if new & (1 << bit) != 0 {
+                    born.push(idx);
+                } else {
+                    died.push(idx);
+                }
+                diff &= diff - 1;
+            }
+        }
+        self.fill_cells(born.into_iter(), width, &self.alive_color);
+        self.fill_cells(died.into_iter(), width, &self.dead_color);
+
+        drawn.as_mut_slice().copy_from_slice(cells);
+        self.drawn = Some((width, height, drawn));
+    }
+
+    // Draws a universe of continuous cells, blending from the dead color
+    // at 0 to the alive color at 1.
+    fn draw_continuous(&mut self, engine: &impl ContinuousEngine) {
+        let levels: Vec<u8> = engine
+            .cells()
+            .iter()
+            .map(|&value| (value * LEVELS as f32).round() as u8)
+            .collect();
+        let palette: Vec<String> = (0..=LEVELS)
+            .map(|level| {
+                format!(
+                    "color-mix(in srgb, {} {}%, {})",
+                    self.alive_color,
+                    100 * level / LEVELS,
+                    self.dead_color
+                )
+            })
+            .collect();
+        self.drawn = None;
+        self.draw_indexed(engine.width(), engine.height(), &levels, &palette);
+    }
+}
+
+#[wasm_bindgen]
+impl Renderer {
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas: HtmlCanvasElement, cell_size: u32) -> Result<Renderer, Error> {
+        if cell_size == 0 {
+            return Err(Error::new("cell size must be at least 1px"));
+        }
+        let ctx = canvas
+            .get_context("2d")
+            .map_err(|_| Error::new("could not get a 2d context"))?
+            .ok_or_else(|| Error::new("canvas already has a different kind of context"))?
+            .dyn_into::<CanvasRenderingContext2d>()
+            .map_err(|_| Error::new("could not get a 2d context"))?;
+        Ok(Renderer {
+            canvas,
+            ctx,
+            cell_size,
+            alive_color: ALIVE_COLOR.to_string(),
+            dead_color: DEAD_COLOR.to_string(),
+            grid_color: GRID_COLOR.to_string(),
+            drawn: None,
+            species_colors: SPECIES_COLORS.iter().map(|color| color.to_string()).collect(),
+            drawn_indexed: None,
+        })
+    }
+
+    // Any CSS color. Takes effect on the next draw(), which repaints everything.
+    pub fn set_colors(&mut self, alive: &str, dead: &str, grid: &str) {
+        self.alive_color = alive.to_string();
+        self.dead_color = dead.to_string();
+        self.grid_color = grid.to_string();
+        self.invalidate();
+    }
+
+    // One CSS color per species color, for universes with several
+    // species. Colors past the end of the list are drawn in the alive color.
+    pub fn set_species_colors(&mut self, colors: Vec<String>) {
+        self.species_colors = colors;
+        self.invalidate();
+    }
+
+    // Forgets what is on the canvas, e.g. after something else drew on it.
+    pub fn invalidate(&mut self) {
+        self.drawn = None;
+        self.drawn_indexed = None;
+    }
+
+    pub fn draw(&mut self, universe: &Universe) {
+        let _timer = Timer::new("Renderer::draw");
+        let universe = universe.inner();
+        if !universe.colors().is_empty() {
+            let mut palette = vec![self.dead_color.clone()];
+            palette.extend(self.species_colors.iter().cloned());
+            self.drawn = None;
+            return self.draw_indexed(universe.width(), universe.height(), universe.colors(), &palette);
+        }
+        if universe.is_wireworld() {
+            // Golly's colors: blue heads, red tails and yellow wire.
+            let palette = [self.dead_color.clone(), "#0080ff".into(), "#ff4000".into(), "#ffd000".into()];
+            self.drawn = None;
+            return self.draw_indexed(universe.width(), universe.height(), universe.states(), &palette);
+        }
+        if !universe.states().is_empty() {
+            // Dying cells fade from black to white, lighter with every state.
+            let states = u32::from(universe.state_count());
+            let mut palette = vec![self.dead_color.clone(), self.alive_color.clone()];
+            palette.extend((2..states).map(|state| {
+                let gray = 255 * (state - 1) / (states - 1);
+                format!("rgb({0}, {0}, {0})", gray)
+            }));
+            // A rule file's @COLORS win over the fade, state by state.
+            if let Some(table) = universe.rule_table() {
+                for (color, rgb) in palette.iter_mut().zip(table.colors()) {
+                    if let Some([r, g, b]) = rgb {
+                        *color = format!("rgb({}, {}, {})", r, g, b);
+                    }
+                }
+            }
+            self.drawn = None;
+            return self.draw_indexed(universe.width(), universe.height(), universe.states(), &palette);
+        }
+        self.draw_cells(universe.width(), universe.height(), universe.cells());
+    }
+
+    pub fn draw_margolus(&mut self, universe: &MargolusUniverse) {
+        let universe = universe.inner();
+        self.draw_cells(universe.width(), universe.height(), universe.cells());
+    }
+
+    // Each state gets its own hue, evenly spaced around the color wheel.
+    pub fn draw_cyclic(&mut self, universe: &CyclicUniverse) {
+        let universe = universe.inner();
+        let states = u32::from(universe.states());
+        let palette: Vec<String> = (0..states)
+            .map(|state| format!("hsl({}, 80%, 50%)", 360 * state / states))
+            .collect();
+        self.draw_indexed(universe.width(), universe.height(), universe.cells(), &palette);
+    }
+
+    // Empty cells in the dead color, trees green and fires orange.
+    pub fn draw_forest_fire(&mut self, forest: &ForestFire) {
+        let forest = forest.inner();
+        let palette = [self.dead_color.clone(), "#208020".into(), "#ff8000".into()];
+        self.draw_indexed(forest.width(), forest.height(), forest.cells(), &palette);
+    }
+
+    // The space-time diagram, newest generation on top.
+    pub fn draw_1d(&mut self, universe: &Universe1D) {
+        let universe = universe.inner();
+        self.draw_cells(universe.width(), universe.height(), universe.cells());
+    }
+
+    pub fn draw_lenia(&mut self, lenia: &Lenia) {
+        self.draw_continuous(lenia.inner());
+    }
+
+    pub fn draw_smooth_life(&mut self, life: &SmoothLife) {
+        self.draw_continuous(life.inner());
+    }
+
+    // Draws a universe of triangles, cell_size pixels on a side, with
+    // the grid lines around every triangle. Repaints everything each time.
+    pub fn draw_triangular(&mut self, universe: &TriangularUniverse) {
+        self.invalidate();
+        let universe = universe.inner();
+        let (width, height) = (universe.width(), universe.height());
+        let side = f64::from(self.cell_size);
+        let rise = side * 3f64.sqrt() / 2.0;
+        self.canvas.set_width((f64::from(width + 1) * side / 2.0).ceil() as u32);
+        self.canvas.set_height((f64::from(height) * rise).ceil() as u32);
+        self.ctx.set_fill_style_str(&self.dead_color);
+        self.ctx.fill_rect(0.0, 0.0, f64::from(self.canvas.width()), f64::from(self.canvas.height()));
+
+        let cells = universe.cells();
+        let triangles = |alive: Option<bool>| {
+            self.ctx.begin_path();
+            for row in 0..height {
+                for col in 0..width {
+                    let idx = (row * width + col) as usize;
+                    if alive.is_some_and(|alive| (cells[idx / 32] >> (idx % 32) & 1 == 1) != alive) {
+                        continue;
+                    }
+                    let (x, y) = (f64::from(col) * side / 2.0, f64::from(row) * rise);
+                    let (tip, base) = if life::Triangular::points_up(row, col) { (y, y + rise) } else { (y + rise, y) };
+                    self.ctx.move_to(x, base);
+                    self.ctx.line_to(x + side / 2.0, tip);
+                    self.ctx.line_to(x + side, base);
+                    self.ctx.close_path();
+                }
+            }
+        };
+        triangles(Some(true));
+        self.ctx.set_fill_style_str(&self.alive_color);
+        self.ctx.fill();
+        triangles(None);
+        self.ctx.set_stroke_style_str(&self.grid_color);
+        self.ctx.stroke();
+    }
+}
@@ -0,0 +1,114 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{life, Error};
+use life::ContinuousEngine;
+
+#[wasm_bindgen]
+pub struct SmoothLife {
+    inner: life::SmoothLife,
+}
+
+impl SmoothLife {
+    pub(crate) fn inner(&self) -> &life::SmoothLife {
+        &self.inner
+    }
+}
+
+impl From<life::SmoothLife> for SmoothLife {
+    fn from(inner: life::SmoothLife) -> SmoothLife {
+        SmoothLife { inner }
+    }
+}
+
+#[wasm_bindgen]
+impl SmoothLife {
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u32, height: u32) -> Result<SmoothLife, Error> {
+        Ok(life::SmoothLife::new(width, height)?.into())
+    }
+
+    // `count` full disks of the outer radius at random places.
+    pub fn random(width: u32, height: u32, count: u32, seed: u64) -> Result<SmoothLife, Error> {
+        Ok(life::SmoothLife::random(width, height, count, seed)?.into())
+    }
+
+    pub fn tick(&mut self) {
+        self.inner.tick();
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        self.inner.tick_many(n);
+    }
+
+    pub fn width(&self) -> u32 {
+        self.inner.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.inner.height()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    // A pointer to width * height f32 values, row by row, for a
+    // Float32Array over the wasm memory.
+    pub fn cells(&self) -> *const f32 {
+        self.inner.cells().as_ptr()
+    }
+
+    pub fn mass(&self) -> f32 {
+        self.inner.mass()
+    }
+
+    pub fn get_cell(&self, row: u32, col: u32) -> Result<f32, Error> {
+        Ok(self.inner.get_cell(row, col)?)
+    }
+
+    pub fn set_cell(&mut self, row: u32, col: u32, value: f32) -> Result<(), Error> {
+        Ok(self.inner.set_cell(row, col, value)?)
+    }
+
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    pub fn radius(&self) -> u32 {
+        self.inner.radius()
+    }
+
+    pub fn set_radius(&mut self, radius: u32) -> Result<(), Error> {
+        Ok(self.inner.set_radius(radius)?)
+    }
+
+    // [low, high]
+    pub fn birth(&self) -> Vec<f32> {
+        let (low, high) = self.inner.birth();
+        vec![low, high]
+    }
+
+    pub fn set_birth(&mut self, low: f32, high: f32) -> Result<(), Error> {
+        Ok(self.inner.set_birth(low, high)?)
+    }
+
+    // [low, high]
+    pub fn death(&self) -> Vec<f32> {
+        let (low, high) = self.inner.death();
+        vec![low, high]
+    }
+
+    pub fn set_death(&mut self, low: f32, high: f32) -> Result<(), Error> {
+        Ok(self.inner.set_death(low, high)?)
+    }
+
+    // [over the ring, over the inner disk]
+    pub fn steepness(&self) -> Vec<f32> {
+        let (n, m) = self.inner.steepness();
+        vec![n, m]
+    }
+
+    pub fn set_steepness(&mut self, n: f32, m: f32) -> Result<(), Error> {
+        Ok(self.inner.set_steepness(n, m)?)
+    }
+}
@@ -0,0 +1,51 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{life, options};
+
+#[wasm_bindgen(typescript_custom_section)]
+const TICK_STATS_JSON: &str = r#"
+export interface TickStatsJSON {
+    births: number;
+    deaths: number;
+    population: number;
+    changed: number;
+}
+"#;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TickStats {
+    inner: life::TickStats,
+}
+
+impl From<life::TickStats> for TickStats {
+    fn from(inner: life::TickStats) -> TickStats {
+        TickStats { inner }
+    }
+}
+
+#[wasm_bindgen]
+impl TickStats {
+    pub fn births(&self) -> u32 {
+        self.inner.births()
+    }
+
+    pub fn deaths(&self) -> u32 {
+        self.inner.deaths()
+    }
+
+    pub fn population(&self) -> u32 {
+        self.inner.population()
+    }
+
+    pub fn changed(&self) -> u32 {
+        self.inner.changed()
+    }
+
+    // { births, deaths, population, changed }, for JSON.stringify() and
+    // for keeping around after the universe has ticked on.
+    #[wasm_bindgen(js_name = toJSON, unchecked_return_type = "TickStatsJSON")]
+    pub fn to_json(&self) -> JsValue {
+        options::to_js(&self.inner)
+    }
+}
@@ -0,0 +1,244 @@
+use js_sys::{Array, Date, Object, Promise, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+use web_sys::{IdbDatabase, IdbObjectStore, IdbRequest, IdbTransactionMode};
+
+use crate::{Error, Universe};
+
+// Snapshots are kept in IndexedDB, which takes binary blobs of any size,
+// one record per name in a single object store:
+//
+//     await save_universe("my glider gun", universe);
+//     for (const saved of await list_saved_universes()) { ... }
+//     universe = await load_universe("my glider gun");
+//
+// A record holds what Universe.save() writes, plus enough to list the
+// saved universes without restoring them: the rule, generation, size,
+// population, when it was saved and a small thumbnail.
+const DATABASE: &str = "wasm-game-of-life";
+const STORE: &str = "universes";
+
+// The longer side of a thumbnail, in pixels.
+const THUMBNAIL_SIZE: u32 = 64;
+
+fn storage_error(what: &str) -> impl Fn(JsValue) -> Error + '_ {
+    move |e| Error::new(&format!("{}: {:?}", what, e))
+}
+
+// Waits for an IndexedDB request to succeed or fail. Exactly one of the
+// two happens, so one function can handle both.
+async fn finished(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let done = request.clone();
+        let settle = Closure::once_into_js(move || {
+            let _ = match done.error() {
+                Ok(None) => resolve.call1(&JsValue::UNDEFINED, &done.result().unwrap_or(JsValue::UNDEFINED)),
+                Ok(Some(error)) => reject.call1(&JsValue::UNDEFINED, &error),
+                Err(error) => reject.call1(&JsValue::UNDEFINED, &error),
+            };
+        });
+        request.set_onsuccess(Some(settle.unchecked_ref()));
+        request.set_onerror(Some(settle.unchecked_ref()));
+    });
+    JsFuture::from(promise).await
+}
+
+async fn open() -> Result<IdbDatabase, Error> {
+    let factory = web_sys::window()
+        .and_then(|window| window.indexed_db().ok().flatten())
+        .ok_or_else(|| Error::new("IndexedDB is not available"))?;
+    let request = factory
+        .open_with_u32(DATABASE, 1)
+        .map_err(storage_error("could not open the database"))?;
+    // Only runs the first time, to create the store.
+    let upgrading = request.clone();
+    let upgrade: Closure<dyn FnMut()> = Closure::once(move || {
+        if let Ok(database) = upgrading.result() {
+            let _ = database.unchecked_into::<IdbDatabase>().create_object_store(STORE);
+        }
+    });
+    request.set_onupgradeneeded(Some(upgrade.as_ref().unchecked_ref()));
+    let database = finished(&request).await.map_err(storage_error("could not open the database"))?;
+    request.set_onupgradeneeded(None);
+    Ok(database.unchecked_into())
+}
+
+async fn store(mode: IdbTransactionMode) -> Result<IdbObjectStore, Error> {
+    open()
+        .await?
+        .transaction_with_str_and_mode(STORE, mode)
+        .and_then(|transaction| transaction.object_store(STORE))
+        .map_err(storage_error("could not use the database"))
+}
+
+fn set(record: &Object, key: &str, value: impl Into<JsValue>) {
+    let _ = Reflect::set(record, &key.into(), &value.into());
+}
+
+fn get(record: &JsValue, key: &str) -> JsValue {
+    Reflect::get(record, &key.into()).unwrap_or(JsValue::UNDEFINED)
+}
+
+fn number(record: &JsValue, key: &str) -> f64 {
+    get(record, key).as_f64().unwrap_or(0.0)
+}
+
+fn bytes(record: &JsValue, key: &str) -> Vec<u8> {
+    get(record, key).dyn_into::<Uint8Array>().map(|array| array.to_vec()).unwrap_or_default()
+}
+
+// What list_saved_universes() knows about a snapshot without restoring it.
+#[wasm_bindgen]
+pub struct SavedUniverse {
+    name: String,
+    rule: String,
+    generation: u64,
+    width: u32,
+    height: u32,
+    population: u32,
+    saved_at: f64,
+    thumbnail: Vec<u8>,
+    thumbnail_width: u32,
+    thumbnail_height: u32,
+}
+
+impl SavedUniverse {
+    fn from_record(record: &JsValue) -> SavedUniverse {
+        SavedUniverse {
+            name: get(record, "name").as_string().unwrap_or_default(),
+            rule: get(record, "rule").as_string().unwrap_or_default(),
+            generation: number(record, "generation") as u64,
+            width: number(record, "width") as u32,
+            height: number(record, "height") as u32,
+            population: number(record, "population") as u32,
+            saved_at: number(record, "savedAt"),
+            thumbnail: bytes(record, "thumbnail"),
+            thumbnail_width: number(record, "thumbnailWidth") as u32,
+            thumbnail_height: number(record, "thumbnailHeight") as u32,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl SavedUniverse {
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn rule(&self) -> String {
+        self.rule.clone()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn population(&self) -> u32 {
+        self.population
+    }
+
+    // Milliseconds since the epoch, for `new Date(saved.saved_at())`.
+    pub fn saved_at(&self) -> f64 {
+        self.saved_at
+    }
+
+    // RGBA pixels, ready for `new ImageData(...)`.
+    pub fn thumbnail(&self) -> Vec<u8> {
+        self.thumbnail.clone()
+    }
+
+    pub fn thumbnail_width(&self) -> u32 {
+        self.thumbnail_width
+    }
+
+    pub fn thumbnail_height(&self) -> u32 {
+        self.thumbnail_height
+    }
+}
+
+// Saves the universe under `name`, replacing whatever was saved there.
+// The snapshot is taken right away, so the universe can keep ticking
+// while the promise settles.
+#[wasm_bindgen]
+pub fn save_universe(name: &str, universe: &Universe) -> Promise {
+    let universe = &universe.inner;
+    let snapshot = match universe.save() {
+        Ok(snapshot) => snapshot,
+        Err(error) => return Promise::reject(&Error::from(error).into()),
+    };
+    let (width, height) = (universe.width(), universe.height());
+    let zoom = f64::from(THUMBNAIL_SIZE) / f64::from(width.max(height));
+    let thumbnail_width = ((f64::from(width) * zoom).ceil() as u32).clamp(1, THUMBNAIL_SIZE);
+    let thumbnail_height = ((f64::from(height) * zoom).ceil() as u32).clamp(1, THUMBNAIL_SIZE);
+    let thumbnail = universe
+        .render_viewport(0, 0, thumbnail_width, thumbnail_height, zoom)
+        .unwrap_or_default();
+
+    let record = Object::new();
+    set(&record, "name", name);
+    set(&record, "rule", universe.rule());
+    set(&record, "generation", universe.generation() as f64);
+    set(&record, "width", width);
+    set(&record, "height", height);
+    set(&record, "population", universe.population());
+    set(&record, "savedAt", Date::now());
+    set(&record, "thumbnail", Uint8Array::from(&thumbnail[..]));
+    set(&record, "thumbnailWidth", thumbnail_width);
+    set(&record, "thumbnailHeight", thumbnail_height);
+    set(&record, "snapshot", Uint8Array::from(&snapshot[..]));
+
+    let name = name.to_string();
+    future_to_promise(async move {
+        let request = store(IdbTransactionMode::Readwrite)
+            .await?
+            .put_with_key(&record, &name.into())
+            .map_err(storage_error("could not save the universe"))?;
+        finished(&request).await.map_err(storage_error("could not save the universe"))?;
+        Ok(JsValue::UNDEFINED)
+    })
+}
+
+#[wasm_bindgen]
+pub async fn load_universe(name: String) -> Result<Universe, Error> {
+    let request = store(IdbTransactionMode::Readonly)
+        .await?
+        .get(&name.clone().into())
+        .map_err(storage_error("could not load the universe"))?;
+    let record = finished(&request).await.map_err(storage_error("could not load the universe"))?;
+    if record.is_undefined() {
+        return Err(Error::new(&format!("no universe is saved as {:?}", name)));
+    }
+    Universe::restore(&bytes(&record, "snapshot"))
+}
+
+#[wasm_bindgen]
+pub async fn list_saved_universes() -> Result<Vec<SavedUniverse>, Error> {
+    let request = store(IdbTransactionMode::Readonly)
+        .await?
+        .get_all()
+        .map_err(storage_error("could not list the saved universes"))?;
+    let records: Array = finished(&request)
+        .await
+        .map_err(storage_error("could not list the saved universes"))?
+        .unchecked_into();
+    Ok(records.iter().map(|record| SavedUniverse::from_record(&record)).collect())
+}
+
+#[wasm_bindgen]
+pub async fn delete_saved_universe(name: String) -> Result<(), Error> {
+    let request = store(IdbTransactionMode::Readwrite)
+        .await?
+        .delete(&name.into())
+        .map_err(storage_error("could not delete the universe"))?;
+    finished(&request).await.map_err(storage_error("could not delete the universe"))?;
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use wasm_bindgen::prelude::*;
+
+use crate::life;
+
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct SvgOptions {
+    inner: life::SvgOptions,
+}
+
+impl SvgOptions {
+    pub(crate) fn inner(&self) -> &life::SvgOptions {
+        &self.inner
+    }
+}
+
+#[wasm_bindgen]
+impl SvgOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> SvgOptions {
+        SvgOptions::default()
+    }
+
+    pub fn set_cell_size(&mut self, cell_size: u32) {
+        self.inner.set_cell_size(cell_size);
+    }
+
+    pub fn set_colors(&mut self, alive: &str, background: &str) {
+        self.inner.set_colors(alive, background);
+    }
+
+    pub fn set_grid(&mut self, color: &str) {
+        self.inner.set_grid(color);
+    }
+
+    pub fn clear_grid(&mut self) {
+        self.inner.clear_grid();
+    }
+
+    pub fn set_viewport(&mut self, row: u32, col: u32, width: u32, height: u32) {
+        self.inner.set_viewport(row, col, width, height);
+    }
+
+    pub fn clear_viewport(&mut self) {
+        self.inner.clear_viewport();
+    }
+}
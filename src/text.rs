@@ -0,0 +1,39 @@
+use wasm_bindgen::prelude::*;
+
+use crate::life;
+
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct TextOptions {
+    inner: life::TextOptions,
+}
+
+impl TextOptions {
+    pub(crate) fn inner(&self) -> &life::TextOptions {
+        &self.inner
+    }
+}
+
+#[wasm_bindgen]
+impl TextOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> TextOptions {
+        TextOptions::default()
+    }
+
+    pub fn set_glyphs(&mut self, dead: &str, alive: &str) {
+        self.inner.set_glyphs(dead, alive);
+    }
+
+    pub fn set_row_separator(&mut self, separator: &str, trailing: bool) {
+        self.inner.set_row_separator(separator, trailing);
+    }
+
+    pub fn set_viewport(&mut self, row: u32, col: u32, width: u32, height: u32) {
+        self.inner.set_viewport(row, col, width, height);
+    }
+
+    pub fn clear_viewport(&mut self) {
+        self.inner.clear_viewport();
+    }
+}
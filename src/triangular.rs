@@ -0,0 +1,94 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{life, Cell, Error};
+
+mirror_enum! {
+    pub enum TriangularNeighborhood {
+        Edges,
+        Vertices,
+    }
+}
+
+#[wasm_bindgen]
+pub struct TriangularUniverse {
+    inner: life::TriangularUniverse,
+}
+
+impl TriangularUniverse {
+    pub(crate) fn inner(&self) -> &life::TriangularUniverse {
+        &self.inner
+    }
+}
+
+#[wasm_bindgen]
+impl TriangularUniverse {
+    pub fn with_rule(
+        width: u32,
+        height: u32,
+        neighborhood: TriangularNeighborhood,
+        rule: &str,
+    ) -> Result<TriangularUniverse, Error> {
+        Ok(TriangularUniverse {
+            inner: life::TriangularUniverse::with_rule(width, height, neighborhood.into(), rule)?,
+        })
+    }
+
+    pub fn rule(&self) -> String {
+        self.inner.rule()
+    }
+
+    pub fn neighborhood(&self) -> TriangularNeighborhood {
+        self.inner.topology().neighborhood().into()
+    }
+
+    pub fn tick(&mut self) {
+        self.inner.tick();
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        self.inner.tick_many(n);
+    }
+
+    pub fn width(&self) -> u32 {
+        self.inner.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.inner.height()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    pub fn population(&self) -> u32 {
+        self.inner.population()
+    }
+
+    // Whether the triangle at (row, col) points up; see the core crate's Triangular.
+    pub fn points_up(row: u32, col: u32) -> bool {
+        life::Triangular::points_up(row, col)
+    }
+
+    // A pointer to the packed cells, bit `row * width + col`, in the
+    // same layout as Universe::cells().
+    pub fn cells(&self) -> *const u32 {
+        self.inner.cells().as_ptr()
+    }
+
+    pub fn get_cell(&self, row: u32, col: u32) -> Result<Cell, Error> {
+        Ok(self.inner.get_cell(row, col)?.into())
+    }
+
+    pub fn set_cell(&mut self, row: u32, col: u32, cell: Cell) -> Result<(), Error> {
+        Ok(self.inner.set_cell(row, col, cell.into())?)
+    }
+
+    pub fn toggle_cell(&mut self, row: u32, col: u32) -> Result<(), Error> {
+        Ok(self.inner.toggle_cell(row, col)?)
+    }
+
+    pub fn randomize(&mut self, density: f32, seed: u64) -> Result<(), Error> {
+        Ok(self.inner.randomize(density, seed)?)
+    }
+}
@@ -0,0 +1,95 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{life, Boundary, Cell, Error};
+
+#[wasm_bindgen]
+pub struct Universe1D {
+    inner: life::Universe1D,
+}
+
+impl Universe1D {
+    pub(crate) fn inner(&self) -> &life::Universe1D {
+        &self.inner
+    }
+}
+
+#[wasm_bindgen]
+impl Universe1D {
+    // An empty diagram running an elementary rule, e.g. 30, 90 or 110.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u32, height: u32, rule: u8) -> Result<Universe1D, Error> {
+        Ok(Universe1D {
+            inner: life::Universe1D::new(width, height, rule)?,
+        })
+    }
+
+    pub fn rule(&self) -> u8 {
+        self.inner.rule()
+    }
+
+    pub fn set_rule(&mut self, rule: u8) {
+        self.inner.set_rule(rule);
+    }
+
+    pub fn boundary(&self) -> Boundary {
+        self.inner.boundary().into()
+    }
+
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.inner.set_boundary(boundary.into());
+    }
+
+    pub fn tick(&mut self) {
+        self.inner.tick();
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        self.inner.tick_many(n);
+    }
+
+    pub fn width(&self) -> u32 {
+        self.inner.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.inner.height()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    pub fn population(&self) -> u32 {
+        self.inner.population()
+    }
+
+    // A pointer to the packed diagram, in the same layout as
+    // Universe::cells(), with the current generation as row 0.
+    pub fn cells(&self) -> *const u32 {
+        self.inner.cells().as_ptr()
+    }
+
+    pub fn get_cell(&self, row: u32, col: u32) -> Result<Cell, Error> {
+        Ok(self.inner.get_cell(row, col)?.into())
+    }
+
+    pub fn set_cell(&mut self, col: u32, cell: Cell) -> Result<(), Error> {
+        Ok(self.inner.set_cell(col, cell.into())?)
+    }
+
+    pub fn toggle_cell(&mut self, col: u32) -> Result<(), Error> {
+        Ok(self.inner.toggle_cell(col)?)
+    }
+
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    pub fn seed_center(&mut self) {
+        self.inner.seed_center();
+    }
+
+    pub fn randomize(&mut self, density: f32, seed: u64) -> Result<(), Error> {
+        Ok(self.inner.randomize(density, seed)?)
+    }
+}
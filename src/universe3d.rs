@@ -0,0 +1,89 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{life, Cell, Error};
+
+mirror_enum! {
+    pub enum Axis {
+        X,
+        Y,
+        Z,
+    }
+}
+
+#[wasm_bindgen]
+pub struct Universe3D {
+    inner: life::Universe3D,
+}
+
+#[wasm_bindgen]
+impl Universe3D {
+    pub fn with_rule(width: u32, height: u32, depth: u32, rule: &str) -> Result<Universe3D, Error> {
+        Ok(Universe3D {
+            inner: life::Universe3D::with_rule(width, height, depth, rule)?,
+        })
+    }
+
+    pub fn rule(&self) -> String {
+        self.inner.rule()
+    }
+
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), Error> {
+        Ok(self.inner.set_rule(rule)?)
+    }
+
+    pub fn tick(&mut self) {
+        self.inner.tick();
+    }
+
+    pub fn tick_many(&mut self, n: u32) {
+        self.inner.tick_many(n);
+    }
+
+    pub fn width(&self) -> u32 {
+        self.inner.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.inner.height()
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.inner.depth()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    pub fn population(&self) -> u32 {
+        self.inner.population()
+    }
+
+    // A pointer to width * height * depth bytes, 0 or 1, at
+    // x + width * (y + height * z): a Uint8Array over it can go
+    // straight into texImage3D with an R8 format.
+    pub fn cells(&self) -> *const u8 {
+        self.inner.cells().as_ptr()
+    }
+
+    pub fn get_cell(&self, x: u32, y: u32, z: u32) -> Result<Cell, Error> {
+        Ok(self.inner.get_cell(x, y, z)?.into())
+    }
+
+    pub fn set_cell(&mut self, x: u32, y: u32, z: u32, cell: Cell) -> Result<(), Error> {
+        Ok(self.inner.set_cell(x, y, z, cell.into())?)
+    }
+
+    pub fn toggle_cell(&mut self, x: u32, y: u32, z: u32) -> Result<(), Error> {
+        Ok(self.inner.toggle_cell(x, y, z)?)
+    }
+
+    pub fn randomize(&mut self, density: f32, seed: u64) -> Result<(), Error> {
+        Ok(self.inner.randomize(density, seed)?)
+    }
+
+    // One cross-section of the universe; see the core crate's Universe3D::slice().
+    pub fn slice(&self, axis: Axis, index: u32) -> Result<Vec<u8>, Error> {
+        Ok(self.inner.slice(axis.into(), index)?)
+    }
+}
@@ -1,3 +1,7 @@
+use std::sync::Once;
+
+use crate::error;
+
 pub fn set_panic_hook() {
     // When the `console_error_panic_hook` feature is enabled, we can call the
     // `set_panic_hook` function at least once during initialization, and then
@@ -5,6 +9,49 @@ pub fn set_panic_hook() {
     //
     // For more details see
     // https://github.com/rustwasm/console_error_panic_hook#readme
-    #[cfg(feature = "console_error_panic_hook")]
-    console_error_panic_hook::set_once();
+    //
+    // The module calls it when it starts. Either way, the panic is also
+    // handed to the callback given to set_error_callback().
+    static SET: Once = Once::new();
+    SET.call_once(|| {
+        std::panic::set_hook(Box::new(|info| {
+            #[cfg(feature = "console_error_panic_hook")]
+            console_error_panic_hook::hook(info);
+            error::report(info.payload_as_str().unwrap_or("panic"), info.location(), true);
+        }));
+    });
+}
+
+// Shows how long something took in the devtools: console.time() when
+// it is created and console.timeEnd() when it goes out of scope, e.g.
+//
+//     let _timer = Timer::new("Universe::tick");
+//
+// With the `timers` feature off it does nothing and compiles away.
+pub struct Timer<'a> {
+    #[cfg(feature = "timers")]
+    name: &'a str,
+    #[cfg(not(feature = "timers"))]
+    name: std::marker::PhantomData<&'a str>,
+}
+
+impl<'a> Timer<'a> {
+    #[cfg(feature = "timers")]
+    pub fn new(name: &'a str) -> Timer<'a> {
+        web_sys::console::time_with_label(name);
+        Timer { name }
+    }
+
+    #[cfg(not(feature = "timers"))]
+    #[inline(always)]
+    pub fn new(_name: &'a str) -> Timer<'a> {
+        Timer { name: std::marker::PhantomData }
+    }
+}
+
+#[cfg(feature = "timers")]
+impl<'a> Drop for Timer<'a> {
+    fn drop(&mut self) {
+        web_sys::console::time_end_with_label(self.name);
+    }
 }
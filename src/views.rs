@@ -0,0 +1,105 @@
+use std::cell::RefCell;
+
+use js_sys::{ArrayBuffer, Float32Array, Function, Uint16Array, Uint8Array, WebAssembly};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::Universe;
+
+thread_local! {
+    // The memory buffer the last view was made over, if one was made.
+    static VIEWED_BUFFER: RefCell<Option<ArrayBuffer>> = const { RefCell::new(None) };
+    static GROW_CALLBACK: RefCell<Option<Function>> = const { RefCell::new(None) };
+}
+
+// When wasm memory grows, its old ArrayBuffer is detached and every
+// view over it goes empty, without an error from the browser. Once a
+// view has been handed out, the universe checks for that after every
+// tick and before making a new view, and calls `callback` the first
+// time it finds the memory has moved. Views are cheap to make again.
+#[wasm_bindgen]
+pub fn set_memory_grow_callback(callback: Option<Function>) {
+    GROW_CALLBACK.with(|current| *current.borrow_mut() = callback);
+}
+
+fn memory_buffer() -> ArrayBuffer {
+    wasm_bindgen::memory().unchecked_into::<WebAssembly::Memory>().buffer().unchecked_into()
+}
+
+pub fn check_memory() {
+    let grown = VIEWED_BUFFER.with(|viewed| {
+        let mut viewed = viewed.borrow_mut();
+        match viewed.as_ref() {
+            Some(buffer) if *buffer != memory_buffer() => {
+                *viewed = None;
+                true
+            }
+            _ => false,
+        }
+    });
+    if grown {
+        GROW_CALLBACK.with(|callback| {
+            if let Some(callback) = callback.borrow().as_ref() {
+                let _ = callback.call0(&JsValue::NULL);
+            }
+        });
+    }
+}
+
+// For the stepping methods that return something, e.g.
+// `views::stepped(self.inner.tick_delta())`.
+pub fn stepped<T>(result: T) -> T {
+    check_memory();
+    result
+}
+
+// The view constructors below are where the `unsafe` is: they lay a
+// typed array over the slice without copying it. That is sound for as
+// long as the slice stays put, which is exactly what the comment on
+// the raw pointers in lib.rs is about.
+fn viewed<T>(view: T) -> T {
+    check_memory();
+    VIEWED_BUFFER.with(|viewed| *viewed.borrow_mut() = Some(memory_buffer()));
+    view
+}
+
+fn bytes(words: &[u32]) -> &[u8] {
+    // Any u32 is four valid u8s, in wasm's little-endian order.
+    unsafe { std::slice::from_raw_parts(words.as_ptr() as *const u8, words.len() * 4) }
+}
+
+// Typed arrays over the same memory as cells(), ages() and the rest,
+// for JavaScript that would rather not do the pointer arithmetic with
+// memory.buffer itself. They go stale the same way, so make them again
+// every frame rather than keeping them.
+#[wasm_bindgen]
+impl Universe {
+    // cells_bits_len() bytes, laid out like cells().
+    pub fn cells_view(&self) -> Uint8Array {
+        viewed(unsafe { Uint8Array::view(bytes(self.inner.cells())) })
+    }
+
+    pub fn changed_cells_view(&self) -> Uint8Array {
+        viewed(unsafe { Uint8Array::view(bytes(self.inner.changed_cells())) })
+    }
+
+    pub fn ages_view(&mut self) -> Uint16Array {
+        let ages = self.inner.ages();
+        viewed(unsafe { Uint16Array::view(ages) })
+    }
+
+    pub fn activity_view(&mut self) -> Float32Array {
+        let activity = self.inner.activity();
+        viewed(unsafe { Float32Array::view(activity) })
+    }
+
+    // Empty with Species::Single, like colors().
+    pub fn colors_view(&self) -> Uint8Array {
+        viewed(unsafe { Uint8Array::view(self.inner.colors()) })
+    }
+
+    // Empty with two-state rules, like states().
+    pub fn states_view(&self) -> Uint8Array {
+        viewed(unsafe { Uint8Array::view(self.inner.states()) })
+    }
+}
@@ -0,0 +1,242 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlCanvasElement, WebGl2RenderingContext as Gl, WebGlProgram, WebGlShader, WebGlTexture, WebGlUniformLocation};
+
+use crate::utils::Timer;
+use crate::{Error, Universe};
+
+// One triangle big enough to cover the whole canvas, made up from
+// gl_VertexID alone, so there are no vertex buffers to set up.
+const VERTEX_SHADER: &str = r#"#version 300 es
+void main() {
+    vec2 p = vec2(float((gl_VertexID << 1) & 2), float(gl_VertexID & 2));
+    gl_Position = vec4(p * 2.0 - 1.0, 0.0, 1.0);
+}
+"#;
+
+// Every pixel works out which cell (or grid line) it belongs to and
+// reads that cell's bit straight out of the packed words, which are
+// uploaded as they are, TEXTURE_WIDTH words per texture row.
+const FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+precision highp int;
+precision highp usampler2D;
+
+uniform usampler2D u_cells;
+uniform uint u_width;
+uniform uint u_height;
+uniform float u_pitch;
+uniform bool u_grid;
+uniform float u_canvas_height;
+uniform vec3 u_alive;
+uniform vec3 u_dead;
+uniform vec3 u_grid_color;
+
+out vec4 color;
+
+void main() {
+    // gl_FragCoord starts at the bottom left; row 0 is at the top.
+    vec2 p = floor(vec2(gl_FragCoord.x, u_canvas_height - gl_FragCoord.y));
+    uvec2 cell = uvec2(p / u_pitch);
+    bool line = u_grid && (mod(p.x, u_pitch) < 1.0 || mod(p.y, u_pitch) < 1.0);
+    if (line || cell.x >= u_width || cell.y >= u_height) {
+        color = vec4(u_grid_color, 1.0);
+        return;
+    }
+
+    uint idx = cell.y * u_width + cell.x;
+    uint word = idx >> 5u;
+    uint width = uint(textureSize(u_cells, 0).x);
+    uint bits = texelFetch(u_cells, ivec2(int(word % width), int(word / width)), 0).r;
+    color = vec4(((bits >> (idx & 31u)) & 1u) == 1u ? u_alive : u_dead, 1.0);
+}
+"#;
+
+// Words per texture row. WebGL2 guarantees textures at least 2048 wide,
+// which with 2048 rows is enough for universes of 2^27 cells.
+const TEXTURE_WIDTH: usize = 2048;
+
+// Parses "#rgb" or "#rrggbb" into the 0..1 floats the shader wants.
+fn parse_color(color: &str) -> Result<[f32; 3], Error> {
+    let invalid = || Error::new(&format!("{:?} is not a #rgb or #rrggbb color", color));
+    let hex = color.strip_prefix('#').ok_or_else(invalid)?;
+    let digits: Vec<u32> = hex.chars().map(|c| c.to_digit(16)).collect::<Option<_>>().ok_or_else(invalid)?;
+    let channels = match digits.len() {
+        3 => [digits[0] * 17, digits[1] * 17, digits[2] * 17],
+        6 => [digits[0] * 16 + digits[1], digits[2] * 16 + digits[3], digits[4] * 16 + digits[5]],
+        _ => return Err(invalid()),
+    };
+    Ok(channels.map(|c| c as f32 / 255.0))
+}
+
+fn compile(gl: &Gl, kind: u32, source: &str) -> Result<WebGlShader, Error> {
+    let shader = gl
+        .create_shader(kind)
+        .ok_or_else(|| Error::new("could not create a shader"))?;
+    gl.shader_source(&shader, source);
+    gl.compile_shader(&shader);
+    if gl.get_shader_parameter(&shader, Gl::COMPILE_STATUS).as_bool() != Some(true) {
+        let log = gl.get_shader_info_log(&shader).unwrap_or_default();
+        return Err(Error::new(&format!("shader did not compile: {}", log)));
+    }
+    Ok(shader)
+}
+
+fn link(gl: &Gl) -> Result<WebGlProgram, Error> {
+    let program = gl
+        .create_program()
+        .ok_or_else(|| Error::new("could not create a shader program"))?;
+    gl.attach_shader(&program, &compile(gl, Gl::VERTEX_SHADER, VERTEX_SHADER)?);
+    gl.attach_shader(&program, &compile(gl, Gl::FRAGMENT_SHADER, FRAGMENT_SHADER)?);
+    gl.link_program(&program);
+    if gl.get_program_parameter(&program, Gl::LINK_STATUS).as_bool() != Some(true) {
+        let log = gl.get_program_info_log(&program).unwrap_or_default();
+        return Err(Error::new(&format!("shader program did not link: {}", log)));
+    }
+    Ok(program)
+}
+
+// Draws a universe with WebGL2: the packed cells go up as one integer
+// texture per frame and a single fragment shader does the rest, so the
+// cost barely depends on how many cells there are. The layout is the
+// same as Renderer's, grid lines included.
+#[wasm_bindgen]
+pub struct WebGlRenderer {
+    canvas: HtmlCanvasElement,
+    gl: Gl,
+    program: WebGlProgram,
+    texture: WebGlTexture,
+    cell_size: u32,
+    grid: bool,
+    colors: [[f32; 3]; 3],
+    // The cell words padded out to whole texture rows.
+    staging: Vec<u32>,
+    // The size of the texture as last allocated, in texels.
+    texture_size: (usize, usize),
+}
+
+impl WebGlRenderer {
+    fn uniform(&self, name: &str) -> Option<WebGlUniformLocation> {
+        self.gl.get_uniform_location(&self.program, name)
+    }
+
+    fn upload(&mut self, universe: &Universe) -> Result<(), Error> {
+        let words = universe.inner().cells();
+        let rows = words.len().div_ceil(TEXTURE_WIDTH);
+        self.staging.clear();
+        self.staging.extend_from_slice(words);
+        self.staging.resize(rows * TEXTURE_WIDTH, 0);
+
+        self.gl.bind_texture(Gl::TEXTURE_2D, Some(&self.texture));
+        // The view is only alive during the call below, which copies it,
+        // and nothing in between can allocate and move wasm memory.
+        let view = unsafe { js_sys::Uint32Array::view(&self.staging) };
+        let result = if self.texture_size == (TEXTURE_WIDTH, rows) {
+            self.gl
+                .tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_opt_array_buffer_view(
+                    Gl::TEXTURE_2D,
+                    0,
+                    0,
+                    0,
+                    TEXTURE_WIDTH as i32,
+                    rows as i32,
+                    Gl::RED_INTEGER,
+                    Gl::UNSIGNED_INT,
+                    Some(&view),
+                )
+        } else {
+            self.gl
+                .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_array_buffer_view(
+                    Gl::TEXTURE_2D,
+                    0,
+                    Gl::R32UI as i32,
+                    TEXTURE_WIDTH as i32,
+                    rows as i32,
+                    0,
+                    Gl::RED_INTEGER,
+                    Gl::UNSIGNED_INT,
+                    Some(&view),
+                )
+        };
+        result.map_err(|_| Error::new("could not upload the cells to the GPU"))?;
+        self.texture_size = (TEXTURE_WIDTH, rows);
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl WebGlRenderer {
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas: HtmlCanvasElement, cell_size: u32) -> Result<WebGlRenderer, Error> {
+        if cell_size == 0 {
+            return Err(Error::new("cell size must be at least 1px"));
+        }
+        let gl = canvas
+            .get_context("webgl2")
+            .map_err(|_| Error::new("could not get a WebGL2 context"))?
+            .ok_or_else(|| Error::new("WebGL2 is not available"))?
+            .dyn_into::<Gl>()
+            .map_err(|_| Error::new("could not get a WebGL2 context"))?;
+        let program = link(&gl)?;
+        let texture = gl
+            .create_texture()
+            .ok_or_else(|| Error::new("could not create a texture"))?;
+        gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
+        // Integer textures can't be filtered.
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MIN_FILTER, Gl::NEAREST as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MAG_FILTER, Gl::NEAREST as i32);
+
+        Ok(WebGlRenderer {
+            canvas,
+            gl,
+            program,
+            texture,
+            cell_size,
+            grid: true,
+            colors: [parse_color("#000000")?, parse_color("#FFFFFF")?, parse_color("#CCCCCC")?],
+            staging: Vec::new(),
+            texture_size: (0, 0),
+        })
+    }
+
+    // Colors are "#rgb" or "#rrggbb".
+    pub fn set_colors(&mut self, alive: &str, dead: &str, grid: &str) -> Result<(), Error> {
+        self.colors = [parse_color(alive)?, parse_color(dead)?, parse_color(grid)?];
+        Ok(())
+    }
+
+    // Grid lines cost a pixel per cell, which adds up on big universes
+    // drawn at 1px per cell.
+    pub fn set_grid(&mut self, grid: bool) {
+        self.grid = grid;
+    }
+
+    pub fn draw(&mut self, universe: &Universe) -> Result<(), Error> {
+        let _timer = Timer::new("WebGlRenderer::draw");
+        let pitch = self.cell_size + self.grid as u32;
+        let width = universe.width() * pitch + self.grid as u32;
+        let height = universe.height() * pitch + self.grid as u32;
+        if (self.canvas.width(), self.canvas.height()) != (width, height) {
+            self.canvas.set_width(width);
+            self.canvas.set_height(height);
+        }
+
+        self.upload(universe)?;
+
+        let gl = &self.gl;
+        gl.viewport(0, 0, width as i32, height as i32);
+        gl.use_program(Some(&self.program));
+        gl.active_texture(Gl::TEXTURE0);
+        gl.uniform1i(self.uniform("u_cells").as_ref(), 0);
+        gl.uniform1ui(self.uniform("u_width").as_ref(), universe.width());
+        gl.uniform1ui(self.uniform("u_height").as_ref(), universe.height());
+        gl.uniform1f(self.uniform("u_pitch").as_ref(), pitch as f32);
+        gl.uniform1i(self.uniform("u_grid").as_ref(), self.grid as i32);
+        gl.uniform1f(self.uniform("u_canvas_height").as_ref(), height as f32);
+        for (name, [r, g, b]) in ["u_alive", "u_dead", "u_grid_color"].iter().zip(self.colors) {
+            gl.uniform3f(self.uniform(name).as_ref(), r, g, b);
+        }
+        gl.draw_arrays(Gl::TRIANGLES, 0, 3);
+        Ok(())
+    }
+}
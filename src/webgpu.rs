@@ -0,0 +1,295 @@
+use js_sys::{Number, Uint32Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    gpu_buffer_usage, gpu_map_mode, gpu_texture_usage, GpuAutoLayoutMode, GpuBindGroup, GpuBindGroupDescriptor,
+    GpuBindGroupEntry, GpuBuffer, GpuBufferBinding, GpuBufferDescriptor, GpuComputePipeline,
+    GpuComputePipelineDescriptor, GpuDevice, GpuExtent3dDict, GpuProgrammableStage, GpuShaderModuleDescriptor,
+    GpuTexelCopyBufferInfo, GpuTexelCopyBufferLayout, GpuTexelCopyTextureInfo, GpuTexture, GpuTextureDescriptor,
+    GpuTextureFormat,
+};
+
+use crate::{Error, LifeRule, Universe};
+
+// One invocation per cell, on a torus. Each texel holds a single cell,
+// 0 or 1, and the rule comes in as the same two bitmasks LifeRule uses.
+const TICK_SHADER: &str = r#"
+@group(0) @binding(0) var current: texture_2d<u32>;
+@group(0) @binding(1) var next: texture_storage_2d<r32uint, write>;
+@group(0) @binding(2) var<uniform> rule: vec2<u32>;
+
+@compute @workgroup_size(8, 8)
+fn tick(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = vec2<i32>(textureDimensions(current));
+    let cell = vec2<i32>(id.xy);
+    if (cell.x >= size.x || cell.y >= size.y) {
+        return;
+    }
+
+    var count = 0u;
+    for (var dy = -1; dy <= 1; dy++) {
+        for (var dx = -1; dx <= 1; dx++) {
+            if (dx == 0 && dy == 0) {
+                continue;
+            }
+            let neighbor = (cell + vec2<i32>(dx, dy) + size) % size;
+            count += textureLoad(current, neighbor, 0).r;
+        }
+    }
+
+    let alive = textureLoad(current, cell, 0).r;
+    let mask = select(rule.x, rule.y, alive == 1u);
+    textureStore(next, cell, vec4<u32>((mask >> count) & 1u, 0u, 0u, 0u));
+}
+"#;
+
+const WORKGROUP_SIZE: u32 = 8;
+
+// Texture rows copied into a buffer must start on 256 byte boundaries.
+const ROW_ALIGNMENT: u32 = 256;
+
+fn gpu_error(what: &str) -> impl Fn(JsValue) -> Error + '_ {
+    move |e| Error::new(&format!("{}: {:?}", what, e))
+}
+
+fn extent(width: u32, height: u32) -> GpuExtent3dDict {
+    let size = GpuExtent3dDict::new(width);
+    size.set_height(height);
+    size
+}
+
+// A universe that lives on the GPU: generations are computed by a compute
+// shader that ping-pongs between two textures, and the cells only come
+// back to the CPU when read_cells() is asked for them. It always wraps
+// around like Boundary::Torus.
+//
+// A typical loop loads a Universe once, calls tick_many() every frame,
+// and now and then copies the result back with
+// `universe.set_cells_bits(await gpu.read_cells())`.
+#[wasm_bindgen]
+pub struct GpuUniverse {
+    device: GpuDevice,
+    pipeline: GpuComputePipeline,
+    textures: [GpuTexture; 2],
+    // bind_groups[i] reads textures[i] and writes the other one.
+    bind_groups: [GpuBindGroup; 2],
+    // Which texture holds the current generation.
+    front: usize,
+    width: u32,
+    height: u32,
+    generation: u64,
+}
+
+// bind_group(.., read) runs a generation from textures[read] into the other one.
+fn bind_group(
+    device: &GpuDevice,
+    pipeline: &GpuComputePipeline,
+    textures: &[GpuTexture; 2],
+    rule: &GpuBuffer,
+    read: usize,
+) -> Result<GpuBindGroup, Error> {
+    let view = |i: usize| textures[i].create_view().map_err(gpu_error("could not create a texture view"));
+    let entries = [
+        GpuBindGroupEntry::new_with_gpu_texture_view(0, &view(read)?),
+        GpuBindGroupEntry::new_with_gpu_texture_view(1, &view(1 - read)?),
+        GpuBindGroupEntry::new_with_gpu_buffer_binding(2, &GpuBufferBinding::new(rule)),
+    ];
+    let layout = pipeline.get_bind_group_layout(0);
+    Ok(device.create_bind_group(&GpuBindGroupDescriptor::new(&entries, &layout)))
+}
+
+impl GpuUniverse {
+    // Bytes per row of a readback buffer, padded for copy_texture_to_buffer.
+    fn readback_stride(&self) -> u32 {
+        (self.width * 4).div_ceil(ROW_ALIGNMENT) * ROW_ALIGNMENT
+    }
+}
+
+#[wasm_bindgen]
+impl GpuUniverse {
+    // Asks the browser for a GPU and sets everything up for an all-dead
+    // width x height universe. Resolves to an error if WebGPU is missing.
+    pub async fn create(width: u32, height: u32, rule: String) -> Result<GpuUniverse, Error> {
+        if width == 0 || height == 0 {
+            return Err(Error::new("universe width and height must be non-zero"));
+        }
+        let rule: LifeRule = rule.parse()?;
+        if !rule.is_life_like() {
+            return Err(Error::new("the GPU universe only runs plain B/S rules"));
+        }
+
+        let window = web_sys::window().ok_or_else(|| Error::new("WebGPU needs a window"))?;
+        let gpu = window.navigator().gpu();
+        let adapter = JsFuture::from(gpu.request_adapter())
+            .await
+            .map_err(gpu_error("could not get a GPU adapter"))?;
+        if adapter.is_null() || adapter.is_undefined() {
+            return Err(Error::new("WebGPU is not available"));
+        }
+        let adapter: web_sys::GpuAdapter = adapter.unchecked_into();
+        let device: GpuDevice = JsFuture::from(adapter.request_device())
+            .await
+            .map_err(gpu_error("could not get a GPU device"))?
+            .unchecked_into();
+
+        let module = device.create_shader_module(&GpuShaderModuleDescriptor::new(TICK_SHADER));
+        let stage = GpuProgrammableStage::new(&module);
+        stage.set_entry_point("tick");
+        let pipeline = device.create_compute_pipeline(&GpuComputePipelineDescriptor::new_with_gpu_auto_layout_mode(
+            GpuAutoLayoutMode::Auto,
+            &stage,
+        ));
+
+        let size = [Number::from(width), Number::from(height)];
+        let usage = gpu_texture_usage::TEXTURE_BINDING
+            | gpu_texture_usage::STORAGE_BINDING
+            | gpu_texture_usage::COPY_DST
+            | gpu_texture_usage::COPY_SRC;
+        let texture = || {
+            device
+                .create_texture(&GpuTextureDescriptor::new(GpuTextureFormat::R32uint, &size, usage))
+                .map_err(gpu_error("could not create a texture"))
+        };
+        let textures = [texture()?, texture()?];
+
+        let (birth, survival) = rule.masks();
+        let rule_buffer = device
+            .create_buffer(&GpuBufferDescriptor::new(
+                8,
+                gpu_buffer_usage::UNIFORM | gpu_buffer_usage::COPY_DST,
+            ))
+            .map_err(gpu_error("could not create the rule buffer"))?;
+        let masks: Vec<u8> = [u32::from(birth), u32::from(survival)]
+            .iter()
+            .flat_map(|mask| mask.to_le_bytes())
+            .collect();
+        device
+            .queue()
+            .write_buffer_with_u32_and_u8_slice(&rule_buffer, 0, &masks)
+            .map_err(gpu_error("could not upload the rule"))?;
+
+        let bind_groups = [
+            bind_group(&device, &pipeline, &textures, &rule_buffer, 0)?,
+            bind_group(&device, &pipeline, &textures, &rule_buffer, 1)?,
+        ];
+        Ok(GpuUniverse {
+            device,
+            pipeline,
+            textures,
+            bind_groups,
+            front: 0,
+            width,
+            height,
+            generation: 0,
+        })
+    }
+
+    // Copies the cells and generation of a Universe of the same size
+    // onto the GPU. The rule given to create() is kept.
+    pub fn load(&mut self, universe: &Universe) -> Result<(), Error> {
+        let universe = universe.inner();
+        if (universe.width(), universe.height()) != (self.width, self.height) {
+            return Err(Error::new("universe size does not match the GPU universe"));
+        }
+        let words = universe.cells();
+        let texels: Vec<u8> = (0..(self.width * self.height) as usize)
+            .flat_map(|idx| ((words[idx / 32] >> (idx % 32)) & 1).to_le_bytes())
+            .collect();
+        let layout = GpuTexelCopyBufferLayout::new();
+        layout.set_bytes_per_row(self.width * 4);
+        self.device
+            .queue()
+            .write_texture_with_u8_slice_and_gpu_extent_3d_dict(
+                &GpuTexelCopyTextureInfo::new(&self.textures[self.front]),
+                &texels,
+                &layout,
+                &extent(self.width, self.height),
+            )
+            .map_err(gpu_error("could not upload the cells"))?;
+        self.generation = universe.generation();
+        Ok(())
+    }
+
+    pub fn tick(&mut self) {
+        self.tick_many(1);
+    }
+
+    // Queues n generations in a single submission; the GPU works through
+    // them while JavaScript carries on.
+    pub fn tick_many(&mut self, n: u32) {
+        let encoder = self.device.create_command_encoder();
+        let pass = encoder.begin_compute_pass();
+        pass.set_pipeline(&self.pipeline);
+        for _ in 0..n {
+            pass.set_bind_group(0, Some(&self.bind_groups[self.front]));
+            pass.dispatch_workgroups_with_workgroup_count_y(
+                self.width.div_ceil(WORKGROUP_SIZE),
+                self.height.div_ceil(WORKGROUP_SIZE),
+            );
+            self.front = 1 - self.front;
+        }
+        pass.end();
+        self.device.queue().submit(&[encoder.finish()]);
+        self.generation += u64::from(n);
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    // Reads the current generation back from the GPU. Resolves to the
+    // packed cell words, in the same layout as Universe::cells_bits(),
+    // ready for Universe::set_cells_bits().
+    pub fn read_cells(&self) -> Result<js_sys::Promise, Error> {
+        let (width, height) = (self.width, self.height);
+        let stride = self.readback_stride();
+        let buffer = self
+            .device
+            .create_buffer(&GpuBufferDescriptor::new(
+                stride * height,
+                gpu_buffer_usage::MAP_READ | gpu_buffer_usage::COPY_DST,
+            ))
+            .map_err(gpu_error("could not create the readback buffer"))?;
+
+        let encoder = self.device.create_command_encoder();
+        let destination = GpuTexelCopyBufferInfo::new(&buffer);
+        destination.set_bytes_per_row(stride);
+        encoder
+            .copy_texture_to_buffer_with_gpu_extent_3d_dict(
+                &GpuTexelCopyTextureInfo::new(&self.textures[self.front]),
+                &destination,
+                &extent(width, height),
+            )
+            .map_err(gpu_error("could not copy the cells"))?;
+        self.device.queue().submit(&[encoder.finish()]);
+
+        Ok(wasm_bindgen_futures::future_to_promise(async move {
+            JsFuture::from(buffer.map_async(gpu_map_mode::READ)).await?;
+            let mapped = buffer.get_mapped_range()?;
+            let texels = Uint32Array::new(&mapped).to_vec();
+            buffer.unmap();
+            buffer.destroy();
+
+            let row_texels = (stride / 4) as usize;
+            let mut bits = vec![0u32; (width as usize * height as usize).div_ceil(32)];
+            for row in 0..height as usize {
+                for col in 0..width as usize {
+                    if texels[row * row_texels + col] != 0 {
+                        let idx = row * width as usize + col;
+                        bits[idx / 32] |= 1 << (idx % 32);
+                    }
+                }
+            }
+            Ok(Uint32Array::from(&bits[..]).into())
+        }))
+    }
+}
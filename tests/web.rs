@@ -4,6 +4,10 @@
 
 extern crate wasm_bindgen_test;
 use wasm_bindgen_test::*;
+use wasm_game_of_life::{
+    Anchor, Axis, Boundary, Cell, CyclicUniverse, Direction, Engine, ForestFire, Heading, Lenia, MargolusRule, MargolusUniverse, MethuselahSearch, NeighborhoodShape, ObjectKind, Pattern, PatternFormat, Player, PlayerState, Preset, Renderer, SmoothLife, SoupSearch, Species, StatsFormat, StopReason, SvgOptions, TextOptions,
+    TriangularNeighborhood, TriangularUniverse, Universe, Universe1D, Universe3D, UniverseBuilder,
+};
 
 wasm_bindgen_test_configure!(run_in_browser);
 
@@ -11,3 +15,1929 @@ wasm_bindgen_test_configure!(run_in_browser);
 fn pass() {
     assert_eq!(1 + 1, 2);
 }
+
+#[wasm_bindgen_test]
+fn rule_is_read_back_in_canonical_form() {
+    let universe = Universe::with_rule(8, 8, "s23/b36").unwrap();
+    assert_eq!(universe.rule(), "B36/S23");
+}
+
+#[wasm_bindgen_test]
+fn rle_import_sizes_universe_from_header() {
+    let universe = Universe::from_rle("#N Glider\nx = 3, y = 3, rule = B3/S23:T16,8\nbob$2bo$3o!").unwrap();
+    assert_eq!(universe.width(), 16);
+    assert_eq!(universe.height(), 8);
+    assert_eq!(universe.rule(), "B3/S23");
+    // Rows the parser can count to but not past.
+    assert!(Universe::from_rle("4294967295$o!").is_err());
+}
+
+#[wasm_bindgen_test]
+fn rle_export_round_trips() {
+    let glider = "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+    let universe = Universe::from_rle(glider).unwrap();
+    assert_eq!(universe.to_rle(), glider);
+}
+
+#[wasm_bindgen_test]
+fn toggling_a_cell_flips_it() {
+    let mut universe = Universe::with_rule(4, 4, "B3/S23").unwrap();
+    universe.toggle_cell(1, 2).unwrap();
+    assert_eq!(universe.get_cell(1, 2).unwrap(), Cell::Alive);
+    universe.set_cell(1, 2, Cell::Dead).unwrap();
+    assert_eq!(universe.get_cell(1, 2).unwrap(), Cell::Dead);
+    assert!(universe.toggle_cell(4, 0).is_err());
+}
+
+#[wasm_bindgen_test]
+fn set_cells_is_all_or_nothing() {
+    let mut universe = Universe::with_rule(4, 4, "B3/S23").unwrap();
+    universe.set_cells(&[0, 0, 3, 3]).unwrap();
+    assert_eq!(universe.get_cell(3, 3).unwrap(), Cell::Alive);
+    assert!(universe.set_cells(&[1, 1, 9, 9]).is_err());
+    assert_eq!(universe.get_cell(1, 1).unwrap(), Cell::Dead);
+}
+
+#[wasm_bindgen_test]
+fn random_soups_are_reproducible() {
+    let a = Universe::random(32, 32, 0.4, 7).unwrap();
+    let b = Universe::random(32, 32, 0.4, 7).unwrap();
+    assert_eq!(a.to_rle(), b.to_rle());
+    assert!(Universe::random(32, 32, 1.5, 7).is_err());
+}
+
+#[wasm_bindgen_test]
+fn resizing_keeps_the_pattern() {
+    let glider = "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+    let mut universe = Universe::from_rle(glider).unwrap();
+    universe.resize(9, 9, Anchor::Center).unwrap();
+    assert_eq!(universe.get_cell(3, 4).unwrap(), Cell::Alive);
+    universe.resize(3, 3, Anchor::Center).unwrap();
+    assert_eq!(universe.to_rle(), glider);
+}
+
+#[wasm_bindgen_test]
+fn population_follows_ticks_and_edits() {
+    let mut universe = Universe::from_rle("x = 3, y = 1\n3o!").unwrap();
+    universe.resize(5, 5, Anchor::Center).unwrap();
+    assert_eq!(universe.population(), 3);
+    universe.tick();
+    universe.toggle_cell(0, 0).unwrap();
+    assert_eq!(universe.population(), 4);
+    assert_eq!(universe.generation(), 1);
+}
+
+#[wasm_bindgen_test]
+fn tick_many_matches_repeated_ticks() {
+    let mut one_by_one = Universe::random(32, 24, 0.4, 11).unwrap();
+    let mut batched = Universe::random(32, 24, 0.4, 11).unwrap();
+    for _ in 0..25 {
+        one_by_one.tick();
+    }
+    batched.tick_many(25);
+    assert_eq!(one_by_one.to_rle(), batched.to_rle());
+    assert_eq!(batched.generation(), 25);
+}
+
+#[wasm_bindgen_test]
+fn hashlife_agrees_with_the_naive_engine() {
+    let soup = Universe::random(16, 16, 0.4, 3).unwrap().to_rle();
+    let mut naive = Universe::from_rle(&soup).unwrap();
+    naive.resize(96, 96, Anchor::Center).unwrap();
+    let mut hashlife = Universe::from_rle(&soup).unwrap();
+    hashlife.resize(96, 96, Anchor::Center).unwrap();
+    hashlife.set_engine(Engine::Hashlife);
+
+    naive.tick_many(20);
+    hashlife.tick_many(20);
+    assert_eq!(naive.to_rle(), hashlife.to_rle());
+    assert_eq!(hashlife.generation(), 20);
+}
+
+#[wasm_bindgen_test]
+fn hashlife_leaves_b0_rules_to_the_naive_engine() {
+    let soup = |engine: Engine| {
+        let mut universe = Universe::with_rule(70, 50, "B0/S8").unwrap();
+        universe.set_boundary(Boundary::Dead);
+        universe.insert_rle("bo$2bo$3o!", 20, 30).unwrap();
+        universe.set_engine(engine);
+        universe
+    };
+    let (mut naive, mut hashlife) = (soup(Engine::Naive), soup(Engine::Hashlife));
+    naive.tick();
+    hashlife.tick();
+    assert_eq!(hashlife.population(), naive.population());
+    naive.tick_many(3);
+    hashlife.tick_many(3);
+    assert_eq!(hashlife.to_rle(), naive.to_rle());
+}
+
+#[wasm_bindgen_test]
+fn gliders_fly_off_an_infinite_universe() {
+    let mut universe = wasm_game_of_life::InfiniteUniverse::new();
+    universe.insert_rle("bo$2bo$3o!", -1, -1).unwrap();
+    universe.tick_many(400);
+    assert_eq!(universe.bounds(), vec![99, 99, 101, 101]);
+    assert_eq!(universe.population(), 5);
+    assert!(wasm_game_of_life::InfiniteUniverse::with_rule("B36/S23").is_ok());
+    assert!(wasm_game_of_life::InfiniteUniverse::with_rule("B03/S23").is_err());
+}
+
+#[wasm_bindgen_test]
+fn glider_turns_into_a_block_against_dead_edges() {
+    let mut universe = Universe::from_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+    universe.resize(8, 8, Anchor::TopLeft).unwrap();
+    universe.set_boundary(Boundary::Dead);
+    universe.tick_many(40);
+    assert_eq!(universe.to_rle(), "x = 2, y = 2, rule = B3/S23\n2o$2o!\n");
+}
+
+#[wasm_bindgen_test]
+fn built_in_patterns_can_be_inserted_by_name() {
+    let glider = wasm_game_of_life::list_patterns()
+        .into_iter()
+        .find(|info| info.name() == "glider")
+        .unwrap();
+    assert_eq!((glider.width(), glider.height()), (3, 3));
+
+    let mut universe = Universe::with_rule(16, 16, "B3/S23").unwrap();
+    universe.insert_pattern("glider", 2, 2).unwrap();
+    assert_eq!(universe.population(), 5);
+    assert!(universe.insert_pattern("no-such-pattern", 0, 0).is_err());
+}
+
+#[wasm_bindgen_test]
+fn four_quarter_turns_give_back_the_pattern() {
+    let glider = Pattern::builtin("glider").unwrap();
+    let turned = glider.rotate90();
+    assert_eq!(turned.to_rle(), "x = 3, y = 3\no$obo$2o!\n");
+    assert_eq!(turned.rotate90().rotate90().rotate90(), glider);
+
+    let mut universe = Universe::with_rule(8, 8, "B3/S23").unwrap();
+    universe.stamp(&glider.flip_h().translate(1, 1), 0, 0).unwrap();
+    assert_eq!(universe.get_cell(1, 2).unwrap(), Cell::Alive);
+}
+
+#[wasm_bindgen_test]
+fn undo_restores_the_pattern_before_a_tick() {
+    let mut universe = Universe::with_rule(8, 8, "B3/S23").unwrap();
+    universe.insert_pattern("glider", 1, 1).unwrap();
+    let before = universe.to_rle();
+    universe.tick();
+    assert!(universe.undo());
+    assert_eq!(universe.to_rle(), before);
+    assert_eq!(universe.generation(), 0);
+    assert!(universe.redo());
+    assert_eq!(universe.generation(), 1);
+}
+
+#[wasm_bindgen_test]
+fn save_and_restore_keep_the_whole_state() {
+    let mut universe = Universe::random(20, 12, 0.4, 5).unwrap();
+    universe.tick_many(3);
+    universe.set_boundary(Boundary::Dead);
+
+    let restored = Universe::restore(&universe.save().unwrap()).unwrap();
+    assert_eq!(restored.to_rle(), universe.to_rle());
+    assert_eq!(restored.generation(), 3);
+    assert_eq!(restored.boundary(), Boundary::Dead);
+    assert!(Universe::restore(&[1, 2, 3]).is_err());
+    // 65536x65536, whose cell count doesn't fit a wasm usize.
+    let mut bytes = universe.save().unwrap();
+    bytes[4..12].copy_from_slice(&[0, 0, 1, 0, 0, 0, 1, 0]);
+    assert!(Universe::restore(&bytes).is_err());
+
+    // Dying cells, colors and turmites come back too, so the restored
+    // universe goes on the same way.
+    let mut brain = Universe::from_preset(32, 32, Preset::BriansBrain, 7).unwrap();
+    brain.set_species(Species::Immigration);
+    brain.tick_many(3);
+    let mut restored = Universe::restore(&brain.save().unwrap()).unwrap();
+    brain.tick_many(10);
+    restored.tick_many(10);
+    let cells = |universe: &Universe| -> Vec<(u8, u8)> {
+        (0..32 * 32).map(|i| (universe.get_state(i / 32, i % 32).unwrap(), universe.get_color(i / 32, i % 32).unwrap())).collect()
+    };
+    assert_eq!(cells(&restored), cells(&brain));
+    let mut ant = Universe::with_rule(16, 16, "B/S012345678").unwrap();
+    ant.add_ant(5, 5, Direction::North, "RL").unwrap();
+    ant.tick_many(30);
+    let mut restored = Universe::restore(&ant.save().unwrap()).unwrap();
+    ant.tick_many(30);
+    restored.tick_many(30);
+    assert_eq!(restored.turmites(), ant.turmites());
+    assert_eq!(restored.to_rle(), ant.to_rle());
+
+    // A JavaScript rule can't be saved, and isn't left out silently.
+    brain.set_custom_rule(js_sys::Function::new_with_args("state, count", "return state"));
+    assert!(brain.save().is_err());
+    assert!(brain.enable_autosave(10).is_err());
+}
+
+#[wasm_bindgen_test]
+fn autosave_brings_back_the_last_capture() {
+    let mut universe = Universe::with_rule(64, 64, "B3/S23").unwrap();
+    universe.insert_pattern("glider", 4, 4).unwrap();
+    let mut reference = Universe::restore(&universe.save().unwrap()).unwrap();
+    assert!(universe.export_autosave().is_err());
+    assert!(universe.enable_autosave(0).is_err());
+
+    universe.enable_autosave(10).unwrap();
+    universe.tick_many(25);
+    reference.tick_many(20);
+    let autosave = universe.export_autosave().unwrap();
+    let recovered = Universe::import_autosave(&autosave).unwrap();
+    assert_eq!(recovered.generation(), 20);
+    assert_eq!(recovered.to_rle(), reference.to_rle());
+    // A glider only changes a few words, so the capture is barely more than the base.
+    assert!(autosave.len() < universe.save().unwrap().len() + 128);
+    assert!(Universe::import_autosave(&[1, 2, 3]).is_err());
+}
+
+#[wasm_bindgen_test]
+fn selections_copy_as_rle() {
+    let mut universe = Universe::with_rule(20, 20, "B3/S23").unwrap();
+    universe.insert_pattern("glider", 2, 2).unwrap();
+    universe.insert_pattern("block", 12, 12).unwrap();
+    assert_eq!(universe.selection_to_rle(), universe.to_rle());
+
+    universe.select(0, 0, 8, 8).unwrap();
+    assert_eq!(universe.selection(), Some(vec![0, 0, 8, 8]));
+    let copied = Pattern::from_rle(&universe.selection_to_rle()).unwrap();
+    assert_eq!((copied.width(), copied.height()), (8, 8));
+    let mut pasted = Universe::with_rule(20, 20, "B3/S23").unwrap();
+    pasted.stamp(&copied, 0, 0).unwrap();
+    assert_eq!(pasted.population(), 5);
+
+    assert!(universe.select(15, 15, 8, 8).is_err());
+    universe.clear_selection();
+    assert_eq!(universe.selection(), None);
+}
+
+#[cfg(feature = "gif")]
+#[wasm_bindgen_test]
+fn runs_record_as_looping_gifs() {
+    let mut universe = Universe::with_rule(8, 8, "B3/S23").unwrap();
+    universe.insert_pattern("blinker", 3, 2).unwrap();
+    let gif = universe.record_gif(4, 3, 1).unwrap();
+    assert_eq!(&gif[..6], b"GIF89a");
+    // 8 cells of 3 pixels plus the grid lines.
+    assert_eq!(u16::from_le_bytes([gif[6], gif[7]]), 33);
+    assert!(gif.windows(11).any(|w| w == b"NETSCAPE2.0"));
+    // The first frame and one every other generation.
+    assert_eq!(gif.windows(2).filter(|w| w == &[0x21, 0xF9]).count(), 3);
+    assert_eq!(universe.generation(), 4);
+    assert!(universe.record_gif(4, 0, 0).is_err());
+}
+
+#[cfg(feature = "apng")]
+#[wasm_bindgen_test]
+fn runs_record_as_full_color_apngs() {
+    let mut universe = Universe::with_rule(8, 8, "B3/S23").unwrap();
+    universe.insert_pattern("blinker", 3, 2).unwrap();
+    let apng = universe.record_apng(4, 3, 1, wasm_game_of_life::Coloring::Age).unwrap();
+    assert_eq!(&apng[..8], b"\x89PNG\r\n\x1a\n");
+    let chunk = |name: &[u8]| apng.windows(4).position(|w| w == name).unwrap();
+    let frames = chunk(b"acTL") + 4;
+    assert_eq!(apng[frames..frames + 4], [0, 0, 0, 3]);
+    assert_eq!(apng.windows(4).filter(|w| w == b"fcTL").count(), 3);
+    assert_eq!(universe.generation(), 4);
+    assert!(universe.record_apng(4, 0, 0, wasm_game_of_life::Coloring::Plain).is_err());
+}
+
+#[wasm_bindgen_test]
+fn svg_export_merges_runs_of_live_cells() {
+    let mut universe = Universe::with_rule(6, 4, "B3/S23").unwrap();
+    universe.insert_pattern("glider", 0, 0).unwrap();
+    let mut options = SvgOptions::new();
+    let svg = universe.to_svg(&options);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"60\" height=\"40\""));
+    assert!(svg.contains("d=\"M1 0h1v1h-1zM2 1h1v1h-1zM0 2h3v1h-3z\""));
+    assert!(!svg.contains("stroke"));
+
+    options.set_cell_size(2);
+    options.set_colors("red", "");
+    options.set_grid("#CCCCCC");
+    options.set_viewport(2, 0, 3, 1);
+    let svg = universe.to_svg(&options);
+    assert!(svg.contains("width=\"6\" height=\"2\" viewBox=\"0 0 3 1\""));
+    assert!(svg.contains("<path fill=\"red\" d=\"M0 0h3v1h-3z\"/>"));
+    assert!(!svg.contains("<rect"));
+    assert!(svg.contains("stroke=\"#CCCCCC\""));
+}
+
+#[wasm_bindgen_test]
+fn recorded_stats_export_as_csv_and_json() {
+    let mut universe = Universe::with_rule(10, 10, "B3/S23").unwrap();
+    universe.insert_pattern("blinker", 4, 3).unwrap();
+    assert!(universe.export_stats(StatsFormat::Csv).is_empty());
+    universe.record_stats(true);
+    universe.tick_many(2);
+
+    let csv = String::from_utf8(universe.export_stats(StatsFormat::Csv)).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "generation,population,births,deaths,top,left,width,height");
+    assert_eq!(lines[1..], ["0,3,0,0,4,3,3,1", "1,3,2,2,3,4,1,3", "2,3,2,2,4,3,3,1"]);
+
+    let json = String::from_utf8(universe.export_stats(StatsFormat::Json)).unwrap();
+    assert!(json.starts_with("[{\"generation\":0,\"population\":3,\"births\":0,\"deaths\":0,\"top\":4,"));
+    assert_eq!(json.matches("\"generation\"").count(), 3);
+
+    universe.record_stats(false);
+    assert!(!universe.is_recording_stats());
+}
+
+#[wasm_bindgen_test]
+fn changes_are_counted_per_column() {
+    let mut universe = Universe::with_rule(8, 8, "B3/S23").unwrap();
+    universe.insert_pattern("blinker", 3, 2).unwrap();
+    universe.tick();
+    // The horizontal blinker turns vertical around column 3.
+    assert_eq!(universe.changed_per_column(), vec![0, 0, 1, 2, 1, 0, 0, 0]);
+}
+
+#[wasm_bindgen_test]
+fn player_runs_the_generations_that_are_due() {
+    let mut universe = Universe::with_rule(16, 16, "B3/S23").unwrap();
+    universe.insert_pattern("glider", 2, 2).unwrap();
+    let mut player = Player::new();
+    assert_eq!(player.advance(&mut universe, 0.0), 0);
+
+    player.set_speed(20.0).unwrap();
+    player.play();
+    assert_eq!(player.state(), PlayerState::Playing);
+    assert_eq!(player.advance(&mut universe, 1000.0), 0);
+    assert_eq!(player.advance(&mut universe, 1125.0), 2);
+    // Half a generation is carried over to the next frame.
+    assert_eq!(player.advance(&mut universe, 1150.0), 1);
+    // Long gaps only count for a quarter of a second.
+    assert_eq!(player.advance(&mut universe, 60_000.0), 5);
+    assert_eq!(universe.generation(), 8);
+
+    player.step(&mut universe);
+    assert_eq!(player.state(), PlayerState::Paused);
+    assert_eq!(universe.generation(), 9);
+    assert!(player.step_back(&mut universe));
+    assert_eq!(universe.generation(), 8);
+
+    player.faster();
+    assert_eq!(player.speed(), 30.0);
+    player.set_speed(3.0).unwrap();
+    player.slower();
+    assert_eq!(player.speed(), 2.0);
+}
+
+#[wasm_bindgen_test]
+fn universe_builder_makes_the_universe_it_was_told_to() {
+    let glider = Pattern::from_rle("bob$2bo$3o!").unwrap();
+    let universe = UniverseBuilder::new()
+        .size(9, 7)
+        .rule("B36/S23")
+        .boundary(Boundary::Dead)
+        .engine(Engine::BitParallel)
+        .seed_pattern(&glider)
+        .build()
+        .unwrap();
+    assert_eq!((universe.width(), universe.height()), (9, 7));
+    assert_eq!(universe.rule(), "B36/S23");
+    assert_eq!(universe.boundary(), Boundary::Dead);
+    assert_eq!(universe.engine(), Engine::BitParallel);
+    assert_eq!(universe.to_rle(), "x = 3, y = 3, rule = B36/S23\nbo$2bo$3o!\n");
+    // In the middle: the glider's top cell is at (0, 1) of its 3x3 box.
+    assert_eq!(universe.get_cell(2, 4).unwrap(), Cell::Alive);
+    assert!(!universe.can_undo());
+
+    let soup = UniverseBuilder::new().seed_random(0.5, 42);
+    assert_eq!(soup.build().unwrap().to_rle(), Universe::random(64, 64, 0.5, 42).unwrap().to_rle());
+    let tutorial = game_of_life_core::Universe::new();
+    assert_eq!(UniverseBuilder::new().build().unwrap().to_rle(), tutorial.to_rle());
+}
+
+#[wasm_bindgen_test]
+fn bad_sizes_and_speeds_are_errors_rather_than_traps() {
+    use game_of_life_core as life;
+    let error = life::Universe::with_rule(1 << 16, 1 << 16, "B3/S23").err().unwrap();
+    assert!(error.to_string().contains("65536x65536 universe is too big"));
+    assert!(life::Universe3D::with_rule(2048, 2048, 2048, "4555").is_err());
+    assert!(life::Universe::with_rule(0, 8, "B3/S23").is_err());
+    assert!(life::Player::new().set_speed(f64::NAN).is_err());
+    assert!(life::InfiniteUniverse::new().window_bits(0, 0, u32::MAX, u32::MAX).is_err());
+}
+
+#[wasm_bindgen_test]
+fn ticking_for_a_budget_stops_before_it_runs_out() {
+    use game_of_life_core as life;
+
+    // A clock where every generation takes 3ms.
+    let mut universe = life::Universe::random(32, 32, 0.3, 7).unwrap();
+    let mut clock = 0.0;
+    let ran = universe.tick_for_budget(12.0, || {
+        clock += 3.0;
+        clock
+    });
+    assert_eq!(ran, 4);
+    assert_eq!(universe.generation(), 4);
+    assert!(universe.undo());
+    assert_eq!(universe.generation(), 0);
+
+    let mut universe = Universe::random(32, 32, 0.3, 7).unwrap();
+    let ran = universe.tick_for_budget(5.0);
+    assert!(ran >= 1);
+    assert_eq!(universe.generation(), u64::from(ran));
+}
+
+#[wasm_bindgen_test]
+fn share_string_round_trips() {
+    let universe = Universe::random(40, 30, 0.3, 11).unwrap();
+    let share = universe.to_share_string();
+    assert!(share.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+    let shared = Universe::from_share_string(&share).unwrap();
+    assert_eq!((shared.width(), shared.height()), (40, 30));
+    assert_eq!(shared.to_rle(), universe.to_rle());
+    assert!(Universe::from_share_string("not a share string!").is_err());
+}
+
+#[wasm_bindgen_test]
+fn detects_oscillator_period() {
+    let mut universe = Universe::with_rule(16, 16, "B3/S23").unwrap();
+    universe.insert_pattern("blinker", 6, 6).unwrap();
+    universe.tick();
+    assert!(universe.detect_cycle(8).is_none());
+
+    universe.tick();
+    let cycle = universe.detect_cycle(8).unwrap();
+    assert_eq!(cycle.period(), 2);
+    assert_eq!(cycle.since(), 0);
+
+    universe.toggle_cell(0, 0).unwrap();
+    assert!(universe.detect_cycle(8).is_none());
+}
+
+#[wasm_bindgen_test]
+fn run_until_stable_reports_why_it_stopped() {
+    let mut universe = Universe::with_rule(64, 64, "B3/S23").unwrap();
+    universe.insert_pattern("diehard", 28, 28).unwrap();
+    let report = universe.run_until_stable(500, 8);
+    assert_eq!(report.reason(), StopReason::DiedOut);
+    assert_eq!(report.generation(), 130);
+
+    let mut universe = Universe::with_rule(16, 16, "B3/S23").unwrap();
+    universe.insert_pattern("toad", 6, 6).unwrap();
+    let report = universe.run_until_stable(500, 8);
+    assert_eq!(report.reason(), StopReason::Cycle);
+    assert_eq!(report.period(), Some(2));
+
+    let report = universe.run_until_stable(0, 8);
+    assert_eq!(report.ticks(), 0);
+}
+
+#[wasm_bindgen_test]
+fn ages_count_consecutive_live_generations() {
+    let mut universe = Universe::with_rule(8, 8, "B3/S23").unwrap();
+    universe.insert_pattern("block", 2, 2).unwrap();
+    universe.ages();
+    universe.tick_many(5);
+
+    let ages = unsafe { std::slice::from_raw_parts(universe.ages(), 64) };
+    assert_eq!(ages[2 * 8 + 2], 6);
+    assert_eq!(ages[0], 0);
+}
+
+#[wasm_bindgen_test]
+fn activity_fades_between_flips() {
+    let mut universe = Universe::with_rule(8, 8, "B3/S23").unwrap();
+    universe.insert_pattern("blinker", 3, 2).unwrap();
+    universe.set_activity_decay(0.5).unwrap();
+    universe.activity();
+    universe.tick();
+    universe.tick();
+
+    let activity = unsafe { std::slice::from_raw_parts(universe.activity(), 64) };
+    // The blinker's ends flip every generation, its center never does.
+    assert_eq!(activity[3 * 8 + 2], 1.5);
+    assert_eq!(activity[3 * 8 + 3], 0.0);
+    assert!(universe.set_activity_decay(2.0).is_err());
+}
+
+#[wasm_bindgen_test]
+fn renderer_sizes_the_canvas() {
+    use wasm_bindgen::JsCast;
+
+    let canvas = web_sys::window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .create_element("canvas")
+        .unwrap()
+        .dyn_into::<web_sys::HtmlCanvasElement>()
+        .unwrap();
+    let mut universe = Universe::with_rule(10, 4, "B3/S23").unwrap();
+    universe.insert_pattern("blinker", 1, 1).unwrap();
+
+    let mut renderer = Renderer::new(canvas.clone(), 5).unwrap();
+    renderer.draw(&universe);
+    universe.tick();
+    renderer.draw(&universe);
+    assert_eq!((canvas.width(), canvas.height()), (61, 25));
+}
+
+#[wasm_bindgen_test]
+fn set_cells_bits_replaces_every_cell() {
+    let mut source = Universe::random(20, 5, 0.5, 3).unwrap();
+    source.tick();
+    let words = unsafe { std::slice::from_raw_parts(source.cells_bits(), source.cells_bits_len() / 4) }.to_vec();
+
+    let mut copy = Universe::with_rule(20, 5, "B3/S23").unwrap();
+    copy.set_cells_bits(&words).unwrap();
+    assert_eq!(copy.to_rle(), source.to_rle());
+    assert_eq!(copy.population(), source.population());
+    assert!(copy.set_cells_bits(&[0]).is_err());
+}
+
+#[wasm_bindgen_test]
+fn glider_wraps_around_a_wide_torus() {
+    // Wide enough for the SIMD path, when this build has it.
+    let mut universe = Universe::with_rule(40, 40, "B3/S23").unwrap();
+    universe.insert_pattern("glider", 0, 0).unwrap();
+    let start = universe.to_share_string();
+    universe.tick_many(160);
+    assert_eq!(universe.to_share_string(), start);
+    assert_eq!(universe.population(), 5);
+    let _ = wasm_game_of_life::simd_enabled();
+}
+
+#[wasm_bindgen_test]
+fn tick_parallel_matches_tick() {
+    let mut serial = Universe::random(50, 40, 0.4, 21).unwrap();
+    let mut banded = Universe::random(50, 40, 0.4, 21).unwrap();
+    for _ in 0..10 {
+        serial.tick();
+        banded.tick_parallel(4);
+    }
+    assert_eq!(banded.to_rle(), serial.to_rle());
+    assert_eq!(banded.generation(), 10);
+
+    // However many workers are asked for, even on a very tall universe.
+    serial.tick();
+    banded.tick_parallel(u32::MAX);
+    assert_eq!(banded.to_rle(), serial.to_rle());
+    let mut tall = Universe::with_rule(32, 70_000, "B3/S23").unwrap();
+    tall.insert_pattern("glider", 69_990, 10).unwrap();
+    tall.tick_parallel(70_000);
+    tall.tick_parallel(70_000);
+    assert_eq!(tall.population(), 5);
+}
+
+#[wasm_bindgen_test]
+fn tick_alternates_between_two_buffers() {
+    let mut universe = Universe::random(30, 30, 0.5, 4).unwrap();
+    let first = universe.cells();
+    universe.tick();
+    let second = universe.cells();
+    let after_one = universe.to_rle();
+    universe.tick();
+    assert_ne!(second, first);
+    assert_eq!(universe.cells(), first);
+    // Undo flips cells in place rather than swapping buffers.
+    assert!(universe.undo());
+    assert_eq!(universe.cells(), first);
+    assert_eq!(universe.to_rle(), after_one);
+}
+
+#[wasm_bindgen_test]
+fn bit_parallel_agrees_with_the_naive_engine() {
+    for boundary in [Boundary::Torus, Boundary::Dead, Boundary::Mirror, Boundary::AliveWall] {
+        let mut naive = Universe::random(70, 20, 0.4, 8).unwrap();
+        naive.set_boundary(boundary);
+        let mut bitwise = Universe::random(70, 20, 0.4, 8).unwrap();
+        bitwise.set_boundary(boundary);
+        bitwise.set_engine(Engine::BitParallel);
+
+        naive.tick_many(30);
+        bitwise.tick_many(30);
+        assert_eq!(bitwise.to_rle(), naive.to_rle());
+        assert_eq!(bitwise.population(), naive.population());
+    }
+}
+
+#[wasm_bindgen_test]
+fn tick_stats_count_births_and_deaths() {
+    let mut universe = Universe::with_rule(6, 6, "B3/S23").unwrap();
+    universe.insert_pattern("blinker", 1, 1).unwrap();
+    assert_eq!(universe.tick_stats().changed(), 0);
+    universe.tick_many(3);
+    let stats = universe.tick_stats();
+    assert_eq!((stats.births(), stats.deaths(), stats.population()), (2, 2, 3));
+    assert_eq!(stats.changed(), 4);
+    assert_eq!(universe.population_history(10), vec![3, 3, 3]);
+    assert_eq!(universe.population_history(1), vec![3]);
+}
+
+#[wasm_bindgen_test]
+fn errors_are_handed_to_the_error_callback() {
+    let reports = js_sys::Array::new();
+    let callback = js_sys::Function::new_with_args("report", "this.push(report)").bind0(&reports);
+    wasm_game_of_life::set_error_callback(Some(callback));
+    assert!(Universe::with_rule(4, 4, "B3/S23/X").is_err());
+    wasm_game_of_life::set_error_callback(None);
+    assert!(Universe::with_rule(4, 4, "B3/S23/X").is_err());
+
+    assert_eq!(reports.length(), 1);
+    let report = reports.get(0);
+    let field = |name: &str| js_sys::Reflect::get(&report, &name.into()).unwrap();
+    assert!(field("message").as_string().unwrap().contains("B3/S23/X"));
+    assert!(field("location").as_string().unwrap().starts_with("src/lib.rs:"));
+    assert_eq!(field("panic").as_bool(), Some(false));
+}
+
+#[wasm_bindgen_test]
+fn perf_stats_time_each_tick() {
+    let mut universe = Universe::with_rule(16, 16, "B3/S23").unwrap();
+    universe.insert_pattern("glider", 1, 1).unwrap();
+    assert_eq!(universe.perf_stats().ticks(), 0);
+    universe.tick_many(8);
+    let stats = universe.perf_stats();
+    assert_eq!(stats.ticks(), 1);
+    assert_eq!(stats.average_tick_ms(), stats.last_tick_ms());
+    assert!((stats.count_ms() + stats.write_ms() - stats.last_tick_ms()).abs() < 1e-9);
+    // The undo history keeps which cells flipped.
+    assert!(stats.allocations() > 0);
+    universe.tick();
+    assert_eq!(universe.perf_stats().ticks(), 2);
+}
+
+#[wasm_bindgen_test]
+fn render_viewport_paints_rgba() {
+    let mut universe = Universe::with_rule(4, 4, "B3/S23").unwrap();
+    universe.toggle_cell(1, 2).unwrap();
+    // Cells (2, 1) to (3, 2) at 2px per cell, plus one column past the edge.
+    let pixels = universe.render_viewport(1, 1, 6, 4, 2.0).unwrap();
+    assert_eq!(pixels.len(), 6 * 4 * 4);
+    let pixel = |x: usize, y: usize| pixels[(y * 6 + x) * 4..][..4].to_vec();
+    assert_eq!(pixel(0, 0), vec![0xFF, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(pixel(2, 0), vec![0x00, 0x00, 0x00, 0xFF]);
+    assert_eq!(pixel(3, 1), vec![0x00, 0x00, 0x00, 0xFF]);
+    assert_eq!(pixel(4, 0), vec![0xFF, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(pixel(2, 2), vec![0xFF, 0xFF, 0xFF, 0xFF]);
+
+    let mut zoomed_out = vec![0; 2 * 2 * 4];
+    universe.render_viewport_into(0, -1, 2, 2, 0.5, &mut zoomed_out).unwrap();
+    assert_eq!(zoomed_out[..4], [0xCC, 0xCC, 0xCC, 0xFF]);
+    assert_eq!(zoomed_out[8..12], [0xFF, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(zoomed_out[12..], [0x00, 0x00, 0x00, 0xFF]);
+    assert!(universe.render_viewport(0, 0, u32::MAX, u32::MAX, 1.0).is_err());
+    assert!(universe.render_viewport_into(0, 0, 65536, 65536, 1.0, &mut []).is_err());
+}
+
+#[wasm_bindgen_test]
+fn density_levels_follow_ticks_and_edits() {
+    let counts = |universe: &mut Universe, level: u32, len: usize| {
+        let ptr = universe.density_level(level).unwrap();
+        unsafe { std::slice::from_raw_parts(ptr, len) }.to_vec()
+    };
+    let mut universe = Universe::random(70, 20, 0.4, 5).unwrap();
+    let at_start = counts(&mut universe, 0, 9 * 3);
+    assert_eq!(at_start.iter().sum::<u32>(), universe.population());
+    // The one 64x64 block along the right edge is only 6 cells wide.
+    let right_edge = at_start[8] + at_start[17] + at_start[26];
+    assert_eq!(counts(&mut universe, 1, 2), vec![universe.population() - right_edge, right_edge]);
+
+    universe.tick_many(25);
+    universe.toggle_cell(19, 69).unwrap();
+    universe.tick();
+    assert!(universe.undo());
+    let words = unsafe { std::slice::from_raw_parts(universe.cells_bits(), universe.cells_bits_len() / 4) }.to_vec();
+    let mut fresh = Universe::with_rule(70, 20, "B3/S23").unwrap();
+    fresh.set_cells_bits(&words).unwrap();
+    assert_eq!(counts(&mut universe, 0, 27), counts(&mut fresh, 0, 27));
+    assert_eq!(counts(&mut universe, 1, 2), counts(&mut fresh, 1, 2));
+    assert!(universe.density_level(2).is_err());
+
+    // Ticks keep them up to date with history turned off too.
+    universe.set_history_depth(0);
+    universe.tick_many(3);
+    assert_eq!(counts(&mut universe, 0, 27).iter().sum::<u32>(), universe.population());
+    let words = unsafe { std::slice::from_raw_parts(universe.cells_bits(), universe.cells_bits_len() / 4) }.to_vec();
+    fresh.set_cells_bits(&words).unwrap();
+    assert_eq!(counts(&mut universe, 0, 27), counts(&mut fresh, 0, 27));
+}
+
+#[wasm_bindgen_test]
+fn changed_cells_marks_what_flipped() {
+    let changed = |universe: &Universe| {
+        let words = unsafe { std::slice::from_raw_parts(universe.changed_cells(), universe.changed_cells_len() / 4) };
+        (0..25).filter(|&idx| words[idx / 32] & (1 << (idx % 32)) != 0).collect::<Vec<_>>()
+    };
+    let mut universe = Universe::with_rule(5, 5, "B3/S23").unwrap();
+    assert!(changed(&universe).is_empty());
+    universe.insert_pattern("blinker", 1, 1).unwrap();
+    assert_eq!(changed(&universe), vec![6, 7, 8]);
+    universe.tick();
+    assert_eq!(changed(&universe), vec![2, 6, 8, 12]);
+    universe.tick_many(2);
+    assert!(changed(&universe).is_empty());
+    assert!(universe.undo());
+    assert_eq!(changed(&universe), Vec::<usize>::new());
+    assert!(universe.undo());
+    assert_eq!(changed(&universe), vec![2, 6, 8, 12]);
+
+    // Still kept up to date with no history to undo.
+    universe.set_history_depth(0);
+    universe.toggle_cell(4, 4).unwrap();
+    assert_eq!(changed(&universe), vec![24]);
+    universe.toggle_cell(4, 4).unwrap();
+    universe.tick();
+    assert_eq!(changed(&universe), vec![2, 6, 8, 12]);
+}
+
+#[wasm_bindgen_test]
+fn render_text_uses_the_given_glyphs() {
+    let mut universe = Universe::with_rule(4, 3, "B3/S23").unwrap();
+    universe.toggle_cell(1, 1).unwrap();
+    assert_eq!(universe.to_string(), "◻◻◻◻\n◻◼◻◻\n◻◻◻◻\n");
+
+    let mut options = TextOptions::new();
+    options.set_glyphs(".", "#");
+    options.set_row_separator("|", false);
+    assert_eq!(universe.render_text(&options), "....|.#..|....");
+    options.set_viewport(1, 1, 5, 1);
+    assert_eq!(universe.render_text(&options), "#..");
+}
+
+#[wasm_bindgen_test]
+fn render_braille_packs_eight_cells_per_char() {
+    let mut universe = Universe::with_rule(3, 5, "B3/S23").unwrap();
+    // Top left and bottom right of the first block, and the lone cell
+    // in the bottom row.
+    universe.toggle_cell(0, 0).unwrap();
+    universe.toggle_cell(3, 1).unwrap();
+    universe.toggle_cell(4, 2).unwrap();
+    assert_eq!(universe.render_braille(), "⢁⠀\n⠀⠁\n");
+}
+
+#[wasm_bindgen_test]
+fn immigration_newborns_take_the_majority_color() {
+    let mut universe = Universe::with_rule(5, 5, "B3/S23").unwrap();
+    universe.set_species(Species::Immigration);
+    universe.set_cell_color(1, 2, 2).unwrap();
+    universe.set_cell_color(2, 2, 1).unwrap();
+    universe.set_cell_color(3, 2, 2).unwrap();
+    assert!(universe.set_cell_color(0, 0, 3).is_err());
+
+    universe.tick();
+    assert_eq!(universe.get_color(2, 1).unwrap(), 2);
+    assert_eq!(universe.get_color(2, 2).unwrap(), 1);
+    assert_eq!(universe.get_color(2, 3).unwrap(), 2);
+    assert_eq!(universe.get_color(1, 2).unwrap(), 0);
+
+    universe.toggle_cell(0, 0).unwrap();
+    assert_eq!(universe.get_color(0, 0).unwrap(), 1);
+    universe.set_species(Species::Single);
+    assert_eq!(universe.get_color(2, 1).unwrap(), 1);
+}
+
+#[wasm_bindgen_test]
+fn quadlife_newborns_of_three_colors_take_the_fourth() {
+    let mut universe = Universe::with_rule(5, 5, "B3/S23").unwrap();
+    universe.set_species(Species::QuadLife);
+    universe.set_cell_color(1, 2, 1).unwrap();
+    universe.set_cell_color(2, 2, 2).unwrap();
+    universe.set_cell_color(3, 2, 3).unwrap();
+    universe.tick();
+    assert_eq!(universe.get_color(2, 1).unwrap(), 4);
+    assert_eq!(universe.get_color(2, 2).unwrap(), 2);
+
+    universe.set_cell_color(2, 1, 3).unwrap();
+    universe.tick();
+    // (1, 2) is born from colors 3, 2 and 4.
+    assert_eq!(universe.get_color(1, 2).unwrap(), 1);
+}
+
+#[wasm_bindgen_test]
+fn generations_cells_die_through_refractory_states() {
+    assert_eq!(Universe::with_rule(4, 4, "23/3/8").unwrap().rule(), "B3/S23/C8");
+    assert!(Universe::with_rule(4, 4, "B2/S/C1").is_err());
+
+    // Brian's Brain: every live cell dies, and stays dying for one generation.
+    let mut universe = Universe::with_rule(6, 6, "B2/S/C3").unwrap();
+    assert_eq!(universe.state_count(), 3);
+    universe.toggle_cell(2, 2).unwrap();
+    universe.toggle_cell(2, 3).unwrap();
+    universe.tick();
+    assert_eq!(universe.get_state(2, 2).unwrap(), 2);
+    assert_eq!(universe.get_state(1, 2).unwrap(), 1);
+    assert_eq!(universe.get_state(3, 3).unwrap(), 1);
+    assert_eq!(universe.population(), 4);
+
+    universe.tick();
+    assert_eq!(universe.get_state(2, 2).unwrap(), 0);
+    assert_eq!(universe.get_state(1, 2).unwrap(), 2);
+    assert_eq!(universe.get_cell(1, 2).unwrap(), Cell::Dead);
+
+    // Undo brings the dying cells back too, so the tick after it goes
+    // the same way again.
+    let states = |universe: &Universe| -> Vec<u8> { (0..36).map(|i| universe.get_state(i / 6, i % 6).unwrap()).collect() };
+    let after = states(&universe);
+    assert!(universe.undo());
+    assert_eq!(universe.get_state(2, 2).unwrap(), 2);
+    universe.tick();
+    assert_eq!(states(&universe), after);
+    universe.set_state(0, 0, 2).unwrap();
+    assert!(universe.undo());
+    assert_eq!(universe.get_state(0, 0).unwrap(), 0);
+    assert!(universe.redo());
+    assert_eq!(universe.get_state(0, 0).unwrap(), 2);
+}
+
+#[wasm_bindgen_test]
+fn isotropic_rules_look_at_which_neighbors_are_alive() {
+    let life = "MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA";
+    assert_eq!(Universe::with_rule(4, 4, life).unwrap().rule(), "B3/S23");
+    assert_eq!(Universe::with_rule(4, 4, "b2-a/s12").unwrap().rule(), "B2-a/S12");
+    assert!(Universe::with_rule(4, 4, "B2x/S23").is_err());
+
+    // Two neighbors side by side give birth with 2a, two across from
+    // each other don't.
+    let mut universe = Universe::with_rule(5, 5, "B2a/S").unwrap();
+    universe.toggle_cell(1, 1).unwrap();
+    universe.toggle_cell(1, 2).unwrap();
+    universe.tick();
+    assert_eq!(universe.get_cell(0, 1).unwrap(), Cell::Alive);
+
+    let mut universe = Universe::with_rule(5, 5, "B2a/S").unwrap();
+    universe.toggle_cell(1, 2).unwrap();
+    universe.toggle_cell(3, 2).unwrap();
+    universe.tick();
+    assert_eq!(universe.population(), 0);
+}
+
+#[wasm_bindgen_test]
+fn larger_than_life_counts_over_a_radius() {
+    let bosco = Universe::with_rule(4, 4, "r5,c0,m1,s34..58,b34..45,nm").unwrap();
+    assert_eq!(bosco.rule(), "R5,C0,M1,S34..58,B34..45,NM");
+    assert!(Universe::with_rule(4, 4, "R0,C0,M1,S34..58,B34..45,NM").is_err());
+
+    // Radius 1 without the middle cell is plain Life.
+    let soup = Universe::random(23, 17, 0.4, 7).unwrap();
+    let bits = |universe: &Universe| {
+        unsafe { std::slice::from_raw_parts(universe.cells_bits(), universe.cells_bits_len() / 4) }.to_vec()
+    };
+    let mut life = Universe::with_rule(23, 17, "B3/S23").unwrap();
+    let mut larger = Universe::with_rule(23, 17, "R1,C0,M0,S2..3,B3..3,NM").unwrap();
+    life.set_cells_bits(&bits(&soup)).unwrap();
+    larger.set_cells_bits(&bits(&soup)).unwrap();
+    life.tick_many(20);
+    larger.tick_many(20);
+    assert_eq!(bits(&life), bits(&larger));
+
+    // A von Neumann diamond of radius 2 has 12 cells around the middle.
+    let mut diamond = Universe::with_rule(7, 7, "R2,C0,M0,S0..0,B1..1,NN").unwrap();
+    diamond.toggle_cell(3, 3).unwrap();
+    diamond.tick();
+    assert_eq!(diamond.population(), 13);
+    assert_eq!(diamond.get_cell(2, 2).unwrap(), Cell::Alive);
+    assert_eq!(diamond.get_cell(1, 2).unwrap(), Cell::Dead);
+}
+
+#[wasm_bindgen_test]
+fn lenia_cells_grow_towards_mu() {
+    let mut lenia = Lenia::new(40, 30).unwrap();
+    assert_eq!((lenia.radius(), lenia.mu(), lenia.sigma(), lenia.dt()), (13, 0.15, 0.015, 0.1));
+    assert!(lenia.set_radius(0).is_err());
+    assert!(lenia.set_growth(0.15, 0.0).is_err());
+    assert!(lenia.set_cell(0, 0, 1.5).is_err());
+
+    // Nothing grows out of nothing.
+    lenia.tick();
+    assert_eq!(lenia.mass(), 0.0);
+
+    // The ring averages a uniform field to its own value, so a field
+    // right at mu grows by a whole dt everywhere.
+    for row in 0..30 {
+        for col in 0..40 {
+            lenia.set_cell(row, col, 0.15).unwrap();
+        }
+    }
+    lenia.tick();
+    assert_eq!(lenia.generation(), 2);
+    assert!((lenia.get_cell(7, 11).unwrap() - 0.25).abs() < 1e-4);
+
+    // A lone speck is too small for the ring to see, and fades away.
+    lenia.clear();
+    lenia.set_cell(15, 20, 1.0).unwrap();
+    lenia.tick_many(10);
+    assert_eq!(lenia.mass(), 0.0);
+}
+
+#[wasm_bindgen_test]
+fn smooth_life_disks_hollow_out() {
+    let mut life = SmoothLife::new(64, 64).unwrap();
+    assert_eq!(life.radius(), 12);
+    assert_eq!(life.birth(), vec![0.278, 0.365]);
+    assert!(life.set_radius(2).is_err());
+    assert!(life.set_death(0.4, 0.3).is_err());
+    assert!(life.set_steepness(0.0, 0.1).is_err());
+
+    // With every cell alive, the ring is full and everything dies.
+    for row in 0..64 {
+        for col in 0..64 {
+            life.set_cell(row, col, 1.0).unwrap();
+        }
+    }
+    life.tick();
+    assert!(life.mass() < 1e-3);
+
+    // A full disk as wide as the ring hollows out: its middle is
+    // overcrowded, while its rim has just enough neighbors to live.
+    life.clear();
+    for row in 0..64i32 {
+        for col in 0..64i32 {
+            if (row - 32) * (row - 32) + (col - 32) * (col - 32) <= 144 {
+                life.set_cell(row as u32, col as u32, 1.0).unwrap();
+            }
+        }
+    }
+    life.tick();
+    assert_eq!(life.generation(), 2);
+    assert!(life.get_cell(32, 32).unwrap() < 0.01);
+    assert!(life.get_cell(32, 42).unwrap() > 0.5);
+}
+
+#[wasm_bindgen_test]
+fn triangles_have_three_or_twelve_neighbors() {
+    assert!(TriangularUniverse::with_rule(7, 4, TriangularNeighborhood::Edges, "B1/S").is_err());
+    assert!(TriangularUniverse::points_up(0, 0) && !TriangularUniverse::points_up(0, 1));
+
+    // Every neighbor of a lone triangle is born under B1, and it dies.
+    let mut edges = TriangularUniverse::with_rule(8, 4, TriangularNeighborhood::Edges, "B1/S").unwrap();
+    edges.set_cell(2, 2, Cell::Alive).unwrap();
+    edges.tick();
+    assert_eq!(edges.population(), 3);
+    // It points up, so the third one is below it.
+    assert_eq!(edges.get_cell(3, 2).unwrap(), Cell::Alive);
+    assert_eq!(edges.get_cell(1, 2).unwrap(), Cell::Dead);
+
+    let mut vertices = TriangularUniverse::with_rule(8, 4, TriangularNeighborhood::Vertices, "B1/S").unwrap();
+    vertices.set_cell(2, 2, Cell::Alive).unwrap();
+    vertices.tick();
+    assert_eq!(vertices.population(), 12);
+    assert_eq!(vertices.get_cell(3, 0).unwrap(), Cell::Alive);
+    assert_eq!(vertices.get_cell(1, 0).unwrap(), Cell::Dead);
+    assert_eq!(vertices.get_cell(2, 2).unwrap(), Cell::Dead);
+}
+
+#[wasm_bindgen_test]
+fn universe_3d_counts_the_26_cells_of_the_cube() {
+    assert_eq!(Universe3D::with_rule(4, 4, 4, "4,5,5,5").unwrap().rule(), "4555");
+    assert_eq!(Universe3D::with_rule(4, 4, 4, "5,14,6,6").unwrap().rule(), "5,14,6,6");
+    assert!(Universe3D::with_rule(4, 4, 4, "5576").is_err());
+
+    // A lone cell survives with no neighbors and fills the cube around it.
+    let mut universe = Universe3D::with_rule(6, 5, 4, "0,0,1,26").unwrap();
+    universe.set_cell(2, 2, 1, Cell::Alive).unwrap();
+    universe.tick();
+    assert_eq!(universe.population(), 27);
+    // The cube wraps around to the last layer.
+    assert_eq!(universe.get_cell(1, 3, 3).unwrap(), Cell::Dead);
+    assert_eq!(universe.get_cell(1, 3, 0).unwrap(), Cell::Alive);
+
+    let layer = universe.slice(Axis::Z, 3).unwrap();
+    assert_eq!(layer.len(), 30);
+    assert_eq!(layer.iter().filter(|&&cell| cell == 1).count(), 0);
+    let side = universe.slice(Axis::X, 3).unwrap();
+    assert_eq!(side.len(), 20);
+    assert_eq!(side[5 + 3], 1);
+    assert_eq!(side.iter().filter(|&&cell| cell == 1).count(), 9);
+    assert!(universe.slice(Axis::Y, 5).is_err());
+}
+
+#[wasm_bindgen_test]
+fn neighborhoods_change_which_cells_are_counted() {
+    let mut universe = Universe::with_rule(9, 9, "B1/S").unwrap();
+    assert_eq!(universe.neighborhood_shape(), NeighborhoodShape::Moore);
+    assert!(universe.set_neighborhood(NeighborhoodShape::Moore, 0).is_err());
+
+    // Under B1/S, every neighbor of a lone cell is born and the cell dies.
+    for (shape, radius, neighbors) in [
+        (NeighborhoodShape::VonNeumann, 1, 4),
+        (NeighborhoodShape::VonNeumann, 2, 12),
+        (NeighborhoodShape::Moore, 2, 24),
+    ] {
+        let mut universe = Universe::with_rule(9, 9, "B1/S").unwrap();
+        universe.set_neighborhood(shape, radius).unwrap();
+        universe.toggle_cell(4, 4).unwrap();
+        universe.tick();
+        assert_eq!(universe.population(), neighbors);
+    }
+
+    // Edges work as usual: only two neighbors of a corner are inside.
+    universe.set_boundary(Boundary::Dead);
+    universe.set_neighborhood(NeighborhoodShape::VonNeumann, 1).unwrap();
+    universe.toggle_cell(0, 0).unwrap();
+    universe.tick();
+    assert_eq!(universe.population(), 2);
+    assert_eq!(universe.get_cell(0, 1).unwrap(), Cell::Alive);
+
+    let restored = Universe::restore(&universe.save().unwrap()).unwrap();
+    assert_eq!(restored.neighborhood_shape(), NeighborhoodShape::VonNeumann);
+
+    let mut isotropic = Universe::with_rule(9, 9, "B2a/S").unwrap();
+    assert!(isotropic.set_neighborhood(NeighborhoodShape::VonNeumann, 1).is_err());
+}
+
+#[wasm_bindgen_test]
+fn weighted_kernels_sum_their_weights() {
+    // Life itself, as the ring of ones around a zero.
+    let soup = Universe::random(20, 16, 0.4, 5).unwrap();
+    let bits = |universe: &Universe| {
+        unsafe { std::slice::from_raw_parts(universe.cells_bits(), universe.cells_bits_len() / 4) }.to_vec()
+    };
+    let mut life = Universe::with_rule(20, 16, "B3/S23").unwrap();
+    let mut weighted = Universe::with_rule(20, 16, "B3/S23").unwrap();
+    life.set_cells_bits(&bits(&soup)).unwrap();
+    weighted.set_cells_bits(&bits(&soup)).unwrap();
+    weighted.set_weighted_rule(3, &[1, 1, 1, 1, 0, 1, 1, 1, 1], &[3], &[2, 3]).unwrap();
+    assert!(weighted.has_weighted_rule());
+    life.tick_many(10);
+    weighted.tick_many(10);
+    assert_eq!(bits(&life), bits(&weighted));
+
+    assert!(weighted.set_weighted_rule(4, &[1; 16], &[3], &[2, 3]).is_err());
+    assert!(weighted.set_weighted_rule(3, &[1; 8], &[3], &[2, 3]).is_err());
+
+    // Only cells with a live neighbor to their left and none above are born.
+    let mut universe = Universe::with_rule(8, 8, "B3/S23").unwrap();
+    universe.set_weighted_rule(3, &[0, -1, 0, 2, 0, 0, 0, 0, 0], &[2], &[]).unwrap();
+    universe.toggle_cell(3, 3).unwrap();
+    universe.toggle_cell(3, 4).unwrap();
+    universe.tick();
+    assert_eq!(universe.population(), 1);
+    assert_eq!(universe.get_cell(3, 5).unwrap(), Cell::Alive);
+
+    universe.clear_weighted_rule();
+    assert!(!universe.has_weighted_rule());
+}
+
+#[wasm_bindgen_test]
+fn margolus_blocks_move_balls_and_run_backwards() {
+    assert!(MargolusUniverse::new(7, 8, MargolusRule::Critters).is_err());
+
+    // A lone billiard ball crosses its block, then the next one.
+    let mut balls = MargolusUniverse::new(8, 8, MargolusRule::BilliardBall).unwrap();
+    balls.set_cell(0, 0, Cell::Alive).unwrap();
+    balls.tick_many(4);
+    assert_eq!(balls.population(), 1);
+    assert_eq!(balls.get_cell(4, 4).unwrap(), Cell::Alive);
+
+    let mut critters = MargolusUniverse::new(16, 12, MargolusRule::Critters).unwrap();
+    critters.randomize(0.3, 9).unwrap();
+    let start = unsafe { std::slice::from_raw_parts(critters.cells(), 6) }.to_vec();
+    critters.tick_many(25);
+    assert!(critters.is_reversible());
+    for _ in 0..25 {
+        critters.tick_back().unwrap();
+    }
+    assert_eq!(critters.generation(), 0);
+    assert_eq!(unsafe { std::slice::from_raw_parts(critters.cells(), 6) }, &start[..]);
+    assert!(critters.tick_back().is_err());
+
+    let mut sink = MargolusUniverse::with_tables(4, 4, &[0; 16]).unwrap();
+    assert!(!sink.is_reversible());
+    assert!(sink.tick_back().is_err());
+}
+
+#[wasm_bindgen_test]
+fn wireworld_electrons_run_along_wires() {
+    // A tail, a head and three cells of wire, with room around them.
+    let mut circuit = Universe::from_rle("x = 5, y = 3, rule = WireWorld\n$BA3C!").unwrap();
+    assert!(circuit.is_wireworld());
+    assert_eq!(circuit.rule(), "WireWorld");
+    circuit.tick();
+    let states: Vec<u8> = (0..5).map(|col| circuit.get_state(1, col).unwrap()).collect();
+    assert_eq!(states, [3, 2, 1, 3, 3]);
+    circuit.tick_many(2);
+    assert_eq!(circuit.get_state(1, 4).unwrap(), 1);
+    assert_eq!(circuit.population(), 1);
+    assert!(circuit.to_rle().ends_with("3CBA!\n"));
+
+    // The electron fizzles out at the end of the wire, and empty cells stay empty.
+    circuit.set_state(1, 0, 0).unwrap();
+    circuit.tick_many(3);
+    assert_eq!(circuit.population(), 0);
+    assert_eq!(circuit.get_state(1, 0).unwrap(), 0);
+    assert!(circuit.set_state(1, 0, 4).is_err());
+}
+
+#[wasm_bindgen_test]
+fn golly_rule_tables_run_like_the_built_in_rules() {
+    let wireworld = "@RULE WireWorld
+@TABLE
+n_states:4
+neighborhood:Moore
+symmetries:permute
+var a={0,1,2,3}
+var b={0,1,2,3}
+var c={0,1,2,3}
+var d={0,1,2,3}
+var e={0,1,2,3}
+var f={0,1,2,3}
+var g={0,1,2,3}
+var h={0,1,2,3}
+var p={0,2,3}
+var q={0,2,3}
+var r={0,2,3}
+var s={0,2,3}
+var t={0,2,3}
+var u={0,2,3}
+var v={0,2,3}
+# C,N,NE,E,SE,S,SW,W,NW,C'
+1,a,b,c,d,e,f,g,h,2
+2,a,b,c,d,e,f,g,h,3
+3,1,p,q,r,s,t,u,v,1
+3,1,1,p,q,r,s,t,u,1
+@COLORS
+1 0 128 255
+";
+    let mut built_in = Universe::from_rle("x = 5, y = 3, rule = WireWorld\n$BA3C!").unwrap();
+    let mut table = Universe::with_rule(5, 3, "B3/S23").unwrap();
+    table.load_rule_file(wireworld).unwrap();
+    assert!(table.has_rule_table());
+    assert_eq!((table.rule().as_str(), table.state_count()), ("WireWorld", 4));
+    for (col, &state) in [2, 1, 3, 3, 3].iter().enumerate() {
+        table.set_state(1, col as u32, state).unwrap();
+    }
+    let states = |universe: &Universe| -> Vec<u8> { (0..5).map(|col| universe.get_state(1, col).unwrap()).collect() };
+    for _ in 0..5 {
+        built_in.tick();
+        table.tick();
+        assert_eq!(states(&table), states(&built_in));
+    }
+    let mut restored = Universe::restore(&table.save().unwrap()).unwrap();
+    assert_eq!(restored.rule(), "WireWorld");
+    restored.tick();
+    table.tick();
+    assert_eq!(states(&restored), states(&table));
+
+    // Back to Life, where the wires are gone.
+    table.clear_rule_table();
+    assert_eq!((table.rule().as_str(), table.state_count()), ("B3/S23", 2));
+    assert!(table.load_rule_file("@RULE Tree\n@TREE\nnum_states=2\n").is_err());
+}
+
+#[wasm_bindgen_test]
+fn plaintext_cells_files_round_trip() {
+    let glider = "!Name: Glider\n!\n.O\n..O\nOOO\n";
+    let universe = Universe::from_plaintext(glider).unwrap();
+    assert_eq!((universe.width(), universe.height(), universe.population()), (3, 3, 5));
+    assert_eq!(universe.to_plaintext().unwrap(), ".O\n..O\nOOO\n");
+    assert_eq!(Pattern::from_plaintext(glider).unwrap(), Pattern::builtin("glider").unwrap());
+
+    // Short rows and empty lines are dead cells.
+    let mut big = Universe::with_rule(8, 8, "B3/S23").unwrap();
+    big.insert_plaintext("O\n\n*.O\n\n", 1, 1).unwrap();
+    assert_eq!(big.to_plaintext().unwrap(), "O\n\nO.O\n");
+    assert!(Universe::from_plaintext("!Nothing here\n").is_err());
+    assert!(Pattern::from_plaintext(".O\nbo\n").is_err());
+
+    // Two cells far apart are mostly empty rows, but too far apart is
+    // an error rather than a grid the size of the gap.
+    let apart = Pattern::parse("#Life 1.06\n0 0\n100000 100000\n").unwrap();
+    assert_eq!(apart.to_plaintext().unwrap().len(), 100_001 + 1 + 100_001);
+    assert!(Pattern::parse("#Life 1.06\n0 0\n100000000 100000000\n").unwrap().to_plaintext().is_err());
+    assert!(Pattern::parse("#Life 1.06\n0 0\n100000000 100000000\n").unwrap().to_text(PatternFormat::Life105).is_err());
+}
+
+#[wasm_bindgen_test]
+fn life_1_05_and_1_06_files_are_recognized() {
+    let life106 = "#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n";
+    let life105 = "#Life 1.05\n#D Glider\n#R 23/36\n#P -1 -1\n.*\n..*\n***\n";
+    let glider = Pattern::builtin("glider").unwrap();
+    for text in [life106, life105, "!Name: Glider\n.O\n..O\nOOO\n", "bo$2bo$3o!"].iter() {
+        assert_eq!(Pattern::parse(text).unwrap(), glider);
+    }
+    let highlife = Universe::from_pattern_text(life105).unwrap();
+    assert_eq!((highlife.rule().as_str(), highlife.population()), ("B36/S23", 5));
+
+    // Writing and reading back again gives the same cells.
+    for &format in [PatternFormat::Rle, PatternFormat::Plaintext, PatternFormat::Life105, PatternFormat::Life106].iter() {
+        let text = highlife.to_pattern_text(format).unwrap();
+        assert_eq!(Pattern::parse(&text).unwrap(), glider);
+        assert_eq!(glider.to_text(format).unwrap(), Pattern::parse(&text).unwrap().to_text(format).unwrap());
+    }
+    assert!(highlife.to_pattern_text(PatternFormat::Life105).unwrap().contains("#R 23/36"));
+    assert!(Pattern::parse("hello").is_err());
+}
+
+#[wasm_bindgen_test]
+fn macrocell_files_keep_what_left_the_window() {
+    let glider = "[M2] (golly 4.2)\n#R B3/S23\n.*$..*$***$\n4 0 0 0 1\n5 0 0 2 0\n";
+    let mut universe = Universe::from_macrocell(glider, 64, 64).unwrap();
+    assert_eq!(universe.engine(), Engine::Hashlife);
+    assert_eq!(universe.population(), 5);
+    assert_eq!(universe.get_cell(40, 25).unwrap(), Cell::Alive);
+
+    // Long gone from the window, but still in the tree.
+    universe.tick_many(400);
+    assert_eq!(universe.population(), 0);
+    let saved = universe.to_macrocell();
+    assert!(saved.starts_with("[M2]") && saved.contains("#G 400"));
+    let mut reloaded = Universe::from_macrocell(&saved, 64, 64).unwrap();
+    assert_eq!((reloaded.population(), reloaded.generation()), (5, 400));
+    assert!(reloaded.to_rle().ends_with("\nbo$2bo$3o!\n"));
+    assert_eq!(Universe::from_macrocell(&reloaded.to_macrocell(), 64, 64).unwrap().to_rle(), reloaded.to_rle());
+    assert!(Universe::from_macrocell("#R B3/S23\n", 8, 8).is_err());
+    assert!(Universe::from_macrocell("[M2]\n4 0 0 0 7\n", 8, 8).is_err());
+}
+
+#[wasm_bindgen_test]
+fn text_snapshots_parse_back_into_universes() {
+    let mut blinker = Universe::from_text(
+        "
+.....
+.....
+.OOO.
+.....
+.....
+",
+    )
+    .unwrap();
+    blinker.tick();
+    let vertical = Universe::from_text(".....\n..O..\n..O..\n..O..\n.....\n").unwrap();
+    assert_eq!(blinker.to_string(), vertical.to_string());
+    assert_eq!(Universe::from_text(&blinker.to_string()).unwrap().to_string(), blinker.to_string());
+    assert!(Universe::from_text("◻◼\n◼").is_err());
+    assert!(Universe::from_text("ab\n").is_err());
+}
+
+#[wasm_bindgen_test]
+fn dropped_pattern_files_are_recognized_even_gzipped() {
+    let glider = Pattern::builtin("glider").unwrap();
+    let rle = b"#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+    assert_eq!(Pattern::from_file(rle).unwrap(), glider);
+    let macrocell = b"[M2] (golly 4.2)\n#R B3/S23\n.*$..*$***$\n4 0 0 0 1\n";
+    assert_eq!(Pattern::from_file(macrocell).unwrap(), glider);
+
+    // The same RLE in a gzip file holding a single stored deflate block.
+    let mut gzipped = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff, 1];
+    gzipped.extend_from_slice(&(rle.len() as u16).to_le_bytes());
+    gzipped.extend_from_slice(&(!(rle.len() as u16)).to_le_bytes());
+    gzipped.extend_from_slice(rle);
+    gzipped.extend_from_slice(&[0; 8]);
+    assert_eq!(Pattern::from_file(&gzipped).unwrap(), glider);
+
+    assert!(Pattern::from_file(&gzipped[..20]).is_err());
+    assert!(Pattern::from_file(&[0xff, 0xfe, 0]).is_err());
+}
+
+#[wasm_bindgen_test]
+fn deep_empty_macrocell_trees_are_not_walked() {
+    // A few hundred bytes for 4^27 empty leaves, which used to be visited
+    // one by one.
+    let mut empty = String::from("[M2]\n$\n");
+    for level in 4..=30 {
+        let below = level - 3;
+        empty.push_str(&format!("{} {} {} {} {}\n", level, below, below, below, below));
+    }
+    assert_eq!(Pattern::from_file(empty.as_bytes()).unwrap().width(), 0);
+
+    // A glider down in the corner of the same tree is found all the same.
+    let mut glider = String::from("[M2]\n#R B3/S23\n$\n.*$..*$***$\n");
+    let (mut nodes, mut empty_below, mut glider_below) = (2, 1, 2);
+    for level in 4..=30 {
+        let e = empty_below;
+        glider.push_str(&format!("{} {} {} {} {}\n{} {} {} {} {}\n", level, e, e, e, e, level, e, e, e, glider_below));
+        empty_below = nodes + 1;
+        glider_below = nodes + 2;
+        nodes += 2;
+    }
+    assert_eq!(Pattern::from_file(glider.as_bytes()).unwrap(), Pattern::builtin("glider").unwrap());
+}
+
+#[wasm_bindgen_test]
+fn apgcodes_name_objects_like_catagolue() {
+    let code = |name: &str| Pattern::builtin(name).unwrap().apgcode("B3/S23").unwrap();
+    assert_eq!(code("block"), "xs4_33");
+    assert_eq!(code("beehive"), "xs6_696");
+    assert_eq!(code("blinker"), "xp2_7");
+    assert_eq!(code("glider"), "xq4_153");
+    assert_eq!(code("lwss"), "xq4_6frc");
+    // Any phase and orientation gets the same code.
+    let turned = Pattern::builtin("glider").unwrap().rotate90().flip_h();
+    assert_eq!(turned.apgcode("B3/S23").unwrap(), "xq4_153");
+
+    let pulsar = "xp3_co9nas0san9oczgoldlo0oldlogz1047210127401";
+    let decoded = Pattern::from_apgcode(pulsar).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (13, 13));
+    assert_eq!(decoded.apgcode("B3/S23").unwrap(), pulsar);
+    assert!(Pattern::from_apgcode("xs4_3!").is_err());
+    assert!(Pattern::builtin("r-pentomino").unwrap().apgcode("B3/S23").is_err());
+}
+
+#[wasm_bindgen_test]
+fn brians_brain_cells_rest_after_firing() {
+    let mut brain = Universe::from_preset(32, 32, Preset::BriansBrain, 7).unwrap();
+    assert_eq!(brain.rule(), "B2/S/C3");
+    assert_eq!(brain.state_count(), 3);
+    let states = |universe: &Universe| -> Vec<u8> {
+        (0..32 * 32).map(|idx| universe.get_state(idx / 32, idx % 32).unwrap()).collect()
+    };
+    let before = states(&brain);
+    assert!(before.contains(&1) && before.contains(&2));
+
+    brain.tick();
+    for (was, now) in before.into_iter().zip(states(&brain)) {
+        match was {
+            1 => assert_eq!(now, 2),
+            2 => assert_eq!(now, 0),
+            _ => assert!(now < 2),
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn langtons_ant_draws_a_block_then_turns_back() {
+    // A rule under which nothing moves but the ant.
+    let mut universe = Universe::with_rule(16, 16, "B/S012345678").unwrap();
+    universe.add_ant(5, 5, Direction::North, "RL").unwrap();
+    assert!(universe.add_ant(16, 0, Direction::North, "RL").is_err());
+    assert!(universe.add_ant(0, 0, Direction::North, "RLR").is_err());
+
+    // Four right turns on empty cells leave a block behind.
+    universe.tick_many(4);
+    assert_eq!(universe.population(), 4);
+    assert_eq!(universe.turmites(), [5, 5, Direction::North as u32, 0]);
+    universe.tick();
+    assert_eq!(universe.population(), 3);
+    assert_eq!(universe.turmites(), [5, 4, Direction::West as u32, 0]);
+
+    // The same ant as a table.
+    universe.add_turmite(0, 0, Direction::East, &[1, 1, 0, 0, 3, 0]).unwrap();
+    assert_eq!(universe.turmite_count(), 2);
+    universe.remove_turmite(0).unwrap();
+    universe.tick();
+    assert_eq!(universe.turmites(), [1, 0, Direction::South as u32, 0]);
+    universe.clear_turmites();
+    assert!(universe.remove_turmite(0).is_err());
+}
+
+#[wasm_bindgen_test]
+fn rule_90_draws_sierpinski_down_the_diagram() {
+    let mut universe = Universe1D::new(9, 4, 90).unwrap();
+    universe.seed_center();
+    universe.tick_many(2);
+    assert_eq!(universe.generation(), 2);
+    // Newest first: every cell is the XOR of its two neighbors in the row below.
+    let rows: Vec<String> = (0..4)
+        .map(|row| {
+            (0..9)
+                .map(|col| match universe.get_cell(row, col).unwrap() {
+                    Cell::Alive => '#',
+                    Cell::Dead => '.',
+                })
+                .collect()
+        })
+        .collect();
+    assert_eq!(rows, ["..#...#..", "...#.#...", "....#....", "........."]);
+    assert_eq!(universe.population(), 2);
+
+    // Rule 30 from the same start, against dead edges.
+    universe.set_rule(30);
+    universe.set_boundary(Boundary::Dead);
+    universe.seed_center();
+    universe.tick();
+    assert_eq!(universe.population(), 3);
+    assert!(universe.set_cell(9, Cell::Alive).is_err());
+}
+
+#[wasm_bindgen_test]
+fn stochastic_rules_repeat_with_the_same_seed() {
+    let noisy = |seed| {
+        let mut universe = Universe::random(32, 32, 0.4, 3).unwrap();
+        universe.set_seed(seed);
+        universe.set_stochastic(0.9, 1.0, 0.01).unwrap();
+        universe.tick_many(20);
+        universe.to_rle()
+    };
+    assert_eq!(noisy(1), noisy(1));
+    assert_ne!(noisy(1), noisy(2));
+
+    // Certain births and survivals are plain Life.
+    let mut life = Universe::random(32, 32, 0.4, 3).unwrap();
+    let mut dice = Universe::random(32, 32, 0.4, 3).unwrap();
+    dice.set_stochastic(1.0, 1.0, 0.0).unwrap();
+    life.tick_many(20);
+    dice.tick_many(20);
+    assert!(dice.is_stochastic());
+    assert_eq!(life.to_rle(), dice.to_rle());
+
+    dice.set_stochastic(1.0, 1.0, 1.0).unwrap();
+    dice.tick();
+    assert_eq!(dice.population(), 0);
+    assert!(dice.set_stochastic(1.5, 1.0, 0.0).is_err());
+}
+
+#[wasm_bindgen_test]
+fn one_seed_gives_the_same_soups_dice_and_mutations() {
+    let run = |seed| {
+        let mut universe = Universe::with_rule(32, 32, "B3/S23").unwrap();
+        universe.set_seed(seed);
+        universe.randomize(0.4).unwrap();
+        universe.mutate_rule().unwrap();
+        universe.set_stochastic(0.9, 1.0, 0.01).unwrap();
+        universe.tick_many(10);
+        (universe.rule(), universe.to_rle())
+    };
+    assert_eq!(run(9), run(9));
+    assert_ne!(run(9), run(10));
+
+    // Universe.random() seeds the generator it draws the soup from.
+    let mut universe = Universe::random(32, 32, 0.4, 3).unwrap();
+    assert_eq!(universe.get_seed(), 3);
+    let mut again = Universe::with_rule(32, 32, "B3/S23").unwrap();
+    again.set_seed(3);
+    again.randomize(0.4).unwrap();
+    assert_eq!(again.to_rle(), universe.to_rle());
+
+    // A save carries on with the same dice.
+    universe.set_stochastic(0.9, 1.0, 0.01).unwrap();
+    universe.tick_many(5);
+    let mut restored = Universe::restore(&universe.save().unwrap()).unwrap();
+    universe.tick_many(5);
+    restored.tick_many(5);
+    assert_eq!(restored.get_seed(), 3);
+    assert_eq!(restored.to_rle(), universe.to_rle());
+
+    let mut table = Universe::with_rule(8, 8, "B2/S/C3").unwrap();
+    assert!(table.mutate_rule().is_err());
+    assert!(again.randomize(1.5).is_err());
+}
+
+#[wasm_bindgen_test]
+fn symmetric_soups_map_onto_themselves() {
+    let soup = |width, height, symmetry| {
+        let mut universe = Universe::with_rule(width, height, "B3/S23").unwrap();
+        universe.set_seed(4);
+        universe.randomize_symmetric(symmetry, 0.5).unwrap();
+        let mut cells = get_cells(&universe);
+        cells.sort_unstable();
+        cells
+    };
+    let maps_onto = |cells: &[(u32, u32)], transform: &dyn Fn(u32, u32) -> (u32, u32)| {
+        let mut moved: Vec<_> = cells.iter().map(|&(row, col)| transform(row, col)).collect();
+        moved.sort_unstable();
+        moved == cells
+    };
+
+    // 31x31 around the cell at (20, 20).
+    let d8 = soup(41, 41, "D8_1");
+    assert!(!d8.is_empty() && d8.iter().all(|&(row, col)| (5..36).contains(&row) && (5..36).contains(&col)));
+    assert!(maps_onto(&d8, &|row, col| (col, row)));
+    assert!(maps_onto(&d8, &|row, col| (row, 40 - col)));
+
+    // 32x32 around the corner between (19, 19) and (20, 20).
+    let c2 = soup(40, 40, "C2_4");
+    assert!(maps_onto(&c2, &|row, col| (39 - row, 39 - col)));
+    assert!(!maps_onto(&c2, &|row, col| (row, 39 - col)));
+
+    // 31 wide and 16 high, mirrored across column 20.
+    let d2 = soup(41, 40, "D2_+1");
+    assert!(d2.iter().all(|&(row, _)| (12..28).contains(&row)));
+    assert!(maps_onto(&d2, &|row, col| (row, 40 - col)));
+
+    assert_eq!(soup(40, 40, "C1"), soup(40, 40, "C1"));
+    let mut small = Universe::with_rule(20, 20, "B3/S23").unwrap();
+    assert!(small.randomize_symmetric("D8_4", 0.5).is_err());
+    assert!(small.randomize_symmetric("D5", 0.5).is_err());
+    small.randomize_symmetric("D2_x", 0.5).unwrap();
+    assert!(maps_onto(&get_cells(&small), &|row, col| (col, row)));
+}
+
+#[wasm_bindgen_test]
+fn noise_makes_blobs_rather_than_a_soup() {
+    let noise = |seed, frequency, threshold| {
+        let mut universe = Universe::with_rule(64, 64, "B3/S23").unwrap();
+        universe.set_seed(seed);
+        universe.randomize_noise(frequency, threshold).unwrap();
+        universe
+    };
+    // How often a cell is the same as the one to its right.
+    let alike = |universe: &Universe| {
+        let cells = get_cells(universe);
+        let same = (0..64).flat_map(|row| (0..63).map(move |col| (row, col)))
+            .filter(|&(row, col)| cells.contains(&(row, col)) == cells.contains(&(row, col + 1)))
+            .count();
+        same as f32 / (64.0 * 63.0)
+    };
+
+    let blobs = noise(1, 0.08, 0.0);
+    assert!(blobs.population() > 64 * 64 / 5 && blobs.population() < 64 * 64 * 4 / 5);
+    assert!(alike(&blobs) > 0.8);
+    assert!(alike(&Universe::random(64, 64, 0.5, 1).unwrap()) < 0.6);
+    assert_eq!(noise(1, 0.08, 0.0).to_rle(), blobs.to_rle());
+    assert_ne!(noise(2, 0.08, 0.0).to_rle(), blobs.to_rle());
+    assert!(noise(1, 0.08, 0.3).population() < blobs.population());
+    assert_eq!(noise(1, 0.08, 2.0).population(), 0);
+
+    let mut universe = Universe::with_rule(8, 8, "B3/S23").unwrap();
+    assert!(universe.randomize_noise(0.0, 0.0).is_err());
+    assert!(universe.randomize_noise(0.1, f32::NAN).is_err());
+}
+
+#[wasm_bindgen_test]
+fn soup_searches_take_a_census_of_the_ash() {
+    let search = || {
+        let mut search = SoupSearch::new("B3/S23", "C1", 4, 11).unwrap();
+        search.set_size(96).unwrap();
+        search
+    };
+    let mut first = search();
+    assert_eq!(first.run(3).unwrap(), 3);
+    assert_eq!(first.done(), 3);
+    assert!(!first.is_finished());
+    let census = first.run_all().unwrap();
+    assert!(first.is_finished());
+    assert_eq!(first.run(1).unwrap(), 0);
+    assert_eq!(census.soups(), 4);
+
+    // Blocks are the commonest ash there is, and everything else has an
+    // apgcode of a still life, an oscillator or a spaceship.
+    assert!(census.count("xs4_33") > 0.0);
+    assert!(census.codes().iter().all(|code| code == "unidentified" || ["xs", "xp", "xq"].contains(&&code[..2])));
+    assert_eq!(search().run_all().unwrap(), census);
+
+    assert!(SoupSearch::new("B03/S23", "C1", 1, 0).is_err());
+    assert!(SoupSearch::new("B3/S23", "C3", 1, 0).is_err());
+    assert!(SoupSearch::new("B3/S23", "D8_4", 1, 0).unwrap().set_size(20).is_err());
+}
+
+#[wasm_bindgen_test]
+fn objects_are_told_apart_and_named() {
+    let mut universe = Universe::with_rule(32, 32, "B3/S23").unwrap();
+    universe.insert_pattern("block", 2, 2).unwrap();
+    universe.insert_pattern("blinker", 2, 20).unwrap();
+    universe.insert_pattern("glider", 20, 4).unwrap();
+    universe.insert_pattern("r-pentomino", 20, 20).unwrap();
+    let objects = universe.identify_objects().unwrap();
+    let found: Vec<(ObjectKind, Option<String>, Option<u32>)> = objects.iter().map(|object| (object.kind(), object.name(), object.period())).collect();
+    assert_eq!(
+        found,
+        vec![
+            (ObjectKind::StillLife, Some("block".to_string()), Some(1)),
+            (ObjectKind::Oscillator, Some("blinker".to_string()), Some(2)),
+            (ObjectKind::Spaceship, Some("glider".to_string()), Some(4)),
+            (ObjectKind::Unidentified, None, None),
+        ]
+    );
+    assert_eq!(objects[0].code(), Some("xs4_33".to_string()));
+    assert_eq!((objects[0].top(), objects[0].left(), objects[0].width(), objects[0].height()), (2, 2, 2, 2));
+    assert!(objects[2].contains(21, 5));
+    assert!(!objects[2].contains(2, 2));
+    assert_eq!(objects[3].population(), 5);
+    assert_eq!(wasm_game_of_life::object_name("xq4_6frc"), Some("lightweight spaceship".to_string()));
+    assert_eq!(wasm_game_of_life::object_name("xs4_252"), Some("tub".to_string()));
+
+    let mut brain = Universe::with_rule(8, 8, "B2/S/C3").unwrap();
+    brain.toggle_cell(2, 2).unwrap();
+    assert!(brain.identify_objects().is_err());
+}
+
+#[wasm_bindgen_test]
+fn spaceships_have_a_velocity_and_a_heading() {
+    let mut universe = Universe::with_rule(48, 48, "B3/S23").unwrap();
+    universe.insert_pattern("block", 20, 20).unwrap();
+    // One glider on its way out to the south east, one coming in from
+    // the north west, and a lightweight spaceship going west.
+    universe.insert_pattern("glider", 32, 32).unwrap();
+    universe.insert_pattern("glider", 4, 16).unwrap();
+    universe.insert_pattern("lwss", 21, 2).unwrap();
+    let objects = universe.identify_objects().unwrap();
+    let headings: Vec<Option<Heading>> = objects.iter().map(|object| object.heading()).collect();
+    assert_eq!(headings, vec![Some(Heading::SouthEast), None, Some(Heading::West), Some(Heading::SouthEast)]);
+    assert_eq!((objects[0].velocity_rows(), objects[0].velocity_cols(), objects[0].speed()), (0.25, 0.25, 0.25));
+    assert_eq!((objects[2].velocity_rows(), objects[2].velocity_cols(), objects[2].speed()), (0.0, -0.5, 0.5));
+    assert!(!objects[1].is_moving());
+
+    // Only the glider already past the block has got away.
+    let escaping = universe.escaping_objects().unwrap();
+    assert_eq!(escaping.len(), 2);
+    assert_eq!((escaping[0].top(), escaping[0].left()), (21, 2));
+    assert_eq!((escaping[1].top(), escaping[1].left()), (32, 32));
+}
+
+#[wasm_bindgen_test]
+fn methuselah_searches_evolve_longer_lived_patterns() {
+    let search = || {
+        let mut search = MethuselahSearch::new("B3/S23", 6, 5).unwrap();
+        search.set_box(4, 4).unwrap();
+        search.set_candidates(8).unwrap();
+        search.set_size(96).unwrap();
+        search.set_max_generations(3000);
+        search
+    };
+    let mut first = search();
+    assert_eq!(first.run(2).unwrap(), 2);
+    let early = first.best()[0].lifespan();
+    let best = first.run_all().unwrap();
+    assert!(first.is_finished());
+    assert!(first.tried() > 8);
+
+    // The best only get better, and come out longest first, with RLE
+    // that gives back a pattern of the size they started at.
+    assert!(best[0].lifespan() >= early);
+    assert!(best.windows(2).all(|pair| pair[0].lifespan() >= pair[1].lifespan()));
+    let pattern = Pattern::from_rle(&best[0].rle()).unwrap();
+    assert_eq!(pattern.cells().len() as u32, 2 * best[0].population());
+    assert!(pattern.width() <= 4 && pattern.height() <= 4);
+    assert_eq!(search().run_all().unwrap(), best);
+
+    assert!(MethuselahSearch::new("B03/S23", 1, 0).is_err());
+    assert!(search().set_box(9, 8).is_err());
+    assert!(search().set_box(1, 1).is_err());
+    assert!(search().set_box(65536, 65536).is_err());
+    assert!(search().set_candidates(1).is_err());
+}
+
+#[wasm_bindgen_test]
+fn forest_fires_burn_through_the_trees() {
+    let mut forest = ForestFire::new(8, 8, 1).unwrap();
+    forest.set_probabilities(0.0, 0.0).unwrap();
+    forest.plant(1.0).unwrap();
+    assert_eq!(forest.population(), 64);
+    forest.set_state(0, 0, 2).unwrap();
+    assert_eq!(forest.population(), 63);
+
+    // The fire reaches the four nearest trees, wrapping around the edges.
+    forest.tick();
+    assert_eq!(forest.fires(), 4);
+    assert_eq!(forest.get_state(0, 0).unwrap(), 0);
+    assert_eq!(forest.get_state(7, 0).unwrap(), 2);
+    assert_eq!(forest.tick_stats().deaths(), 4);
+
+    forest.tick_many(10);
+    assert_eq!(forest.population(), 0);
+    assert_eq!(forest.fires(), 0);
+    assert_eq!(forest.population_history(3), [0, 0, 0]);
+
+    // Trees grow back.
+    forest.set_probabilities(0.5, 0.0).unwrap();
+    forest.tick();
+    assert_eq!(forest.population(), forest.tick_stats().births());
+    assert!(forest.population() > 0);
+    assert!(forest.set_state(0, 0, 3).is_err());
+}
+
+#[wasm_bindgen_test]
+fn cyclic_states_eat_the_one_before() {
+    let mut universe = CyclicUniverse::new(8, 8, 3, 2).unwrap();
+    // One neighbor in state 1 isn't enough to eat state 0, two are.
+    universe.set_state(3, 3, 1).unwrap();
+    universe.tick();
+    assert_eq!(universe.get_state(3, 4).unwrap(), 0);
+    universe.set_state(4, 3, 1).unwrap();
+    universe.tick();
+    assert_eq!(universe.get_state(3, 4).unwrap(), 1);
+    assert_eq!(universe.get_state(0, 0).unwrap(), 0);
+
+    // And the last state is eaten by the first.
+    let mut cycle = CyclicUniverse::new(4, 4, 3, 1).unwrap();
+    for row in 0..4 {
+        for col in 0..4 {
+            cycle.set_state(row, col, 2).unwrap();
+        }
+    }
+    cycle.set_state(0, 0, 0).unwrap();
+    cycle.tick();
+    assert_eq!(cycle.get_state(0, 1).unwrap(), 0);
+    assert_eq!(cycle.get_state(2, 2).unwrap(), 2);
+
+    assert!(CyclicUniverse::new(8, 8, 3, 9).is_err());
+    universe.set_neighborhood(NeighborhoodShape::VonNeumann, 2).unwrap();
+    assert!(universe.set_threshold(13).is_err());
+}
+
+#[wasm_bindgen_test]
+fn custom_rules_from_javascript_replace_the_rule() {
+    let life = js_sys::Function::new_with_args("state, count", "return count == 3 || (state == 1 && count == 2)");
+    let mut custom = Universe::random(24, 24, 0.4, 8).unwrap();
+    let mut builtin = Universe::random(24, 24, 0.4, 8).unwrap();
+    custom.set_custom_rule(life);
+    assert!(custom.has_custom_rule());
+    custom.tick_many(10);
+    builtin.tick_many(10);
+    assert_eq!(custom.to_rle(), builtin.to_rle());
+
+    // Only cells with their north neighbor alive, and nothing else, live on.
+    let north = js_sys::Function::new_with_args("state, count, bits", "return bits == 64 ? 1 : 0");
+    let mut universe = Universe::with_rule(8, 8, "B3/S23").unwrap();
+    universe.set_custom_rule(north);
+    universe.toggle_cell(2, 2).unwrap();
+    universe.tick();
+    assert_eq!(universe.population(), 1);
+    assert_eq!(universe.get_cell(3, 2).unwrap(), Cell::Alive);
+
+    universe.clear_custom_rule();
+    universe.tick();
+    assert_eq!(universe.population(), 0);
+}
+
+#[wasm_bindgen_test]
+fn rust_rules_plug_into_the_core_universe() {
+    use game_of_life_core as life;
+
+    // Fredkin's replicator: alive with an odd number of live neighbors.
+    struct Replicator;
+    impl life::Rule for Replicator {
+        fn next(&self, _cell: life::Cell, neighbors: life::Neighbors) -> life::Cell {
+            if neighbors.count() % 2 == 1 {
+                life::Cell::Alive
+            } else {
+                life::Cell::Dead
+            }
+        }
+    }
+
+    let mut custom = life::Universe::random(24, 24, 0.3, 4).unwrap();
+    let mut builtin = life::Universe::with_rule(24, 24, "B1357/S1357").unwrap();
+    builtin.set_cells_bits(custom.cells()).unwrap();
+    custom.set_custom_rule(Some(Box::new(Replicator)));
+    assert!(custom.has_custom_rule());
+    custom.tick_many(5);
+    builtin.tick_many(5);
+    assert_eq!(custom.cells(), builtin.cells());
+
+    // Built-in rules are rules too.
+    let highlife: life::LifeRule = "B36/S23".parse().unwrap();
+    let mut wrapped = life::Universe::random(16, 16, 0.4, 2).unwrap();
+    let mut plain = life::Universe::with_rule(16, 16, "B36/S23").unwrap();
+    plain.set_cells_bits(wrapped.cells()).unwrap();
+    wrapped.set_custom_rule(Some(Box::new(highlife)));
+    wrapped.tick_many(5);
+    plain.tick_many(5);
+    assert_eq!(wrapped.cells(), plain.cells());
+}
+
+#[wasm_bindgen_test]
+fn options_objects_go_in_and_plain_objects_come_out() {
+    let options = |json: &str| js_sys::JSON::parse(json).unwrap();
+    let universe = Universe::create(options(r#"{"width": 9, "height": 7, "rule": "B36/S23", "boundary": "Dead", "pattern": "bob$2bo$3o!"}"#)).unwrap();
+    assert_eq!((universe.width(), universe.height()), (9, 7));
+    assert_eq!(universe.boundary(), Boundary::Dead);
+    assert_eq!(universe.get_cell(2, 4).unwrap(), Cell::Alive);
+    let soup = Universe::create(options(r#"{"seed": 42}"#)).unwrap();
+    assert_eq!(soup.to_rle(), Universe::random(64, 64, 0.5, 42).unwrap().to_rle());
+    assert!(Universe::create(options(r#"{"widht": 9}"#)).is_err());
+    assert!(Universe::create(options(r#"{"density": 0.3}"#)).is_err());
+
+    let mut blinker = Universe::create(options(r#"{"width": 5, "height": 5, "pattern": "3o!"}"#)).unwrap();
+    blinker.tick();
+    let stats = blinker.tick_stats();
+    let json = js_sys::JSON::stringify(&stats.to_json()).unwrap();
+    assert_eq!(String::from(json), r#"{"births":2,"deaths":2,"population":3,"changed":4}"#);
+    let report = js_sys::JSON::stringify(&blinker.run_until_stable(10, 4).to_json()).unwrap();
+    assert!(String::from(report).contains(r#""reason":"Cycle""#));
+}
+
+#[wasm_bindgen_test]
+fn typed_array_views_show_the_cells_in_place() {
+    let blinker = Pattern::from_rle("3o!").unwrap();
+    let mut universe = UniverseBuilder::new().size(5, 5).seed_pattern(&blinker).build().unwrap();
+    let live = |view: js_sys::Uint8Array| view.to_vec().iter().map(|byte| byte.count_ones()).sum::<u32>();
+    let view = universe.cells_view();
+    assert_eq!(view.length() as usize, universe.cells_bits_len());
+    assert_eq!(live(view), 3);
+    universe.tick();
+    assert_eq!(live(universe.cells_view()), 3);
+    assert_eq!(live(universe.changed_cells_view()), 4);
+    assert_eq!(universe.ages_view().length(), 25);
+
+    // Memory grown since the last view is noticed by whatever steps
+    // next, not just by tick().
+    let grown = js_sys::Array::new();
+    let callback = js_sys::Function::new_no_args("this.push(1)").bind0(&grown);
+    wasm_game_of_life::set_memory_grow_callback(Some(callback));
+    let _ = universe.cells_view();
+    let mut big = Universe::with_rule(4096, 4096, "B3/S23").unwrap();
+    big.tick_delta();
+    assert_eq!(grown.length(), 1);
+    wasm_game_of_life::set_memory_grow_callback(None);
+}
+
+#[wasm_bindgen_test]
+fn memory_report_counts_what_the_universe_holds() {
+    let mut universe = Universe::random(64, 64, 0.5, 1).unwrap();
+    let report = universe.memory_report();
+    assert_eq!(report.cells(), 4 * universe.cells_bits_len());
+    assert_eq!((report.hashlife(), report.overlays()), (0, 0));
+    assert!(report.patterns() > 0);
+
+    universe.tick_many(10);
+    universe.ages();
+    universe.set_engine(Engine::Hashlife);
+    universe.tick();
+    let report = universe.memory_report();
+    assert!(report.history() > 0);
+    assert!(report.hashlife() > 0);
+    assert!(report.overlays() >= 64 * 64 * 2);
+    assert_eq!(report.total(), report.cells() + report.history() + report.hashlife() + report.overlays() + report.patterns());
+}
+
+#[wasm_bindgen_test]
+fn reserve_shrink_and_the_history_budget_manage_memory() {
+    let mut universe = Universe::random(32, 32, 0.5, 3).unwrap();
+    universe.ages();
+    universe.reserve(128, 128).unwrap();
+    assert!(universe.memory_report().overlays() >= 128 * 128 * 2);
+    universe.resize(64, 64, Anchor::TopLeft).unwrap();
+    assert!(universe.memory_report().overlays() >= 128 * 128 * 2);
+    universe.shrink_to_fit();
+    assert_eq!(universe.memory_report().overlays(), 64 * 64 * 2);
+    assert!(universe.reserve(1 << 16, 1 << 16).is_err());
+
+    universe.set_history_budget(Some(64));
+    universe.tick_many(5);
+    assert!(!universe.can_undo());
+    universe.set_history_budget(None);
+    universe.tick_many(5);
+    assert!(universe.can_undo());
+}
+
+// The tutorial's testing helpers: live cells in and out as (row, col)
+// pairs, so the expected generations below can be written down as the
+// cells they contain.
+fn set_cells(universe: &mut Universe, cells: &[(u32, u32)]) {
+    let pairs: Vec<u32> = cells.iter().flat_map(|&(row, col)| vec![row, col]).collect();
+    universe.set_cells(&pairs).unwrap();
+}
+
+fn get_cells(universe: &Universe) -> Vec<(u32, u32)> {
+    let mut cells = Vec::new();
+    for row in 0..universe.height() {
+        for col in 0..universe.width() {
+            if universe.get_cell(row, col).unwrap() == Cell::Alive {
+                cells.push((row, col));
+            }
+        }
+    }
+    cells
+}
+
+#[wasm_bindgen_test]
+fn spaceships_move_on_after_four_ticks() {
+    let mut universe = Universe::with_rule(8, 8, "B3/S23").unwrap();
+    set_cells(&mut universe, &[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]);
+    universe.tick_many(4);
+    assert_eq!(get_cells(&universe), vec![(2, 3), (3, 4), (4, 2), (4, 3), (4, 4)]);
+
+    // A lightweight spaceship goes two cells to the left in the same
+    // four ticks.
+    let mut universe = Universe::with_rule(12, 8, "B3/S23").unwrap();
+    universe.insert_pattern("lwss", 2, 6).unwrap();
+    let start: Vec<(u32, u32)> = get_cells(&universe).iter().map(|&(row, col)| (row, col - 2)).collect();
+    universe.tick_many(4);
+    assert_eq!(get_cells(&universe), start);
+}
+
+#[wasm_bindgen_test]
+fn blinkers_have_period_two() {
+    let mut universe = Universe::with_rule(5, 5, "B3/S23").unwrap();
+    set_cells(&mut universe, &[(2, 1), (2, 2), (2, 3)]);
+    universe.tick();
+    assert_eq!(get_cells(&universe), vec![(1, 2), (2, 2), (3, 2)]);
+    universe.tick();
+    assert_eq!(get_cells(&universe), vec![(2, 1), (2, 2), (2, 3)]);
+}
+
+#[wasm_bindgen_test]
+fn boundaries_decide_what_a_blinker_on_the_edge_sees() {
+    let next = |boundary: Boundary| {
+        let mut universe = Universe::with_rule(5, 5, "B3/S23").unwrap();
+        universe.set_boundary(boundary);
+        set_cells(&mut universe, &[(0, 1), (0, 2), (0, 3)]);
+        universe.tick();
+        get_cells(&universe)
+    };
+    // Wrapping around puts the top of the blinker on the bottom row.
+    assert_eq!(next(Boundary::Torus), vec![(0, 2), (1, 2), (4, 2)]);
+    assert_eq!(next(Boundary::Dead), vec![(0, 2), (1, 2)]);
+    // The mirrored row above gives the middle cell too many neighbors
+    // and the ends just enough.
+    assert_eq!(next(Boundary::Mirror), vec![(0, 1), (0, 3), (1, 2)]);
+    // A live wall kills the blinker, but gives birth along the sides.
+    assert_eq!(next(Boundary::AliveWall), vec![(1, 2), (2, 0), (2, 4), (3, 0), (3, 4), (4, 1), (4, 2), (4, 3)]);
+}
+
+#[wasm_bindgen_test]
+fn soups_round_trip_through_rle() {
+    let mut soup = Universe::random(24, 16, 0.4, 9).unwrap();
+    soup.tick_many(3);
+    let copy = Universe::from_rle(&soup.to_rle()).unwrap();
+    // The RLE only spans the live cells, so compare them from the corner of their box.
+    let corner = |cells: Vec<(u32, u32)>| {
+        let top = cells.iter().map(|cell| cell.0).min().unwrap();
+        let left = cells.iter().map(|cell| cell.1).min().unwrap();
+        cells.iter().map(|&(row, col)| (row - top, col - left)).collect::<Vec<_>>()
+    };
+    assert_eq!(corner(get_cells(&copy)), corner(get_cells(&soup)));
+    assert_eq!(copy.to_rle(), soup.to_rle());
+}
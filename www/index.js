@@ -1,5 +1,4 @@
-import { Universe, Cell } from "wasm-game-of-life";
-import { memory } from "wasm-game-of-life/wasm_game_of_life_bg";
+import { Universe, Renderer, GameLoop } from "wasm-game-of-life";
 
 const CELL_SIZE = 5; // px
 const GRID_COLOR = "#CCCCCC";
@@ -26,121 +25,61 @@ const ALIVE_COLOR = "#000000";
 // to no longer write to the <pre>'s textContent 
 // but instead draw to the <canvas>
 // Construct the universe, and get its width and height.
-const universe = Universe.new();
+// A link made with "Copy link" carries its universe in the #fragment.
+const universe = window.location.hash.length > 1
+  ? Universe.from_share_string(window.location.hash.slice(1))
+  : Universe.new();
 const width = universe.width();
 const height = universe.height();
 
-// Give the canvas room for all of our cells and a 1px border
-// around each of them.
+// The Renderer sizes the canvas for all of our cells and a 1px border
+// around each of them, draws the grid, and from then on only repaints
+// the cells that changed since the last frame.
 const canvas = document.getElementById("game-of-life-canvas");
-canvas.height = (CELL_SIZE + 1) * height + 1;
-canvas.width = (CELL_SIZE + 1) * width + 1;
-
-const ctx = canvas.getContext('2d');
-
-const renderLoop = () => {
-  universe.tick();
-
-  drawGrid();
-  drawCells();
-
-  requestAnimationFrame(renderLoop);
+const renderer = new Renderer(canvas, CELL_SIZE);
+renderer.set_colors(ALIVE_COLOR, DEAD_COLOR, GRID_COLOR);
+
+// The GameLoop takes the universe and the renderer over, and on every
+// animation frame ticks, draws, and then calls us back. It also draws
+// the initial state right away, so the first frame we see is the first
+// "tick" of the life of these cells and not the second.
+const gameLoop = new GameLoop(universe, renderer);
+
+// generation() and population() are kept up to date by the universe,
+// and so are the perf_stats() of its ticks, so this is cheap enough to
+// do every frame.
+const stats = document.getElementById("stats");
+const drawStats = () => {
+  const generation = Number(gameLoop.generation()).toLocaleString();
+  const population = gameLoop.population().toLocaleString();
+  const tickMs = gameLoop.perf_stats().average_tick_ms().toFixed(2);
+  stats.textContent = `Gen ${generation} – ${population} alive – ${tickMs} ms/tick`;
 };
+gameLoop.set_on_frame(drawStats);
 
-// To draw the grid between cells, 
-// we draw a set of equally-spaced horizontal lines, 
-// and a set of equally-spaced vertical lines. 
-// These lines criss-cross to form the grid.
-const drawGrid = () => {
-  ctx.beginPath();
-  ctx.strokeStyle = GRID_COLOR;
-
-  // Vertical lines.
-  for (let i = 0; i <= width; i++) {
-    ctx.moveTo(i * (CELL_SIZE + 1) + 1, 0);
-    ctx.lineTo(i * (CELL_SIZE + 1) + 1, (CELL_SIZE + 1) * height + 1);
-  }
+// Clicking a cell flips it. We translate the click from page coordinates
+// into canvas coordinates, then into a row and column.
+canvas.addEventListener("click", event => {
+  const boundingRect = canvas.getBoundingClientRect();
 
-  // Horizontal lines.
-  for (let j = 0; j <= height; j++) {
-    ctx.moveTo(0,                           j * (CELL_SIZE + 1) + 1);
-    ctx.lineTo((CELL_SIZE + 1) * width + 1, j * (CELL_SIZE + 1) + 1);
-  }
+  const scaleX = canvas.width / boundingRect.width;
+  const scaleY = canvas.height / boundingRect.height;
 
-  ctx.stroke();
-};
+  const canvasLeft = (event.clientX - boundingRect.left) * scaleX;
+  const canvasTop = (event.clientY - boundingRect.top) * scaleY;
 
-// We can directly access WebAssembly's linear memory via memory, 
-// which is defined in the raw wasm module wasm_game_of_life_bg. 
-//
-// To draw the cells, we get a pointer to the universe's cells, 
-// construct a Uint8Array overlaying the cells buffer, iterate over each cell, 
-// and draw a white or black rectangle depending on whether the cell is dead or alive, 
-// respectively. 
-//
-// By working with pointers and overlays, 
-// we avoid copying the cells across the boundary on every tick.
-// Import the WebAssembly memory at the top of the file,
-// with import { memory } from "wasm-game-of-life/wasm_game_of_life_bg";
+  const row = Math.min(Math.floor(canvasTop / (CELL_SIZE + 1)), height - 1);
+  const col = Math.min(Math.floor(canvasLeft / (CELL_SIZE + 1)), width - 1);
 
-const getIndex = (row, column) => {
-  return row * width + column;
-};
+  gameLoop.toggle_cell(row, col);
+});
 
-// Given an index and Uint8Array, 
-// you can determine whether the nth bit is set with the following function
-const bitIsSet = (n, arr) => {
-  const byte = Math.floor(n / 8);
-  const mask = 1 << (n % 8);
-  return (arr[byte] & mask) === mask;
-};
-
-const drawCells = () => {
-  const cellsPtr = universe.cells();
-  
-    //
-    // In JavaScript, constructing a Uint8Array from Wasm memory is the same as 
-    // before, except that the length of the array is 
-    // not width * height anymore, but width * height / 8 
-    // since we have a cell per bit rather than per byte
-  //const cells = new Uint8Array(memory.buffer, cellsPtr, width * height);
-  const cells = new Uint8Array(memory.buffer, cellsPtr, width * height / 8);
-
-  ctx.beginPath();
-
-  for (let row = 0; row < height; row++) {
-    for (let col = 0; col < width; col++) {
-      const idx = getIndex(row, col);
-
-      //  ctx.fillStyle = cells[idx] === Cell.Dead
-      ctx.fillStyle = bitIsSet(idx, cells)
-        ? DEAD_COLOR
-        : ALIVE_COLOR;
-
-      ctx.fillRect(
-        col * (CELL_SIZE + 1) + 1,
-        row * (CELL_SIZE + 1) + 1,
-        CELL_SIZE,
-        CELL_SIZE
-      );
-    }
-  }
-
-  ctx.stroke();
-};
-
-// To start the rendering process, all we have to do is make the initial call 
-// for the first iteration of the rendering loop
-// Note: We call drawGrid() and drawCells() here before 
-// we call requestAnimationFrame(). 
-// The reason we do this is so that the initial state of the universe 
-// is drawn before we make modifications. 
-//
-// If we instead simply called requestAnimationFrame(renderLoop), 
-// we'd end up with a situation where the first frame that was drawn 
-// would actually be after the first call to universe.tick(), 
-// which is the second "tick" of the life of these cells.
-drawGrid();
-drawCells();
-requestAnimationFrame(renderLoop);
+// The share string is URL-safe, so it can go into the #fragment as is.
+document.getElementById("copy-link").addEventListener("click", () => {
+  const url = new URL(window.location.href);
+  url.hash = gameLoop.to_share_string();
+  navigator.clipboard.writeText(url.toString());
+});
 
+drawStats();
+gameLoop.start();